@@ -34,10 +34,12 @@ fn main() -> Result<()> {
                     "control::motion::walking_engine",
                     "control::motion::walk_manager",
                     "control::odometry",
+                    "control::pass_selector",
                     "control::penalty_shot_direction_estimation",
                     "control::primary_state_filter",
                     "control::ready_signal_detection_filter",
                     "control::referee_position_provider",
+                    "control::robot_tracker",
                     "control::role_assignment",
                     "control::rule_obstacle_composer",
                     "control::search_suggestor",
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use linear_algebra::{point, vector, Isometry2};
+use scenario::scenario;
+use spl_network_messages::{GameState, HulkMessage, PassMessage, PlayerNumber};
+use types::{ball_position::SimulatorBallState, obstacles::Obstacle};
+
+use bevyhavior_simulator::{
+    ball::BallResource,
+    game_controller::{GameController, GameControllerCommand},
+    robot::{cycle_robots, Message, Messages, Robot},
+    time::{Ticks, TicksTime},
+};
+
+#[derive(Default)]
+struct PassProgress {
+    is_sent: bool,
+    is_received: bool,
+}
+
+#[scenario]
+fn pass(app: &mut App) {
+    app.add_systems(Startup, startup);
+    app.add_systems(Update, update.after(cycle_robots));
+}
+
+fn startup(
+    mut commands: Commands,
+    mut game_controller: ResMut<GameController>,
+    mut game_controller_commands: EventWriter<GameControllerCommand>,
+    mut ball: ResMut<BallResource>,
+) {
+    for (number, position) in [
+        (PlayerNumber::Six, vector![-1.0, 0.0]),
+        (PlayerNumber::Seven, vector![1.5, 1.5]),
+    ] {
+        let mut robot = Robot::new(number);
+        *robot.ground_to_field_mut() = Isometry2::from_parts(position, 0.0);
+        robot.parameters.pass_selector.enable = true;
+        robot.parameters.pass_selector.teammate_pose_timeout = Duration::from_secs(5);
+        robot.parameters.pass_selector.incoming_pass_timeout = Duration::from_secs(5);
+        robot.parameters.pass_selector.announcement_interval = Duration::from_secs(1);
+        robot.parameters.pass_selector.minimum_pass_distance = 1.0;
+        robot.parameters.pass_selector.maximum_pass_distance = 4.0;
+        robot.parameters.pass_selector.minimum_forward_progress = 0.5;
+        robot.parameters.pass_selector.opponent_clearance = 0.5;
+        robot.parameters.pass_selector.opponent_distance_weight = 0.5;
+        robot.parameters.pass_selector.minimum_kick_strength = 0.3;
+        robot.parameters.pass_selector.reception_radius = 0.5;
+        robot.parameters.pass_selector.reception_probability_weight = 0.5;
+        robot.parameters.pass_selector.receiver_score_weight = 0.1;
+        robot.parameters.pass_selector.minimum_score_advantage = 0.1;
+        commands.spawn(robot);
+    }
+    game_controller.state.game_state = GameState::Playing;
+    game_controller_commands.send(GameControllerCommand::SetGameState(GameState::Playing));
+    ball.state = Some(SimulatorBallState {
+        position: point![-0.5, 0.0],
+        velocity: vector![0.0, 0.0],
+    });
+}
+
+fn update(
+    time: Res<Time<Ticks>>,
+    mut exit: EventWriter<AppExit>,
+    mut robots: Query<&mut Robot>,
+    ball: Res<BallResource>,
+    messages: Res<Messages>,
+    mut progress: Local<PassProgress>,
+) {
+    // The opponent blocks the shot towards the goal, but not the pass line to the receiver
+    let opponent_position = point![1.0, 0.0];
+    for mut robot in &mut robots {
+        let field_to_ground = robot.ground_to_field().inverse();
+        robot.database.main_outputs.obstacles = vec![Obstacle::robot(
+            field_to_ground * opponent_position,
+            0.3,
+            0.3,
+        )];
+    }
+
+    progress.is_sent |= messages.messages.iter().any(|message| {
+        matches!(
+            message,
+            Message {
+                sender: PlayerNumber::Six,
+                payload: HulkMessage::Pass(PassMessage {
+                    receiver: PlayerNumber::Seven,
+                    ..
+                }),
+            }
+        )
+    });
+
+    let receiver = robots
+        .iter()
+        .find(|robot| robot.parameters.player_number == PlayerNumber::Seven)
+        .unwrap();
+    progress.is_received |= receiver
+        .database
+        .main_outputs
+        .incoming_pass
+        .is_some_and(|incoming_pass| incoming_pass.passer == PlayerNumber::Six);
+    let has_ball_reached_receiver = ball.state.as_ref().is_some_and(|ball| {
+        let ball_in_ground = receiver.ground_to_field().inverse() * ball.position;
+        ball_in_ground.coords().norm() < 0.5
+    });
+
+    if progress.is_sent && progress.is_received && has_ball_reached_receiver {
+        println!("Pass was received");
+        exit.send(AppExit::Success);
+    }
+    if time.ticks() >= 5_000 {
+        println!(
+            "Pass was not completed in time, sent: {}, received: {}, ball reached receiver: {}",
+            progress.is_sent, progress.is_received, has_ball_reached_receiver
+        );
+        exit.send(AppExit::from_code(1));
+    }
+}
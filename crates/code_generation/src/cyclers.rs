@@ -546,10 +546,19 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers, mode: CyclerMode) -
                 }
             });
 
+            let execution_time_warning_threshold = match cycler.execution_time_warning_threshold {
+                Some(threshold) => {
+                    let threshold_seconds = threshold.as_secs_f32();
+                    quote! { Some(std::time::Duration::from_secs_f32(#threshold_seconds)) }
+                }
+                None => quote! { None },
+            };
+
             quote! {
                 #after_remaining_nodes
                 let recording_duration = recording_timestamp.elapsed().expect("time ran backwards");
                 own_database.cycle_timings.total = recording_duration;
+                own_database.cycle_timings.execution_time_warning_threshold = #execution_time_warning_threshold;
 
                 #duration_warning
 
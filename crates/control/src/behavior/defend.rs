@@ -7,7 +7,7 @@ use geometry::{
     line::{Line, Line2},
     look_at::LookAt,
 };
-use linear_algebra::{distance, point, Point2, Pose2, Vector2};
+use linear_algebra::{distance, point, Isometry2, Point2, Pose2, Vector2};
 use serde::{Deserialize, Serialize};
 use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
@@ -16,6 +16,7 @@ use types::{
     motion_command::{JumpDirection, MotionCommand, OrientationMode, WalkSpeed},
     parameters::{KeeperMotionParameters, RolePositionsParameters},
     path_obstacles::PathObstacle,
+    tracked_robots::TrackedRobot,
     world_state::{BallState, WorldState},
 };
 
@@ -269,6 +270,13 @@ fn defend_pose(
     };
     *last_defender_mode = mode;
 
+    let threat_position = anticipated_ball_position(
+        &ball,
+        &world_state.tracked_robots,
+        ground_to_field,
+        role_positions,
+    );
+
     if mode == DefendMode::Passive {
         let passive_target_position = position_to_defend
             + (Vector2::x_axis() * role_positions.defender_aggressive_ring_radius);
@@ -276,7 +284,7 @@ fn defend_pose(
             ground_to_field.inverse()
                 * Pose2::<Field>::new(
                     passive_target_position,
-                    passive_target_position.look_at(&ball.ball_in_field).angle(),
+                    passive_target_position.look_at(&threat_position).angle(),
                 ),
         );
     }
@@ -288,11 +296,10 @@ fn defend_pose(
     };
 
     let position_to_defend_to_ball_max_length =
-        (ball.ball_in_field - position_to_defend).norm() * (2.0 / 3.0);
+        (threat_position - position_to_defend).norm() * (2.0 / 3.0);
     let distance_to_target = distance_to_target.min(position_to_defend_to_ball_max_length);
 
-    let mut defend_pose =
-        block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let mut defend_pose = block_on_circle(threat_position, position_to_defend, distance_to_target);
 
     if let Some(FilteredGameControllerState {
         kicking_team: Some(Team::Opponent),
@@ -311,6 +318,36 @@ fn defend_pose(
     Some(ground_to_field.inverse() * defend_pose)
 }
 
+/// Anticipates where the ball will be when an opponent dribbles it, so that the defender blocks
+/// the opponent's way instead of the current ball position.
+fn anticipated_ball_position(
+    ball: &BallState,
+    tracked_robots: &[TrackedRobot],
+    ground_to_field: Isometry2<Ground, Field>,
+    role_positions: &RolePositionsParameters,
+) -> Point2<Field> {
+    let opponent_at_ball = tracked_robots
+        .iter()
+        .filter(|robot| robot.is_opponent())
+        .map(|robot| (robot, distance(robot.position(), ball.ball_in_ground)))
+        .filter(|(_, distance_to_ball)| {
+            *distance_to_ball < role_positions.defender_opponent_ball_possession_distance
+        })
+        .min_by(|(_, left), (_, right)| left.total_cmp(right));
+
+    match opponent_at_ball {
+        Some((opponent, _)) => {
+            ball.ball_in_field
+                + ground_to_field
+                    * opponent.velocity()
+                    * role_positions
+                        .defender_opponent_anticipation_horizon
+                        .as_secs_f32()
+        }
+        None => ball.ball_in_field,
+    }
+}
+
 fn defend_penalty_kick(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
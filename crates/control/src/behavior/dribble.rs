@@ -84,7 +84,7 @@ pub fn execute(
     }
 }
 
-fn is_kick_pose_reached(
+pub fn is_kick_pose_reached(
     kick_pose: Pose2<Ground>,
     kick_info: &InWalkKickInfoParameters,
     ground_to_upcoming_support: Isometry2<Ground, UpcomingSupport>,
@@ -12,13 +12,16 @@ mod look_at_referee;
 mod lost_ball;
 mod no_ground_contact;
 pub mod node;
+mod pass;
 mod penalize;
 mod prepare_jump;
+mod receive_pass;
 mod search;
 mod sit_down;
 mod stand;
 mod stand_up;
 mod support;
+mod tree;
 mod unstiff;
 mod walk_to_kick_off;
 mod walk_to_penalty_kick;
@@ -10,6 +10,7 @@ use linear_algebra::{point, Point2};
 use spl_network_messages::{GamePhase, PlayerNumber, SubState, Team};
 use types::{
     action::Action,
+    behavior_tree::BehaviorTreeTraceEntry,
     cycle_time::CycleTime,
     dribble_path_plan::DribblePathPlan,
     field_dimensions::{FieldDimensions, GlobalFieldSide, Side},
@@ -35,8 +36,9 @@ use super::{
     dribble, fall_safely,
     head::LookAction,
     initial, intercept_ball, jump, look_around, look_at_referee, lost_ball, no_ground_contact,
-    penalize, prepare_jump, search, sit_down, stand, stand_up, support, unstiff, walk_to_kick_off,
-    walk_to_penalty_kick,
+    pass, penalize, prepare_jump, receive_pass, search, sit_down, stand, stand_up, support,
+    tree::{select_action, ConditionContext},
+    unstiff, walk_to_kick_off, walk_to_penalty_kick,
     walk_to_pose::{WalkAndStand, WalkPathPlanner},
 };
 
@@ -85,6 +87,7 @@ pub struct CycleContext {
 
     path_obstacles_output: AdditionalOutput<Vec<PathObstacle>, "path_obstacles">,
     active_action_output: AdditionalOutput<Action, "active_action">,
+    behavior_tree_trace: AdditionalOutput<Vec<BehaviorTreeTraceEntry>, "behavior_tree_trace">,
 
     last_motion_command: CyclerState<MotionCommand, "last_motion_command">,
 }
@@ -155,108 +158,26 @@ impl Behavior {
             Action::Calibrate,
         ];
 
-        if let Some(active_since) = self.active_since {
-            let duration_active = now.duration_since(active_since)?;
-            if !context.is_localization_converged
-                && (duration_active < context.parameters.maximum_lookaround_duration)
-            {
-                actions.push(Action::LookAround);
+        let should_look_around = match self.active_since {
+            Some(active_since) => {
+                let duration_active = now.duration_since(active_since)?;
+                !context.is_localization_converged
+                    && (duration_active < context.parameters.maximum_lookaround_duration)
             }
-        }
-
-        if matches!(world_state.robot.player_number, PlayerNumber::One) {
-            actions.push(Action::KeeperMotion);
-        }
-        actions.push(Action::InterceptBall);
+            None => false,
+        };
 
-        match world_state.robot.role {
-            Role::DefenderLeft if should_do_kick_in_pose_detection(world_state) => {
-                actions.push(Action::LookAtReferee);
-                actions.push(Action::DefendLeft);
-            }
-            Role::DefenderLeft => match world_state.filtered_game_controller_state {
-                Some(FilteredGameControllerState {
-                    sub_state: Some(SubState::CornerKick),
-                    kicking_team: Some(Team::Opponent),
-                    ..
-                }) => actions.push(Action::DefendOpponentCornerKick { side: Side::Left }),
-                _ => actions.push(Action::DefendLeft),
-            },
-            Role::DefenderRight if should_do_kick_in_pose_detection(world_state) => {
-                actions.push(Action::LookAtReferee);
-                actions.push(Action::DefendRight);
-            }
-            Role::DefenderRight => match world_state.filtered_game_controller_state {
-                Some(FilteredGameControllerState {
-                    sub_state: Some(SubState::CornerKick),
-                    kicking_team: Some(Team::Opponent),
-                    ..
-                }) => actions.push(Action::DefendOpponentCornerKick { side: Side::Right }),
-                _ => actions.push(Action::DefendRight),
-            },
-            Role::Keeper => match world_state.filtered_game_controller_state {
-                Some(FilteredGameControllerState {
-                    game_phase: GamePhase::PenaltyShootout { .. },
-                    ..
-                })
-                | Some(FilteredGameControllerState {
-                    game_state: FilteredGameState::Playing { .. },
-                    kicking_team: Some(Team::Opponent),
-                    sub_state: Some(SubState::PenaltyKick),
-                    ..
-                }) => {
-                    actions.push(Action::Jump);
-                    actions.push(Action::PrepareJump);
-                }
-                _ => actions.push(Action::DefendGoal),
-            },
-            Role::Loser => actions.push(Action::SearchForLostBall),
-            Role::MidfielderLeft if should_do_kick_in_pose_detection(world_state) => {
-                actions.push(Action::LookAtReferee);
-                actions.push(Action::SupportLeft);
-            }
-            Role::MidfielderLeft => actions.push(Action::SupportLeft),
-            Role::MidfielderRight if should_do_kick_in_pose_detection(world_state) => {
-                actions.push(Action::LookAtReferee);
-                actions.push(Action::SupportRight);
+        if context.parameters.behavior_tree.is_none() {
+            if should_look_around {
+                actions.push(Action::LookAround);
             }
-            Role::MidfielderRight => actions.push(Action::SupportRight),
-            Role::ReplacementKeeper => actions.push(Action::DefendGoal),
-            Role::Searcher if should_do_kick_in_pose_detection(world_state) => {
-                actions.push(Action::LookAtReferee);
-                actions.push(Action::Search);
+            if matches!(world_state.robot.player_number, PlayerNumber::One) {
+                actions.push(Action::KeeperMotion);
             }
-            Role::Searcher => actions.push(Action::Search),
-            Role::Striker => match world_state.filtered_game_controller_state {
-                None
-                | Some(FilteredGameControllerState {
-                    game_state:
-                        FilteredGameState::Playing {
-                            ball_is_free: true, ..
-                        },
-                    ..
-                }) => {
-                    actions.push(Action::Dribble);
-                }
-                Some(FilteredGameControllerState {
-                    game_state: FilteredGameState::Ready,
-                    kicking_team: Some(Team::Hulks),
-                    sub_state,
-                    ..
-                }) => match sub_state {
-                    Some(SubState::PenaltyKick) => actions.push(Action::WalkToPenaltyKick),
-                    _ => actions.push(Action::WalkToKickOff),
-                },
-                Some(FilteredGameControllerState {
-                    game_state: FilteredGameState::Ready | FilteredGameState::Playing { .. },
-                    sub_state: Some(SubState::PenaltyKick),
-                    kicking_team: Some(Team::Opponent),
-                    ..
-                }) => actions.push(Action::DefendPenaltyKick),
-                _ => actions.push(Action::DefendKickOff),
-            },
-            Role::StrikerSupporter => actions.push(Action::SupportStriker),
-        };
+            actions.push(Action::InterceptBall);
+            actions.push(Action::ReceivePass);
+            push_role_actions(&mut actions, world_state);
+        }
 
         let walk_path_planner = WalkPathPlanner::new(
             context.field_dimensions,
@@ -280,261 +201,291 @@ impl Behavior {
             &mut self.last_defender_mode,
         );
 
-        let (action, motion_command) = actions
-            .iter()
-            .find_map(|action| {
-                let motion_command = match action {
-                    Action::Animation => animation::execute(world_state),
-                    Action::Unstiff => unstiff::execute(world_state),
-                    Action::SitDown => sit_down::execute(world_state),
-                    Action::Penalize => penalize::execute(world_state),
-                    Action::Initial => initial::execute(
-                        world_state,
-                        context.expected_referee_position,
-                        *context.enable_pose_detection,
-                        context.initial_poses,
-                    ),
-                    Action::LookAtReferee => look_at_referee::execute(
-                        *context.enable_pose_detection,
-                        &walk_and_stand,
-                        context.expected_referee_position,
-                        context.world_state,
-                        &mut context.path_obstacles_output,
-                        *context.support_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::FallSafely => {
-                        fall_safely::execute(world_state, *context.has_ground_contact)
-                    }
-                    Action::StandUp => {
-                        stand_up::execute(world_state, context.parameters.maximum_standup_attempts)
-                    }
-                    Action::NoGroundContact => no_ground_contact::execute(world_state),
-                    Action::LookAround => look_around::execute(world_state),
-                    Action::KeeperMotion => defend.keeper_motion(context.keeper_motion.clone()),
-                    Action::InterceptBall => intercept_ball::execute(
-                        world_state,
-                        *context.intercept_ball_parameters,
-                        *context.intercept_ball_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::Calibrate => {
-                        calibrate::execute(world_state, *context.use_stand_head_unstiff_calibration)
-                    }
-                    Action::DefendGoal => defend.goal(
-                        &mut context.path_obstacles_output,
-                        *context.defend_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .defender_distance_to_be_aligned,
-                    ),
-                    Action::DefendKickOff => defend.kick_off(
-                        &mut context.path_obstacles_output,
-                        *context.defend_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .defender_distance_to_be_aligned,
-                    ),
-                    Action::DefendLeft => defend.left(
-                        &mut context.path_obstacles_output,
-                        *context.defend_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .defender_distance_to_be_aligned,
-                    ),
-                    Action::DefendRight => defend.right(
+        let mut execute = |action: Action| -> Option<MotionCommand> {
+            match action {
+                Action::Animation => animation::execute(world_state),
+                Action::Unstiff => unstiff::execute(world_state),
+                Action::SitDown => sit_down::execute(world_state),
+                Action::Penalize => penalize::execute(world_state),
+                Action::Initial => initial::execute(
+                    world_state,
+                    context.expected_referee_position,
+                    *context.enable_pose_detection,
+                    context.initial_poses,
+                ),
+                Action::LookAtReferee => look_at_referee::execute(
+                    *context.enable_pose_detection,
+                    &walk_and_stand,
+                    context.expected_referee_position,
+                    context.world_state,
+                    &mut context.path_obstacles_output,
+                    *context.support_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::FallSafely => {
+                    fall_safely::execute(world_state, *context.has_ground_contact)
+                }
+                Action::StandUp => {
+                    stand_up::execute(world_state, context.parameters.maximum_standup_attempts)
+                }
+                Action::NoGroundContact => no_ground_contact::execute(world_state),
+                Action::LookAround => look_around::execute(world_state),
+                Action::KeeperMotion => defend.keeper_motion(context.keeper_motion.clone()),
+                Action::InterceptBall => intercept_ball::execute(
+                    world_state,
+                    *context.intercept_ball_parameters,
+                    *context.intercept_ball_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::Calibrate => {
+                    calibrate::execute(world_state, *context.use_stand_head_unstiff_calibration)
+                }
+                Action::DefendGoal => defend.goal(
+                    &mut context.path_obstacles_output,
+                    *context.defend_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .defender_distance_to_be_aligned,
+                ),
+                Action::DefendKickOff => defend.kick_off(
+                    &mut context.path_obstacles_output,
+                    *context.defend_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .defender_distance_to_be_aligned,
+                ),
+                Action::DefendLeft => defend.left(
+                    &mut context.path_obstacles_output,
+                    *context.defend_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .defender_distance_to_be_aligned,
+                ),
+                Action::DefendRight => defend.right(
+                    &mut context.path_obstacles_output,
+                    *context.defend_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .defender_distance_to_be_aligned,
+                ),
+                Action::DefendPenaltyKick => defend.penalty_kick(
+                    &mut context.path_obstacles_output,
+                    *context.defend_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .defender_distance_to_be_aligned,
+                ),
+                Action::DefendOpponentCornerKick { side: Side::Left } => defend
+                    .opponent_corner_kick(
                         &mut context.path_obstacles_output,
                         *context.defend_walk_speed,
+                        Side::Left,
                         context
                             .parameters
                             .walk_and_stand
                             .defender_distance_to_be_aligned,
                     ),
-                    Action::DefendPenaltyKick => defend.penalty_kick(
+                Action::DefendOpponentCornerKick { side: Side::Right } => defend
+                    .opponent_corner_kick(
                         &mut context.path_obstacles_output,
                         *context.defend_walk_speed,
+                        Side::Right,
                         context
                             .parameters
                             .walk_and_stand
                             .defender_distance_to_be_aligned,
                     ),
-                    Action::DefendOpponentCornerKick { side: Side::Left } => defend
-                        .opponent_corner_kick(
-                            &mut context.path_obstacles_output,
-                            *context.defend_walk_speed,
-                            Side::Left,
-                            context
-                                .parameters
-                                .walk_and_stand
-                                .defender_distance_to_be_aligned,
-                        ),
-                    Action::DefendOpponentCornerKick { side: Side::Right } => defend
-                        .opponent_corner_kick(
-                            &mut context.path_obstacles_output,
-                            *context.defend_walk_speed,
-                            Side::Right,
-                            context
-                                .parameters
-                                .walk_and_stand
-                                .defender_distance_to_be_aligned,
-                        ),
-                    Action::Stand => stand::execute(
-                        world_state,
-                        context.field_dimensions,
-                        &context.world_state.robot.role,
-                    ),
-                    Action::Dribble => dribble::execute(
-                        world_state,
-                        &walk_path_planner,
-                        context.in_walk_kicks,
-                        &context.parameters.dribbling,
-                        context.dribble_path_plan.cloned(),
-                        *context.dribble_walk_speed,
-                        context.parameters.dribbling.distance_to_be_aligned,
-                    ),
-                    Action::Jump => jump::execute(world_state),
-                    Action::PrepareJump => prepare_jump::execute(world_state),
-                    Action::Search => search::execute(
-                        world_state,
-                        &walk_path_planner,
-                        &walk_and_stand,
-                        context.field_dimensions,
-                        &context.parameters.search,
-                        &mut context.path_obstacles_output,
-                        self.previous_role,
-                        self.last_time_role_changed,
-                        self.last_known_ball_position,
-                        *context.search_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                        context.cycle_time.start_time,
-                    ),
-                    Action::SearchForLostBall => lost_ball::execute(
-                        world_state,
-                        self.last_known_ball_position,
-                        &walk_path_planner,
-                        context.lost_ball_parameters,
-                        &mut context.path_obstacles_output,
-                        *context.lost_ball_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::SupportLeft => support::execute(
-                        world_state,
-                        context.field_dimensions,
-                        Some(Side::Left),
-                        context
-                            .parameters
-                            .role_positions
-                            .left_midfielder_distance_to_ball,
-                        context
-                            .parameters
-                            .role_positions
-                            .left_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
-                        context.parameters.role_positions.left_midfielder_minimum_x,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        *context.support_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::SupportRight => support::execute(
-                        world_state,
-                        context.field_dimensions,
-                        Some(Side::Right),
-                        context
-                            .parameters
-                            .role_positions
-                            .right_midfielder_distance_to_ball,
-                        context
-                            .parameters
-                            .role_positions
-                            .right_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
-                        context.parameters.role_positions.right_midfielder_minimum_x,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        *context.support_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::SupportStriker => support::execute(
-                        world_state,
-                        context.field_dimensions,
-                        None,
-                        context
-                            .parameters
-                            .role_positions
-                            .striker_supporter_distance_to_ball,
-                        context
-                            .parameters
-                            .role_positions
-                            .striker_supporter_maximum_x_in_ready_and_when_ball_is_not_free,
-                        context
-                            .parameters
-                            .role_positions
-                            .striker_supporter_minimum_x,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        *context.support_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::WalkToKickOff => walk_to_kick_off::execute(
-                        world_state,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        context.parameters.role_positions.striker_kickoff_position,
-                        context.kick_decision_parameters.kick_off_angle,
-                        *context.walk_to_kickoff_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                    Action::WalkToPenaltyKick => walk_to_penalty_kick::execute(
-                        world_state,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        context.field_dimensions,
-                        *context.walk_to_penalty_kick_walk_speed,
-                        context
-                            .parameters
-                            .walk_and_stand
-                            .normal_distance_to_be_aligned,
-                    ),
-                }?;
-                Some((action, motion_command))
-            })
-            .unwrap_or_else(|| {
-                panic!(
-                    "there has to be at least one action available, world_state: {world_state:#?}",
-                )
-            });
-        context.active_action_output.fill_if_subscribed(|| *action);
+                Action::Stand => stand::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.world_state.robot.role,
+                ),
+                Action::Dribble => dribble::execute(
+                    world_state,
+                    &walk_path_planner,
+                    context.in_walk_kicks,
+                    &context.parameters.dribbling,
+                    context.dribble_path_plan.cloned(),
+                    *context.dribble_walk_speed,
+                    context.parameters.dribbling.distance_to_be_aligned,
+                ),
+                Action::Pass => pass::execute(
+                    world_state,
+                    &walk_and_stand,
+                    context.in_walk_kicks,
+                    &mut context.path_obstacles_output,
+                    *context.dribble_walk_speed,
+                    context.parameters.dribbling.distance_to_be_aligned,
+                ),
+                Action::ReceivePass => receive_pass::execute(
+                    world_state,
+                    &walk_and_stand,
+                    &mut context.path_obstacles_output,
+                    context.intercept_ball_parameters.minimum_ball_velocity,
+                    *context.intercept_ball_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::Jump => jump::execute(world_state),
+                Action::PrepareJump => prepare_jump::execute(world_state),
+                Action::Search => search::execute(
+                    world_state,
+                    &walk_path_planner,
+                    &walk_and_stand,
+                    context.field_dimensions,
+                    &context.parameters.search,
+                    &mut context.path_obstacles_output,
+                    self.previous_role,
+                    self.last_time_role_changed,
+                    self.last_known_ball_position,
+                    *context.search_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                    context.cycle_time.start_time,
+                ),
+                Action::SearchForLostBall => lost_ball::execute(
+                    world_state,
+                    self.last_known_ball_position,
+                    &walk_path_planner,
+                    context.lost_ball_parameters,
+                    &mut context.path_obstacles_output,
+                    *context.lost_ball_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::SupportLeft => support::execute(
+                    world_state,
+                    context.field_dimensions,
+                    Some(Side::Left),
+                    context
+                        .parameters
+                        .role_positions
+                        .left_midfielder_distance_to_ball,
+                    context
+                        .parameters
+                        .role_positions
+                        .left_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
+                    context.parameters.role_positions.left_midfielder_minimum_x,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    *context.support_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::SupportRight => support::execute(
+                    world_state,
+                    context.field_dimensions,
+                    Some(Side::Right),
+                    context
+                        .parameters
+                        .role_positions
+                        .right_midfielder_distance_to_ball,
+                    context
+                        .parameters
+                        .role_positions
+                        .right_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
+                    context.parameters.role_positions.right_midfielder_minimum_x,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    *context.support_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::SupportStriker => support::execute(
+                    world_state,
+                    context.field_dimensions,
+                    None,
+                    context
+                        .parameters
+                        .role_positions
+                        .striker_supporter_distance_to_ball,
+                    context
+                        .parameters
+                        .role_positions
+                        .striker_supporter_maximum_x_in_ready_and_when_ball_is_not_free,
+                    context
+                        .parameters
+                        .role_positions
+                        .striker_supporter_minimum_x,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    *context.support_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::WalkToKickOff => walk_to_kick_off::execute(
+                    world_state,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    context.parameters.role_positions.striker_kickoff_position,
+                    context.kick_decision_parameters.kick_off_angle,
+                    *context.walk_to_kickoff_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+                Action::WalkToPenaltyKick => walk_to_penalty_kick::execute(
+                    world_state,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    context.field_dimensions,
+                    *context.walk_to_penalty_kick_walk_speed,
+                    context
+                        .parameters
+                        .walk_and_stand
+                        .normal_distance_to_be_aligned,
+                ),
+            }
+        };
+
+        let mut behavior_tree_trace = Vec::new();
+        let condition_context = ConditionContext {
+            world_state,
+            should_look_around,
+        };
+        let (action, motion_command) = select_action(
+            &actions,
+            context.parameters.behavior_tree.as_ref(),
+            &condition_context,
+            &mut execute,
+            &mut behavior_tree_trace,
+        )
+        .unwrap_or_else(|| {
+            panic!("there has to be at least one action available, world_state: {world_state:#?}")
+        });
+        context.active_action_output.fill_if_subscribed(|| action);
+        context
+            .behavior_tree_trace
+            .fill_if_subscribed(|| behavior_tree_trace);
 
         *context.last_motion_command = motion_command.clone();
 
@@ -544,6 +495,101 @@ impl Behavior {
     }
 }
 
+pub fn push_role_actions(actions: &mut Vec<Action>, world_state: &WorldState) {
+    match world_state.robot.role {
+        Role::DefenderLeft if should_do_kick_in_pose_detection(world_state) => {
+            actions.push(Action::LookAtReferee);
+            actions.push(Action::DefendLeft);
+        }
+        Role::DefenderLeft => match world_state.filtered_game_controller_state {
+            Some(FilteredGameControllerState {
+                sub_state: Some(SubState::CornerKick),
+                kicking_team: Some(Team::Opponent),
+                ..
+            }) => actions.push(Action::DefendOpponentCornerKick { side: Side::Left }),
+            _ => actions.push(Action::DefendLeft),
+        },
+        Role::DefenderRight if should_do_kick_in_pose_detection(world_state) => {
+            actions.push(Action::LookAtReferee);
+            actions.push(Action::DefendRight);
+        }
+        Role::DefenderRight => match world_state.filtered_game_controller_state {
+            Some(FilteredGameControllerState {
+                sub_state: Some(SubState::CornerKick),
+                kicking_team: Some(Team::Opponent),
+                ..
+            }) => actions.push(Action::DefendOpponentCornerKick { side: Side::Right }),
+            _ => actions.push(Action::DefendRight),
+        },
+        Role::Keeper => match world_state.filtered_game_controller_state {
+            Some(FilteredGameControllerState {
+                game_phase: GamePhase::PenaltyShootout { .. },
+                ..
+            })
+            | Some(FilteredGameControllerState {
+                game_state: FilteredGameState::Playing { .. },
+                kicking_team: Some(Team::Opponent),
+                sub_state: Some(SubState::PenaltyKick),
+                ..
+            }) => {
+                actions.push(Action::Jump);
+                actions.push(Action::PrepareJump);
+            }
+            _ => actions.push(Action::DefendGoal),
+        },
+        Role::Loser => actions.push(Action::SearchForLostBall),
+        Role::MidfielderLeft if should_do_kick_in_pose_detection(world_state) => {
+            actions.push(Action::LookAtReferee);
+            actions.push(Action::SupportLeft);
+        }
+        Role::MidfielderLeft => actions.push(Action::SupportLeft),
+        Role::MidfielderRight if should_do_kick_in_pose_detection(world_state) => {
+            actions.push(Action::LookAtReferee);
+            actions.push(Action::SupportRight);
+        }
+        Role::MidfielderRight => actions.push(Action::SupportRight),
+        Role::ReplacementKeeper => actions.push(Action::DefendGoal),
+        Role::Searcher if should_do_kick_in_pose_detection(world_state) => {
+            actions.push(Action::LookAtReferee);
+            actions.push(Action::Search);
+        }
+        Role::Searcher => actions.push(Action::Search),
+        Role::Striker => match world_state.filtered_game_controller_state {
+            None
+            | Some(FilteredGameControllerState {
+                game_state:
+                    FilteredGameState::Playing {
+                        ball_is_free: true, ..
+                    },
+                ..
+            }) => {
+                // The pass selector only decides to pass if the pass beats the best kick
+                if world_state.pass_decision.is_some() {
+                    actions.push(Action::Pass);
+                }
+                actions.push(Action::Dribble);
+            }
+            Some(FilteredGameControllerState {
+                game_state: FilteredGameState::Ready,
+                kicking_team: Some(Team::Hulks),
+                sub_state,
+                ..
+            }) => match sub_state {
+                Some(SubState::PenaltyKick) => actions.push(Action::WalkToPenaltyKick),
+                _ => actions.push(Action::WalkToKickOff),
+            },
+            Some(FilteredGameControllerState {
+                game_state: FilteredGameState::Ready | FilteredGameState::Playing { .. },
+                sub_state: Some(SubState::PenaltyKick),
+                kicking_team: Some(Team::Opponent),
+                ..
+            }) => actions.push(Action::DefendPenaltyKick),
+            _ => actions.push(Action::DefendKickOff),
+        },
+        Role::StrikerSupporter => actions.push(Action::SupportStriker),
+    }
+}
+
 pub fn should_do_kick_in_pose_detection(world_state: &WorldState) -> bool {
     if let Some(filtered_game_controller_state) = &world_state.filtered_game_controller_state {
         let is_kick_in_filtered_game_controller_state = matches!(
@@ -0,0 +1,64 @@
+use framework::AdditionalOutput;
+use types::{
+    camera_position::CameraPosition,
+    motion_command::{
+        ArmMotion, HeadMotion, ImageRegion, MotionCommand, OrientationMode, WalkSpeed,
+    },
+    parameters::InWalkKicksParameters,
+    path_obstacles::PathObstacle,
+    world_state::WorldState,
+};
+
+use super::{dribble::is_kick_pose_reached, walk_to_pose::WalkAndStand};
+
+pub fn execute(
+    world_state: &WorldState,
+    walk_and_stand: &WalkAndStand,
+    in_walk_kicks: &InWalkKicksParameters,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+    walk_speed: WalkSpeed,
+    distance_to_be_aligned: f32,
+) -> Option<MotionCommand> {
+    let pass_decision = world_state.pass_decision.as_ref()?;
+    let ball_position = world_state.ball?.ball_in_ground;
+    let head = HeadMotion::LookAt {
+        target: ball_position,
+        image_region_target: ImageRegion::Center,
+        camera: Some(CameraPosition::Bottom),
+    };
+
+    let available_kick = pass_decision.kick_decisions.iter().find(|decision| {
+        is_kick_pose_reached(
+            decision.kick_pose,
+            &in_walk_kicks[decision.variant],
+            world_state.robot.ground_to_upcoming_support,
+        )
+    });
+    if let Some(kick) = available_kick {
+        return Some(MotionCommand::InWalkKick {
+            head,
+            kick: kick.variant,
+            kicking_side: kick.kicking_side,
+            strength: kick.strength,
+            left_arm: ArmMotion::Swing,
+            right_arm: ArmMotion::Swing,
+        });
+    }
+
+    let closest_kick = pass_decision.kick_decisions.iter().min_by(|left, right| {
+        left.kick_pose
+            .position()
+            .coords()
+            .norm()
+            .total_cmp(&right.kick_pose.position().coords().norm())
+    })?;
+    walk_and_stand.execute(
+        closest_kick.kick_pose,
+        head,
+        path_obstacles_output,
+        walk_speed,
+        OrientationMode::AlignWithPath,
+        distance_to_be_aligned,
+        walk_and_stand.parameters.hysteresis,
+    )
+}
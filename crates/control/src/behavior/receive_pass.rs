@@ -0,0 +1,61 @@
+use framework::AdditionalOutput;
+use geometry::{line::Line, look_at::LookAt};
+use linear_algebra::{Point, Pose2};
+use types::{
+    motion_command::{HeadMotion, ImageRegion, MotionCommand, OrientationMode, WalkSpeed},
+    path_obstacles::PathObstacle,
+    roles::Role,
+    world_state::WorldState,
+};
+
+use super::walk_to_pose::WalkAndStand;
+
+pub fn execute(
+    world_state: &WorldState,
+    walk_and_stand: &WalkAndStand,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+    minimum_ball_velocity: f32,
+    walk_speed: WalkSpeed,
+    distance_to_be_aligned: f32,
+) -> Option<MotionCommand> {
+    let incoming_pass = world_state.incoming_pass?;
+    if matches!(
+        world_state.robot.role,
+        Role::Keeper | Role::ReplacementKeeper | Role::Striker
+    ) {
+        return None;
+    }
+    let field_to_ground = world_state.robot.ground_to_field?.inverse();
+
+    let interception_point = match world_state.ball {
+        Some(ball) if ball.ball_in_ground_velocity.norm() > minimum_ball_velocity => {
+            let ball_line = Line {
+                point: ball.ball_in_ground,
+                direction: ball.ball_in_ground_velocity,
+            };
+            ball_line.closest_point(Point::origin())
+        }
+        _ => field_to_ground * incoming_pass.target,
+    };
+    let look_target = world_state
+        .ball
+        .map(|ball| ball.ball_in_ground)
+        .unwrap_or(field_to_ground * incoming_pass.target);
+
+    walk_and_stand.execute(
+        Pose2::from_parts(interception_point, interception_point.look_at(&look_target)),
+        HeadMotion::LookAt {
+            target: look_target,
+            image_region_target: ImageRegion::Center,
+            camera: None,
+        },
+        path_obstacles_output,
+        walk_speed,
+        OrientationMode::LookAt {
+            target: look_target,
+            tolerance: 0.0,
+        },
+        distance_to_be_aligned,
+        walk_and_stand.parameters.hysteresis,
+    )
+}
@@ -0,0 +1,309 @@
+use spl_network_messages::GamePhase;
+use types::{
+    action::Action,
+    behavior_tree::{
+        BehaviorCondition, BehaviorTreeNode, BehaviorTreeStatus, BehaviorTreeTraceEntry,
+    },
+    filtered_game_state::FilteredGameState,
+    world_state::WorldState,
+};
+
+use super::node::{push_role_actions, should_do_kick_in_pose_detection};
+
+pub struct ConditionContext<'a> {
+    pub world_state: &'a WorldState,
+    pub should_look_around: bool,
+}
+
+enum TickResult<T> {
+    Success,
+    Failure,
+    Running(Action, T),
+}
+
+impl<T> TickResult<T> {
+    fn status(&self) -> BehaviorTreeStatus {
+        match self {
+            TickResult::Success => BehaviorTreeStatus::Success,
+            TickResult::Failure => BehaviorTreeStatus::Failure,
+            TickResult::Running(..) => BehaviorTreeStatus::Running,
+        }
+    }
+}
+
+/// Executes the first available action of the list, then ticks the tree if there is one.
+///
+/// If the tree produces no action, e.g. because it is empty or misconfigured, the actions of the
+/// current role are tried instead.
+pub fn select_action<T>(
+    actions: &[Action],
+    behavior_tree: Option<&BehaviorTreeNode>,
+    context: &ConditionContext,
+    execute: &mut impl FnMut(Action) -> Option<T>,
+    trace: &mut Vec<BehaviorTreeTraceEntry>,
+) -> Option<(Action, T)> {
+    if let Some(selected) = execute_first(actions, execute) {
+        return Some(selected);
+    }
+    let behavior_tree = behavior_tree?;
+    if let Some(selected) = tick_behavior_tree(behavior_tree, context, execute, trace) {
+        return Some(selected);
+    }
+    let mut role_actions = Vec::new();
+    push_role_actions(&mut role_actions, context.world_state);
+    execute_first(&role_actions, execute)
+}
+
+fn execute_first<T>(
+    actions: &[Action],
+    execute: &mut impl FnMut(Action) -> Option<T>,
+) -> Option<(Action, T)> {
+    actions
+        .iter()
+        .find_map(|action| Some((*action, execute(*action)?)))
+}
+
+/// Ticks the tree from the root and returns the first action which produced an output.
+///
+/// Every visited node is appended to the trace, which allows reconstructing the active branch.
+pub fn tick_behavior_tree<T>(
+    root: &BehaviorTreeNode,
+    context: &ConditionContext,
+    execute: &mut impl FnMut(Action) -> Option<T>,
+    trace: &mut Vec<BehaviorTreeTraceEntry>,
+) -> Option<(Action, T)> {
+    match tick(root, context, execute, 0, trace) {
+        TickResult::Running(action, output) => Some((action, output)),
+        TickResult::Success | TickResult::Failure => None,
+    }
+}
+
+fn tick<T>(
+    node: &BehaviorTreeNode,
+    context: &ConditionContext,
+    execute: &mut impl FnMut(Action) -> Option<T>,
+    depth: usize,
+    trace: &mut Vec<BehaviorTreeTraceEntry>,
+) -> TickResult<T> {
+    let index = trace.len();
+    trace.push(BehaviorTreeTraceEntry {
+        depth,
+        label: label(node),
+        status: BehaviorTreeStatus::Failure,
+    });
+
+    let result = match node {
+        BehaviorTreeNode::Sequence(children) => {
+            let mut result = TickResult::Success;
+            for child in children {
+                match tick(child, context, execute, depth + 1, trace) {
+                    TickResult::Success => {}
+                    other => {
+                        result = other;
+                        break;
+                    }
+                }
+            }
+            result
+        }
+        BehaviorTreeNode::Fallback(children) => {
+            let mut result = TickResult::Failure;
+            for child in children {
+                match tick(child, context, execute, depth + 1, trace) {
+                    TickResult::Failure => {}
+                    other => {
+                        result = other;
+                        break;
+                    }
+                }
+            }
+            result
+        }
+        BehaviorTreeNode::Condition(condition) => {
+            if evaluate_condition(condition, context) {
+                TickResult::Success
+            } else {
+                TickResult::Failure
+            }
+        }
+        BehaviorTreeNode::Action(action) => match execute(*action) {
+            Some(output) => TickResult::Running(*action, output),
+            None => TickResult::Failure,
+        },
+    };
+
+    trace[index].status = result.status();
+    result
+}
+
+fn label(node: &BehaviorTreeNode) -> String {
+    match node {
+        BehaviorTreeNode::Sequence(_) => "Sequence".to_string(),
+        BehaviorTreeNode::Fallback(_) => "Fallback".to_string(),
+        BehaviorTreeNode::Condition(condition) => format!("{condition:?}"),
+        BehaviorTreeNode::Action(action) => format!("{action:?}"),
+    }
+}
+
+pub fn evaluate_condition(condition: &BehaviorCondition, context: &ConditionContext) -> bool {
+    let world_state = context.world_state;
+    let game_controller_state = world_state.filtered_game_controller_state.as_ref();
+    match condition {
+        BehaviorCondition::Not(condition) => !evaluate_condition(condition, context),
+        BehaviorCondition::All(conditions) => conditions
+            .iter()
+            .all(|condition| evaluate_condition(condition, context)),
+        BehaviorCondition::Any(conditions) => conditions
+            .iter()
+            .any(|condition| evaluate_condition(condition, context)),
+        BehaviorCondition::Role(role) => world_state.robot.role == *role,
+        BehaviorCondition::PlayerNumber(player_number) => {
+            world_state.robot.player_number == *player_number
+        }
+        BehaviorCondition::PrimaryState(primary_state) => {
+            world_state.robot.primary_state == *primary_state
+        }
+        BehaviorCondition::HasGameControllerState => game_controller_state.is_some(),
+        BehaviorCondition::GameStateReady => game_controller_state
+            .is_some_and(|state| matches!(state.game_state, FilteredGameState::Ready)),
+        BehaviorCondition::GameStatePlaying => game_controller_state
+            .is_some_and(|state| matches!(state.game_state, FilteredGameState::Playing { .. })),
+        BehaviorCondition::BallIsFree => game_controller_state.is_some_and(|state| {
+            matches!(
+                state.game_state,
+                FilteredGameState::Playing {
+                    ball_is_free: true,
+                    ..
+                }
+            )
+        }),
+        BehaviorCondition::PenaltyShootout => game_controller_state
+            .is_some_and(|state| matches!(state.game_phase, GamePhase::PenaltyShootout { .. })),
+        BehaviorCondition::SubState(sub_state) => {
+            game_controller_state.is_some_and(|state| state.sub_state == Some(*sub_state))
+        }
+        BehaviorCondition::KickingTeam(team) => {
+            game_controller_state.is_some_and(|state| state.kicking_team == Some(*team))
+        }
+        BehaviorCondition::HasBall => world_state.ball.is_some(),
+        BehaviorCondition::ShouldLookAround => context.should_look_around,
+        BehaviorCondition::ShouldDoKickInPoseDetection => {
+            should_do_kick_in_pose_detection(world_state)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::roles::Role;
+
+    use super::*;
+
+    fn world_state_with_role(role: Role) -> WorldState {
+        let mut world_state = WorldState::default();
+        world_state.robot.role = role;
+        world_state
+    }
+
+    fn striker_tree() -> BehaviorTreeNode {
+        BehaviorTreeNode::Fallback(vec![
+            BehaviorTreeNode::Sequence(vec![
+                BehaviorTreeNode::Condition(BehaviorCondition::Role(Role::Keeper)),
+                BehaviorTreeNode::Action(Action::DefendGoal),
+            ]),
+            BehaviorTreeNode::Sequence(vec![
+                BehaviorTreeNode::Condition(BehaviorCondition::Role(Role::Striker)),
+                BehaviorTreeNode::Fallback(vec![
+                    BehaviorTreeNode::Action(Action::Dribble),
+                    BehaviorTreeNode::Action(Action::Search),
+                ]),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn first_available_action_of_matching_branch_is_chosen() {
+        let world_state = world_state_with_role(Role::Striker);
+        let context = ConditionContext {
+            world_state: &world_state,
+            should_look_around: false,
+        };
+        let mut trace = Vec::new();
+
+        let result = tick_behavior_tree(
+            &striker_tree(),
+            &context,
+            &mut |action| (action == Action::Search).then_some(()),
+            &mut trace,
+        );
+
+        assert_eq!(result, Some((Action::Search, ())));
+        let running: Vec<_> = trace
+            .iter()
+            .filter(|entry| entry.status == BehaviorTreeStatus::Running)
+            .map(|entry| entry.label.as_str())
+            .collect();
+        assert_eq!(running, ["Fallback", "Sequence", "Fallback", "Search"]);
+        assert!(trace
+            .iter()
+            .any(|entry| entry.label == "Dribble" && entry.status == BehaviorTreeStatus::Failure));
+    }
+
+    #[test]
+    fn tree_without_available_action_fails() {
+        let world_state = world_state_with_role(Role::Loser);
+        let context = ConditionContext {
+            world_state: &world_state,
+            should_look_around: false,
+        };
+        let mut trace = Vec::new();
+
+        let result = tick_behavior_tree(&striker_tree(), &context, &mut |_| Some(()), &mut trace);
+
+        assert_eq!(result, None);
+        assert_eq!(trace[0].status, BehaviorTreeStatus::Failure);
+    }
+
+    #[test]
+    fn empty_tree_falls_back_to_role_actions() {
+        let world_state = world_state_with_role(Role::Keeper);
+        let context = ConditionContext {
+            world_state: &world_state,
+            should_look_around: false,
+        };
+        let mut trace = Vec::new();
+
+        let result = select_action(
+            &[Action::Unstiff, Action::Stand],
+            Some(&BehaviorTreeNode::Fallback(Vec::new())),
+            &context,
+            &mut |action| (action == Action::DefendGoal).then_some(()),
+            &mut trace,
+        );
+
+        assert_eq!(result, Some((Action::DefendGoal, ())));
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].status, BehaviorTreeStatus::Failure);
+    }
+
+    #[test]
+    fn role_actions_are_not_tried_without_tree() {
+        let world_state = world_state_with_role(Role::Keeper);
+        let context = ConditionContext {
+            world_state: &world_state,
+            should_look_around: false,
+        };
+        let mut trace = Vec::new();
+
+        let result = select_action(
+            &[Action::Unstiff],
+            None,
+            &context,
+            &mut |action| (action == Action::DefendGoal).then_some(()),
+            &mut trace,
+        );
+
+        assert_eq!(result, None);
+        assert!(trace.is_empty());
+    }
+}
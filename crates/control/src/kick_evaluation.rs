@@ -0,0 +1,381 @@
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+use coordinate_systems::{Field, Ground};
+use linear_algebra::{distance, Isometry2, Point2, Rotation2, Vector2};
+use types::{
+    field_dimensions::FieldDimensions,
+    kick_decision::{KickDecision, KickDecisionEvaluation, KickEvaluationParameters},
+    parameters::{KickNoiseParameters, PassSelectorParameters},
+    pass::PassEvaluation,
+    tracked_robots::TrackedRobot,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Opponent {
+    position: Point2<Field>,
+    velocity: Vector2<Field>,
+}
+
+impl Opponent {
+    pub fn from_tracked_robot(
+        robot: &TrackedRobot,
+        ground_to_field: Isometry2<Ground, Field>,
+    ) -> Self {
+        Self {
+            position: ground_to_field * robot.position(),
+            velocity: ground_to_field * robot.velocity(),
+        }
+    }
+
+    fn can_reach(
+        &self,
+        ball: Point2<Field>,
+        time: f32,
+        parameters: &KickEvaluationParameters,
+    ) -> bool {
+        let reaction_time = parameters.opponent_reaction_time.as_secs_f32();
+        let drifted_position = self.position + self.velocity * time.min(reaction_time);
+        let reach = parameters.opponent_interception_radius
+            + parameters.opponent_walking_speed * (time - reaction_time).max(0.0);
+        distance(drifted_position, ball) <= reach
+    }
+}
+
+enum Outcome {
+    Goal,
+    Intercepted,
+    Other,
+}
+
+/// Simulates noisy rollouts of the ball trajectory of a kick and estimates how likely the ball is
+/// intercepted by an opponent or ends up in the opponent goal.
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_kick_decision(
+    decision: KickDecision,
+    ball_position: Point2<Ground>,
+    ground_to_field: Isometry2<Ground, Field>,
+    opponents: &[Opponent],
+    shot_distance: f32,
+    noise: &KickNoiseParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &KickEvaluationParameters,
+    random_state: &mut impl Rng,
+) -> KickDecisionEvaluation {
+    let rollouts = sample_rollouts(
+        &decision,
+        ball_position,
+        ground_to_field,
+        opponents,
+        shot_distance,
+        noise,
+        field_dimensions,
+        parameters,
+        random_state,
+    );
+
+    let goal_probability = probability(&rollouts, parameters, |outcome, _| {
+        matches!(outcome, Outcome::Goal)
+    });
+    let interception_probability = probability(&rollouts, parameters, |outcome, _| {
+        matches!(outcome, Outcome::Intercepted)
+    });
+    let field_to_ground = ground_to_field.inverse();
+    KickDecisionEvaluation {
+        decision,
+        goal_probability,
+        interception_probability,
+        score: parameters.goal_probability_weight * goal_probability
+            - parameters.interception_probability_weight * interception_probability,
+        sampled_ball_end_positions: rollouts
+            .into_iter()
+            .map(|(_, end_position)| field_to_ground * end_position)
+            .collect(),
+    }
+}
+
+/// Simulates noisy rollouts of a pass and estimates how likely the ball is intercepted by an
+/// opponent or stops within the reception radius of the receiver.
+///
+/// The score is in the units of the kick evaluation: a received pass is worth its reception
+/// weight plus the weighted receiver score in place of a goal.
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_pass(
+    decision: KickDecision,
+    ball_position: Point2<Ground>,
+    ground_to_field: Isometry2<Ground, Field>,
+    receiver: Point2<Field>,
+    receiver_score: f32,
+    opponents: &[Opponent],
+    shot_distance: f32,
+    noise: &KickNoiseParameters,
+    field_dimensions: &FieldDimensions,
+    kick_evaluation_parameters: &KickEvaluationParameters,
+    parameters: &PassSelectorParameters,
+    random_state: &mut impl Rng,
+) -> PassEvaluation {
+    let rollouts = sample_rollouts(
+        &decision,
+        ball_position,
+        ground_to_field,
+        opponents,
+        shot_distance,
+        noise,
+        field_dimensions,
+        kick_evaluation_parameters,
+        random_state,
+    );
+
+    let reception_probability = probability(
+        &rollouts,
+        kick_evaluation_parameters,
+        |outcome, end_position| {
+            matches!(outcome, Outcome::Other)
+                && distance(end_position, receiver) <= parameters.reception_radius
+        },
+    );
+    let interception_probability =
+        probability(&rollouts, kick_evaluation_parameters, |outcome, _| {
+            matches!(outcome, Outcome::Intercepted)
+        });
+    let reception_value =
+        parameters.reception_probability_weight + parameters.receiver_score_weight * receiver_score;
+    PassEvaluation {
+        decision,
+        reception_probability,
+        interception_probability,
+        score: reception_value * reception_probability
+            - kick_evaluation_parameters.interception_probability_weight * interception_probability,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sample_rollouts(
+    decision: &KickDecision,
+    ball_position: Point2<Ground>,
+    ground_to_field: Isometry2<Ground, Field>,
+    opponents: &[Opponent],
+    shot_distance: f32,
+    noise: &KickNoiseParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &KickEvaluationParameters,
+    random_state: &mut impl Rng,
+) -> Vec<(Outcome, Point2<Field>)> {
+    let ball_in_field = ground_to_field * ball_position;
+    let direction = (ground_to_field * (decision.target - ball_position))
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector2::x_axis);
+
+    (0..parameters.number_of_samples)
+        .map(|_| {
+            let direction_noise: f32 = random_state.sample(StandardNormal);
+            let strength_noise: f32 = random_state.sample(StandardNormal);
+            let sampled_direction = Rotation2::<Field, Field>::new(
+                direction_noise * noise.direction_standard_deviation,
+            ) * direction;
+            let sampled_strength =
+                (decision.strength + strength_noise * noise.strength_standard_deviation).max(0.0);
+
+            simulate_ball_rollout(
+                ball_in_field,
+                sampled_direction,
+                shot_distance * sampled_strength,
+                opponents,
+                field_dimensions,
+                parameters,
+            )
+        })
+        .collect()
+}
+
+fn probability(
+    rollouts: &[(Outcome, Point2<Field>)],
+    parameters: &KickEvaluationParameters,
+    predicate: impl Fn(&Outcome, Point2<Field>) -> bool,
+) -> f32 {
+    let count = rollouts
+        .iter()
+        .filter(|(outcome, end_position)| predicate(outcome, *end_position))
+        .count();
+    count as f32 / parameters.number_of_samples.max(1) as f32
+}
+
+fn simulate_ball_rollout(
+    start: Point2<Field>,
+    direction: Vector2<Field>,
+    travel_distance: f32,
+    opponents: &[Opponent],
+    field_dimensions: &FieldDimensions,
+    parameters: &KickEvaluationParameters,
+) -> (Outcome, Point2<Field>) {
+    let deceleration = parameters.ball_deceleration.max(f32::EPSILON);
+    let initial_velocity = (2.0 * deceleration * travel_distance).sqrt();
+    let rollout_duration = initial_velocity / deceleration;
+    let time_step = parameters
+        .simulation_time_step
+        .as_secs_f32()
+        .max(f32::EPSILON);
+
+    let mut position = start;
+    let mut time = 0.0;
+    while time < rollout_duration {
+        time = (time + time_step).min(rollout_duration);
+        let traveled_distance = initial_velocity * time - deceleration * time.powi(2) / 2.0;
+        position = start + direction * traveled_distance;
+
+        if opponents
+            .iter()
+            .any(|opponent| opponent.can_reach(position, time, parameters))
+        {
+            return (Outcome::Intercepted, position);
+        }
+        if position.x() > field_dimensions.length / 2.0 {
+            let is_between_goal_posts =
+                position.y().abs() < field_dimensions.goal_inner_width / 2.0;
+            return if is_between_goal_posts {
+                (Outcome::Goal, position)
+            } else {
+                (Outcome::Other, position)
+            };
+        }
+        if !field_dimensions.is_inside_field(position) {
+            return (Outcome::Other, position);
+        }
+    }
+    (Outcome::Other, position)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use linear_algebra::{point, Pose2};
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use types::{motion_command::KickVariant, support_foot::Side};
+
+    use super::*;
+
+    fn parameters() -> KickEvaluationParameters {
+        KickEvaluationParameters {
+            random_seed: 42,
+            number_of_samples: 100,
+            maximum_number_of_rollouts: 1000,
+            maximum_number_of_opponents: 5,
+            evaluation_interval: Duration::from_millis(100),
+            maximum_reused_target_deviation: 0.1,
+            maximum_reused_strength_deviation: 0.05,
+            simulation_time_step: Duration::from_millis(50),
+            ball_deceleration: 0.5,
+            opponent_reaction_time: Duration::from_millis(500),
+            opponent_walking_speed: 0.3,
+            opponent_interception_radius: 0.2,
+            goal_probability_weight: 1.0,
+            interception_probability_weight: 1.0,
+        }
+    }
+
+    fn noise() -> KickNoiseParameters {
+        KickNoiseParameters {
+            direction_standard_deviation: 0.05,
+            strength_standard_deviation: 0.1,
+        }
+    }
+
+    fn decision_towards(target: Point2<Ground>) -> KickDecision {
+        KickDecision {
+            target,
+            variant: KickVariant::Forward,
+            kicking_side: Side::Left,
+            kick_pose: Pose2::default(),
+            strength: 1.0,
+        }
+    }
+
+    #[test]
+    fn unobstructed_close_shot_scores() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let ball_position = point![field_dimensions.length / 2.0 - 1.0, 0.0];
+        let target = point![field_dimensions.length / 2.0 + 0.1, 0.0];
+        let mut random_state = ChaChaRng::seed_from_u64(42);
+
+        let evaluation = evaluate_kick_decision(
+            decision_towards(target),
+            ball_position,
+            Isometry2::identity(),
+            &[],
+            3.0,
+            &noise(),
+            &field_dimensions,
+            &parameters(),
+            &mut random_state,
+        );
+
+        assert!(evaluation.goal_probability > 0.9);
+        assert_eq!(evaluation.interception_probability, 0.0);
+        assert_eq!(evaluation.sampled_ball_end_positions.len(), 100);
+    }
+
+    #[test]
+    fn opponent_in_the_way_intercepts() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let ball_position = point![field_dimensions.length / 2.0 - 2.0, 0.0];
+        let target = point![field_dimensions.length / 2.0 + 0.1, 0.0];
+        let opponents = [Opponent {
+            position: point![field_dimensions.length / 2.0 - 1.0, 0.0],
+            velocity: Vector2::zeros(),
+        }];
+        let mut random_state = ChaChaRng::seed_from_u64(42);
+
+        let evaluation = evaluate_kick_decision(
+            decision_towards(target),
+            ball_position,
+            Isometry2::identity(),
+            &opponents,
+            3.0,
+            &noise(),
+            &field_dimensions,
+            &parameters(),
+            &mut random_state,
+        );
+
+        assert!(evaluation.interception_probability > 0.9);
+        assert!(evaluation.goal_probability < 0.1);
+        assert!(evaluation.score < 0.0);
+    }
+
+    #[test]
+    fn unobstructed_pass_is_received() {
+        let pass_parameters = PassSelectorParameters {
+            reception_radius: 0.75,
+            reception_probability_weight: 0.5,
+            receiver_score_weight: 0.1,
+            ..Default::default()
+        };
+        let receiver = point![2.0, 0.0];
+        let decision = KickDecision {
+            strength: 2.0 / 3.0,
+            ..decision_towards(point![2.0, 0.0])
+        };
+        let mut random_state = ChaChaRng::seed_from_u64(42);
+
+        let evaluation = evaluate_pass(
+            decision,
+            Point2::origin(),
+            Isometry2::identity(),
+            receiver,
+            1.0,
+            &[],
+            3.0,
+            &noise(),
+            &FieldDimensions::SPL_2025,
+            &parameters(),
+            &pass_parameters,
+            &mut random_state,
+        );
+
+        assert!(evaluation.reception_probability > 0.9);
+        assert_eq!(evaluation.interception_probability, 0.0);
+        assert!(evaluation.score > 0.5);
+    }
+}
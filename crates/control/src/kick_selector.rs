@@ -1,7 +1,12 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    time::{Duration, Instant, SystemTime},
+};
 
 use color_eyre::Result;
 use itertools::iproduct;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
@@ -14,19 +19,37 @@ use linear_algebra::{
 };
 use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
+    cycle_time::CycleTime,
     field_dimensions::{self, FieldDimensions, Half},
     filtered_game_controller_state::FilteredGameControllerState,
     filtered_game_state::FilteredGameState,
-    kick_decision::{DecisionParameters, KickDecision, PlayingSituation},
+    kick_decision::{
+        DecisionParameters, KickDecision, KickDecisionEvaluation, KickEvaluationParameters,
+        PlayingSituation,
+    },
     motion_command::KickVariant,
-    obstacles::Obstacle,
+    obstacles::{Obstacle, ObstacleKind},
     parameters::{InWalkKickInfoParameters, InWalkKicksParameters},
     support_foot::Side,
+    tracked_robots::TrackedRobot,
     world_state::BallState,
 };
 
+use crate::kick_evaluation::{evaluate_kick_decision, Opponent};
+
 #[derive(Deserialize, Serialize)]
-pub struct KickSelector {}
+pub struct KickSelector {
+    last_evaluation: Option<LastEvaluation>,
+}
+
+/// Evaluations are only repeated every `evaluation_interval`, in between the rollouts of the last
+/// evaluation are moved along with the candidates
+#[derive(Deserialize, Serialize)]
+struct LastEvaluation {
+    evaluated_at: SystemTime,
+    ground_to_field: Isometry2<Ground, Field>,
+    evaluations: Vec<KickDecisionEvaluation>,
+}
 
 #[context]
 pub struct CreationContext {}
@@ -35,7 +58,9 @@ pub struct CreationContext {}
 pub struct CycleContext {
     ground_to_field: RequiredInput<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
     ball_state: RequiredInput<Option<BallState>, "ball_state?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
     obstacles: Input<Vec<Obstacle>, "obstacles">,
+    tracked_robots: Input<Vec<TrackedRobot>, "tracked_robots">,
     filtered_game_controller_state:
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
     ground_to_upcoming_support:
@@ -46,6 +71,9 @@ pub struct CycleContext {
     in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
 
     playing_situation: AdditionalOutput<PlayingSituation, "playing_situation">,
+    kick_decision_evaluations:
+        AdditionalOutput<Vec<KickDecisionEvaluation>, "kick_decision_evaluations">,
+    kick_evaluation_duration: AdditionalOutput<Duration, "kick_evaluation_duration">,
 }
 
 #[context]
@@ -53,11 +81,14 @@ pub struct CycleContext {
 pub struct MainOutputs {
     pub kick_decisions: MainOutput<Option<Vec<KickDecision>>>,
     pub instant_kick_decisions: MainOutput<Option<Vec<KickDecision>>>,
+    pub best_kick_evaluation: MainOutput<Option<KickDecisionEvaluation>>,
 }
 
 impl KickSelector {
     pub fn new(_context: CreationContext) -> Result<Self> {
-        Ok(Self {})
+        Ok(Self {
+            last_evaluation: None,
+        })
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
@@ -99,8 +130,13 @@ impl KickSelector {
         };
 
         let targets = collect_kick_targets(&context, playing_situation);
+        let obstacles = merge_predicted_opponents(
+            context.obstacles,
+            context.tracked_robots,
+            context.decision_parameters,
+        );
 
-        let mut kick_decisions = kick_decisions_from_targets(
+        let kick_decisions = kick_decisions_from_targets(
             &targets,
             &variants,
             &sides,
@@ -108,19 +144,27 @@ impl KickSelector {
             ball_position,
             context.in_walk_kicks,
         );
-
+        let evaluation_start = Instant::now();
+        let mut kick_decisions = self.evaluate_kick_decisions(kick_decisions, &context);
+        context
+            .kick_evaluation_duration
+            .fill_if_subscribed(|| evaluation_start.elapsed());
         kick_decisions.sort_by(|left, right| {
             compare_decisions(
                 left,
                 right,
                 ball_position,
                 *context.ground_to_upcoming_support,
-                context.obstacles,
+                &obstacles,
                 context.decision_parameters,
             )
         });
+        context
+            .kick_decision_evaluations
+            .fill_if_subscribed(|| kick_decisions.clone());
+        let best_kick_evaluation = kick_decisions.first().cloned();
 
-        let mut instant_kick_decisions = generate_decisions_for_instant_kicks(
+        let instant_kick_decisions = generate_decisions_for_instant_kicks(
             &variants,
             &sides,
             context.in_walk_kicks,
@@ -130,24 +174,169 @@ impl KickSelector {
             context.filtered_game_controller_state,
             context.decision_parameters,
         );
+        // Instant kicks are only taken if the ball is already in front of a foot, they are not
+        // worth the rollouts
+        let mut instant_kick_decisions = instant_kick_decisions
+            .into_iter()
+            .map(unevaluated)
+            .collect::<Vec<_>>();
         instant_kick_decisions.sort_by(|left, right| {
             compare_decisions(
                 left,
                 right,
                 ball_position,
                 *context.ground_to_upcoming_support,
-                context.obstacles,
+                &obstacles,
                 context.decision_parameters,
             )
         });
 
+        let kick_decisions = kick_decisions
+            .into_iter()
+            .map(|evaluation| evaluation.decision)
+            .collect();
+        let instant_kick_decisions = instant_kick_decisions
+            .into_iter()
+            .map(|evaluation| evaluation.decision)
+            .collect();
+
         Ok(MainOutputs {
             kick_decisions: Some(kick_decisions).into(),
             instant_kick_decisions: Some(instant_kick_decisions).into(),
+            best_kick_evaluation: best_kick_evaluation.into(),
         })
     }
 }
 
+impl KickSelector {
+    fn evaluate_kick_decisions(
+        &mut self,
+        kick_decisions: Vec<KickDecision>,
+        context: &CycleContext,
+    ) -> Vec<KickDecisionEvaluation> {
+        let now = context.cycle_time.start_time;
+        let ground_to_field = *context.ground_to_field;
+        let parameters = &context.decision_parameters.evaluation;
+
+        if let Some(last_evaluation) = &self.last_evaluation {
+            let is_recent = now
+                .duration_since(last_evaluation.evaluated_at)
+                .is_ok_and(|age| age < parameters.evaluation_interval);
+            if is_recent {
+                if let Some(evaluations) =
+                    last_evaluation.reuse_for(&kick_decisions, ground_to_field, parameters)
+                {
+                    return evaluations;
+                }
+            }
+        }
+
+        let ball_position = context.ball_state.ball_in_ground;
+        let mut opponents = context
+            .tracked_robots
+            .iter()
+            .filter(|robot| robot.is_opponent())
+            .collect::<Vec<_>>();
+        opponents.sort_by(|left, right| {
+            distance(left.position(), ball_position)
+                .total_cmp(&distance(right.position(), ball_position))
+        });
+        let opponents = opponents
+            .into_iter()
+            .take(parameters.maximum_number_of_opponents)
+            .map(|robot| Opponent::from_tracked_robot(robot, ground_to_field))
+            .collect::<Vec<_>>();
+
+        let budgeted_parameters = KickEvaluationParameters {
+            number_of_samples: (parameters.maximum_number_of_rollouts
+                / kick_decisions.len().max(1))
+            .min(parameters.number_of_samples),
+            ..parameters.clone()
+        };
+        let evaluations: Vec<_> = kick_decisions
+            .into_iter()
+            .map(|decision| {
+                let kick_info = &context.in_walk_kicks[decision.variant];
+                evaluate_kick_decision(
+                    decision,
+                    ball_position,
+                    ground_to_field,
+                    &opponents,
+                    kick_info.shot_distance,
+                    &kick_info.noise,
+                    context.field_dimensions,
+                    &budgeted_parameters,
+                    &mut ChaChaRng::seed_from_u64(parameters.random_seed),
+                )
+            })
+            .collect();
+
+        self.last_evaluation = Some(LastEvaluation {
+            evaluated_at: now,
+            ground_to_field,
+            evaluations: evaluations.clone(),
+        });
+        evaluations
+    }
+}
+
+impl LastEvaluation {
+    /// Transfers the evaluations to the same candidates at their current targets and kick poses
+    fn reuse_for(
+        &self,
+        kick_decisions: &[KickDecision],
+        ground_to_field: Isometry2<Ground, Field>,
+        parameters: &KickEvaluationParameters,
+    ) -> Option<Vec<KickDecisionEvaluation>> {
+        let last_ground_to_ground = ground_to_field.inverse() * self.ground_to_field;
+        let is_same_candidates = self.evaluations.len() == kick_decisions.len()
+            && self
+                .evaluations
+                .iter()
+                .zip(kick_decisions)
+                .all(|(evaluation, decision)| {
+                    evaluation.decision.variant == decision.variant
+                        && evaluation.decision.kicking_side == decision.kicking_side
+                        && distance(
+                            last_ground_to_ground * evaluation.decision.target,
+                            decision.target,
+                        ) < parameters.maximum_reused_target_deviation
+                        && (evaluation.decision.strength - decision.strength).abs()
+                            < parameters.maximum_reused_strength_deviation
+                });
+        if !is_same_candidates {
+            return None;
+        }
+
+        Some(
+            self.evaluations
+                .iter()
+                .zip(kick_decisions)
+                .map(|(evaluation, &decision)| KickDecisionEvaluation {
+                    decision,
+                    sampled_ball_end_positions: evaluation
+                        .sampled_ball_end_positions
+                        .iter()
+                        .map(|&position| last_ground_to_ground * position)
+                        .collect(),
+                    ..evaluation.clone()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Candidates without rollouts are only ranked by obstacles and the distance to their kick pose
+fn unevaluated(decision: KickDecision) -> KickDecisionEvaluation {
+    KickDecisionEvaluation {
+        decision,
+        goal_probability: 0.0,
+        interception_probability: 0.0,
+        score: 0.0,
+        sampled_ball_end_positions: Vec::new(),
+    }
+}
+
 fn is_ball_in_opponents_corners(
     ball_position: Point2<Field>,
     field_dimensions: &FieldDimensions,
@@ -269,14 +458,61 @@ fn generate_penalty_shot_kick_targets(context: &CycleContext) -> Vec<Point2<Grou
     vec![left_target, right_target]
 }
 
+/// Moves the robot obstacle of each tracked opponent to its predicted position, opponents without
+/// a matching robot obstacle are added
+fn merge_predicted_opponents(
+    obstacles: &[Obstacle],
+    tracked_robots: &[TrackedRobot],
+    parameters: &DecisionParameters,
+) -> Vec<Obstacle> {
+    let mut obstacles = obstacles.to_vec();
+    let mut is_merged = vec![false; obstacles.len()];
+    for robot in tracked_robots.iter().filter(|robot| robot.is_opponent()) {
+        let predicted_position = robot.predicted_position(parameters.opponent_prediction_horizon);
+        let matching_obstacle = obstacles
+            .iter()
+            .zip(&is_merged)
+            .enumerate()
+            .filter(|(_, (obstacle, is_merged))| {
+                obstacle.kind == ObstacleKind::Robot && !**is_merged
+            })
+            .map(|(index, (obstacle, _))| (index, distance(obstacle.position, robot.position())))
+            .filter(|(_, distance)| *distance < parameters.predicted_opponent_matching_distance)
+            .min_by(|(_, left), (_, right)| left.total_cmp(right))
+            .map(|(index, _)| index);
+
+        match matching_obstacle {
+            Some(index) => {
+                let obstacle = &mut obstacles[index];
+                obstacle.position = predicted_position;
+                obstacle.radius_at_foot_height = obstacle
+                    .radius_at_foot_height
+                    .max(parameters.predicted_opponent_radius);
+                obstacle.radius_at_hip_height = obstacle
+                    .radius_at_hip_height
+                    .max(parameters.predicted_opponent_radius);
+                is_merged[index] = true;
+            }
+            None => obstacles.push(Obstacle::robot(
+                predicted_position,
+                parameters.predicted_opponent_radius,
+                parameters.predicted_opponent_radius,
+            )),
+        }
+    }
+    obstacles
+}
+
 fn compare_decisions(
-    left: &KickDecision,
-    right: &KickDecision,
+    left_evaluation: &KickDecisionEvaluation,
+    right_evaluation: &KickDecisionEvaluation,
     ball_position: Point2<Ground>,
     ground_to_upcoming_support: Isometry2<Ground, UpcomingSupport>,
     obstacles: &[Obstacle],
     parameters: &DecisionParameters,
 ) -> Ordering {
+    let left = &left_evaluation.decision;
+    let right = &right_evaluation.decision;
     let left_in_obstacle = is_inside_any_obstacle(left.kick_pose, obstacles, parameters);
     let right_in_obstacle = is_inside_any_obstacle(right.kick_pose, obstacles, parameters);
     let left_is_intersecting_with_obstacle =
@@ -302,10 +538,25 @@ fn compare_decisions(
         (true, false, _, _) => Ordering::Greater,
         (_, _, false, true) => Ordering::Less,
         (_, _, true, false) => Ordering::Greater,
-        _ => distance_to_left.total_cmp(&distance_to_right),
+        _ => decision_cost(left_evaluation, distance_to_left, parameters).total_cmp(
+            &decision_cost(right_evaluation, distance_to_right, parameters),
+        ),
     }
 }
 
+/// Combines the walking distance to the kick pose and the evaluation score in units of the score
+fn decision_cost(
+    evaluation: &KickDecisionEvaluation,
+    distance_to_kick_pose: f32,
+    parameters: &DecisionParameters,
+) -> f32 {
+    let normalized_distance = distance_to_kick_pose
+        / parameters
+            .kick_pose_distance_normalization
+            .max(f32::EPSILON);
+    normalized_distance - evaluation.score
+}
+
 fn is_intersecting_with_an_obstacle(
     obstacles: &[Obstacle],
     ball_position: Point2<Ground>,
@@ -503,7 +754,7 @@ fn mirror_kick_pose<Frame>(kick_pose: Pose2<Frame>) -> Pose2<Frame> {
     )
 }
 
-fn compute_kick_pose(
+pub fn compute_kick_pose(
     ball_position: Point2<Ground>,
     target_to_kick_to: Point2<Ground>,
     kick_info: &InWalkKickInfoParameters,
@@ -535,3 +786,117 @@ pub fn is_inside_kick_off_target_region(position: Point2<Field>, field_width: f3
         && position.x().abs() < field_width / 2.0
         && position.x().abs() <= position.y().abs()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use nalgebra::Matrix4;
+    use types::{
+        multivariate_normal_distribution::MultivariateNormalDistribution,
+        tracked_robots::RobotAffiliation,
+    };
+
+    use super::*;
+
+    fn opponent(position: Point2<Ground>, velocity: Vector2<Ground>) -> TrackedRobot {
+        TrackedRobot {
+            id: 0,
+            state: MultivariateNormalDistribution {
+                mean: nalgebra::vector![position.x(), position.y(), velocity.x(), velocity.y()],
+                covariance: Matrix4::identity(),
+            },
+            affiliation: RobotAffiliation::Opponent,
+            measurement_count: 10,
+            last_update: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn tracked_opponents_replace_their_obstacle() {
+        let parameters = DecisionParameters {
+            opponent_prediction_horizon: Duration::from_secs(1),
+            predicted_opponent_radius: 0.3,
+            predicted_opponent_matching_distance: 0.5,
+            ..Default::default()
+        };
+        let obstacles = [
+            Obstacle::robot(point![1.1, 0.0], 0.2, 0.2),
+            Obstacle::goal_post(point![1.0, 0.0], 0.05),
+        ];
+        let tracked_robots = [
+            opponent(point![1.0, 0.0], vector![0.5, 0.0]),
+            opponent(point![3.0, 1.0], Vector2::zeros()),
+        ];
+
+        let merged = merge_predicted_opponents(&obstacles, &tracked_robots, &parameters);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].position, point![1.5, 0.0]);
+        assert_eq!(merged[0].radius_at_foot_height, 0.3);
+        assert_eq!(merged[1].kind, ObstacleKind::GoalPost);
+        assert_eq!(merged[2].position, point![3.0, 1.0]);
+    }
+
+    #[test]
+    fn last_evaluation_is_only_reused_for_the_same_candidates() {
+        let decision = |kicking_side, target| KickDecision {
+            target,
+            variant: KickVariant::Forward,
+            kicking_side,
+            kick_pose: Pose2::default(),
+            strength: 1.0,
+        };
+        let last_evaluation = LastEvaluation {
+            evaluated_at: SystemTime::UNIX_EPOCH,
+            ground_to_field: Isometry2::identity(),
+            evaluations: vec![KickDecisionEvaluation {
+                sampled_ball_end_positions: vec![point![2.0, 0.0]],
+                score: 0.5,
+                ..unevaluated(decision(Side::Left, point![2.0, 0.0]))
+            }],
+        };
+        let ground_to_field = Isometry2::from_parts(vector![1.0, 0.0], 0.0);
+        let parameters = KickEvaluationParameters {
+            maximum_reused_target_deviation: 0.1,
+            maximum_reused_strength_deviation: 0.05,
+            ..Default::default()
+        };
+
+        let reused = last_evaluation
+            .reuse_for(
+                &[decision(Side::Left, point![1.05, 0.0])],
+                ground_to_field,
+                &parameters,
+            )
+            .unwrap();
+
+        assert_eq!(reused[0].score, 0.5);
+        assert_eq!(reused[0].decision.target, point![1.05, 0.0]);
+        assert_eq!(reused[0].sampled_ball_end_positions, vec![point![1.0, 0.0]]);
+        assert!(last_evaluation
+            .reuse_for(
+                &[decision(Side::Right, point![1.0, 0.0])],
+                ground_to_field,
+                &parameters
+            )
+            .is_none());
+        assert!(last_evaluation
+            .reuse_for(
+                &[decision(Side::Left, point![1.0, 1.0])],
+                ground_to_field,
+                &parameters
+            )
+            .is_none());
+        assert!(last_evaluation
+            .reuse_for(
+                &[KickDecision {
+                    strength: 0.5,
+                    ..decision(Side::Left, point![1.0, 0.0])
+                }],
+                ground_to_field,
+                &parameters
+            )
+            .is_none());
+    }
+}
@@ -16,6 +16,7 @@ pub mod game_controller_filter;
 pub mod game_controller_state_filter;
 pub mod ground_contact_detector;
 pub mod ground_provider;
+pub mod kick_evaluation;
 pub mod kick_selector;
 pub mod kinematics_provider;
 pub mod led_status;
@@ -25,11 +26,13 @@ pub mod obstacle_filter;
 pub mod obstacle_receiver;
 pub mod odometry;
 pub mod orientation_filter;
+pub mod pass_selector;
 pub mod path_planner;
 pub mod penalty_shot_direction_estimation;
 pub mod primary_state_filter;
 pub mod ready_signal_detection_filter;
 pub mod referee_position_provider;
+pub mod robot_tracker;
 pub mod role_assignment;
 pub mod rule_obstacle_composer;
 pub mod sacrificial_lamb;
@@ -19,6 +19,8 @@ pub struct CreationContext {}
 pub struct CycleContext {
     sensor_data: Input<SensorData, "sensor_data">,
     motion_command: Input<MotionCommand, "motion_command">,
+
+    injected_positions: Parameter<Option<Joints<f32>>, "animation.injected_positions?">,
 }
 
 #[context]
@@ -40,7 +42,10 @@ impl Animation {
             stiffnesses: Joints::fill(0.0),
         };
         let animation_stiff_command = MotorCommands {
-            positions: self.saved_joint_values,
+            positions: context
+                .injected_positions
+                .copied()
+                .unwrap_or(self.saved_joint_values),
             stiffnesses: Joints::fill(1.0),
         };
         let output = match context.motion_command {
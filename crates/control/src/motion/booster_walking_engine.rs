@@ -0,0 +1,281 @@
+use booster::{CommandType, LowCommand, LowState, MotorCommand, MotorState};
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use coordinate_systems::{Field, Ground, Robot, Walk};
+use filtering::low_pass_filter::LowPassFilter;
+use framework::AdditionalOutput;
+use hardware::LowCommandInterface;
+use linear_algebra::{vector, Isometry3, Orientation3, Vector3};
+use serde::{Deserialize, Serialize};
+use types::{
+    cycle_time::CycleTime,
+    joints::body::{BodyJoints, LowerBodyJoints},
+    motor_commands::MotorCommands,
+    obstacle_avoiding_arms::ArmCommands,
+    support_foot::Side,
+    walk_command::WalkCommand,
+    walk_volume_extents::WalkVolumeExtents,
+};
+use walking_engine::{
+    backend::{
+        booster::{
+            serial_motor_legs, Booster, BoosterParameters, LEFT_LEG_MOTORS, NUMBER_OF_MOTORS,
+            RIGHT_LEG_MOTORS,
+        },
+        Backend,
+    },
+    kick_steps::KickSteps,
+    parameters::Parameters,
+    Context, Engine,
+};
+
+/// Drives the Booster with the walking engine, replacing the `command_sender`
+#[derive(Deserialize, Serialize)]
+pub struct BoosterWalkingEngine {
+    engine: Engine,
+    filtered_gyro: LowPassFilter<nalgebra::Vector3<f32>>,
+    linear_acceleration_filter: LowPassFilter<Vector3<Robot>>,
+    consecutive_cycles_zero_moment_point_outside_support_polygon: i32,
+    last_actuated_joints: Option<BodyJoints>,
+}
+
+#[context]
+pub struct CreationContext {
+    parameters: Parameter<Parameters, "walking_engine">,
+    linear_acceleration_low_pass_factor:
+        Parameter<f32, "zero_moment_point.linear_acceleration_low_pass_factor">,
+}
+
+#[context]
+pub struct CycleContext {
+    parameters: Parameter<Parameters, "walking_engine">,
+    booster: Parameter<BoosterParameters, "booster_walking_engine">,
+    walk_volume_extents: Parameter<WalkVolumeExtents, "step_planner.walk_volume_extents">,
+    kick_steps: Parameter<KickSteps, "kick_steps">,
+    gravity_acceleration: Parameter<f32, "physical_constants.gravity_acceleration">,
+
+    cycle_time: Input<CycleTime, "cycle_time">,
+    low_state: Input<LowState, "low_state">,
+    walk_command: Input<WalkCommand, "walk_command">,
+
+    debug_output: AdditionalOutput<Engine, "walking.engine">,
+    robot_to_walk: AdditionalOutput<Isometry3<Robot, Walk>, "walking.robot_to_walk">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl BoosterWalkingEngine {
+    pub fn new(context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            engine: Engine::default(),
+            filtered_gyro: LowPassFilter::with_smoothing_factor(
+                nalgebra::Vector3::zeros(),
+                context.parameters.gyro_balancing.low_pass_factor,
+            ),
+            linear_acceleration_filter: LowPassFilter::with_smoothing_factor(
+                Vector3::zeros(),
+                *context.linear_acceleration_low_pass_factor,
+            ),
+            consecutive_cycles_zero_moment_point_outside_support_polygon: 0,
+            last_actuated_joints: None,
+        })
+    }
+
+    pub fn cycle(
+        &mut self,
+        mut context: CycleContext<impl LowCommandInterface>,
+    ) -> Result<MainOutputs> {
+        let motor_states = &context.low_state.motor_state_serial;
+        if motor_states.len() < NUMBER_OF_MOTORS {
+            return Ok(MainOutputs {});
+        }
+
+        let imu = &context.low_state.imu_state;
+        self.filtered_gyro.update(imu.angular_velocity.inner);
+        self.linear_acceleration_filter
+            .update(imu.linear_acceleration);
+        let orientation =
+            Orientation3::from_euler_angles(imu.roll_pitch_yaw.x(), imu.roll_pitch_yaw.y(), 0.0);
+
+        let measured_joints = body_joints(motor_states, |motor| motor.position);
+        let last_actuated_joints = self.last_actuated_joints.unwrap_or(measured_joints);
+        let backend = Booster {
+            parameters: context.booster,
+            leg_torques: body_joints(motor_states, |motor| motor.torque).into(),
+        };
+
+        let robot_to_walk = Isometry3::from_parts(
+            vector![
+                context.parameters.base.torso_offset,
+                0.0,
+                context.parameters.base.walk_height,
+            ],
+            Orientation3::new(Vector3::y_axis() * context.parameters.base.torso_tilt_base),
+        );
+
+        let measured_legs: LowerBodyJoints = measured_joints.into();
+        let center_of_mass = backend.center_of_mass(&measured_legs);
+        let robot_to_ground = robot_to_ground(&backend, &measured_legs, &orientation);
+        let zero_moment_point = backend.zero_moment_point(
+            center_of_mass,
+            self.linear_acceleration_filter.state(),
+            &robot_to_ground,
+            *context.gravity_acceleration,
+        );
+        if backend.is_inside_support_polygon(&measured_legs, &robot_to_ground, zero_moment_point) {
+            self.consecutive_cycles_zero_moment_point_outside_support_polygon = 0;
+        } else {
+            self.consecutive_cycles_zero_moment_point_outside_support_polygon += 1;
+        }
+
+        let walking_context = Context {
+            parameters: context.parameters,
+            walk_volume_extents: context.walk_volume_extents,
+            kick_steps: context.kick_steps,
+            cycle_time: context.cycle_time,
+            center_of_mass: &center_of_mass,
+            gravity_acceleration: *context.gravity_acceleration,
+            zero_moment_point: &zero_moment_point,
+            consecutive_cycles_zero_moment_point_outside_support_polygon: &self
+                .consecutive_cycles_zero_moment_point_outside_support_polygon,
+            backend: &backend,
+            robot_orientation: &orientation,
+            robot_to_ground: Some(&robot_to_ground),
+            gyro: self.filtered_gyro.state(),
+            last_actuated_joints,
+            measured_joints,
+            robot_to_walk,
+            obstacle_avoiding_arms: &ArmCommands::default(),
+        };
+
+        match *context.walk_command {
+            WalkCommand::Stand => self.engine.stand(&walking_context),
+            WalkCommand::Walk { step } => self.engine.walk(&walking_context, step),
+            WalkCommand::Kick {
+                variant,
+                side,
+                strength,
+            } => self.engine.kick(&walking_context, variant, side, strength),
+        };
+        self.engine.tick(&walking_context);
+        let motor_commands = self.engine.compute_commands(&walking_context);
+        self.last_actuated_joints = Some(motor_commands.positions);
+
+        context
+            .hardware_interface
+            .write_low_command(low_command(context.booster, motor_states, &motor_commands))
+            .wrap_err("failed to write to actuators")?;
+
+        context
+            .debug_output
+            .fill_if_subscribed(|| self.engine.clone());
+        context.robot_to_walk.fill_if_subscribed(|| robot_to_walk);
+
+        Ok(MainOutputs {})
+    }
+}
+
+/// Ground below the soles, leveled by the IMU and at the height of the more loaded sole
+fn robot_to_ground(
+    backend: &Booster,
+    legs: &LowerBodyJoints,
+    orientation: &Orientation3<Field>,
+) -> Isometry3<Robot, Ground> {
+    let left_sole = backend.sole_to_robot(Side::Left, &legs.left_leg).position();
+    let right_sole = backend
+        .sole_to_robot(Side::Right, &legs.right_leg)
+        .position();
+    let support_sole = if backend.sole_pressure(Side::Left) >= backend.sole_pressure(Side::Right) {
+        left_sole
+    } else {
+        right_sole
+    };
+
+    let (roll, pitch, _) = orientation.inner.euler_angles();
+    let imu_orientation = Orientation3::<Robot>::from_euler_angles(roll, pitch, 0.0).mirror();
+    let between_soles = left_sole.coords() + (right_sole - left_sole) / 2.0;
+    let ground_origin = vector![between_soles.x(), between_soles.y(), support_sole.z()];
+
+    let ground_to_robot: Isometry3<Ground, Robot> =
+        Isometry3::from_parts(ground_origin, imu_orientation);
+    ground_to_robot.inverse()
+}
+
+/// Reads the legs from the motors, the arms are not controlled by the walking engine
+fn body_joints(motor_states: &[MotorState], value: impl Fn(&MotorState) -> f32) -> BodyJoints {
+    let legs = serial_motor_legs(motor_states, value);
+    BodyJoints {
+        left_leg: legs.left_leg,
+        right_leg: legs.right_leg,
+        ..Default::default()
+    }
+}
+
+/// Commands the legs and holds the head and arms at their configured positions
+fn low_command(
+    parameters: &BoosterParameters,
+    motor_states: &[MotorState],
+    motor_commands: &MotorCommands<BodyJoints>,
+) -> LowCommand {
+    let mut commands: Vec<_> = motor_states
+        .iter()
+        .take(NUMBER_OF_MOTORS)
+        .enumerate()
+        .map(|(index, motor)| MotorCommand {
+            position: parameters
+                .upper_body_positions
+                .get(index)
+                .copied()
+                .unwrap_or(motor.position),
+            kp: parameters.maximum_proportional_gain,
+            kd: parameters.derivative_gain,
+            weight: 1.0,
+            ..Default::default()
+        })
+        .collect();
+
+    for (motors, positions, stiffnesses) in [
+        (
+            LEFT_LEG_MOTORS,
+            motor_commands.positions.left_leg,
+            motor_commands.stiffnesses.left_leg,
+        ),
+        (
+            RIGHT_LEG_MOTORS,
+            motor_commands.positions.right_leg,
+            motor_commands.stiffnesses.right_leg,
+        ),
+    ] {
+        let positions = [
+            positions.hip_pitch,
+            positions.hip_roll,
+            positions.hip_yaw_pitch,
+            positions.knee_pitch,
+            positions.ankle_pitch,
+            positions.ankle_roll,
+        ];
+        let stiffnesses = [
+            stiffnesses.hip_pitch,
+            stiffnesses.hip_roll,
+            stiffnesses.hip_yaw_pitch,
+            stiffnesses.knee_pitch,
+            stiffnesses.ankle_pitch,
+            stiffnesses.ankle_roll,
+        ];
+        for ((command, position), stiffness) in
+            commands[motors].iter_mut().zip(positions).zip(stiffnesses)
+        {
+            command.position = position;
+            command.kp = stiffness * parameters.maximum_proportional_gain;
+        }
+    }
+
+    LowCommand {
+        command_type: CommandType::Serial,
+        motor_commands: commands,
+    }
+}
@@ -4,7 +4,10 @@ use filtering::low_pass_filter::LowPassFilter;
 use framework::MainOutput;
 use nalgebra::Vector3;
 use serde::{Deserialize, Serialize};
-use types::{condition_input::ConditionInput, fall_state::FallState, sensor_data::SensorData};
+use types::{
+    condition_input::ConditionInput, fall_state::FallState, sensor_data::SensorData,
+    sole_pressure::SolePressure,
+};
 
 #[derive(Default, Deserialize, Serialize)]
 pub struct ConditionInputProvider {
@@ -22,6 +25,7 @@ pub struct CycleContext {
     sensor_data: Input<SensorData, "sensor_data">,
     fall_state: Input<FallState, "fall_state">,
     has_ground_contact: Input<bool, "has_ground_contact">,
+    sole_pressure: Input<SolePressure, "sole_pressure">,
 }
 
 #[context]
@@ -53,6 +57,13 @@ impl ConditionInputProvider {
                 filtered_angular_velocity: self.angular_velocity_filter.state(),
                 fall_state: *context.fall_state,
                 ground_contact: *context.has_ground_contact,
+                joint_positions: context.sensor_data.positions,
+                torso_roll_pitch: context
+                    .sensor_data
+                    .inertial_measurement_unit
+                    .roll_pitch
+                    .inner,
+                sole_pressure: context.sole_pressure.clone(),
             }
             .into(),
         })
@@ -0,0 +1,366 @@
+use std::{
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use booster::{CommandType, LowCommand, LowState, MotorCommand};
+use color_eyre::{
+    eyre::{bail, eyre, ContextCompat, WrapErr},
+    Result,
+};
+use context_attribute::context;
+use framework::{deserialize_not_implemented, AdditionalOutput, MainOutput};
+use hardware::{LowCommandInterface, PathsInterface};
+use nalgebra::{vector, UnitQuaternion};
+use openvino::{
+    CompiledModel, Core, DeviceType, ElementType, InferenceError::GeneralError, Tensor,
+};
+use serde::{Deserialize, Serialize};
+use types::{
+    cycle_time::CycleTime, parameters::LocomotionPolicyParameters, step::Step,
+    walk_command::WalkCommand,
+};
+
+/// Angular velocity, projected gravity, command, and sine and cosine of the gait phase
+const NUMBER_OF_BASE_OBSERVATIONS: usize = 11;
+
+/// Walks with a reinforcement learned policy instead of the walking engine.
+///
+/// The observation consists of the scaled angular velocity, the gravity in the robot frame, the
+/// scaled walk command, sine and cosine of the gait phase, followed by the scaled joint positions
+/// relative to the defaults, the scaled joint velocities and the previous actions of all policy
+/// motors. Actions are clipped, scaled and added to the default joint positions.
+#[derive(Deserialize, Serialize)]
+pub struct LocomotionPolicy {
+    #[serde(skip, default = "deserialize_not_implemented")]
+    network: CompiledModel,
+    last_actions: Vec<f32>,
+    gait_phase: f32,
+}
+
+#[context]
+pub struct CreationContext {
+    hardware_interface: HardwareInterface,
+    parameters: Parameter<LocomotionPolicyParameters, "locomotion_policy">,
+}
+
+#[context]
+pub struct CycleContext {
+    hardware_interface: HardwareInterface,
+    parameters: Parameter<LocomotionPolicyParameters, "locomotion_policy">,
+
+    cycle_time: Input<CycleTime, "cycle_time">,
+    low_state: Input<LowState, "low_state">,
+    walk_command: Input<WalkCommand, "walk_command">,
+
+    observation: AdditionalOutput<Vec<f32>, "locomotion_policy.observation">,
+    inference_duration: AdditionalOutput<Duration, "locomotion_policy.inference_duration">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub locomotion_policy_actions: MainOutput<Vec<f32>>,
+}
+
+impl LocomotionPolicy {
+    pub fn new(context: CreationContext<impl PathsInterface>) -> Result<Self> {
+        let parameters = context.parameters;
+        let number_of_motors = parameters.motor_indices.len();
+        if parameters.default_joint_positions.len() != number_of_motors
+            || parameters.proportional_gains.len() != number_of_motors
+            || parameters.derivative_gains.len() != number_of_motors
+        {
+            bail!(
+                "expected default joint positions and gains for each of the {number_of_motors} \
+                 policy motors"
+            );
+        }
+
+        let model_path = context
+            .hardware_interface
+            .get_paths()
+            .neural_networks
+            .join(&parameters.model);
+        let mut core = Core::new()?;
+        let model = core
+            .read_model_from_file(
+                model_path
+                    .to_str()
+                    .wrap_err("failed to get locomotion policy path")?,
+                "",
+            )
+            .map_err(|error| match error {
+                GeneralError => eyre!("{error}: possible incomplete OpenVino installation"),
+                _ => eyre!("{error}: failed to read {}", model_path.display()),
+            })?;
+
+        let number_of_inputs = model.get_inputs_len()?;
+        let number_of_outputs = model.get_outputs_len()?;
+        if number_of_inputs != 1 || number_of_outputs != 1 {
+            bail!("expected exactly one input and one output of the locomotion policy");
+        }
+        let network = core.compile_model(&model, DeviceType::CPU)?;
+
+        let input_shape = network.get_input()?.get_shape()?;
+        let input_dimensions = input_shape.get_dimensions();
+        let expected_observation_size = NUMBER_OF_BASE_OBSERVATIONS + 3 * number_of_motors;
+        // The observation is copied into the input tensor as is, so all other dimensions have to
+        // be single batches
+        let is_single_observation =
+            input_dimensions
+                .split_last()
+                .is_some_and(|(&observation_size, batch_dimensions)| {
+                    observation_size == expected_observation_size as i64
+                        && batch_dimensions.iter().all(|&dimension| dimension == 1)
+                });
+        if !is_single_observation {
+            bail!(
+                "expected an input of shape [1, {expected_observation_size}], the policy takes {input_dimensions:?}"
+            );
+        }
+
+        Ok(Self {
+            network,
+            last_actions: vec![0.0; number_of_motors],
+            gait_phase: 0.0,
+        })
+    }
+
+    pub fn cycle(
+        &mut self,
+        mut context: CycleContext<impl LowCommandInterface>,
+    ) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let low_state = context.low_state;
+        if parameters
+            .motor_indices
+            .iter()
+            .any(|&index| index >= low_state.motor_state_serial.len())
+        {
+            return Ok(MainOutputs::default());
+        }
+
+        let command = match *context.walk_command {
+            WalkCommand::Walk { step } => step,
+            WalkCommand::Stand | WalkCommand::Kick { .. } => Step::ZERO,
+        };
+        let gait_period = parameters.gait_period.as_secs_f32();
+        if gait_period > 0.0 {
+            self.gait_phase = (self.gait_phase
+                + context.cycle_time.last_cycle_duration.as_secs_f32() / gait_period)
+                .fract();
+        }
+
+        let observation = observation(
+            parameters,
+            low_state,
+            command,
+            self.gait_phase,
+            &self.last_actions,
+        );
+
+        let inference_start = Instant::now();
+        let mut tensor = Tensor::new(ElementType::F32, &self.network.get_input()?.get_shape()?)?;
+        tensor.get_data_mut::<f32>()?.copy_from_slice(&observation);
+        let mut infer_request = self.network.create_infer_request()?;
+        infer_request.set_input_tensor(&tensor)?;
+        infer_request.infer()?;
+        let prediction = infer_request.get_output_tensor_by_index(0)?;
+        let actions: Vec<_> = prediction
+            .get_data::<f32>()?
+            .iter()
+            .map(|action| action.clamp(-parameters.action_clip, parameters.action_clip))
+            .collect();
+        context
+            .inference_duration
+            .fill_if_subscribed(|| inference_start.elapsed());
+
+        if actions.len() != parameters.motor_indices.len() {
+            bail!(
+                "expected {} actions, the policy returned {}",
+                parameters.motor_indices.len(),
+                actions.len()
+            );
+        }
+
+        context
+            .hardware_interface
+            .write_low_command(low_command(parameters, low_state, &actions))
+            .wrap_err("failed to write to actuators")?;
+
+        context.observation.fill_if_subscribed(|| observation);
+        self.last_actions.clone_from(&actions);
+
+        Ok(MainOutputs {
+            locomotion_policy_actions: actions.into(),
+        })
+    }
+}
+
+fn observation(
+    parameters: &LocomotionPolicyParameters,
+    low_state: &LowState,
+    command: Step,
+    gait_phase: f32,
+    last_actions: &[f32],
+) -> Vec<f32> {
+    let imu = &low_state.imu_state;
+    let angular_velocity = imu.angular_velocity.inner * parameters.angular_velocity_scale;
+    let robot_to_world =
+        UnitQuaternion::from_euler_angles(imu.roll_pitch_yaw.x(), imu.roll_pitch_yaw.y(), 0.0);
+    let projected_gravity = robot_to_world.inverse() * vector![0.0, 0.0, -1.0];
+    let command = [
+        command.forward * parameters.command_scale.forward,
+        command.left * parameters.command_scale.left,
+        command.turn * parameters.command_scale.turn,
+    ];
+    let gait_angle = gait_phase * TAU;
+
+    let motors: Vec<_> = parameters
+        .motor_indices
+        .iter()
+        .map(|&index| low_state.motor_state_serial[index])
+        .collect();
+    let joint_positions = motors
+        .iter()
+        .zip(&parameters.default_joint_positions)
+        .map(|(motor, default)| (motor.position - default) * parameters.joint_position_scale);
+    let joint_velocities = motors
+        .iter()
+        .map(|motor| motor.velocity * parameters.joint_velocity_scale);
+
+    angular_velocity
+        .iter()
+        .chain(projected_gravity.iter())
+        .chain(command.iter())
+        .copied()
+        .chain([gait_angle.sin(), gait_angle.cos()])
+        .chain(joint_positions)
+        .chain(joint_velocities)
+        .chain(last_actions.iter().copied())
+        .collect()
+}
+
+/// Commands the policy motors, all other motors stay with the internal controller
+fn low_command(
+    parameters: &LocomotionPolicyParameters,
+    low_state: &LowState,
+    actions: &[f32],
+) -> LowCommand {
+    let mut motor_commands = vec![MotorCommand::default(); low_state.motor_state_serial.len()];
+    for (policy_index, (&motor_index, action)) in
+        parameters.motor_indices.iter().zip(actions).enumerate()
+    {
+        motor_commands[motor_index] = MotorCommand {
+            position: parameters.default_joint_positions[policy_index]
+                + action * parameters.action_scale,
+            kp: parameters.proportional_gains[policy_index],
+            kd: parameters.derivative_gains[policy_index],
+            weight: 1.0,
+            ..Default::default()
+        };
+    }
+
+    LowCommand {
+        command_type: CommandType::Serial,
+        motor_commands,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use booster::MotorState;
+    use linear_algebra::vector;
+
+    use super::*;
+
+    fn parameters() -> LocomotionPolicyParameters {
+        LocomotionPolicyParameters {
+            motor_indices: vec![2, 0],
+            default_joint_positions: vec![0.5, -0.5],
+            proportional_gains: vec![40.0, 20.0],
+            derivative_gains: vec![1.0, 0.5],
+            angular_velocity_scale: 0.25,
+            joint_position_scale: 1.0,
+            joint_velocity_scale: 0.05,
+            command_scale: Step {
+                forward: 2.0,
+                left: 2.0,
+                turn: 0.5,
+            },
+            gait_period: Duration::from_millis(800),
+            action_scale: 0.25,
+            action_clip: 10.0,
+            ..Default::default()
+        }
+    }
+
+    fn low_state() -> LowState {
+        let motor = |position, velocity| MotorState {
+            position,
+            velocity,
+            acceleration: 0.0,
+            torque: 0.0,
+        };
+        let mut low_state = LowState {
+            motor_state_serial: vec![motor(-0.4, 1.0), motor(3.0, 3.0), motor(0.7, -2.0)],
+            ..Default::default()
+        };
+        low_state.imu_state.angular_velocity = vector![0.4, 0.0, -0.8];
+        low_state
+    }
+
+    #[test]
+    fn observation_follows_documented_layout() {
+        let parameters = parameters();
+        let command = Step {
+            forward: 0.1,
+            left: 0.0,
+            turn: 0.2,
+        };
+
+        let observation = observation(&parameters, &low_state(), command, 0.25, &[0.3, -0.3]);
+
+        assert_eq!(observation.len(), NUMBER_OF_BASE_OBSERVATIONS + 3 * 2);
+        let expected = [
+            0.1, 0.0, -0.2, // angular velocity
+            0.0, 0.0, -1.0, // projected gravity
+            0.2, 0.0, 0.1, // command
+            1.0, 0.0, // gait phase
+            0.2, 0.1, // joint positions
+            -0.1, 0.05, // joint velocities
+            0.3, -0.3, // last actions
+        ];
+        for (actual, expected) in observation.iter().zip(expected) {
+            assert_relative_eq!(*actual, expected, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn gravity_is_projected_into_tilted_robot() {
+        let parameters = parameters();
+        let mut low_state = low_state();
+        low_state.imu_state.roll_pitch_yaw = vector![0.0, 0.3, 0.0];
+
+        let observation = observation(&parameters, &low_state, Step::ZERO, 0.0, &[0.0, 0.0]);
+
+        assert_relative_eq!(observation[3], 0.3_f32.sin(), epsilon = 1e-6);
+        assert_relative_eq!(observation[4], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(observation[5], -(0.3_f32.cos()), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn actions_only_command_policy_motors() {
+        let parameters = parameters();
+
+        let command = low_command(&parameters, &low_state(), &[0.4, -0.4]);
+
+        assert_relative_eq!(command.motor_commands[2].position, 0.6);
+        assert_relative_eq!(command.motor_commands[2].kp, 40.0);
+        assert_relative_eq!(command.motor_commands[0].position, -0.6);
+        assert_relative_eq!(command.motor_commands[0].kd, 0.5);
+        assert_relative_eq!(command.motor_commands[1].weight, 0.0);
+    }
+}
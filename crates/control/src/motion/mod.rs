@@ -1,6 +1,7 @@
 pub mod animation;
 pub mod arms_up_squat;
 pub mod arms_up_stand;
+pub mod booster_walking_engine;
 pub mod center_jump;
 pub mod command_sender;
 pub mod condition_input_provider;
@@ -11,6 +12,7 @@ pub mod jump_left;
 pub mod jump_right;
 pub mod keeper_jump_left;
 pub mod keeper_jump_right;
+pub mod locomotion_policy;
 pub mod look_around;
 pub mod look_at;
 pub mod motion_selector;
@@ -21,6 +23,7 @@ pub mod stand_up_back;
 pub mod stand_up_front;
 pub mod stand_up_sitting;
 pub mod step_planner;
+pub mod walk_command_injector;
 pub mod walk_manager;
 pub mod walking_engine;
 pub mod wide_stance;
@@ -1,29 +1,34 @@
-use std::{array, f32::consts::PI};
+use std::f32::consts::PI;
 
 use color_eyre::Result;
-use itertools::Itertools;
-use nalgebra::SVector;
 use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
-use coordinate_systems::{Ground, UpcomingSupport};
+use coordinate_systems::{Field, Ground, UpcomingSupport};
 use filtering::hysteresis::greater_than_with_absolute_hysteresis;
 use framework::{AdditionalOutput, MainOutput};
-use geometry::{direction::Rotate90Degrees, look_at::LookAt};
-use linear_algebra::{vector, Isometry2, Orientation2, Point2, Pose2};
+use geometry::{
+    circle::Circle, direction::Rotate90Degrees, line_segment::LineSegment, look_at::LookAt,
+};
+use linear_algebra::{point, vector, Isometry2, Orientation2, Point2, Pose2};
 use step_planning::{
     geometry::{normalized_step::NormalizedStep, orientation::Orientation, pose::Pose},
     step_plan::StepPlan,
     traits::{EndPoints, Project},
-    NUM_STEPS, NUM_VARIABLES,
+    VARIABLES_PER_STEP,
 };
+use step_planning_solver::StepPlanningSolver;
 use types::{
     motion_command::{MotionCommand, OrientationMode, WalkSpeed},
+    obstacles::Obstacle,
     parameters::{StepPlannerMode, StepPlanningOptimizationParameters},
+    path_obstacles::PathObstacleShape,
     planned_path::{Path, PathSegment},
+    rule_obstacles::RuleObstacle,
     sensor_data::SensorData,
     step::Step,
+    step_planning::StepPlanningSolverStatistics,
     support_foot::Side,
     walk_volume_extents::WalkVolumeExtents,
 };
@@ -31,9 +36,11 @@ use walking_engine::mode::Mode;
 
 #[derive(Deserialize, Serialize)]
 pub struct StepPlanner {
-    last_step_plan: Option<[f64; NUM_VARIABLES]>,
+    last_step_plan: Option<Vec<f64>>,
     last_support_side: Option<Side>,
     leg_joints_hot: bool,
+    #[serde(skip)]
+    solver: StepPlanningSolver,
 }
 
 #[context]
@@ -43,6 +50,9 @@ pub struct CreationContext {}
 pub struct CycleContext {
     motion_command: Input<MotionCommand, "motion_command">,
     sensor_data: Input<SensorData, "sensor_data">,
+    obstacles: Input<Vec<Obstacle>, "obstacles">,
+    rule_obstacles: Input<Vec<RuleObstacle>, "rule_obstacles">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
 
     injected_step: Parameter<Option<Step>, "step_planner.injected_step?">,
     walk_volume_delta_slow: Parameter<WalkVolumeExtents, "step_planner.walk_volume_delta_slow">,
@@ -60,11 +70,11 @@ pub struct CycleContext {
     ground_to_upcoming_support_out:
         AdditionalOutput<Isometry2<Ground, UpcomingSupport>, "ground_to_upcoming_support">,
     direct_step: AdditionalOutput<Step, "direct_step">,
-    step_plan: AdditionalOutput<[Step; step_planning::NUM_STEPS], "step_plan">,
-    step_plan_greedy: AdditionalOutput<[Step; step_planning::NUM_STEPS], "step_plan_greedy">,
-    step_plan_gradient:
-        AdditionalOutput<SVector<f32, { step_planning::NUM_VARIABLES }>, "step_plan_gradient">,
-    step_plan_cost: AdditionalOutput<f32, "step_plan_cost">,
+    step_plan: AdditionalOutput<Vec<Step>, "step_plan">,
+    step_plan_greedy: AdditionalOutput<Vec<Step>, "step_plan_greedy">,
+    step_plan_gradient: AdditionalOutput<Vec<f32>, "step_plan_gradient">,
+    step_planning_solver_statistics:
+        AdditionalOutput<Option<StepPlanningSolverStatistics>, "step_planning_solver_statistics">,
     next_support_side: AdditionalOutput<Side, "next_support_side">,
 }
 
@@ -80,6 +90,7 @@ impl StepPlanner {
             last_step_plan: None,
             last_support_side: None,
             leg_joints_hot: false,
+            solver: StepPlanningSolver::default(),
         })
     }
 
@@ -197,36 +208,47 @@ impl StepPlanner {
         next_support_side: Side,
         distance_to_be_aligned: f32,
     ) -> Result<Step> {
+        let num_variables = context.optimization_parameters.num_steps * VARIABLES_PER_STEP;
         let current_support_side = context.walking_engine_mode.support_side();
 
-        match (current_support_side, self.last_support_side) {
-            (Some(current_side), Some(last_side)) if current_side != last_side => {
-                self.last_step_plan = None;
+        let mut variables = match self.last_step_plan.take() {
+            Some(mut last_step_plan) if context.optimization_parameters.warm_start => {
+                match (current_support_side, self.last_support_side) {
+                    (Some(current_side), Some(last_side)) if current_side != last_side => {
+                        shift_step_plan(&mut last_step_plan);
+                    }
+                    _ => {}
+                }
+                last_step_plan.resize(num_variables, 0.0);
+                last_step_plan
             }
-            _ => {}
-        }
-
-        let variables = if context.optimization_parameters.warm_start {
-            self.last_step_plan.get_or_insert([0.0; NUM_VARIABLES])
-        } else {
-            &mut [0.0; NUM_VARIABLES]
+            _ => vec![0.0; num_variables],
         };
+        self.last_support_side = current_support_side;
+
+        let obstacles = step_planning_obstacles(
+            context.obstacles,
+            context.rule_obstacles,
+            context.ground_to_field.copied(),
+        );
 
-        let (gradient, cost) = step_planning_solver::plan_steps(
+        let (gradient, statistics) = self.solver.plan_steps(
             path,
+            &obstacles,
             orientation_mode,
             target_orientation,
             distance_to_be_aligned,
             upcoming_support_pose_in_ground(context),
             next_support_side,
-            variables,
+            &mut variables,
             context.walk_volume_extents,
             context.optimization_parameters,
         )?;
 
-        let variables_f32: [f32; NUM_VARIABLES] = array::from_fn(|i| variables[i] as f32);
+        let variables_f32: Vec<f32> = variables.iter().map(|&variable| variable as f32).collect();
+        self.last_step_plan = Some(variables);
 
-        let step_plan: [Step; NUM_STEPS] = StepPlan::from(variables_f32.as_slice())
+        let step_plan: Vec<Step> = StepPlan::from(variables_f32.as_slice())
             .steps()
             .scan(next_support_side, |support_side, step| {
                 let result = step.unnormalize(context.walk_volume_extents, *support_side);
@@ -234,14 +256,17 @@ impl StepPlanner {
 
                 Some(result)
             })
-            .collect_array()
-            .expect("failed to collect steps into fixed-size array");
+            .collect();
 
         let next_step = *step_plan.first().expect("step plan was empty");
 
         context.step_plan.fill_if_subscribed(|| step_plan);
-        context.step_plan_gradient.fill_if_subscribed(|| gradient);
-        context.step_plan_cost.fill_if_subscribed(|| cost);
+        context
+            .step_plan_gradient
+            .fill_if_subscribed(|| gradient.iter().copied().collect());
+        context
+            .step_planning_solver_statistics
+            .fill_if_subscribed(|| statistics);
 
         Ok(next_step)
     }
@@ -271,6 +296,49 @@ impl StepPlanner {
     }
 }
 
+/// Drops the step taken since the plan was made, so that the remaining steps start the next plan
+fn shift_step_plan(step_plan: &mut Vec<f64>) {
+    let taken_step = step_plan.len().min(VARIABLES_PER_STEP);
+    step_plan.drain(..taken_step);
+}
+
+/// Obstacles whose clearance is part of the step planning cost, rule obstacles are only
+/// considered when the robot is localized
+fn step_planning_obstacles(
+    obstacles: &[Obstacle],
+    rule_obstacles: &[RuleObstacle],
+    ground_to_field: Option<Isometry2<Ground, Field>>,
+) -> Vec<PathObstacleShape> {
+    let robot_obstacles = obstacles.iter().map(|obstacle| {
+        PathObstacleShape::Circle(Circle::new(
+            obstacle.position,
+            obstacle.radius_at_foot_height,
+        ))
+    });
+
+    let Some(field_to_ground) = ground_to_field.map(|ground_to_field| ground_to_field.inverse())
+    else {
+        return robot_obstacles.collect();
+    };
+
+    let rule_obstacles = rule_obstacles
+        .iter()
+        .flat_map(|rule_obstacle| match rule_obstacle {
+            RuleObstacle::Rectangle(rectangle) => vec![PathObstacleShape::Rectangle([
+                field_to_ground * rectangle.min,
+                field_to_ground * point![rectangle.max.x(), rectangle.min.y()],
+                field_to_ground * rectangle.max,
+                field_to_ground * point![rectangle.min.x(), rectangle.max.y()],
+            ])],
+            RuleObstacle::Circle(circle) => vec![PathObstacleShape::Circle(Circle::new(
+                field_to_ground * circle.center,
+                circle.radius,
+            ))],
+        });
+
+    robot_obstacles.chain(rule_obstacles).collect()
+}
+
 fn clamp_step_size(
     step: Step,
     support_side: Side,
@@ -289,10 +357,10 @@ fn step_plan_greedy(
     next_support_side: Side,
     distance_to_be_aligned: f32,
     walk_volume_extents: &WalkVolumeExtents,
-) -> Result<[Step; NUM_STEPS]> {
+) -> Result<Vec<Step>> {
     let initial_pose = context.ground_to_upcoming_support.inverse().as_pose();
 
-    let steps = (0..NUM_STEPS)
+    let steps = (0..context.optimization_parameters.num_steps)
         .scan(
             (initial_pose, next_support_side),
             |(pose, support_side), _i| {
@@ -373,8 +441,7 @@ fn step_plan_greedy(
                 Some(step)
             },
         )
-        .collect_array()
-        .unwrap();
+        .collect();
 
     Ok(steps)
 }
@@ -513,4 +580,18 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn shifted_step_plan_starts_with_second_step() {
+        let mut step_plan = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+        shift_step_plan(&mut step_plan);
+        assert_eq!(step_plan, [4.0, 5.0, 6.0]);
+
+        shift_step_plan(&mut step_plan);
+        assert!(step_plan.is_empty());
+
+        shift_step_plan(&mut step_plan);
+        assert!(step_plan.is_empty());
+    }
 }
@@ -0,0 +1,36 @@
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use serde::{Deserialize, Serialize};
+use types::walk_command::WalkCommand;
+
+/// Provides the walk command from the parameters on robots without the motion selection, e.g.
+/// to drive the `booster_walking_engine` from twix
+#[derive(Deserialize, Serialize)]
+pub struct WalkCommandInjector {}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    walk_command: Parameter<WalkCommand, "walk_command_injector.walk_command">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub walk_command: MainOutput<WalkCommand>,
+}
+
+impl WalkCommandInjector {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        Ok(MainOutputs {
+            walk_command: (*context.walk_command).into(),
+        })
+    }
+}
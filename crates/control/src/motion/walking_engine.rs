@@ -21,7 +21,10 @@ use types::{
     walk_command::WalkCommand,
     walk_volume_extents::WalkVolumeExtents,
 };
-use walking_engine::{kick_steps::KickSteps, mode::Mode, parameters::Parameters, Context, Engine};
+use walking_engine::{
+    backend::nao::Nao, capture_point::CapturePoint, kick_steps::KickSteps, mode::Mode,
+    parameters::Parameters, Context, Engine,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalkingEngine {
@@ -40,6 +43,7 @@ pub struct CycleContext {
     parameters: Parameter<Parameters, "walking_engine">,
     walk_volume_extents: Parameter<WalkVolumeExtents, "step_planner.walk_volume_extents">,
     kick_steps: Parameter<KickSteps, "kick_steps">,
+    gravity_acceleration: Parameter<f32, "physical_constants.gravity_acceleration">,
 
     motion_safe_exits: CyclerState<MotionSafeExits, "motion_safe_exits">,
     ground_to_upcoming_support:
@@ -57,6 +61,7 @@ pub struct CycleContext {
     consecutive_cycles_zero_moment_point_outside_support_polygon:
         Input<i32, "consecutive_cycles_zero_moment_point_outside_support_polygon">,
     debug_output: AdditionalOutput<Engine, "walking.engine">,
+    capture_point: AdditionalOutput<Option<CapturePoint>, "walking.capture_point">,
     robot_to_walk: AdditionalOutput<Isometry3<Robot, Walk>, "walking.robot_to_walk">,
     walking_engine_mode: CyclerState<Mode, "walking_engine_mode">,
 }
@@ -130,13 +135,17 @@ impl WalkingEngine {
         let orientation =
             Orientation3::from_euler_angles(imu.roll_pitch.x(), imu.roll_pitch.y(), 0.0);
 
+        let backend = Nao {
+            force_sensitive_resistors: cycle_context.sensor_data.force_sensitive_resistors.clone(),
+        };
         let context = Context {
             parameters: cycle_context.parameters,
             walk_volume_extents: cycle_context.walk_volume_extents,
             kick_steps: cycle_context.kick_steps,
             cycle_time: cycle_context.cycle_time,
             center_of_mass: cycle_context.center_of_mass,
-            force_sensitive_resistors: &cycle_context.sensor_data.force_sensitive_resistors,
+            gravity_acceleration: *cycle_context.gravity_acceleration,
+            backend: &backend,
             robot_orientation: &orientation,
             robot_to_ground: cycle_context.robot_to_ground,
             gyro: self.filtered_gyro.state(),
@@ -175,6 +184,12 @@ impl WalkingEngine {
         cycle_context
             .debug_output
             .fill_if_subscribed(|| self.engine.clone());
+        cycle_context.capture_point.fill_if_subscribed(|| {
+            self.engine
+                .mode
+                .support_side()
+                .map(|support_side| CapturePoint::estimate(&context, support_side))
+        });
         cycle_context
             .robot_to_walk
             .fill_if_subscribed(|| robot_to_walk);
@@ -0,0 +1,414 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::{eyre::WrapErr, Result};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use framework::{MainOutput, PerceptionInput};
+use geometry::line_segment::LineSegment;
+use hardware::NetworkInterface;
+use linear_algebra::{distance, Isometry2, Point2};
+use spl_network_messages::{HulkMessage, PassMessage, PlayerNumber};
+use types::{
+    cycle_time::CycleTime,
+    field_dimensions::FieldDimensions,
+    kick_decision::{KickDecision, KickDecisionEvaluation, KickEvaluationParameters},
+    messages::{IncomingMessage, OutgoingMessage},
+    motion_command::KickVariant,
+    parameters::{InWalkKicksParameters, PassSelectorParameters, SplNetworkParameters},
+    pass::{IncomingPass, PassDecision},
+    players::Players,
+    primary_state::PrimaryState,
+    roles::Role,
+    support_foot::Side,
+    teammate_pose::TeammatePose,
+    tracked_robots::TrackedRobot,
+    world_state::BallState,
+};
+
+use crate::{
+    kick_evaluation::{evaluate_pass, Opponent},
+    kick_selector::compute_kick_pose,
+    team_ball_receiver::get_spl_messages,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct PassSelector {
+    teammate_poses: Players<Option<TeammatePose>>,
+    incoming_pass: Option<IncomingPass>,
+    last_announcement: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball_state: Input<Option<BallState>, "ball_state?">,
+    best_kick_evaluation: Input<Option<KickDecisionEvaluation>, "best_kick_evaluation?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    primary_state: Input<PrimaryState, "primary_state">,
+    remaining_amount_of_messages:
+        Input<Option<u16>, "game_controller_state?.hulks_team.remaining_amount_of_messages">,
+    role: Input<Role, "role">,
+    tracked_robots: Input<Vec<TrackedRobot>, "tracked_robots">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
+    kick_evaluation_parameters: Parameter<KickEvaluationParameters, "kick_selector.evaluation">,
+    parameters: Parameter<PassSelectorParameters, "pass_selector">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+    spl_network_parameters: Parameter<SplNetworkParameters, "spl_network">,
+
+    hardware: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub pass_decision: MainOutput<Option<PassDecision>>,
+    pub incoming_pass: MainOutput<Option<IncomingPass>>,
+}
+
+impl PassSelector {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            teammate_poses: Players::new(None),
+            incoming_pass: None,
+            last_announcement: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl NetworkInterface>) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+
+        for (time, message) in get_spl_messages(&context.network_message.persistent) {
+            if let HulkMessage::Pass(message) = message {
+                if message.receiver == *context.player_number {
+                    self.incoming_pass = Some(IncomingPass {
+                        passer: message.player_number,
+                        target: message.target,
+                        announced_at: time,
+                    });
+                }
+            }
+            if let Some((player_number, teammate_pose)) =
+                TeammatePose::from_message(&message, time, &self.teammate_poses)
+            {
+                self.teammate_poses[player_number] = Some(teammate_pose);
+            }
+        }
+
+        let is_incoming_pass_outdated = self.incoming_pass.is_some_and(|incoming_pass| {
+            is_older_than(
+                now,
+                incoming_pass.announced_at,
+                parameters.incoming_pass_timeout,
+            )
+        });
+        if is_incoming_pass_outdated || *context.role == Role::Striker {
+            self.incoming_pass = None;
+        }
+
+        let pass_decision = match (
+            parameters.enable,
+            *context.role,
+            context.ball_state,
+            context.ground_to_field,
+        ) {
+            (true, Role::Striker, Some(ball_state), Some(ground_to_field)) => {
+                self.select_pass(&context, ball_state, *ground_to_field)
+            }
+            _ => None,
+        };
+        if let Some(pass_decision) = &pass_decision {
+            self.try_announcing_pass(&context, pass_decision)?;
+        }
+
+        Ok(MainOutputs {
+            pass_decision: pass_decision.into(),
+            incoming_pass: self.incoming_pass.into(),
+        })
+    }
+
+    fn select_pass(
+        &self,
+        context: &CycleContext<impl NetworkInterface>,
+        ball_state: &BallState,
+        ground_to_field: Isometry2<Ground, Field>,
+    ) -> Option<PassDecision> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+        let teammates = self
+            .teammate_poses
+            .iter()
+            .filter(|(player_number, _)| *player_number != *context.player_number)
+            .filter_map(|(player_number, teammate_pose)| {
+                let teammate_pose = teammate_pose.as_ref()?;
+                (!teammate_pose.is_outdated(now, parameters.teammate_pose_timeout))
+                    .then_some((player_number, teammate_pose.pose.position()))
+            })
+            .collect::<Vec<_>>();
+        let opponents = context
+            .tracked_robots
+            .iter()
+            .filter(|robot| robot.is_opponent())
+            .map(|robot| ground_to_field * robot.position())
+            .collect::<Vec<_>>();
+
+        let ball_in_field = ground_to_field * ball_state.ball_in_ground;
+        let (receiver, target, score) =
+            best_receiver(&teammates, ball_in_field, &opponents, parameters)?;
+
+        let kick_info = &context.in_walk_kicks[KickVariant::Forward];
+        let strength = (distance(ball_in_field, target) / kick_info.shot_distance)
+            .clamp(parameters.minimum_kick_strength, 1.0);
+        let target_in_ground = ground_to_field.inverse() * target;
+        let kick_decisions: Vec<_> = [Side::Left, Side::Right]
+            .into_iter()
+            .map(|kicking_side| KickDecision {
+                target: target_in_ground,
+                variant: KickVariant::Forward,
+                kicking_side,
+                kick_pose: compute_kick_pose(
+                    ball_state.ball_in_ground,
+                    target_in_ground,
+                    kick_info,
+                    kicking_side,
+                ),
+                strength,
+            })
+            .collect();
+
+        let rollout_opponents = context
+            .tracked_robots
+            .iter()
+            .filter(|robot| robot.is_opponent())
+            .map(|robot| Opponent::from_tracked_robot(robot, ground_to_field))
+            .collect::<Vec<_>>();
+        let evaluation = kick_decisions
+            .iter()
+            .map(|&decision| {
+                evaluate_pass(
+                    decision,
+                    ball_state.ball_in_ground,
+                    ground_to_field,
+                    target,
+                    score,
+                    &rollout_opponents,
+                    kick_info.shot_distance,
+                    &kick_info.noise,
+                    context.field_dimensions,
+                    context.kick_evaluation_parameters,
+                    parameters,
+                    &mut ChaChaRng::seed_from_u64(context.kick_evaluation_parameters.random_seed),
+                )
+            })
+            .max_by(|left, right| left.score.total_cmp(&right.score))?;
+        if !is_pass_better_than_kick(
+            evaluation.score,
+            context.best_kick_evaluation,
+            parameters.minimum_score_advantage,
+        ) {
+            return None;
+        }
+
+        Some(PassDecision {
+            receiver,
+            target,
+            score,
+            evaluation,
+            kick_decisions,
+        })
+    }
+
+    fn try_announcing_pass(
+        &mut self,
+        context: &CycleContext<impl NetworkInterface>,
+        pass_decision: &PassDecision,
+    ) -> Result<()> {
+        let now = context.cycle_time.start_time;
+        let is_cooldown_elapsed = self.last_announcement.is_none_or(|last_announcement| {
+            is_older_than(
+                now,
+                last_announcement,
+                context.parameters.announcement_interval,
+            )
+        });
+        let is_message_budget_exhausted =
+            context
+                .remaining_amount_of_messages
+                .is_some_and(|remaining_amount_of_messages| {
+                    *remaining_amount_of_messages
+                        < context
+                            .spl_network_parameters
+                            .remaining_amount_of_messages_to_stop_sending
+                });
+        let Some(ground_to_field) = context.ground_to_field else {
+            return Ok(());
+        };
+        if *context.primary_state != PrimaryState::Playing
+            || !is_cooldown_elapsed
+            || is_message_budget_exhausted
+        {
+            return Ok(());
+        }
+
+        self.last_announcement = Some(now);
+        context
+            .hardware
+            .write_to_network(OutgoingMessage::Spl(HulkMessage::Pass(PassMessage {
+                player_number: *context.player_number,
+                pose: ground_to_field.as_pose(),
+                receiver: pass_decision.receiver,
+                target: pass_decision.target,
+            })))
+            .wrap_err("failed to write PassMessage to hardware")
+    }
+}
+
+fn is_older_than(now: SystemTime, time: SystemTime, timeout: Duration) -> bool {
+    now.duration_since(time).unwrap_or_default() > timeout
+}
+
+/// Passes have to beat the kick the striker would otherwise dribble towards, without an evaluated
+/// kick there is nothing to compare against and the striker keeps the ball
+fn is_pass_better_than_kick(
+    pass_score: f32,
+    best_kick_evaluation: Option<&KickDecisionEvaluation>,
+    minimum_score_advantage: f32,
+) -> bool {
+    best_kick_evaluation
+        .is_some_and(|kick_evaluation| pass_score > kick_evaluation.score + minimum_score_advantage)
+}
+
+/// Selects the teammate with the best trade-off between field progress and free space around it,
+/// ignoring teammates whose pass line is blocked by an opponent.
+fn best_receiver(
+    teammates: &[(PlayerNumber, Point2<Field>)],
+    ball: Point2<Field>,
+    opponents: &[Point2<Field>],
+    parameters: &PassSelectorParameters,
+) -> Option<(PlayerNumber, Point2<Field>, f32)> {
+    teammates
+        .iter()
+        .filter_map(|&(player_number, position)| {
+            let pass_distance = distance(ball, position);
+            let forward_progress = position.x() - ball.x();
+            if pass_distance < parameters.minimum_pass_distance
+                || pass_distance > parameters.maximum_pass_distance
+                || forward_progress < parameters.minimum_forward_progress
+            {
+                return None;
+            }
+
+            let pass_line = LineSegment(ball, position);
+            let is_pass_line_blocked = opponents.iter().any(|&opponent| {
+                distance(pass_line.closest_point(opponent), opponent)
+                    < parameters.opponent_clearance
+            });
+            if is_pass_line_blocked {
+                return None;
+            }
+
+            let free_space = opponents
+                .iter()
+                .map(|&opponent| distance(opponent, position))
+                .fold(parameters.maximum_pass_distance, f32::min);
+            let score = forward_progress + parameters.opponent_distance_weight * free_space;
+            Some((player_number, position, score))
+        })
+        .max_by(|(_, _, left), (_, _, right)| left.total_cmp(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::point;
+
+    use super::*;
+
+    fn parameters() -> PassSelectorParameters {
+        PassSelectorParameters {
+            enable: true,
+            teammate_pose_timeout: Duration::from_secs(5),
+            incoming_pass_timeout: Duration::from_secs(5),
+            announcement_interval: Duration::from_secs(1),
+            minimum_pass_distance: 1.0,
+            maximum_pass_distance: 4.0,
+            minimum_forward_progress: 0.0,
+            opponent_clearance: 0.5,
+            opponent_distance_weight: 0.5,
+            minimum_kick_strength: 0.3,
+            reception_radius: 0.5,
+            reception_probability_weight: 0.5,
+            receiver_score_weight: 0.1,
+            minimum_score_advantage: 0.1,
+        }
+    }
+
+    #[test]
+    fn blocked_receiver_is_skipped() {
+        let teammates = [
+            (PlayerNumber::Four, point![3.0, 0.0]),
+            (PlayerNumber::Five, point![2.0, 1.5]),
+        ];
+        let opponents = [point![1.5, 0.0]];
+
+        let (receiver, _, _) =
+            best_receiver(&teammates, Point2::origin(), &opponents, &parameters()).unwrap();
+
+        assert_eq!(receiver, PlayerNumber::Five);
+    }
+
+    #[test]
+    fn receivers_out_of_range_or_behind_are_ignored() {
+        let teammates = [
+            (PlayerNumber::Two, point![-2.0, 0.0]),
+            (PlayerNumber::Three, point![0.5, 0.0]),
+            (PlayerNumber::Four, point![5.0, 0.0]),
+        ];
+
+        assert!(best_receiver(&teammates, Point2::origin(), &[], &parameters()).is_none());
+    }
+
+    #[test]
+    fn pass_has_to_beat_kick_by_margin() {
+        let kick_evaluation = |score| KickDecisionEvaluation {
+            decision: KickDecision {
+                target: point![1.0, 0.0],
+                variant: KickVariant::Forward,
+                kicking_side: Side::Left,
+                kick_pose: Default::default(),
+                strength: 1.0,
+            },
+            goal_probability: 0.0,
+            interception_probability: 0.0,
+            score,
+            sampled_ball_end_positions: Vec::new(),
+        };
+        let margin = parameters().minimum_score_advantage;
+
+        assert!(!is_pass_better_than_kick(0.6, None, margin));
+        assert!(is_pass_better_than_kick(
+            0.6,
+            Some(&kick_evaluation(0.3)),
+            margin
+        ));
+        assert!(!is_pass_better_than_kick(
+            0.6,
+            Some(&kick_evaluation(0.55)),
+            margin
+        ));
+        assert!(!is_pass_better_than_kick(
+            0.6,
+            Some(&kick_evaluation(0.8)),
+            margin
+        ));
+    }
+}
@@ -429,6 +429,7 @@ impl PathPlanner {
         match &self.obstacles[obstacle_index].shape {
             PathObstacleShape::Circle(circle) => tangent.get_direction(circle.center),
             PathObstacleShape::LineSegment(_) => panic!("LineSegment not implemented"),
+            PathObstacleShape::Rectangle(_) => panic!("Rectangle not implemented"),
         }
     }
 
@@ -0,0 +1,426 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use hungarian_algorithm::{Assignment, AssignmentProblem};
+use itertools::Itertools;
+use nalgebra::{matrix, Matrix2, Matrix2x4, Matrix4, Matrix4x2};
+use ndarray::Array2;
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use filtering::kalman_filter::KalmanFilter;
+use framework::{AdditionalOutput, MainOutput, PerceptionInput};
+use linear_algebra::{distance, Isometry2, Point2};
+use spl_network_messages::PlayerNumber;
+use types::{
+    cycle_time::CycleTime,
+    messages::IncomingMessage,
+    multivariate_normal_distribution::MultivariateNormalDistribution,
+    obstacles::{Obstacle, ObstacleKind},
+    parameters::RobotTrackerParameters,
+    players::Players,
+    teammate_pose::TeammatePose,
+    tracked_robots::{RobotAffiliation, TrackedRobot},
+};
+
+use crate::team_ball_receiver::get_spl_messages;
+
+#[derive(Deserialize, Serialize)]
+pub struct RobotTracker {
+    hypotheses: Vec<TrackedRobot>,
+    teammate_poses: Players<Option<TeammatePose>>,
+    next_id: usize,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    robot_tracker_hypotheses: AdditionalOutput<Vec<TrackedRobot>, "robot_tracker_hypotheses">,
+
+    cycle_time: Input<CycleTime, "cycle_time">,
+    current_odometry_to_last_odometry:
+        Input<Option<nalgebra::Isometry2<f32>>, "current_odometry_to_last_odometry?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    obstacles: Input<Vec<Obstacle>, "obstacles">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    player_number: Parameter<PlayerNumber, "player_number">,
+    parameters: Parameter<RobotTrackerParameters, "robot_tracker">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub tracked_robots: MainOutput<Vec<TrackedRobot>>,
+}
+
+impl RobotTracker {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            hypotheses: Vec::new(),
+            teammate_poses: Players::default(),
+            next_id: 0,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+
+        for (time, message) in get_spl_messages(&context.network_message.persistent) {
+            let Some((player_number, teammate_pose)) =
+                TeammatePose::from_message(&message, time, &self.teammate_poses)
+            else {
+                continue;
+            };
+            if player_number == *context.player_number {
+                continue;
+            }
+            self.teammate_poses[player_number] = Some(teammate_pose);
+        }
+
+        let last_odometry_to_current_odometry = context
+            .current_odometry_to_last_odometry
+            .copied()
+            .unwrap_or_default()
+            .inverse();
+        self.predict_hypotheses(
+            context.cycle_time.last_cycle_duration,
+            last_odometry_to_current_odometry,
+            parameters,
+        );
+
+        let measurements = context
+            .obstacles
+            .iter()
+            .filter(|obstacle| obstacle.kind == ObstacleKind::Robot)
+            .map(|obstacle| obstacle.position)
+            .collect::<Vec<_>>();
+        self.update_hypotheses_with_measurements(&measurements, now, parameters);
+
+        self.hypotheses.retain(|hypothesis| {
+            now.duration_since(hypothesis.last_update)
+                .unwrap_or_default()
+                < parameters.hypothesis_timeout
+        });
+
+        if let Some(ground_to_field) = context.ground_to_field {
+            self.assign_teammates(*ground_to_field, now, parameters);
+        }
+
+        context
+            .robot_tracker_hypotheses
+            .fill_if_subscribed(|| self.hypotheses.clone());
+
+        let tracked_robots = self
+            .hypotheses
+            .iter()
+            .filter(|hypothesis| {
+                hypothesis.measurement_count >= parameters.measurement_count_threshold
+            })
+            .cloned()
+            .collect();
+
+        Ok(MainOutputs {
+            tracked_robots: tracked_robots.into(),
+        })
+    }
+
+    fn predict_hypotheses(
+        &mut self,
+        delta_time: Duration,
+        last_odometry_to_current_odometry: nalgebra::Isometry2<f32>,
+        parameters: &RobotTrackerParameters,
+    ) {
+        let dt = delta_time.as_secs_f32();
+        let velocity_decay = parameters.velocity_decay_factor;
+        let constant_velocity_prediction = matrix![
+            1.0, 0.0, dt, 0.0;
+            0.0, 1.0, 0.0, dt;
+            0.0, 0.0, velocity_decay, 0.0;
+            0.0, 0.0, 0.0, velocity_decay;
+        ];
+
+        let rotation = last_odometry_to_current_odometry
+            .rotation
+            .to_rotation_matrix();
+        let rotation = rotation.matrix();
+        let state_rotation = matrix![
+            rotation.m11, rotation.m12, 0.0, 0.0;
+            rotation.m21, rotation.m22, 0.0, 0.0;
+            0.0, 0.0, rotation.m11, rotation.m12;
+            0.0, 0.0, rotation.m21, rotation.m22;
+        ];
+        let translation = last_odometry_to_current_odometry.translation.vector;
+
+        for hypothesis in self.hypotheses.iter_mut() {
+            hypothesis.state.predict(
+                constant_velocity_prediction * state_rotation,
+                Matrix4x2::identity(),
+                translation,
+                Matrix4::from_diagonal(&parameters.process_noise),
+            );
+        }
+    }
+
+    fn update_hypotheses_with_measurements(
+        &mut self,
+        measurements: &[Point2<Ground>],
+        detection_time: SystemTime,
+        parameters: &RobotTrackerParameters,
+    ) {
+        let assignment = assign_by_distance(&self.hypotheses, measurements);
+
+        let mut used_measurements = vec![false; measurements.len()];
+        for (hypothesis, assigned_measurement) in
+            self.hypotheses.iter_mut().zip_eq(assignment.iter())
+        {
+            let Some(assigned_measurement) = assigned_measurement else {
+                continue;
+            };
+            let matching_distance = -assigned_measurement.cost;
+            if matching_distance > parameters.measurement_matching_distance {
+                continue;
+            }
+            used_measurements[assigned_measurement.to] = true;
+            hypothesis.state.update(
+                Matrix2x4::identity(),
+                measurements[assigned_measurement.to].inner.coords,
+                Matrix2::from_diagonal(&parameters.measurement_noise),
+            );
+            hypothesis.measurement_count += 1;
+            hypothesis.last_update = detection_time;
+        }
+
+        for (measurement, _) in measurements
+            .iter()
+            .zip(used_measurements)
+            .filter(|(_, is_used)| !is_used)
+        {
+            self.spawn_hypothesis(*measurement, detection_time, parameters);
+        }
+    }
+
+    fn spawn_hypothesis(
+        &mut self,
+        position: Point2<Ground>,
+        detection_time: SystemTime,
+        parameters: &RobotTrackerParameters,
+    ) {
+        self.hypotheses.push(TrackedRobot {
+            id: self.next_id,
+            state: MultivariateNormalDistribution {
+                mean: nalgebra::vector![position.x(), position.y(), 0.0, 0.0],
+                covariance: Matrix4::from_diagonal(&parameters.initial_covariance),
+            },
+            affiliation: RobotAffiliation::Opponent,
+            measurement_count: 1,
+            last_update: detection_time,
+        });
+        self.next_id = self.next_id.wrapping_add(1);
+    }
+
+    fn assign_teammates(
+        &mut self,
+        ground_to_field: Isometry2<Ground, Field>,
+        now: SystemTime,
+        parameters: &RobotTrackerParameters,
+    ) {
+        let field_to_ground = ground_to_field.inverse();
+        let teammates = self
+            .teammate_poses
+            .iter()
+            .filter_map(|(player_number, teammate_pose)| {
+                let teammate_pose = teammate_pose.as_ref()?;
+                (!teammate_pose.is_outdated(now, parameters.teammate_pose_timeout)).then(|| {
+                    (
+                        player_number,
+                        field_to_ground * teammate_pose.pose.position(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        let teammate_positions = teammates
+            .iter()
+            .map(|(_, position)| *position)
+            .collect::<Vec<_>>();
+
+        // Teammates keep their affiliation between their rare broadcasts, but lose it once no
+        // recent pose matches them anymore
+        for hypothesis in self.hypotheses.iter_mut() {
+            if let RobotAffiliation::Teammate { player_number } = hypothesis.affiliation {
+                if !teammates
+                    .iter()
+                    .any(|(teammate, _)| *teammate == player_number)
+                {
+                    hypothesis.affiliation = RobotAffiliation::Opponent;
+                }
+            }
+        }
+
+        let assignment = assign_by_distance(&self.hypotheses, &teammate_positions);
+
+        for (hypothesis, assigned_teammate) in self.hypotheses.iter_mut().zip_eq(assignment) {
+            let Some(assigned_teammate) = assigned_teammate else {
+                continue;
+            };
+            if -assigned_teammate.cost > parameters.teammate_matching_distance {
+                continue;
+            }
+            let (player_number, _) = teammates[assigned_teammate.to];
+            hypothesis.affiliation = RobotAffiliation::Teammate { player_number };
+        }
+    }
+}
+
+fn assign_by_distance(
+    hypotheses: &[TrackedRobot],
+    measurements: &[Point2<Ground>],
+) -> Vec<Option<Assignment>> {
+    if hypotheses.is_empty() || measurements.is_empty() {
+        return vec![None; hypotheses.len()];
+    }
+    let costs = Array2::from_shape_fn((hypotheses.len(), measurements.len()), |(i, j)| {
+        let distance = distance(hypotheses[i].position(), measurements[j]);
+        NotNan::new(-distance).expect("distance is NaN")
+    });
+    AssignmentProblem::from_costs(costs).solve()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use linear_algebra::{point, Pose2};
+
+    use super::*;
+
+    fn parameters() -> RobotTrackerParameters {
+        RobotTrackerParameters {
+            hypothesis_timeout: Duration::from_secs(2),
+            teammate_pose_timeout: Duration::from_secs(5),
+            measurement_matching_distance: 0.5,
+            teammate_matching_distance: 0.5,
+            velocity_decay_factor: 1.0,
+            process_noise: nalgebra::vector![0.001, 0.001, 0.01, 0.01],
+            measurement_noise: nalgebra::vector![0.01, 0.01],
+            initial_covariance: nalgebra::vector![0.1, 0.1, 1.0, 1.0],
+            measurement_count_threshold: 1,
+        }
+    }
+
+    #[test]
+    fn identities_persist_across_measurements() {
+        let parameters = parameters();
+        let mut tracker = RobotTracker::new(CreationContext {}).unwrap();
+        let start = SystemTime::UNIX_EPOCH;
+
+        tracker.update_hypotheses_with_measurements(
+            &[point![1.0, 1.0], point![2.0, -1.0]],
+            start,
+            &parameters,
+        );
+        let ids = tracker
+            .hypotheses
+            .iter()
+            .map(|hypothesis| hypothesis.id)
+            .collect::<Vec<_>>();
+
+        tracker.update_hypotheses_with_measurements(
+            &[point![2.1, -1.0], point![1.1, 1.0]],
+            start + Duration::from_millis(100),
+            &parameters,
+        );
+
+        assert_eq!(tracker.hypotheses.len(), 2);
+        assert_eq!(
+            tracker
+                .hypotheses
+                .iter()
+                .map(|hypothesis| hypothesis.id)
+                .collect::<Vec<_>>(),
+            ids
+        );
+        assert!(tracker.hypotheses[0].position().x() < 1.5);
+        assert!(tracker.hypotheses[1].position().x() > 1.5);
+    }
+
+    #[test]
+    fn velocity_is_estimated_from_moving_measurements() {
+        let parameters = parameters();
+        let mut tracker = RobotTracker::new(CreationContext {}).unwrap();
+        let delta_time = Duration::from_millis(100);
+        let mut time = SystemTime::UNIX_EPOCH;
+
+        for step in 0..50 {
+            tracker.predict_hypotheses(delta_time, nalgebra::Isometry2::identity(), &parameters);
+            let position = point![1.0 + 0.05 * step as f32, 0.0];
+            tracker.update_hypotheses_with_measurements(&[position], time, &parameters);
+            time += delta_time;
+        }
+
+        assert_eq!(tracker.hypotheses.len(), 1);
+        assert_relative_eq!(tracker.hypotheses[0].velocity().x(), 0.5, epsilon = 0.05);
+        assert_relative_eq!(tracker.hypotheses[0].velocity().y(), 0.0, epsilon = 0.05);
+    }
+
+    #[test]
+    fn robots_near_broadcast_poses_are_teammates() {
+        let parameters = parameters();
+        let mut tracker = RobotTracker::new(CreationContext {}).unwrap();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+
+        tracker.update_hypotheses_with_measurements(
+            &[point![1.0, 0.0], point![3.0, 0.0]],
+            now,
+            &parameters,
+        );
+        tracker.teammate_poses[PlayerNumber::Three] = Some(TeammatePose {
+            pose: Pose2::new(point![1.1, 0.0], 0.0),
+            received: now,
+            time_to_reach_kick_position: None,
+        });
+        tracker.assign_teammates(Isometry2::identity(), now, &parameters);
+
+        assert_eq!(
+            tracker.hypotheses[0].affiliation,
+            RobotAffiliation::Teammate {
+                player_number: PlayerNumber::Three
+            }
+        );
+        assert!(tracker.hypotheses[1].is_opponent());
+    }
+
+    #[test]
+    fn teammates_become_opponents_once_their_pose_is_outdated() {
+        let parameters = parameters();
+        let mut tracker = RobotTracker::new(CreationContext {}).unwrap();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+
+        tracker.update_hypotheses_with_measurements(&[point![1.0, 0.0]], now, &parameters);
+        tracker.teammate_poses[PlayerNumber::Three] = Some(TeammatePose {
+            pose: Pose2::new(point![1.1, 0.0], 0.0),
+            received: now,
+            time_to_reach_kick_position: None,
+        });
+        tracker.assign_teammates(Isometry2::identity(), now, &parameters);
+        tracker.assign_teammates(
+            Isometry2::identity(),
+            now + parameters.teammate_pose_timeout / 2,
+            &parameters,
+        );
+
+        assert!(!tracker.hypotheses[0].is_opponent());
+
+        tracker.assign_teammates(
+            Isometry2::identity(),
+            now + parameters.teammate_pose_timeout * 2,
+            &parameters,
+        );
+
+        assert!(tracker.hypotheses[0].is_opponent());
+    }
+}
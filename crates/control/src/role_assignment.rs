@@ -8,13 +8,16 @@ use color_eyre::{
     eyre::{OptionExt, WrapErr},
     Result,
 };
+use hungarian_algorithm::AssignmentProblem;
+use ndarray::Array2;
+use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
 use coordinate_systems::{Field, Ground};
 use framework::{AdditionalOutput, MainOutput, PerceptionInput};
 use hardware::NetworkInterface;
-use linear_algebra::{distance, Isometry2};
+use linear_algebra::{distance, point, Isometry2, Orientation2, Point2, Pose2};
 use spl_network_messages::{
     GameControllerReturnMessage, GamePhase, HulkMessage, LoserMessage, Penalty, PlayerNumber,
     StrikerMessage, SubState, Team,
@@ -27,13 +30,18 @@ use types::{
     filtered_game_controller_state::FilteredGameControllerState,
     initial_pose::InitialPose,
     messages::{IncomingMessage, OutgoingMessage},
-    parameters::SplNetworkParameters,
+    parameters::{
+        CostBasedRoleAssignmentParameters, RolePositionsParameters, SplNetworkParameters,
+    },
     players::Players,
     primary_state::PrimaryState,
     roles::Role,
+    teammate_pose::TeammatePose,
 };
 
-use crate::localization::generate_initial_pose;
+use crate::{localization::generate_initial_pose, team_ball_receiver::get_spl_messages};
+
+const MAXIMUM_VIABLE_TIME_TO_REACH_KICK_POSITION: Duration = Duration::from_secs(1200);
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 enum SentState {
@@ -50,6 +58,8 @@ pub struct RoleAssignment {
     role: Role,
     last_time_player_was_penalized: Players<Option<SystemTime>>,
     last_sent_state: SentState,
+    last_striker: Option<PlayerNumber>,
+    teammate_poses: Players<Option<TeammatePose>>,
 }
 
 #[context]
@@ -84,8 +94,11 @@ pub struct CycleContext {
         Parameter<f32, "role_assignment.maximum_trusted_team_ball_distance">,
     loser_timeout: Parameter<Duration, "role_assignment.loser_timeout">,
     claim_striker_from_team_ball: Parameter<bool, "role_assignment.claim_striker_from_team_ball">,
+    cost_based_role_assignment:
+        Parameter<CostBasedRoleAssignmentParameters, "role_assignment.cost_based">,
     initial_poses: Parameter<Players<InitialPose>, "localization.initial_poses">,
     optional_roles: Parameter<Vec<Role>, "behavior.optional_roles">,
+    role_positions: Parameter<RolePositionsParameters, "behavior.role_positions">,
     player_number: Parameter<PlayerNumber, "player_number">,
     spl_network_parameters: Parameter<SplNetworkParameters, "spl_network">,
 
@@ -95,6 +108,7 @@ pub struct CycleContext {
         AdditionalOutput<Players<Option<SystemTime>>, "last_time_player_penalized">,
     last_sent_state: AdditionalOutput<String, "last_sent_state">,
     last_sent_message: AdditionalOutput<String, "last_sent_message">,
+    cost_based_roles: AdditionalOutput<Players<Option<Role>>, "cost_based_roles">,
 }
 
 #[context]
@@ -124,6 +138,8 @@ impl RoleAssignment {
             role,
             last_time_player_was_penalized: Players::new(None),
             last_sent_state: SentState::Loser,
+            last_striker: None,
+            teammate_poses: Players::new(None),
         })
     }
 
@@ -152,6 +168,14 @@ impl RoleAssignment {
             }
         }
 
+        for (time, message) in get_spl_messages(&context.network_message.persistent) {
+            if let Some((player_number, teammate_pose)) =
+                TeammatePose::from_message(&message, time, &self.teammate_poses)
+            {
+                self.teammate_poses[player_number] = Some(teammate_pose);
+            }
+        }
+
         let role_from_state_machine =
             self.role_from_state_machine(&context, cycle_start_time, self.role);
 
@@ -193,6 +217,15 @@ impl RoleAssignment {
             }
         }
 
+        if let Some(assigned_role) = self.role_from_cost_based_assignment(&mut context, new_role) {
+            new_role = assigned_role;
+        }
+        if new_role == Role::Striker {
+            self.last_striker = Some(*context.player_number);
+        } else if self.last_striker == Some(*context.player_number) {
+            self.last_striker = None;
+        }
+
         context
             .last_time_player_was_penalized
             .fill_if_subscribed(|| self.last_time_player_was_penalized);
@@ -210,6 +243,11 @@ impl RoleAssignment {
                 (Role::Striker, Role::Loser) => {
                     self.try_sending_loser_message(&mut context)?;
                 }
+                // Withdraw the broadcast time to reach the kick position once the cost based
+                // assignment hands the striker role over
+                (Role::Striker, _) if context.cost_based_role_assignment.enable => {
+                    self.try_sending_loser_message(&mut context)?;
+                }
                 _ => {}
             }
         }
@@ -244,18 +282,11 @@ impl RoleAssignment {
             }
             .map(|role| role.unwrap_or(Role::Striker));
 
-            if let Some(game_controller_state) = context.filtered_game_controller_state {
-                if let Some(striker) = [
-                    PlayerNumber::Seven,
-                    PlayerNumber::Six,
-                    PlayerNumber::Five,
-                    PlayerNumber::Four,
-                ]
-                .into_iter()
-                .find(|player| game_controller_state.penalties[*player].is_none())
-                {
-                    player_roles[striker] = Role::Striker;
-                }
+            if let Some(striker) = context
+                .filtered_game_controller_state
+                .and_then(|game_controller_state| ready_striker(&game_controller_state.penalties))
+            {
+                player_roles[striker] = Role::Striker;
             }
 
             self.last_received_striker_message = None;
@@ -283,6 +314,7 @@ impl RoleAssignment {
                         .spl_striker_message_receive_timeout
                 {
                     self.last_received_striker_message = None;
+                    self.last_striker = None;
                     true
                 } else {
                     false
@@ -321,8 +353,13 @@ impl RoleAssignment {
 
         let mut new_role = current_role;
         for event in events {
-            if let Event::Striker(_) = event {
-                self.last_received_striker_message = Some(cycle_start_time)
+            match event {
+                Event::Striker(striker_event) => {
+                    self.last_received_striker_message = Some(cycle_start_time);
+                    self.last_striker = Some(striker_event.player_number);
+                }
+                Event::Loser => self.last_striker = None,
+                Event::None => {}
             }
 
             new_role = update_role_state_machine(
@@ -354,6 +391,74 @@ impl RoleAssignment {
         new_role
     }
 
+    fn role_from_cost_based_assignment(
+        &self,
+        context: &mut CycleContext<'_, impl NetworkInterface>,
+        current_role: Role,
+    ) -> Option<Role> {
+        let parameters = context.cost_based_role_assignment;
+        if !parameters.enable || context.forced_role.is_some() {
+            return None;
+        }
+        let game_controller_state = context.filtered_game_controller_state?;
+        if matches!(
+            game_controller_state.game_phase,
+            GamePhase::PenaltyShootout { .. }
+        ) || matches!(
+            game_controller_state.sub_state,
+            Some(SubState::PenaltyKick | SubState::KickIn | SubState::PushingFreeKick)
+        ) {
+            return None;
+        }
+        let kick_off_striker = match context.primary_state {
+            PrimaryState::Ready | PrimaryState::Set => {
+                ready_striker(&game_controller_state.penalties)
+            }
+            PrimaryState::Playing => None,
+            _ => return None,
+        };
+
+        // All robots assign roles from the team ball and the poses broadcast to the team, so
+        // they agree on the assignment
+        let ball = context
+            .team_ball
+            .map(|ball| ball.position)
+            .or_else(|| {
+                let ground_to_field = context.ground_to_field?;
+                Some(*ground_to_field * context.ball_position?.position)
+            })
+            .unwrap_or_else(Point2::origin);
+        // The own row holds the pose and time this robot last broadcast, as seen by the teammates
+        let players = broadcast_players(
+            &self.teammate_poses,
+            &game_controller_state.penalties,
+            context.cycle_time.start_time,
+            parameters.teammate_pose_timeout,
+            context.initial_poses,
+            context.field_dimensions,
+        );
+
+        let roles = assign_roles(
+            &players,
+            kick_off_striker,
+            context.optional_roles,
+            ball,
+            context.field_dimensions,
+            context.role_positions,
+            parameters,
+        );
+        context.cost_based_roles.fill_if_subscribed(|| roles);
+        match roles[*context.player_number] {
+            Some(Role::Striker) if context.time_to_reach_kick_position.is_none() => None,
+            None if current_role == Role::Striker
+                && roles.iter().any(|(_, role)| *role == Some(Role::Striker)) =>
+            {
+                Some(Role::Searcher)
+            }
+            role => role,
+        }
+    }
+
     fn is_return_message_cooldown_elapsed(
         &self,
         context: &CycleContext<impl NetworkInterface>,
@@ -462,7 +567,14 @@ impl RoleAssignment {
             .or(team_network_ball)
             .ok_or_eyre("we are striker without a ball, this should never happen")?;
 
+        let time_to_reach_kick_position = *context.time_to_reach_kick_position.unwrap();
+
         self.last_sent_state = SentState::Striker;
+        self.teammate_poses[*context.player_number] = Some(TeammatePose {
+            pose,
+            received: context.cycle_time.start_time,
+            time_to_reach_kick_position: Some(time_to_reach_kick_position),
+        });
         context
             .last_sent_message
             .fill_if_subscribed(|| "Striker".to_string());
@@ -472,7 +584,7 @@ impl RoleAssignment {
                 player_number: *context.player_number,
                 pose,
                 ball_position,
-                time_to_reach_kick_position: *context.time_to_reach_kick_position.unwrap(),
+                time_to_reach_kick_position,
             })))
             .wrap_err("failed to write StrikerMessage to hardware")
     }
@@ -501,7 +613,12 @@ impl RoleAssignment {
         self.last_transmitted_spl_message = Some(context.cycle_time.start_time);
         self.last_received_striker_message = None;
 
-        let ground_to_field = ground_to_field_or_initial_pose(context);
+        let pose = ground_to_field_or_initial_pose(context).as_pose();
+        self.teammate_poses[*context.player_number] = Some(TeammatePose {
+            pose,
+            received: context.cycle_time.start_time,
+            time_to_reach_kick_position: None,
+        });
         context
             .last_sent_message
             .fill_if_subscribed(|| "Loser".to_string());
@@ -509,7 +626,7 @@ impl RoleAssignment {
             .hardware
             .write_to_network(OutgoingMessage::Spl(HulkMessage::Loser(LoserMessage {
                 player_number: *context.player_number,
-                pose: ground_to_field.as_pose(),
+                pose,
             })))
             .wrap_err("failed to write LoserMessage to hardware")
     }
@@ -795,7 +912,7 @@ fn claim_striker_or_other_role(
     let shorter_time_to_reach = time_to_reach_kick_position
         .is_some_and(|duration| duration < striker_event.time_to_reach_kick_position);
     let time_to_reach_viable =
-        time_to_reach_kick_position.is_some_and(|duration| duration < Duration::from_secs(1200));
+        time_to_reach_kick_position.is_some_and(is_time_to_reach_kick_position_viable);
 
     if shorter_time_to_reach && time_to_reach_viable {
         return Role::Striker;
@@ -876,6 +993,216 @@ fn pick_role_with_penalties(
     role_assignment[own_player_number].unwrap_or(Role::Striker)
 }
 
+fn ready_striker(penalties: &Players<Option<Penalty>>) -> Option<PlayerNumber> {
+    [
+        PlayerNumber::Seven,
+        PlayerNumber::Six,
+        PlayerNumber::Five,
+        PlayerNumber::Four,
+    ]
+    .into_iter()
+    .find(|player| penalties[*player].is_none())
+}
+
+fn is_positioning_role(role: Role) -> bool {
+    matches!(
+        role,
+        Role::DefenderLeft
+            | Role::DefenderRight
+            | Role::MidfielderLeft
+            | Role::MidfielderRight
+            | Role::StrikerSupporter
+    )
+}
+
+/// A player as the team knows it from its broadcast messages
+#[derive(Clone, Copy, Debug)]
+struct BroadcastPlayer {
+    player_number: PlayerNumber,
+    pose: Pose2<Field>,
+    time_to_reach_kick_position: Option<Duration>,
+}
+
+/// Poses and times to reach the kick position of all unpenalized players as last broadcast to the
+/// team, players without a recent message are assumed at their initial pose
+///
+/// The own pose is taken from the own messages as well, so every robot of the team uses the same
+/// poses.
+fn broadcast_players(
+    teammate_poses: &Players<Option<TeammatePose>>,
+    penalties: &Players<Option<Penalty>>,
+    now: SystemTime,
+    teammate_pose_timeout: Duration,
+    initial_poses: &Players<InitialPose>,
+    field_dimensions: &FieldDimensions,
+) -> Vec<BroadcastPlayer> {
+    penalties
+        .iter()
+        .filter(|(_, penalty)| penalty.is_none())
+        .map(|(player_number, _)| {
+            match teammate_poses[player_number]
+                .filter(|teammate_pose| !teammate_pose.is_outdated(now, teammate_pose_timeout))
+            {
+                Some(teammate_pose) => BroadcastPlayer {
+                    player_number,
+                    pose: teammate_pose.pose,
+                    time_to_reach_kick_position: teammate_pose.time_to_reach_kick_position,
+                },
+                None => BroadcastPlayer {
+                    player_number,
+                    pose: generate_initial_pose(&initial_poses[player_number], field_dimensions),
+                    time_to_reach_kick_position: None,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Time to turn towards the target and walk there
+fn time_to_reach(
+    pose: Pose2<Field>,
+    target: Point2<Field>,
+    parameters: &CostBasedRoleAssignmentParameters,
+) -> f32 {
+    let to_target = target - pose.position();
+    let turning_angle = if to_target.norm() > f32::EPSILON {
+        pose.orientation()
+            .rotation_to(Orientation2::from_vector(to_target))
+            .angle()
+            .abs()
+    } else {
+        0.0
+    };
+    to_target.norm() / parameters.walking_speed.max(f32::EPSILON)
+        + turning_angle / parameters.turning_speed.max(f32::EPSILON)
+}
+
+/// Assigns the striker, keeper and positioning roles to the given players such that the summed
+/// time to take them is minimal.
+///
+/// Every robot solves the same problem from the shared team information, which keeps the
+/// assignment consistent across the team. The striker costs the broadcast time to reach the kick
+/// position and is only assigned if any player can reach the ball, during kick-offs the kick-off
+/// striker is kept. The keeper is filled next, by player one as keeper or by any other player as
+/// replacement keeper. The optional roles are filled in their configured order, surplus players
+/// stay unassigned.
+fn assign_roles(
+    players: &[BroadcastPlayer],
+    kick_off_striker: Option<PlayerNumber>,
+    optional_roles: &[Role],
+    ball: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    role_positions: &RolePositionsParameters,
+    parameters: &CostBasedRoleAssignmentParameters,
+) -> Players<Option<Role>> {
+    let mut assigned_roles = Players::new(None);
+    let mut roles = Vec::new();
+    let players: Vec<_> = match kick_off_striker {
+        Some(striker) => {
+            assigned_roles[striker] = Some(Role::Striker);
+            players
+                .iter()
+                .filter(|player| player.player_number != striker)
+                .copied()
+                .collect()
+        }
+        None => {
+            if players.iter().any(|player| {
+                player
+                    .time_to_reach_kick_position
+                    .is_some_and(is_time_to_reach_kick_position_viable)
+            }) {
+                roles.push(Role::Striker);
+            }
+            players.to_vec()
+        }
+    };
+    roles.push(Role::Keeper);
+    roles.extend(
+        optional_roles
+            .iter()
+            .copied()
+            .filter(|role| is_positioning_role(*role)),
+    );
+    roles.truncate(players.len());
+    if players.is_empty() {
+        return assigned_roles;
+    }
+
+    let costs = Array2::from_shape_fn((players.len(), roles.len()), |(i, j)| {
+        let player = players[i];
+        let cost = match roles[j] {
+            Role::Striker => player
+                .time_to_reach_kick_position
+                .filter(|duration| is_time_to_reach_kick_position_viable(*duration))
+                .unwrap_or(MAXIMUM_VIABLE_TIME_TO_REACH_KICK_POSITION)
+                .as_secs_f32(),
+            role => {
+                let position =
+                    role_position(role, ball, field_dimensions, role_positions, parameters);
+                let factor = match role {
+                    Role::Keeper => parameters.keeper_distance_cost_factor,
+                    _ => 1.0,
+                };
+                factor * time_to_reach(player.pose, position, parameters)
+            }
+        };
+        NotNan::new(-cost).expect("time to reach is NaN")
+    });
+    for (player, assignment) in players
+        .iter()
+        .zip(AssignmentProblem::from_costs(costs).solve())
+    {
+        if let Some(assignment) = assignment {
+            assigned_roles[player.player_number] =
+                Some(match (roles[assignment.to], player.player_number) {
+                    (Role::Keeper, PlayerNumber::One) => Role::Keeper,
+                    (Role::Keeper, _) => Role::ReplacementKeeper,
+                    (role, _) => role,
+                });
+        }
+    }
+    assigned_roles
+}
+
+fn is_time_to_reach_kick_position_viable(time_to_reach_kick_position: Duration) -> bool {
+    time_to_reach_kick_position < MAXIMUM_VIABLE_TIME_TO_REACH_KICK_POSITION
+}
+
+fn role_position(
+    role: Role,
+    ball: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    role_positions: &RolePositionsParameters,
+    parameters: &CostBasedRoleAssignmentParameters,
+) -> Point2<Field> {
+    let own_goal_line = -field_dimensions.length / 2.0;
+    let defender_x = own_goal_line + parameters.defender_distance_to_own_goal;
+    match role {
+        Role::DefenderLeft => point![defender_x, role_positions.defender_y_offset],
+        Role::DefenderRight => point![defender_x, -role_positions.defender_y_offset],
+        Role::MidfielderLeft => point![
+            (ball.x() - role_positions.left_midfielder_distance_to_ball)
+                .max(role_positions.left_midfielder_minimum_x),
+            parameters.midfielder_y_offset
+        ],
+        Role::MidfielderRight => point![
+            (ball.x() - role_positions.right_midfielder_distance_to_ball)
+                .max(role_positions.right_midfielder_minimum_x),
+            -parameters.midfielder_y_offset
+        ],
+        Role::StrikerSupporter => point![
+            (ball.x() - role_positions.striker_supporter_distance_to_ball)
+                .max(role_positions.striker_supporter_minimum_x),
+            ball.y()
+        ],
+        Role::Keeper | Role::ReplacementKeeper => {
+            point![own_goal_line + role_positions.keeper_x_offset, 0.0]
+        }
+        Role::Loser | Role::Searcher | Role::Striker => ball,
+    }
+}
+
 fn pick_keeper_or_searcher(
     own_player_number: PlayerNumber,
     filtered_game_controller_state: Option<&FilteredGameControllerState>,
@@ -903,7 +1230,10 @@ fn pick_keeper_or_searcher(
 
 #[cfg(test)]
 mod test {
+    use std::f32::consts::PI;
+
     use proptest::prelude::*;
+    use types::support_foot::Side;
 
     use super::*;
 
@@ -977,4 +1307,253 @@ mod test {
             assert_eq!(new_role, third_role);
         }
     }
+
+    fn cost_based_parameters() -> CostBasedRoleAssignmentParameters {
+        CostBasedRoleAssignmentParameters {
+            enable: true,
+            teammate_pose_timeout: Duration::from_secs(5),
+            walking_speed: 0.3,
+            turning_speed: 0.5,
+            defender_distance_to_own_goal: 1.5,
+            midfielder_y_offset: 1.5,
+            keeper_distance_cost_factor: 2.0,
+        }
+    }
+
+    fn player(player_number: PlayerNumber, pose: Pose2<Field>) -> BroadcastPlayer {
+        BroadcastPlayer {
+            player_number,
+            pose,
+            time_to_reach_kick_position: None,
+        }
+    }
+
+    #[test]
+    fn closest_players_take_the_positioning_roles() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let players = [
+            player(PlayerNumber::One, Pose2::new(point![-4.0, 0.0], 0.0)),
+            player(PlayerNumber::Two, Pose2::new(point![-3.0, -1.0], 0.0)),
+            player(PlayerNumber::Three, Pose2::new(point![-3.0, 1.0], 0.0)),
+        ];
+
+        let roles = assign_roles(
+            &players,
+            None,
+            &[Role::DefenderLeft, Role::DefenderRight],
+            point![0.0, 0.0],
+            &field_dimensions,
+            &RolePositionsParameters {
+                defender_y_offset: 1.0,
+                ..Default::default()
+            },
+            &cost_based_parameters(),
+        );
+
+        assert_eq!(roles[PlayerNumber::One], Some(Role::Keeper));
+        assert_eq!(roles[PlayerNumber::Two], Some(Role::DefenderRight));
+        assert_eq!(roles[PlayerNumber::Three], Some(Role::DefenderLeft));
+    }
+
+    #[test]
+    fn penalized_keeper_is_replaced_by_the_closest_player() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let players = [
+            player(PlayerNumber::Two, Pose2::new(point![1.0, 0.0], 0.0)),
+            player(PlayerNumber::Three, Pose2::new(point![-4.0, 0.5], 0.0)),
+        ];
+
+        let roles = assign_roles(
+            &players,
+            None,
+            &[Role::DefenderLeft, Role::DefenderRight],
+            point![0.0, 0.0],
+            &field_dimensions,
+            &RolePositionsParameters::default(),
+            &cost_based_parameters(),
+        );
+
+        assert_eq!(roles[PlayerNumber::Three], Some(Role::ReplacementKeeper));
+        assert_eq!(roles[PlayerNumber::Two], Some(Role::DefenderLeft));
+        assert_eq!(roles[PlayerNumber::One], None);
+    }
+
+    #[test]
+    fn players_facing_the_role_position_are_preferred() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let players = [
+            player(PlayerNumber::Two, Pose2::new(point![-2.0, 1.0], 0.0)),
+            player(PlayerNumber::Three, Pose2::new(point![-2.0, 1.0], PI)),
+        ];
+
+        let roles = assign_roles(
+            &players,
+            None,
+            &[Role::StrikerSupporter],
+            point![2.0, 1.0],
+            &field_dimensions,
+            &RolePositionsParameters::default(),
+            &cost_based_parameters(),
+        );
+
+        assert_eq!(roles[PlayerNumber::Two], Some(Role::StrikerSupporter));
+        assert_eq!(roles[PlayerNumber::Three], Some(Role::ReplacementKeeper));
+    }
+
+    #[test]
+    fn fastest_player_to_the_kick_position_becomes_striker() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let players = [
+            player(PlayerNumber::One, Pose2::new(point![-4.0, 0.0], 0.0)),
+            BroadcastPlayer {
+                time_to_reach_kick_position: Some(Duration::from_secs(5)),
+                ..player(PlayerNumber::Two, Pose2::new(point![-1.0, 0.0], 0.0))
+            },
+            BroadcastPlayer {
+                time_to_reach_kick_position: Some(Duration::from_secs(3)),
+                ..player(PlayerNumber::Three, Pose2::new(point![-1.0, 1.0], 0.0))
+            },
+        ];
+
+        let roles = assign_roles(
+            &players,
+            None,
+            &[Role::DefenderLeft],
+            point![1.0, 0.0],
+            &field_dimensions,
+            &RolePositionsParameters::default(),
+            &cost_based_parameters(),
+        );
+
+        assert_eq!(roles[PlayerNumber::Three], Some(Role::Striker));
+        assert_eq!(roles[PlayerNumber::One], Some(Role::Keeper));
+        assert_eq!(roles[PlayerNumber::Two], Some(Role::DefenderLeft));
+    }
+
+    #[test]
+    fn no_striker_is_assigned_if_nobody_can_reach_the_ball() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let players = [
+            player(PlayerNumber::One, Pose2::new(point![-4.0, 0.0], 0.0)),
+            BroadcastPlayer {
+                time_to_reach_kick_position: Some(MAXIMUM_VIABLE_TIME_TO_REACH_KICK_POSITION),
+                ..player(PlayerNumber::Two, Pose2::new(point![-1.0, 0.0], 0.0))
+            },
+        ];
+
+        let roles = assign_roles(
+            &players,
+            None,
+            &[Role::DefenderLeft],
+            point![1.0, 0.0],
+            &field_dimensions,
+            &RolePositionsParameters::default(),
+            &cost_based_parameters(),
+        );
+
+        assert_eq!(roles[PlayerNumber::One], Some(Role::Keeper));
+        assert_eq!(roles[PlayerNumber::Two], Some(Role::DefenderLeft));
+    }
+
+    #[test]
+    fn kick_off_striker_is_kept() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let players = [
+            player(PlayerNumber::One, Pose2::new(point![-4.0, 0.0], 0.0)),
+            player(PlayerNumber::Two, Pose2::new(point![-1.0, 0.0], 0.0)),
+            BroadcastPlayer {
+                time_to_reach_kick_position: Some(Duration::from_secs(1)),
+                ..player(PlayerNumber::Three, Pose2::new(point![-1.0, 1.0], 0.0))
+            },
+        ];
+
+        let roles = assign_roles(
+            &players,
+            Some(PlayerNumber::Two),
+            &[Role::DefenderLeft],
+            point![0.0, 0.0],
+            &field_dimensions,
+            &RolePositionsParameters::default(),
+            &cost_based_parameters(),
+        );
+
+        assert_eq!(roles[PlayerNumber::Two], Some(Role::Striker));
+        assert_eq!(roles[PlayerNumber::One], Some(Role::Keeper));
+        assert_eq!(roles[PlayerNumber::Three], Some(Role::DefenderLeft));
+    }
+
+    #[test]
+    fn robots_with_the_same_messages_agree_on_roles() {
+        let field_dimensions = FieldDimensions::SPL_2025;
+        let parameters = cost_based_parameters();
+        let sent = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let received = sent + Duration::from_millis(20);
+        let now = sent + Duration::from_secs(1);
+        let mut penalties = Players::new(None);
+        penalties[PlayerNumber::One] = Some(Penalty::Manual {
+            remaining: Duration::from_secs(30),
+        });
+        let initial_poses = Players::new(InitialPose {
+            center_line_offset_x: -1.0,
+            side: Side::Left,
+        });
+        let two_pose = Pose2::new(point![-3.0, -1.0], 0.0);
+        let three_pose = Pose2::new(point![0.5, 2.0], 0.0);
+        let teammate_poses_of = |own_player_number| {
+            let mut teammate_poses = Players::new(None);
+            for (player_number, pose, time_to_reach_kick_position) in [
+                (PlayerNumber::Two, two_pose, None),
+                (
+                    PlayerNumber::Three,
+                    three_pose,
+                    Some(Duration::from_secs(4)),
+                ),
+            ] {
+                teammate_poses[player_number] = Some(TeammatePose {
+                    pose,
+                    received: if player_number == own_player_number {
+                        sent
+                    } else {
+                        received
+                    },
+                    time_to_reach_kick_position,
+                });
+            }
+            teammate_poses
+        };
+
+        let roles_of = |own_player_number| {
+            let players = broadcast_players(
+                &teammate_poses_of(own_player_number),
+                &penalties,
+                now,
+                parameters.teammate_pose_timeout,
+                &initial_poses,
+                &field_dimensions,
+            );
+            assign_roles(
+                &players,
+                None,
+                &[
+                    Role::DefenderLeft,
+                    Role::DefenderRight,
+                    Role::MidfielderLeft,
+                    Role::MidfielderRight,
+                ],
+                point![1.0, 0.0],
+                &field_dimensions,
+                &RolePositionsParameters::default(),
+                &parameters,
+            )
+        };
+
+        let roles_of_two = roles_of(PlayerNumber::Two);
+        let roles_of_three = roles_of(PlayerNumber::Three);
+
+        assert_eq!(roles_of_two, roles_of_three);
+        assert_eq!(roles_of_two[PlayerNumber::One], None);
+        assert_eq!(roles_of_two[PlayerNumber::Three], Some(Role::Striker));
+        assert!(roles_of_two[PlayerNumber::Two].is_some());
+        assert!(roles_of_two[PlayerNumber::Seven].is_some());
+    }
 }
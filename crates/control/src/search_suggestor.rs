@@ -200,7 +200,7 @@ impl Heatmap {
                     last_seen: time - striker_message.ball_position.age,
                 }),
             ),
-            HulkMessage::Loser(_) | HulkMessage::VisualReferee(_) => return,
+            HulkMessage::Loser(_) | HulkMessage::VisualReferee(_) | HulkMessage::Pass(_) => return,
         };
         if let Some(ball_position) = ball {
             self[ball_position.position] = team_ball_weight;
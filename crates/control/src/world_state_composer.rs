@@ -14,9 +14,11 @@ use types::{
     filtered_game_controller_state::FilteredGameControllerState,
     kick_decision::KickDecision,
     obstacles::Obstacle,
+    pass::{IncomingPass, PassDecision},
     primary_state::PrimaryState,
     roles::Role,
     rule_obstacles::RuleObstacle,
+    tracked_robots::TrackedRobot,
     world_state::{BallState, RobotState, WorldState},
 };
 
@@ -38,6 +40,8 @@ pub struct CycleContext {
     suggested_search_position: Input<Option<Point2<Field>>, "suggested_search_position?">,
     kick_decisions: Input<Option<Vec<KickDecision>>, "kick_decisions?">,
     instant_kick_decisions: Input<Option<Vec<KickDecision>>, "instant_kick_decisions?">,
+    pass_decision: Input<Option<PassDecision>, "pass_decision?">,
+    incoming_pass: Input<Option<IncomingPass>, "incoming_pass?">,
     ground_to_upcoming_support:
         CyclerState<Isometry2<Ground, UpcomingSupport>, "ground_to_upcoming_support">,
 
@@ -47,6 +51,7 @@ pub struct CycleContext {
     has_ground_contact: Input<bool, "has_ground_contact">,
     obstacles: Input<Vec<Obstacle>, "obstacles">,
     rule_obstacles: Input<Vec<RuleObstacle>, "rule_obstacles">,
+    tracked_robots: Input<Vec<TrackedRobot>, "tracked_robots">,
     primary_state: Input<PrimaryState, "primary_state">,
     role: Input<Role, "role">,
     position_of_interest: Input<Point2<Ground>, "position_of_interest">,
@@ -84,10 +89,13 @@ impl WorldStateComposer {
             suggested_search_position: context.suggested_search_position.copied(),
             obstacles: context.obstacles.clone(),
             rule_obstacles: context.rule_obstacles.clone(),
+            tracked_robots: context.tracked_robots.clone(),
             position_of_interest: *context.position_of_interest,
             robot,
             kick_decisions: context.kick_decisions.cloned(),
             instant_kick_decisions: context.instant_kick_decisions.cloned(),
+            pass_decision: context.pass_decision.cloned(),
+            incoming_pass: context.incoming_pass.copied(),
             filtered_game_controller_state: context.filtered_game_controller_state.cloned(),
             hypothetical_ball_positions: context.hypothetical_ball_position.clone(),
             calibration_command: context.calibration_command.copied(),
@@ -46,6 +46,8 @@ pub fn collect_hulk_cyclers(root: impl AsRef<Path>) -> Result<Cyclers, Error> {
                 instances: vec![""],
                 setup_nodes: vec!["control::sensor_data_receiver"],
                 nodes: vec![
+                    // Enable only one of booster_walking_engine, command_sender and
+                    // locomotion_policy, each of them writes the whole LowCommand
                     // "control::active_vision",
                     // "control::ball_filter",
                     // "control::ball_state_composer",
@@ -70,6 +72,7 @@ pub fn collect_hulk_cyclers(root: impl AsRef<Path>) -> Result<Cyclers, Error> {
                     // "control::motion::animation",
                     // "control::motion::arms_up_squat",
                     // "control::motion::arms_up_stand",
+                    // "control::motion::booster_walking_engine",
                     // "control::motion::center_jump",
                     "control::motion::command_sender",
                     // "control::motion::condition_input_provider",
@@ -80,6 +83,7 @@ pub fn collect_hulk_cyclers(root: impl AsRef<Path>) -> Result<Cyclers, Error> {
                     // "control::motion::jump_right",
                     // "control::motion::keeper_jump_left",
                     // "control::motion::keeper_jump_right",
+                    // "control::motion::locomotion_policy",
                     // "control::motion::look_around",
                     // "control::motion::look_at",
                     // "control::motion::motion_selector",
@@ -90,6 +94,7 @@ pub fn collect_hulk_cyclers(root: impl AsRef<Path>) -> Result<Cyclers, Error> {
                     // "control::motion::stand_up_front",
                     // "control::motion::stand_up_sitting",
                     // "control::motion::step_planner",
+                    // "control::motion::walk_command_injector",
                     // "control::motion::walk_manager",
                     // "control::motion::walking_engine",
                     // "control::motion::wide_stance",
@@ -97,10 +102,12 @@ pub fn collect_hulk_cyclers(root: impl AsRef<Path>) -> Result<Cyclers, Error> {
                     // "control::obstacle_receiver",
                     // "control::odometry",
                     // "control::orientation_filter",
+                    // "control::pass_selector",
                     // "control::penalty_shot_direction_estimation",
                     // "control::primary_state_filter",
                     // "control::ready_signal_detection_filter",
                     // "control::referee_position_provider",
+                    // "control::robot_tracker",
                     // "control::role_assignment",
                     // "control::rule_obstacle_composer",
                     // "control::sacrificial_lamb",
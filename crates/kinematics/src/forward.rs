@@ -8,8 +8,12 @@ use coordinate_systems::{
 };
 use linear_algebra::{IntoFramed, Isometry3, Orientation3, Vector3};
 use types::{
-    joints::{arm::ArmJoints, head::HeadJoints, leg::LegJoints},
+    joints::{arm::ArmJoints, head::HeadJoints, leg::LegJoints, Joints},
     robot_dimensions::RobotDimensions,
+    robot_kinematics::{
+        RobotHeadKinematics, RobotKinematics, RobotLeftArmKinematics, RobotLeftLegKinematics,
+        RobotRightArmKinematics, RobotRightLegKinematics, RobotTorsoKinematics,
+    },
 };
 
 pub fn neck_to_robot(angles: &HeadJoints<f32>) -> Isometry3<Neck, Robot> {
@@ -180,3 +184,100 @@ pub fn right_sole_to_robot(angles: &LegJoints<f32>) -> Isometry3<RightSole, Robo
         * right_foot_to_right_ankle(angles)
         * Isometry3::from(RobotDimensions::RIGHT_ANKLE_TO_RIGHT_SOLE)
 }
+
+/// Computes the transformations of all links of the robot from the joint angles
+pub fn robot_kinematics(joints: &Joints<f32>) -> RobotKinematics {
+    // head
+    let neck_to_robot = neck_to_robot(&joints.head);
+    let head_to_robot = neck_to_robot * head_to_neck(&joints.head);
+    // torso
+    let torso_to_robot = Isometry3::from(RobotDimensions::ROBOT_TO_TORSO);
+    // left arm
+    let left_shoulder_to_robot = left_shoulder_to_robot(&joints.left_arm);
+    let left_upper_arm_to_robot =
+        left_shoulder_to_robot * left_upper_arm_to_left_shoulder(&joints.left_arm);
+    let left_elbow_to_robot =
+        left_upper_arm_to_robot * left_elbow_to_left_upper_arm(&joints.left_arm);
+    let left_forearm_to_robot = left_elbow_to_robot * left_forearm_to_left_elbow(&joints.left_arm);
+    let left_wrist_to_robot = left_forearm_to_robot * left_wrist_to_left_forearm(&joints.left_arm);
+    // right arm
+    let right_shoulder_to_robot = right_shoulder_to_robot(&joints.right_arm);
+    let right_upper_arm_to_robot =
+        right_shoulder_to_robot * right_upper_arm_to_right_shoulder(&joints.right_arm);
+    let right_elbow_to_robot =
+        right_upper_arm_to_robot * right_elbow_to_right_upper_arm(&joints.right_arm);
+    let right_forearm_to_robot =
+        right_elbow_to_robot * right_forearm_to_right_elbow(&joints.right_arm);
+    let right_wrist_to_robot =
+        right_forearm_to_robot * right_wrist_to_right_forearm(&joints.right_arm);
+    // left leg
+    let left_pelvis_to_robot = left_pelvis_to_robot(&joints.left_leg);
+    let left_hip_to_robot = left_pelvis_to_robot * left_hip_to_left_pelvis(&joints.left_leg);
+    let left_thigh_to_robot = left_hip_to_robot * left_thigh_to_left_hip(&joints.left_leg);
+    let left_tibia_to_robot = left_thigh_to_robot * left_tibia_to_left_thigh(&joints.left_leg);
+    let left_ankle_to_robot = left_tibia_to_robot * left_ankle_to_left_tibia(&joints.left_leg);
+    let left_foot_to_robot = left_ankle_to_robot * left_foot_to_left_ankle(&joints.left_leg);
+    let left_sole_to_robot =
+        left_foot_to_robot * Isometry3::from(RobotDimensions::LEFT_ANKLE_TO_LEFT_SOLE);
+    // right leg
+    let right_pelvis_to_robot = right_pelvis_to_robot(&joints.right_leg);
+    let right_hip_to_robot = right_pelvis_to_robot * right_hip_to_right_pelvis(&joints.right_leg);
+    let right_thigh_to_robot = right_hip_to_robot * right_thigh_to_right_hip(&joints.right_leg);
+    let right_tibia_to_robot = right_thigh_to_robot * right_tibia_to_right_thigh(&joints.right_leg);
+    let right_ankle_to_robot = right_tibia_to_robot * right_ankle_to_right_tibia(&joints.right_leg);
+    let right_foot_to_robot = right_ankle_to_robot * right_foot_to_right_ankle(&joints.right_leg);
+    let right_sole_to_robot =
+        right_foot_to_robot * Isometry3::from(RobotDimensions::RIGHT_ANKLE_TO_RIGHT_SOLE);
+
+    let head = RobotHeadKinematics {
+        neck_to_robot,
+        head_to_robot,
+    };
+
+    let torso = RobotTorsoKinematics { torso_to_robot };
+
+    let left_arm = RobotLeftArmKinematics {
+        shoulder_to_robot: left_shoulder_to_robot,
+        upper_arm_to_robot: left_upper_arm_to_robot,
+        elbow_to_robot: left_elbow_to_robot,
+        forearm_to_robot: left_forearm_to_robot,
+        wrist_to_robot: left_wrist_to_robot,
+    };
+
+    let right_arm = RobotRightArmKinematics {
+        shoulder_to_robot: right_shoulder_to_robot,
+        upper_arm_to_robot: right_upper_arm_to_robot,
+        elbow_to_robot: right_elbow_to_robot,
+        forearm_to_robot: right_forearm_to_robot,
+        wrist_to_robot: right_wrist_to_robot,
+    };
+
+    let left_leg = RobotLeftLegKinematics {
+        pelvis_to_robot: left_pelvis_to_robot,
+        hip_to_robot: left_hip_to_robot,
+        thigh_to_robot: left_thigh_to_robot,
+        tibia_to_robot: left_tibia_to_robot,
+        ankle_to_robot: left_ankle_to_robot,
+        foot_to_robot: left_foot_to_robot,
+        sole_to_robot: left_sole_to_robot,
+    };
+
+    let right_leg = RobotRightLegKinematics {
+        pelvis_to_robot: right_pelvis_to_robot,
+        hip_to_robot: right_hip_to_robot,
+        thigh_to_robot: right_thigh_to_robot,
+        tibia_to_robot: right_tibia_to_robot,
+        ankle_to_robot: right_ankle_to_robot,
+        foot_to_robot: right_foot_to_robot,
+        sole_to_robot: right_sole_to_robot,
+    };
+
+    RobotKinematics {
+        head,
+        torso,
+        left_arm,
+        right_arm,
+        left_leg,
+        right_leg,
+    }
+}
@@ -0,0 +1,125 @@
+use coordinate_systems::Robot;
+use linear_algebra::Point3;
+
+/// Segments shorter than this are treated as points
+const EPSILON: f32 = 1e-9;
+
+/// Line segment swept by a sphere
+#[derive(Clone, Copy, Debug)]
+pub struct Capsule {
+    pub start: Point3<Robot>,
+    pub end: Point3<Robot>,
+    pub radius: f32,
+}
+
+impl Capsule {
+    /// Depth by which both capsules overlap, negative if they are apart
+    pub fn penetration(&self, other: &Capsule) -> f32 {
+        self.radius + other.radius - segment_distance(self.start, self.end, other.start, other.end)
+    }
+}
+
+/// Distance between the closest points of two segments, see Ericson, Real-Time Collision
+/// Detection, 5.1.9
+fn segment_distance(
+    first_start: Point3<Robot>,
+    first_end: Point3<Robot>,
+    second_start: Point3<Robot>,
+    second_end: Point3<Robot>,
+) -> f32 {
+    let first_direction = first_end - first_start;
+    let second_direction = second_end - second_start;
+    let between_starts = first_start - second_start;
+    let first_length_squared = first_direction.norm_squared();
+    let second_length_squared = second_direction.norm_squared();
+    let second_projection = second_direction.dot(&between_starts);
+
+    let (first_parameter, second_parameter) =
+        if first_length_squared <= EPSILON && second_length_squared <= EPSILON {
+            (0.0, 0.0)
+        } else if first_length_squared <= EPSILON {
+            (
+                0.0,
+                (second_projection / second_length_squared).clamp(0.0, 1.0),
+            )
+        } else {
+            let first_projection = first_direction.dot(&between_starts);
+            if second_length_squared <= EPSILON {
+                (
+                    (-first_projection / first_length_squared).clamp(0.0, 1.0),
+                    0.0,
+                )
+            } else {
+                let directions = first_direction.dot(&second_direction);
+                let denominator =
+                    first_length_squared * second_length_squared - directions * directions;
+                let first_parameter = if denominator > EPSILON {
+                    ((directions * second_projection - first_projection * second_length_squared)
+                        / denominator)
+                        .clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let second_parameter =
+                    (directions * first_parameter + second_projection) / second_length_squared;
+                if second_parameter < 0.0 {
+                    (
+                        (-first_projection / first_length_squared).clamp(0.0, 1.0),
+                        0.0,
+                    )
+                } else if second_parameter > 1.0 {
+                    (
+                        ((directions - first_projection) / first_length_squared).clamp(0.0, 1.0),
+                        1.0,
+                    )
+                } else {
+                    (first_parameter, second_parameter)
+                }
+            }
+        };
+
+    let first_closest = first_start + first_direction * first_parameter;
+    let second_closest = second_start + second_direction * second_parameter;
+    (first_closest - second_closest).norm()
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::point;
+
+    use super::*;
+
+    fn capsule(start: Point3<Robot>, end: Point3<Robot>) -> Capsule {
+        Capsule {
+            start,
+            end,
+            radius: 0.01,
+        }
+    }
+
+    #[test]
+    fn crossing_segments_overlap_by_both_radii() {
+        let first = capsule(point![-1.0, 0.0, 0.0], point![1.0, 0.0, 0.0]);
+        let second = capsule(point![0.0, -1.0, 0.0], point![0.0, 1.0, 0.0]);
+
+        assert!((first.penetration(&second) - 0.02).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parallel_segments_are_apart_by_their_offset() {
+        let first = capsule(point![0.0, 0.0, 0.0], point![1.0, 0.0, 0.0]);
+        let second = capsule(point![0.5, 0.0, 0.1], point![1.5, 0.0, 0.1]);
+
+        assert!((first.penetration(&second) + 0.08).abs() < 1e-6);
+    }
+
+    #[test]
+    fn closest_points_at_segment_ends() {
+        let first = capsule(point![0.0, 0.0, 0.0], point![1.0, 0.0, 0.0]);
+        let second = capsule(point![2.0, 0.0, 0.0], point![2.0, 1.0, 0.0]);
+        let point = capsule(point![1.0, 0.0, 0.5], point![1.0, 0.0, 0.5]);
+
+        assert!((first.penetration(&second) + 0.98).abs() < 1e-6);
+        assert!((first.penetration(&point) + 0.48).abs() < 1e-6);
+    }
+}
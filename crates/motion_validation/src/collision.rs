@@ -0,0 +1,278 @@
+use coordinate_systems::Head;
+use kinematics::forward::robot_kinematics;
+use linear_algebra::{point, Point3};
+use types::{joints::Joints, robot_dimensions::RobotDimensions, support_foot::Side};
+
+use crate::capsule::Capsule;
+
+const HAND_LENGTH: f32 = 0.058;
+const UPPER_ARM_RADIUS: f32 = 0.03;
+const FOREARM_RADIUS: f32 = 0.025;
+const THIGH_RADIUS: f32 = 0.04;
+const TIBIA_RADIUS: f32 = 0.035;
+const FOOT_RADIUS: f32 = 0.025;
+/// Heel and toe of the foot capsule in the sole frame
+const FOOT_BACK: f32 = -0.03;
+const FOOT_FRONT: f32 = 0.075;
+/// Chest and belly between the hips and the shoulders, in the robot frame
+const TORSO_BOTTOM: f32 = 0.02;
+const TORSO_TOP: f32 = 0.16;
+const TORSO_RADIUS: f32 = 0.055;
+/// Center of the head sphere in the head frame
+const HEAD_CENTER: Point3<Head> = point![0.0, 0.0, 0.05];
+const HEAD_RADIUS: f32 = 0.06;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Link {
+    Torso,
+    Head,
+    LeftUpperArm,
+    LeftForearm,
+    RightUpperArm,
+    RightForearm,
+    LeftThigh,
+    LeftTibia,
+    LeftFoot,
+    RightThigh,
+    RightTibia,
+    RightFoot,
+}
+
+impl Link {
+    /// Side of the limb, the torso and the head are in the middle
+    pub fn side(self) -> Option<Side> {
+        match self {
+            Link::Torso | Link::Head => None,
+            Link::LeftUpperArm
+            | Link::LeftForearm
+            | Link::LeftThigh
+            | Link::LeftTibia
+            | Link::LeftFoot => Some(Side::Left),
+            Link::RightUpperArm
+            | Link::RightForearm
+            | Link::RightThigh
+            | Link::RightTibia
+            | Link::RightFoot => Some(Side::Right),
+        }
+    }
+
+    pub fn is_arm(self) -> bool {
+        matches!(
+            self,
+            Link::LeftUpperArm | Link::LeftForearm | Link::RightUpperArm | Link::RightForearm
+        )
+    }
+
+    /// Links of the same limb are connected by joints and always touch, as do the torso and the
+    /// links attached to it
+    fn can_collide_with(self, other: Link) -> bool {
+        match (self.side(), other.side()) {
+            (Some(side), Some(other_side)) => side != other_side || self.is_arm() != other.is_arm(),
+            (None, None) => false,
+            (None, Some(_)) => !(self == Link::Torso && other.is_attached_to_torso()),
+            (Some(_), None) => other.can_collide_with(self),
+        }
+    }
+
+    fn is_attached_to_torso(self) -> bool {
+        matches!(
+            self,
+            Link::LeftUpperArm | Link::RightUpperArm | Link::LeftThigh | Link::RightThigh
+        )
+    }
+}
+
+/// Capsules enclosing the torso, the head, the arms and the legs in the robot frame
+pub fn link_capsules(joints: &Joints<f32>) -> [(Link, Capsule); 12] {
+    let kinematics = robot_kinematics(joints);
+    let capsule = |start: Point3<_>, end: Point3<_>, radius| Capsule { start, end, radius };
+    let head_center = kinematics.head.head_to_robot * HEAD_CENTER;
+
+    [
+        (
+            Link::Torso,
+            capsule(
+                point![RobotDimensions::ROBOT_TO_TORSO.x(), 0.0, TORSO_BOTTOM],
+                point![RobotDimensions::ROBOT_TO_TORSO.x(), 0.0, TORSO_TOP],
+                TORSO_RADIUS,
+            ),
+        ),
+        (Link::Head, capsule(head_center, head_center, HEAD_RADIUS)),
+        (
+            Link::LeftUpperArm,
+            capsule(
+                kinematics.left_arm.shoulder_to_robot.translation(),
+                kinematics.left_arm.elbow_to_robot.translation(),
+                UPPER_ARM_RADIUS,
+            ),
+        ),
+        (
+            Link::LeftForearm,
+            capsule(
+                kinematics.left_arm.elbow_to_robot.translation(),
+                kinematics.left_arm.wrist_to_robot * point![HAND_LENGTH, 0.0, 0.0],
+                FOREARM_RADIUS,
+            ),
+        ),
+        (
+            Link::RightUpperArm,
+            capsule(
+                kinematics.right_arm.shoulder_to_robot.translation(),
+                kinematics.right_arm.elbow_to_robot.translation(),
+                UPPER_ARM_RADIUS,
+            ),
+        ),
+        (
+            Link::RightForearm,
+            capsule(
+                kinematics.right_arm.elbow_to_robot.translation(),
+                kinematics.right_arm.wrist_to_robot * point![HAND_LENGTH, 0.0, 0.0],
+                FOREARM_RADIUS,
+            ),
+        ),
+        (
+            Link::LeftThigh,
+            capsule(
+                kinematics.left_leg.thigh_to_robot.translation(),
+                kinematics.left_leg.tibia_to_robot.translation(),
+                THIGH_RADIUS,
+            ),
+        ),
+        (
+            Link::LeftTibia,
+            capsule(
+                kinematics.left_leg.tibia_to_robot.translation(),
+                kinematics.left_leg.ankle_to_robot.translation(),
+                TIBIA_RADIUS,
+            ),
+        ),
+        (
+            Link::LeftFoot,
+            capsule(
+                kinematics.left_leg.sole_to_robot * point![FOOT_BACK, 0.0, FOOT_RADIUS],
+                kinematics.left_leg.sole_to_robot * point![FOOT_FRONT, 0.0, FOOT_RADIUS],
+                FOOT_RADIUS,
+            ),
+        ),
+        (
+            Link::RightThigh,
+            capsule(
+                kinematics.right_leg.thigh_to_robot.translation(),
+                kinematics.right_leg.tibia_to_robot.translation(),
+                THIGH_RADIUS,
+            ),
+        ),
+        (
+            Link::RightTibia,
+            capsule(
+                kinematics.right_leg.tibia_to_robot.translation(),
+                kinematics.right_leg.ankle_to_robot.translation(),
+                TIBIA_RADIUS,
+            ),
+        ),
+        (
+            Link::RightFoot,
+            capsule(
+                kinematics.right_leg.sole_to_robot * point![FOOT_BACK, 0.0, FOOT_RADIUS],
+                kinematics.right_leg.sole_to_robot * point![FOOT_FRONT, 0.0, FOOT_RADIUS],
+                FOOT_RADIUS,
+            ),
+        ),
+    ]
+}
+
+/// Pairs of overlapping links, together with the depth by which they overlap
+pub fn collisions(joints: &Joints<f32>, include_arms: bool) -> Vec<(Link, Link, f32)> {
+    let capsules: Vec<_> = link_capsules(joints)
+        .into_iter()
+        .filter(|(link, _)| include_arms || !link.is_arm())
+        .collect();
+
+    let mut collisions = Vec::new();
+    for (index, (first_link, first_capsule)) in capsules.iter().enumerate() {
+        for (second_link, second_capsule) in &capsules[index + 1..] {
+            if !first_link.can_collide_with(*second_link) {
+                continue;
+            }
+            let penetration = first_capsule.penetration(second_capsule);
+            if penetration > 0.0 {
+                collisions.push((*first_link, *second_link, penetration));
+            }
+        }
+    }
+    collisions
+}
+
+#[cfg(test)]
+mod tests {
+    use types::joints::{arm::ArmJoints, Joints};
+
+    use super::*;
+
+    fn standing() -> Joints<f32> {
+        Joints {
+            left_arm: ArmJoints {
+                shoulder_pitch: 1.57,
+                shoulder_roll: 0.1,
+                elbow_yaw: -1.57,
+                elbow_roll: -0.1,
+                wrist_yaw: 0.0,
+                hand: 0.0,
+            },
+            right_arm: ArmJoints {
+                shoulder_pitch: 1.57,
+                shoulder_roll: -0.1,
+                elbow_yaw: 1.57,
+                elbow_roll: 0.1,
+                wrist_yaw: 0.0,
+                hand: 0.0,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn standing_pose_is_collision_free() {
+        assert!(collisions(&standing(), true).is_empty());
+    }
+
+    #[test]
+    fn crossed_legs_collide() {
+        let mut joints = standing();
+        joints.left_leg.hip_roll = -0.35;
+        joints.right_leg.hip_roll = 0.35;
+
+        let collisions = collisions(&joints, false);
+
+        assert!(collisions
+            .iter()
+            .any(|(first, second, _)| (*first, *second) == (Link::LeftTibia, Link::RightTibia)));
+    }
+
+    #[test]
+    fn forearm_folded_onto_torso_collides() {
+        let mut joints = standing();
+        joints.left_arm.elbow_yaw = 0.0;
+        joints.left_arm.elbow_roll = -1.5;
+
+        let collisions = collisions(&joints, true);
+
+        assert!(collisions
+            .iter()
+            .any(|(first, second, _)| (*first, *second) == (Link::Torso, Link::LeftForearm)));
+    }
+
+    #[test]
+    fn forearm_folded_over_head_collides() {
+        let mut joints = standing();
+        joints.left_arm.shoulder_pitch = -1.5;
+        joints.left_arm.elbow_yaw = 0.0;
+        joints.left_arm.elbow_roll = -1.5;
+
+        let collisions = collisions(&joints, true);
+
+        assert!(collisions
+            .iter()
+            .any(|(first, second, _)| (*first, *second) == (Link::Head, Link::LeftForearm)));
+    }
+}
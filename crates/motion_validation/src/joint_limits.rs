@@ -0,0 +1,55 @@
+use std::ops::RangeInclusive;
+
+use types::joints::{arm::ArmJoints, head::HeadJoints, leg::LegJoints, Joints, JointsName};
+
+/// Joint limits of the NAO v6 as documented by the manufacturer, the head pitch is the widest
+/// range over all head yaws
+pub const NAO_JOINT_LIMITS: Joints<RangeInclusive<f32>> = Joints {
+    head: HeadJoints {
+        yaw: -2.0857..=2.0857,
+        pitch: -0.672..=0.5149,
+    },
+    left_arm: ArmJoints {
+        shoulder_pitch: -2.0857..=2.0857,
+        shoulder_roll: -0.3142..=1.3265,
+        elbow_yaw: -2.0857..=2.0857,
+        elbow_roll: -1.5446..=-0.0349,
+        wrist_yaw: -1.8238..=1.8238,
+        hand: 0.0..=1.0,
+    },
+    right_arm: ArmJoints {
+        shoulder_pitch: -2.0857..=2.0857,
+        shoulder_roll: -1.3265..=0.3142,
+        elbow_yaw: -2.0857..=2.0857,
+        elbow_roll: 0.0349..=1.5446,
+        wrist_yaw: -1.8238..=1.8238,
+        hand: 0.0..=1.0,
+    },
+    left_leg: LegJoints {
+        ankle_pitch: -1.189516..=0.922747,
+        ankle_roll: -0.39788..=0.769001,
+        hip_pitch: -1.535889..=0.48409,
+        hip_roll: -0.379472..=0.790477,
+        hip_yaw_pitch: -1.145303..=0.74081,
+        knee_pitch: -0.092346..=2.112528,
+    },
+    right_leg: LegJoints {
+        ankle_pitch: -1.186448..=0.932056,
+        ankle_roll: -0.768992..=0.397935,
+        hip_pitch: -1.535889..=0.48409,
+        hip_roll: -0.790477..=0.379472,
+        hip_yaw_pitch: -1.145303..=0.74081,
+        knee_pitch: -0.103083..=2.120198,
+    },
+};
+
+/// Joints outside of their limits, together with the angle by which they exceed them
+pub fn joint_limit_violations(
+    positions: Joints<f32>,
+) -> impl Iterator<Item = (JointsName, f32, f32)> {
+    positions.enumerate().filter_map(|(joint, position)| {
+        let limits = NAO_JOINT_LIMITS[joint].clone();
+        let excess = (limits.start() - position).max(position - limits.end());
+        (excess > 0.0).then_some((joint, position, excess))
+    })
+}
@@ -0,0 +1,503 @@
+use std::{fmt, path::Path, time::Duration};
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use motionfile::{KeyFrame, MotionFile, MotionFileFrame, TimedSpline};
+use splines::Interpolation;
+use types::{
+    joints::{head::HeadJoints, Joints, JointsName},
+    motion_command::KickVariant,
+    motor_commands::MotorCommands,
+    support_foot::Side,
+};
+use walking_engine::{kick_preview::preview_kick, kick_steps::KickSteps, parameters::Parameters};
+
+use crate::{
+    collision::{collisions, Link},
+    joint_limits::joint_limit_violations,
+};
+
+pub mod capsule;
+pub mod collision;
+pub mod joint_limits;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViolationKind {
+    JointLimit {
+        joint: JointsName,
+        position: f32,
+        excess: f32,
+    },
+    Collision {
+        first: Link,
+        second: Link,
+        penetration: f32,
+    },
+}
+
+impl ViolationKind {
+    fn is_same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::JointLimit { joint, .. }, Self::JointLimit { joint: other, .. }) => {
+                joint == other
+            }
+            (
+                Self::Collision { first, second, .. },
+                Self::Collision {
+                    first: other_first,
+                    second: other_second,
+                    ..
+                },
+            ) => first == other_first && second == other_second,
+            _ => false,
+        }
+    }
+
+    fn severity(&self) -> f32 {
+        match self {
+            Self::JointLimit { excess, .. } => *excess,
+            Self::Collision { penetration, .. } => *penetration,
+        }
+    }
+}
+
+/// Consecutive samples of a frame violating the same joint limit or colliding with the same
+/// links, the kind holds the worst sample
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub frame: String,
+    pub start: Duration,
+    pub end: Duration,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} ({:.3}s - {:.3}s): ",
+            self.frame,
+            self.start.as_secs_f32(),
+            self.end.as_secs_f32()
+        )?;
+        match self.kind {
+            ViolationKind::JointLimit {
+                joint,
+                position,
+                excess,
+            } => write!(
+                formatter,
+                "{joint:?} at {position:.3} rad exceeds its limits by {excess:.3} rad"
+            ),
+            ViolationKind::Collision {
+                first,
+                second,
+                penetration,
+            } => write!(
+                formatter,
+                "{first:?} and {second:?} collide by {:.1} mm",
+                penetration * 1000.0
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Violations {
+    open: Vec<Violation>,
+    closed: Vec<Violation>,
+}
+
+impl Violations {
+    fn add_sample(&mut self, frame: &str, time: Duration, kinds: Vec<ViolationKind>) {
+        let (mut open, closed): (Vec<_>, Vec<_>) = self.open.drain(..).partition(|violation| {
+            violation.frame == frame && kinds.iter().any(|kind| kind.is_same(&violation.kind))
+        });
+        self.closed.extend(closed);
+
+        for kind in kinds {
+            match open
+                .iter_mut()
+                .find(|violation| violation.kind.is_same(&kind))
+            {
+                Some(violation) => {
+                    violation.end = time;
+                    if kind.severity() > violation.kind.severity() {
+                        violation.kind = kind;
+                    }
+                }
+                None => open.push(Violation {
+                    frame: frame.to_string(),
+                    start: time,
+                    end: time,
+                    kind,
+                }),
+            }
+        }
+        self.open = open;
+    }
+
+    fn finish(mut self) -> Vec<Violation> {
+        self.closed.append(&mut self.open);
+        self.closed.sort_by_key(|violation| violation.start);
+        self.closed
+    }
+}
+
+fn sample_violations(joints: &Joints<f32>, include_arms: bool) -> Vec<ViolationKind> {
+    let is_leg =
+        |joint: JointsName| matches!(joint, JointsName::LeftLeg(_) | JointsName::RightLeg(_));
+    joint_limit_violations(*joints)
+        .filter(|(joint, _, _)| include_arms || is_leg(*joint))
+        .map(|(joint, position, excess)| ViolationKind::JointLimit {
+            joint,
+            position,
+            excess,
+        })
+        .chain(
+            collisions(joints, include_arms)
+                .into_iter()
+                .map(|(first, second, penetration)| ViolationKind::Collision {
+                    first,
+                    second,
+                    penetration,
+                }),
+        )
+        .collect()
+}
+
+/// Loads a motion file of joint positions or of motor commands, e.g. `jump_right.json`, the
+/// stiffnesses of the latter are dropped
+pub fn load_motion_file(path: impl AsRef<Path>) -> Result<MotionFile<Joints<f32>>> {
+    let error = match MotionFile::from_path(&path) {
+        Ok(motion_file) => return Ok(motion_file),
+        Err(error) => error,
+    };
+    let Ok(motion_file) = MotionFile::<MotorCommands<Joints<f32>>>::from_path(&path) else {
+        return Err(error);
+    };
+    positions_of(motion_file)
+}
+
+fn positions_of(
+    motion_file: MotionFile<MotorCommands<Joints<f32>>>,
+) -> Result<MotionFile<Joints<f32>>> {
+    let interpolation_mode = match motion_file.interpolation_mode {
+        Interpolation::Step(threshold) => Interpolation::Step(threshold),
+        Interpolation::Linear => Interpolation::Linear,
+        Interpolation::Cosine => Interpolation::Cosine,
+        Interpolation::CatmullRom => Interpolation::CatmullRom,
+        Interpolation::Bezier(control) => Interpolation::Bezier(control.positions),
+        Interpolation::StrokeBezier(input, output) => {
+            Interpolation::StrokeBezier(input.positions, output.positions)
+        }
+        mode => bail!("unsupported interpolation mode {mode:?}"),
+    };
+    Ok(MotionFile {
+        interpolation_mode,
+        initial_positions: motion_file.initial_positions.positions,
+        motion: motion_file
+            .motion
+            .into_iter()
+            .map(|frame| MotionFileFrame {
+                name: frame.name,
+                entry_condition: frame.entry_condition,
+                interrupt_conditions: frame.interrupt_conditions,
+                keyframes: frame
+                    .keyframes
+                    .into_iter()
+                    .map(|keyframe| KeyFrame {
+                        duration: keyframe.duration,
+                        positions: keyframe.positions.positions,
+                    })
+                    .collect(),
+                exit_condition: frame.exit_condition,
+                on_exit_timeout: frame.on_exit_timeout,
+            })
+            .collect(),
+    })
+}
+
+/// Samples all frames of the motion one after another and checks them against the joint limits
+/// and for collisions, conditions are not evaluated and take no time
+pub fn validate_motion_file(
+    motion_file: &MotionFile<Joints<f32>>,
+    sample_period: Duration,
+) -> Result<Vec<Violation>> {
+    if sample_period.is_zero() {
+        bail!("sample period must not be zero");
+    }
+
+    let mut violations = Violations::default();
+    let mut frame_start = Duration::ZERO;
+    let mut start_positions = motion_file.initial_positions;
+    for (index, frame) in motion_file.motion.iter().enumerate() {
+        let name = frame
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("frame {index}"));
+        let spline = TimedSpline::try_new_with_start(
+            start_positions,
+            frame.keyframes.clone(),
+            motion_file.interpolation_mode,
+        )
+        .wrap_err_with(|| format!("failed to interpolate {name}"))?;
+
+        let total_duration = spline.total_duration();
+        let mut time = Duration::ZERO;
+        loop {
+            violations.add_sample(
+                &name,
+                frame_start + time,
+                sample_violations(&spline.value_at(time), true),
+            );
+            if time >= total_duration {
+                break;
+            }
+            time = (time + sample_period).min(total_duration);
+        }
+
+        frame_start += total_duration;
+        start_positions = spline.end_position();
+    }
+
+    Ok(violations.finish())
+}
+
+/// Previews all kicks from both feet at full strength through the walking engine and checks the
+/// legs against the joint limits and for collisions, the arms of the preview are not meaningful
+pub fn validate_kick_steps(
+    parameters: &Parameters,
+    kick_steps: &KickSteps,
+    sample_period: Duration,
+) -> Vec<Violation> {
+    let mut all_violations = Vec::new();
+    for variant in [KickVariant::Forward, KickVariant::Turn, KickVariant::Side] {
+        for kicking_side in [Side::Left, Side::Right] {
+            let mut violations = Violations::default();
+            for sample in preview_kick(
+                parameters,
+                kick_steps,
+                variant,
+                kicking_side,
+                1.0,
+                sample_period,
+            ) {
+                let joints = Joints::from_head_and_body(
+                    HeadJoints {
+                        yaw: 0.0,
+                        pitch: 0.0,
+                    },
+                    sample.joints,
+                );
+                violations.add_sample(
+                    &format!(
+                        "{variant:?} kick with {kicking_side:?} foot, step {}",
+                        sample.step_index
+                    ),
+                    sample.time,
+                    sample_violations(&joints, false),
+                );
+            }
+            all_violations.extend(violations.finish());
+        }
+    }
+    all_violations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_dir;
+
+    use linear_algebra::vector;
+    use types::{
+        joints::{arm::ArmJoints, leg::LegJoint},
+        step::Step,
+    };
+    use walking_engine::{
+        kick_steps::{JointOverrides, KickStep},
+        parameters::Base,
+    };
+
+    use super::*;
+
+    fn standing() -> Joints<f32> {
+        Joints {
+            left_arm: ArmJoints {
+                shoulder_pitch: 1.57,
+                shoulder_roll: 0.1,
+                elbow_yaw: -1.57,
+                elbow_roll: -0.1,
+                wrist_yaw: 0.0,
+                hand: 0.0,
+            },
+            right_arm: ArmJoints {
+                shoulder_pitch: 1.57,
+                shoulder_roll: -0.1,
+                elbow_yaw: 1.57,
+                elbow_roll: 0.1,
+                wrist_yaw: 0.0,
+                hand: 0.0,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_joint_limit_violation_with_times() {
+        let mut bent_knee = standing();
+        bent_knee.left_leg.knee_pitch = 2.3;
+        let motion_file = MotionFile {
+            interpolation_mode: Interpolation::Linear,
+            initial_positions: standing(),
+            motion: vec![
+                MotionFileFrame {
+                    name: Some("bend".to_string()),
+                    entry_condition: None,
+                    interrupt_conditions: Vec::new(),
+                    keyframes: vec![KeyFrame {
+                        duration: Duration::from_secs(1),
+                        positions: bent_knee,
+                    }],
+                    exit_condition: None,
+                    on_exit_timeout: None,
+                },
+                MotionFileFrame {
+                    name: None,
+                    entry_condition: None,
+                    interrupt_conditions: Vec::new(),
+                    keyframes: vec![KeyFrame {
+                        duration: Duration::from_secs(1),
+                        positions: standing(),
+                    }],
+                    exit_condition: None,
+                    on_exit_timeout: None,
+                },
+            ],
+        };
+
+        let violations = validate_motion_file(&motion_file, Duration::from_millis(10)).unwrap();
+
+        assert_eq!(violations.len(), 2, "{violations:#?}");
+        assert_eq!(violations[0].frame, "bend");
+        assert_eq!(violations[0].start, Duration::from_millis(920));
+        assert_eq!(violations[0].end, Duration::from_secs(1));
+        assert_eq!(violations[1].frame, "frame 1");
+        assert_eq!(violations[1].start, Duration::from_secs(1));
+        assert_eq!(violations[1].end, Duration::from_millis(1080));
+        let ViolationKind::JointLimit { joint, excess, .. } = violations[0].kind else {
+            panic!("expected a joint limit violation");
+        };
+        assert_eq!(joint, JointsName::LeftLeg(LegJoint::KneePitch));
+        assert!((excess - 0.187472).abs() < 1e-4, "excess: {excess}");
+    }
+
+    fn kick_parameters() -> Parameters {
+        Parameters {
+            base: Base {
+                foot_lift_apex: 0.015,
+                foot_offset_left: vector![0.0, 0.052, 0.0],
+                foot_offset_right: vector![0.0, -0.052, 0.0],
+                step_duration: Duration::from_millis(250),
+                step_midpoint: 0.5,
+                walk_height: 0.23,
+                ..Default::default()
+            },
+            max_foot_speed: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn kick_step(forward: f32, left: f32) -> KickStep {
+        let no_overrides = JointOverrides {
+            hip_pitch: None,
+            knee_pitch: None,
+            ankle_pitch: None,
+        };
+        KickStep {
+            base_step: Step {
+                forward,
+                left,
+                turn: 0.0,
+            },
+            step_duration: Duration::from_millis(250),
+            foot_lift_apex: 0.015,
+            midpoint: 0.5,
+            support_overrides: no_overrides.clone(),
+            swing_overrides: no_overrides,
+        }
+    }
+
+    #[test]
+    fn forward_kick_steps_are_valid() {
+        let kick_steps = KickSteps {
+            forward: vec![kick_step(0.04, 0.0), kick_step(0.0, 0.0)],
+            ..Default::default()
+        };
+
+        let violations =
+            validate_kick_steps(&kick_parameters(), &kick_steps, Duration::from_millis(12));
+
+        assert!(violations.is_empty(), "{violations:#?}");
+    }
+
+    #[test]
+    fn side_kick_steps_crossing_the_support_leg_collide() {
+        let kick_steps = KickSteps {
+            side: vec![kick_step(0.0, 0.15)],
+            ..Default::default()
+        };
+
+        let violations =
+            validate_kick_steps(&kick_parameters(), &kick_steps, Duration::from_millis(12));
+
+        assert!(
+            violations.iter().any(|violation| matches!(
+                violation.kind,
+                ViolationKind::Collision { first, second, .. }
+                    if !first.is_arm() && !second.is_arm()
+            )),
+            "{violations:#?}"
+        );
+        assert!(violations
+            .iter()
+            .all(|violation| violation.frame.starts_with("Side kick")));
+    }
+
+    #[test]
+    fn shipped_motions_are_validated() {
+        let motions = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../etc/motions");
+        let motion_paths: Vec<_> = read_dir(motions)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect();
+        assert!(!motion_paths.is_empty());
+
+        for path in motion_paths {
+            let motion_file =
+                load_motion_file(&path).unwrap_or_else(|error| panic!("{path:?}: {error:?}"));
+            let duration = motion_file
+                .motion
+                .iter()
+                .flat_map(|frame| &frame.keyframes)
+                .map(|keyframe| keyframe.duration)
+                .sum();
+
+            let violations = validate_motion_file(&motion_file, Duration::from_millis(12))
+                .unwrap_or_else(|error| panic!("{path:?}: {error:?}"));
+
+            assert!(
+                violations
+                    .iter()
+                    .all(|violation| violation.start <= violation.end && violation.end <= duration),
+                "{path:?}: {violations:#?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::{
+    condition::{Response, TimeOut},
+    Condition,
+};
+
+use serde::{Deserialize, Serialize};
+use types::condition_input::ConditionInput;
+
+/// Met if all conditions are met, aborts if any condition aborts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllCondition<T> {
+    pub(crate) conditions: Vec<T>,
+}
+
+impl<T: Condition> Condition for AllCondition<T> {
+    fn evaluate(&self, condition_input: &ConditionInput, time_since_start: Duration) -> Response {
+        self.conditions
+            .iter()
+            .map(|condition| condition.evaluate(condition_input, time_since_start))
+            .fold(Response::Continue, |accumulated, current| {
+                match (accumulated, current) {
+                    (Response::Abort, _) | (_, Response::Abort) => Response::Abort,
+                    (Response::Wait, _) | (_, Response::Wait) => Response::Wait,
+                    (Response::Continue, Response::Continue) => Response::Continue,
+                }
+            })
+    }
+}
+
+impl<T: TimeOut> TimeOut for AllCondition<T> {
+    fn timeout(&self, time_since_start: Duration) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| condition.timeout(time_since_start))
+    }
+}
+
+/// Met if any condition is met, aborts only if all conditions abort
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnyCondition<T> {
+    pub(crate) conditions: Vec<T>,
+}
+
+impl<T: Condition> Condition for AnyCondition<T> {
+    fn evaluate(&self, condition_input: &ConditionInput, time_since_start: Duration) -> Response {
+        self.conditions
+            .iter()
+            .map(|condition| condition.evaluate(condition_input, time_since_start))
+            .reduce(|accumulated, current| match (accumulated, current) {
+                (Response::Continue, _) | (_, Response::Continue) => Response::Continue,
+                (Response::Wait, _) | (_, Response::Wait) => Response::Wait,
+                (Response::Abort, Response::Abort) => Response::Abort,
+            })
+            .unwrap_or(Response::Wait)
+    }
+}
+
+impl<T: TimeOut> TimeOut for AnyCondition<T> {
+    fn timeout(&self, time_since_start: Duration) -> bool {
+        !self.conditions.is_empty()
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.timeout(time_since_start))
+    }
+}
+
+/// Met while the condition is not met, aborts if the condition aborts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotCondition<T> {
+    condition: Box<T>,
+}
+
+impl<T: Condition> Condition for NotCondition<T> {
+    fn evaluate(&self, condition_input: &ConditionInput, time_since_start: Duration) -> Response {
+        match self.condition.evaluate(condition_input, time_since_start) {
+            Response::Continue => Response::Wait,
+            Response::Wait => Response::Continue,
+            Response::Abort => Response::Abort,
+        }
+    }
+}
+
+impl<T: TimeOut> TimeOut for NotCondition<T> {
+    fn timeout(&self, time_since_start: Duration) -> bool {
+        self.condition.timeout(time_since_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::from_value;
+    use types::condition_input::ConditionInput;
+
+    use crate::{
+        condition::{Response, TimeOut},
+        Condition, ContinuousConditionType, DiscreteConditionType,
+    };
+
+    fn evaluate(condition: serde_json::Value, condition_input: &ConditionInput) -> Response {
+        from_value::<DiscreteConditionType>(condition)
+            .unwrap()
+            .evaluate(condition_input, Duration::from_secs(1))
+    }
+
+    #[test]
+    fn all_waits_until_every_condition_is_met() {
+        let mut condition_input = ConditionInput::default();
+        condition_input.sole_pressure.left = 1.0;
+        let foot_contact = |side| {
+            serde_json::json!({
+                "FootContactCondition": {
+                    "side": side,
+                    "pressure_threshold": 0.5,
+                    "timeout_duration": 2.0
+                }
+            })
+        };
+        let all = serde_json::json!({
+            "AllCondition": { "conditions": [foot_contact("Left"), foot_contact("Right")] }
+        });
+
+        assert!(matches!(
+            evaluate(all.clone(), &condition_input),
+            Response::Wait
+        ));
+        condition_input.sole_pressure.right = 1.0;
+        assert!(matches!(
+            evaluate(all, &condition_input),
+            Response::Continue
+        ));
+    }
+
+    #[test]
+    fn any_continues_after_timed_wait() {
+        let condition_input = ConditionInput::default();
+        let any = |duration| {
+            serde_json::json!({
+                "AnyCondition": { "conditions": [
+                    { "TorsoAngleCondition": {
+                        "roll": { "start": -0.1, "end": 0.1 },
+                        "pitch": { "start": 0.5, "end": 1.0 },
+                        "timeout_duration": 2.0
+                    } },
+                    { "TimedWaitCondition": { "duration": duration } }
+                ] }
+            })
+        };
+
+        assert!(matches!(
+            evaluate(any(2.0), &condition_input),
+            Response::Wait
+        ));
+        assert!(matches!(
+            evaluate(any(0.5), &condition_input),
+            Response::Continue
+        ));
+    }
+
+    #[test]
+    fn not_inverts_and_keeps_timeout() {
+        let mut condition_input = ConditionInput::default();
+        condition_input.joint_positions.left_leg.knee_pitch = 1.0;
+        let not: DiscreteConditionType = from_value(serde_json::json!({
+            "NotCondition": { "condition": { "StabilizedCondition": {
+                "tolerance": 0.1,
+                "timeout_duration": 0.5
+            } } }
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            not.evaluate(&condition_input, Duration::ZERO),
+            Response::Wait
+        ));
+        assert!(not.timeout(Duration::from_secs(1)));
+
+        let joint_position: ContinuousConditionType = from_value(serde_json::json!({
+            "NotCondition": { "condition": { "JointPositionCondition": {
+                "targets": [{ "joint": { "LeftLeg": "KneePitch" }, "position": 1.0 }],
+                "tolerance": 0.05,
+                "timeout_duration": 2.0
+            } } }
+        }))
+        .unwrap();
+        assert!(matches!(
+            joint_position.evaluate(&condition_input, Duration::ZERO),
+            Response::Wait
+        ));
+    }
+}
@@ -1,6 +1,10 @@
 use std::{fmt::Debug, time::Duration};
 
-use crate::{FallenAbort, NoGroundContactAbort, StabilizedCondition};
+use crate::{
+    AllCondition, AnyCondition, FallenAbort, FootContactCondition, JointPositionCondition,
+    NoGroundContactAbort, NotCondition, StabilizedCondition, TimedWaitCondition,
+    TorsoAngleCondition,
+};
 
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
@@ -24,7 +28,8 @@ impl Response {
 
 #[enum_dispatch]
 pub trait Condition {
-    fn evaluate(&self, condition_input: &ConditionInput) -> Response;
+    /// `time_since_start` is the time since the motion started checking the condition
+    fn evaluate(&self, condition_input: &ConditionInput, time_since_start: Duration) -> Response;
 }
 
 #[enum_dispatch]
@@ -36,6 +41,13 @@ pub trait TimeOut {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DiscreteConditionType {
     StabilizedCondition,
+    JointPositionCondition,
+    TorsoAngleCondition,
+    TimedWaitCondition,
+    FootContactCondition,
+    AllCondition(AllCondition<DiscreteConditionType>),
+    AnyCondition(AnyCondition<DiscreteConditionType>),
+    NotCondition(NotCondition<DiscreteConditionType>),
 }
 
 #[enum_dispatch(Condition)]
@@ -43,4 +55,31 @@ pub enum DiscreteConditionType {
 pub enum ContinuousConditionType {
     FallenAbort,
     NoGroundContactAbort,
+    JointPositionCondition,
+    TorsoAngleCondition,
+    FootContactCondition,
+    AllCondition(AllCondition<ContinuousConditionType>),
+    AnyCondition(AnyCondition<ContinuousConditionType>),
+    NotCondition(NotCondition<ContinuousConditionType>),
+}
+
+impl ContinuousConditionType {
+    /// Interrupt conditions are checked with a frozen time, so a waiting one would pause the motion forever
+    pub fn can_wait(&self) -> bool {
+        match self {
+            ContinuousConditionType::FallenAbort(_)
+            | ContinuousConditionType::NoGroundContactAbort(_) => false,
+            ContinuousConditionType::JointPositionCondition(_)
+            | ContinuousConditionType::TorsoAngleCondition(_)
+            | ContinuousConditionType::FootContactCondition(_)
+            | ContinuousConditionType::NotCondition(_) => true,
+            ContinuousConditionType::AllCondition(all) => {
+                all.conditions.iter().any(ContinuousConditionType::can_wait)
+            }
+            ContinuousConditionType::AnyCondition(any) => {
+                any.conditions.is_empty()
+                    || any.conditions.iter().any(ContinuousConditionType::can_wait)
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use crate::{condition::Response, Condition};
 
@@ -10,7 +10,7 @@ use types::fall_state::FallState::Fallen;
 pub struct FallenAbort {}
 
 impl Condition for FallenAbort {
-    fn evaluate(&self, condition_input: &ConditionInput) -> Response {
+    fn evaluate(&self, condition_input: &ConditionInput, _time_since_start: Duration) -> Response {
         match condition_input.fall_state {
             Fallen { .. } => Response::Abort,
             _ => Response::Continue,
@@ -0,0 +1,43 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::{
+    condition::{Response, TimeOut},
+    motion_file::{deserialize_float_seconds, serialize_float_seconds},
+    Condition,
+};
+
+use serde::{Deserialize, Serialize};
+use types::{condition_input::ConditionInput, support_foot::Side};
+
+/// Met while the sole pressure of the given foot exceeds the threshold, times out after
+/// `timeout_duration` when checked as entry or exit condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FootContactCondition {
+    side: Side,
+    pressure_threshold: f32,
+    #[serde(
+        serialize_with = "serialize_float_seconds",
+        deserialize_with = "deserialize_float_seconds"
+    )]
+    timeout_duration: Duration,
+}
+
+impl Condition for FootContactCondition {
+    fn evaluate(&self, condition_input: &ConditionInput, _time_since_start: Duration) -> Response {
+        let pressure = match self.side {
+            Side::Left => condition_input.sole_pressure.left,
+            Side::Right => condition_input.sole_pressure.right,
+        };
+        if pressure > self.pressure_threshold {
+            Response::Continue
+        } else {
+            Response::Wait
+        }
+    }
+}
+
+impl TimeOut for FootContactCondition {
+    fn timeout(&self, time_since_start: Duration) -> bool {
+        time_since_start > self.timeout_duration
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::{
+    condition::{Response, TimeOut},
+    motion_file::{deserialize_float_seconds, serialize_float_seconds},
+    Condition,
+};
+
+use serde::{Deserialize, Serialize};
+use types::{condition_input::ConditionInput, joints::JointsName};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JointTarget {
+    pub joint: JointsName,
+    pub position: f32,
+}
+
+/// Met as soon as all target joints are within the tolerance of their target positions, times out
+/// after `timeout_duration` when checked as entry or exit condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JointPositionCondition {
+    targets: Vec<JointTarget>,
+    tolerance: f32,
+    #[serde(
+        serialize_with = "serialize_float_seconds",
+        deserialize_with = "deserialize_float_seconds"
+    )]
+    timeout_duration: Duration,
+}
+
+impl Condition for JointPositionCondition {
+    fn evaluate(&self, condition_input: &ConditionInput, _time_since_start: Duration) -> Response {
+        let is_reached = self.targets.iter().all(|target| {
+            (condition_input.joint_positions[target.joint] - target.position).abs()
+                <= self.tolerance
+        });
+        if is_reached {
+            Response::Continue
+        } else {
+            Response::Wait
+        }
+    }
+}
+
+impl TimeOut for JointPositionCondition {
+    fn timeout(&self, time_since_start: Duration) -> bool {
+        time_since_start > self.timeout_duration
+    }
+}
@@ -1,17 +1,28 @@
+pub mod boolean_condition;
 mod condition;
 pub mod fallen_abort_condition;
+pub mod foot_contact_condition;
+pub mod joint_position_condition;
+pub mod motion2;
 pub mod motion_file;
 pub mod motion_interpolator;
 pub mod no_ground_contact_condition;
 pub mod spline_interpolator;
 pub mod stabilized_condition;
 pub mod timed_spline;
+pub mod timed_wait_condition;
+pub mod torso_angle_condition;
 
+pub use boolean_condition::{AllCondition, AnyCondition, NotCondition};
 pub use condition::{Condition, ContinuousConditionType, DiscreteConditionType, Response, TimeOut};
 pub use fallen_abort_condition::FallenAbort;
+pub use foot_contact_condition::FootContactCondition;
+pub use joint_position_condition::{JointPositionCondition, JointTarget};
 pub use motion_file::*;
 pub use motion_interpolator::{InterpolatorState, MotionInterpolator};
 pub use no_ground_contact_condition::NoGroundContactAbort;
 pub use spline_interpolator::SplineInterpolator;
 pub use stabilized_condition::StabilizedCondition;
 pub use timed_spline::TimedSpline;
+pub use timed_wait_condition::TimedWaitCondition;
+pub use torso_angle_condition::TorsoAngleCondition;
@@ -0,0 +1,466 @@
+use std::{collections::HashSet, fs::File, path::Path, time::Duration};
+
+use color_eyre::eyre::{bail, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use serde_json::from_reader;
+use splines::Interpolation;
+use types::joints::{arm::ArmJoint, head::HeadJoint, leg::LegJoint, Joints, JointsName};
+
+use crate::{
+    condition::{ContinuousConditionType, DiscreteConditionType},
+    KeyFrame, MotionFile, MotionFileFrame,
+};
+
+/// Joints in the order of the joint indices used in the header of `.motion2` files
+pub const MOTION2_JOINTS: [JointsName; 26] = [
+    JointsName::Head(HeadJoint::Yaw),
+    JointsName::Head(HeadJoint::Pitch),
+    JointsName::LeftArm(ArmJoint::ShoulderPitch),
+    JointsName::LeftArm(ArmJoint::ShoulderRoll),
+    JointsName::LeftArm(ArmJoint::ElbowYaw),
+    JointsName::LeftArm(ArmJoint::ElbowRoll),
+    JointsName::LeftArm(ArmJoint::WristYaw),
+    JointsName::LeftArm(ArmJoint::Hand),
+    JointsName::LeftLeg(LegJoint::HipYawPitch),
+    JointsName::LeftLeg(LegJoint::HipRoll),
+    JointsName::LeftLeg(LegJoint::HipPitch),
+    JointsName::LeftLeg(LegJoint::KneePitch),
+    JointsName::LeftLeg(LegJoint::AnklePitch),
+    JointsName::LeftLeg(LegJoint::AnkleRoll),
+    JointsName::RightLeg(LegJoint::HipYawPitch),
+    JointsName::RightLeg(LegJoint::HipRoll),
+    JointsName::RightLeg(LegJoint::HipPitch),
+    JointsName::RightLeg(LegJoint::KneePitch),
+    JointsName::RightLeg(LegJoint::AnklePitch),
+    JointsName::RightLeg(LegJoint::AnkleRoll),
+    JointsName::RightArm(ArmJoint::ShoulderPitch),
+    JointsName::RightArm(ArmJoint::ShoulderRoll),
+    JointsName::RightArm(ArmJoint::ElbowYaw),
+    JointsName::RightArm(ArmJoint::ElbowRoll),
+    JointsName::RightArm(ArmJoint::WristYaw),
+    JointsName::RightArm(ArmJoint::Hand),
+];
+
+/// Type of the commands in command based `.motion2` files which set joint positions
+pub const POSITION_COMMAND: u32 = 0;
+
+/// Keyframe motion in the `.motion2` JSON format.
+///
+/// Only position frames are supported, either given directly or as position commands. Stiffness
+/// frames are kept for completeness but not converted.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Motion2 {
+    pub header: Motion2Header,
+    #[serde(default)]
+    pub position: Vec<Motion2Frame>,
+    #[serde(default)]
+    pub stiffness: Vec<Motion2Frame>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<Motion2Command>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Motion2Header {
+    pub version: String,
+    pub title: String,
+    /// Total duration of the motion in milliseconds, the frame times are scaled to match it
+    pub time: f32,
+    /// Joint index of each parameter column, see [`MOTION2_JOINTS`]
+    pub joints: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Motion2Frame {
+    /// Time in milliseconds to reach this frame from the previous one
+    pub time: f32,
+    pub parameters: Vec<f32>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Motion2Command {
+    pub command: Motion2CommandFrame,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Motion2CommandFrame {
+    /// See [`POSITION_COMMAND`]
+    #[serde(rename = "type")]
+    pub command_type: u32,
+    #[serde(flatten)]
+    pub frame: Motion2Frame,
+}
+
+/// Conditions attached to the imported motion
+#[derive(Clone, Debug, Default)]
+pub struct Motion2Conditions {
+    pub entry_condition: Option<DiscreteConditionType>,
+    pub interrupt_conditions: Vec<ContinuousConditionType>,
+    pub exit_condition: Option<DiscreteConditionType>,
+}
+
+impl Motion2 {
+    pub fn from_path(motion2_path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(&motion2_path)
+            .wrap_err_with(|| format!("failed to open motion2 file {:?}", motion2_path.as_ref()))?;
+        from_reader(file)
+            .wrap_err_with(|| format!("failed to parse motion2 file {:?}", motion2_path.as_ref()))
+    }
+
+    /// Converts the position frames into a linearly interpolated motion file.
+    ///
+    /// The first frame becomes the initial positions, all following frames become keyframes of
+    /// a single motion frame named after the title. Frame times are scaled such that all frames
+    /// including the first one sum up to the total time of the header. A single frame only holds
+    /// a pose and results in a motion frame without keyframes.
+    pub fn to_motion_file(&self, conditions: Motion2Conditions) -> Result<MotionFile<Joints<f32>>> {
+        let title = &self.header.title;
+        if self.header.version != "2.0" {
+            bail!(
+                "{title}: unsupported motion2 version {}",
+                self.header.version
+            );
+        }
+        let joint_columns = self.joint_columns()?;
+        let position_frames = self.position_frames();
+        let Some((initial_frame, frames)) = position_frames.split_first() else {
+            bail!("{title}: no position frames");
+        };
+
+        let time_sum: f32 = position_frames.iter().map(|frame| frame.time).sum();
+        if !frames.is_empty() && time_sum <= 0.0 {
+            bail!("{title}: frame times sum up to {time_sum} ms");
+        }
+        let time_scale = self.header.time / time_sum;
+
+        let to_joints = |frame: &Motion2Frame| -> Result<Joints<f32>> {
+            if frame.parameters.len() != joint_columns.len() {
+                bail!(
+                    "{title}: expected {} parameters per frame, got {}",
+                    joint_columns.len(),
+                    frame.parameters.len()
+                );
+            }
+            let mut joints = Joints::default();
+            for (joint, position) in joint_columns.iter().zip(&frame.parameters) {
+                joints[*joint] = *position;
+            }
+            Ok(joints)
+        };
+
+        let keyframes = frames
+            .iter()
+            .enumerate()
+            .map(|(index, &frame)| {
+                let time = frame.time * time_scale / 1000.0;
+                let Ok(duration) = Duration::try_from_secs_f32(time) else {
+                    bail!(
+                        "{title}: frame {} has an invalid time of {time} s",
+                        index + 1
+                    );
+                };
+                Ok(KeyFrame {
+                    duration,
+                    positions: to_joints(frame)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(MotionFile {
+            interpolation_mode: Interpolation::Linear,
+            initial_positions: to_joints(initial_frame)?,
+            motion: vec![MotionFileFrame {
+                name: Some(title.clone()),
+                entry_condition: conditions.entry_condition,
+                interrupt_conditions: conditions.interrupt_conditions,
+                keyframes,
+                exit_condition: conditions.exit_condition,
+                on_exit_timeout: None,
+            }],
+        })
+    }
+
+    /// Flattens the keyframes of all motion frames into position frames, conditions are lost
+    pub fn from_motion_file(
+        title: impl Into<String>,
+        motion_file: &MotionFile<Joints<f32>>,
+    ) -> Self {
+        let to_parameters = |joints: &Joints<f32>| {
+            MOTION2_JOINTS
+                .iter()
+                .map(|joint| joints[*joint])
+                .collect::<Vec<_>>()
+        };
+        let keyframes = motion_file.motion.iter().flat_map(|frame| &frame.keyframes);
+
+        let position: Vec<_> = [Motion2Frame {
+            time: 0.0,
+            parameters: to_parameters(&motion_file.initial_positions),
+        }]
+        .into_iter()
+        .chain(keyframes.map(|keyframe| Motion2Frame {
+            time: keyframe.duration.as_secs_f32() * 1000.0,
+            parameters: to_parameters(&keyframe.positions),
+        }))
+        .collect();
+
+        Self {
+            header: Motion2Header {
+                version: "2.0".to_string(),
+                title: title.into(),
+                time: position.iter().map(|frame| frame.time).sum(),
+                joints: (0..MOTION2_JOINTS.len()).collect(),
+            },
+            position,
+            stiffness: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Position frames, taken from the position commands if there are none
+    fn position_frames(&self) -> Vec<&Motion2Frame> {
+        if !self.position.is_empty() {
+            return self.position.iter().collect();
+        }
+        self.commands
+            .iter()
+            .filter(|command| command.command.command_type == POSITION_COMMAND)
+            .map(|command| &command.command.frame)
+            .collect()
+    }
+
+    /// Joint of each parameter column, every joint has to appear exactly once
+    fn joint_columns(&self) -> Result<Vec<JointsName>> {
+        let title = &self.header.title;
+        let mut seen = HashSet::new();
+        for &index in &self.header.joints {
+            if index >= MOTION2_JOINTS.len() {
+                bail!("{title}: unknown joint index {index}");
+            }
+            if !seen.insert(index) {
+                bail!("{title}: joint index {index} appears more than once");
+            }
+        }
+        if seen.len() != MOTION2_JOINTS.len() {
+            bail!(
+                "{title}: expected all {} joints, got {}",
+                MOTION2_JOINTS.len(),
+                seen.len()
+            );
+        }
+
+        Ok(self
+            .header
+            .joints
+            .iter()
+            .map(|&index| MOTION2_JOINTS[index])
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::read_dir, path::Path, time::Duration};
+
+    use serde_json::{from_value, json};
+    use types::joints::{arm::ArmJoint, leg::LegJoint, Joints, JointsName};
+
+    use crate::{fallen_abort_condition::FallenAbort, MotionFile};
+
+    use super::{Motion2, Motion2Conditions, MOTION2_JOINTS};
+
+    /// Columns in the order head, left arm, right arm, left leg, right leg
+    fn reordered_joints() -> Vec<usize> {
+        (0..8).chain(20..26).chain(8..20).collect()
+    }
+
+    fn motion2(joints: Vec<usize>, times: [f32; 3], total_time: f32) -> Motion2 {
+        let frame = |time: f32, offset: f32| {
+            json!({
+                "time": time,
+                "parameters": (0..26).map(|column| offset + column as f32).collect::<Vec<_>>(),
+            })
+        };
+        from_value(json!({
+            "header": {
+                "version": "2.0",
+                "title": "test",
+                "time": total_time,
+                "joints": joints,
+            },
+            "position": [frame(times[0], 0.0), frame(times[1], 100.0), frame(times[2], 200.0)],
+            "stiffness": [],
+        }))
+        .unwrap()
+    }
+
+    fn assert_same_motion(left: &MotionFile<Joints<f32>>, right: &MotionFile<Joints<f32>>) {
+        assert_eq!(left.initial_positions, right.initial_positions);
+        let left_keyframes: Vec<_> = left
+            .motion
+            .iter()
+            .flat_map(|frame| &frame.keyframes)
+            .collect();
+        let right_keyframes: Vec<_> = right
+            .motion
+            .iter()
+            .flat_map(|frame| &frame.keyframes)
+            .collect();
+        assert_eq!(left_keyframes.len(), right_keyframes.len());
+        for (left, right) in left_keyframes.iter().zip(right_keyframes) {
+            assert_eq!(left.positions, right.positions);
+            assert!((left.duration.as_secs_f32() - right.duration.as_secs_f32()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn joint_indices_are_mapped() {
+        let motion_file = motion2(reordered_joints(), [0.0, 100.0, 100.0], 200.0)
+            .to_motion_file(Motion2Conditions::default())
+            .unwrap();
+
+        let initial_positions = motion_file.initial_positions;
+        // column 8 holds joint index 20
+        assert_eq!(
+            initial_positions[JointsName::RightArm(ArmJoint::ShoulderPitch)],
+            8.0
+        );
+        // column 14 holds joint index 8
+        assert_eq!(
+            initial_positions[JointsName::LeftLeg(LegJoint::HipYawPitch)],
+            14.0
+        );
+        assert_eq!(initial_positions[MOTION2_JOINTS[0]], 0.0);
+    }
+
+    #[test]
+    fn frame_times_are_scaled_to_total_time() {
+        let motion_file = motion2((0..26).collect(), [500.0, 1000.0, 500.0], 1000.0)
+            .to_motion_file(Motion2Conditions::default())
+            .unwrap();
+
+        let durations: Vec<_> = motion_file.motion[0]
+            .keyframes
+            .iter()
+            .map(|keyframe| keyframe.duration)
+            .collect();
+        assert_eq!(
+            durations,
+            [Duration::from_millis(500), Duration::from_millis(250)]
+        );
+    }
+
+    #[test]
+    fn conditions_are_attached() {
+        let motion_file = motion2((0..26).collect(), [0.0, 100.0, 100.0], 200.0)
+            .to_motion_file(Motion2Conditions {
+                interrupt_conditions: vec![FallenAbort {}.into()],
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(motion_file.motion.len(), 1);
+        assert_eq!(motion_file.motion[0].name.as_deref(), Some("test"));
+        assert_eq!(motion_file.motion[0].interrupt_conditions.len(), 1);
+    }
+
+    #[test]
+    fn negative_frame_times_are_rejected() {
+        let error = motion2((0..26).collect(), [0.0, 300.0, -100.0], 200.0)
+            .to_motion_file(Motion2Conditions::default())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("test: frame 2"), "{error}");
+    }
+
+    #[test]
+    fn motion_survives_round_trip() {
+        for joints in [(0..26).collect(), reordered_joints()] {
+            let motion_file = motion2(joints, [0.0, 120.0, 380.0], 500.0)
+                .to_motion_file(Motion2Conditions::default())
+                .unwrap();
+
+            let exported = Motion2::from_motion_file("test", &motion_file);
+            let reimported = exported
+                .to_motion_file(Motion2Conditions::default())
+                .unwrap();
+
+            assert_same_motion(&motion_file, &reimported);
+        }
+    }
+
+    #[test]
+    fn single_frame_becomes_initial_pose() {
+        let mut motion2 = motion2((0..26).collect(), [1.0, 0.0, 0.0], 150.0);
+        motion2.position.truncate(1);
+
+        let motion_file = motion2
+            .to_motion_file(Motion2Conditions::default())
+            .unwrap();
+
+        assert_eq!(motion_file.initial_positions[MOTION2_JOINTS[25]], 25.0);
+        assert_eq!(motion_file.motion.len(), 1);
+        assert!(motion_file.motion[0].keyframes.is_empty());
+    }
+
+    #[test]
+    fn position_commands_are_converted() {
+        let motion2: Motion2 = from_value(json!({
+            "header": {
+                "version": "2.0",
+                "title": "test",
+                "time": 3000.0,
+                "joints": (0..26).collect::<Vec<_>>(),
+            },
+            "commands": [
+                {"command": {"type": 0, "time": 1.0, "parameters": vec![0.0; 26]}},
+                {"command": {"type": 1, "time": 1.0, "parameters": vec![1.0; 26]}},
+                {"command": {"type": 0, "time": 2.0, "parameters": vec![2.0; 26]}},
+            ],
+        }))
+        .unwrap();
+
+        let motion_file = motion2
+            .to_motion_file(Motion2Conditions::default())
+            .unwrap();
+
+        let keyframes = &motion_file.motion[0].keyframes;
+        assert_eq!(keyframes.len(), 1);
+        assert_eq!(keyframes[0].duration, Duration::from_secs(2));
+        assert_eq!(keyframes[0].positions, Joints::fill(2.0));
+    }
+
+    #[test]
+    fn shipped_motions_are_converted() {
+        let motions = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../etc/motions");
+        let motion2_paths: Vec<_> = read_dir(motions)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "motion2")
+            })
+            .collect();
+        assert!(!motion2_paths.is_empty());
+
+        for path in motion2_paths {
+            Motion2::from_path(&path)
+                .and_then(|motion2| motion2.to_motion_file(Motion2Conditions::default()))
+                .unwrap_or_else(|error| panic!("{path:?}: {error:?}"));
+        }
+    }
+
+    #[test]
+    fn invalid_joint_indices_are_rejected() {
+        let mut duplicated = reordered_joints();
+        duplicated[0] = 1;
+        assert!(motion2(duplicated, [0.0, 100.0, 100.0], 200.0)
+            .to_motion_file(Motion2Conditions::default())
+            .is_err());
+
+        let mut unknown = reordered_joints();
+        unknown[0] = 26;
+        assert!(motion2(unknown, [0.0, 100.0, 100.0], 200.0)
+            .to_motion_file(Motion2Conditions::default())
+            .is_err());
+    }
+}
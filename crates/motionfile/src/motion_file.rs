@@ -41,6 +41,9 @@ pub struct MotionFileFrame<T> {
     pub interrupt_conditions: Vec<ContinuousConditionType>,
     pub keyframes: Vec<KeyFrame<T>>,
     pub exit_condition: Option<DiscreteConditionType>,
+    /// Name of the frame to continue with once the exit condition times out, instead of aborting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_exit_timeout: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -53,14 +56,17 @@ pub struct KeyFrame<T> {
     pub positions: T,
 }
 
-fn serialize_float_seconds<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_float_seconds<S>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     serializer.serialize_f32(duration.as_secs_f32())
 }
 
-fn deserialize_float_seconds<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+pub(crate) fn deserialize_float_seconds<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
     D: Deserializer<'de>,
 {
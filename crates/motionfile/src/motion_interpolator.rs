@@ -6,7 +6,10 @@ use crate::{
     timed_spline::{InterpolatorError, TimedSpline},
     Condition, MotionFile,
 };
-use color_eyre::{Report, Result};
+use color_eyre::{
+    eyre::{bail, eyre},
+    Report, Result,
+};
 use itertools::Itertools;
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
@@ -19,6 +22,15 @@ pub struct ConditionedSpline<T> {
     pub interrupt_conditions: Vec<ContinuousConditionType>,
     pub spline: TimedSpline<T>,
     pub exit_condition: Option<DiscreteConditionType>,
+    pub exit_timeout_branch: Option<Branch<T>>,
+}
+
+/// Continues the motion at another frame instead of aborting it once the exit condition times out
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Branch<T> {
+    pub target_frame_index: usize,
+    /// Leads from the end of the branching frame through the keyframes of the target frame
+    pub spline: TimedSpline<T>,
 }
 
 #[derive(Default, Debug, Deserialize, Serialize)]
@@ -42,6 +54,11 @@ pub enum InterpolatorState<T> {
         current_frame_index: usize,
         time_since_start: Duration,
     },
+    InterpolateBranch {
+        source_frame_index: usize,
+        current_frame_index: usize,
+        time_since_start: Duration,
+    },
     Finished,
     Aborted {
         at_position: T,
@@ -67,11 +84,33 @@ impl<T> InterpolatorState<T> {
             | InterpolatorState::CheckExit {
                 current_frame_index,
                 ..
+            }
+            | InterpolatorState::InterpolateBranch {
+                current_frame_index,
+                ..
             } => Some(*current_frame_index),
             _ => None,
         }
     }
 
+    pub fn time_since_start(&self) -> Duration {
+        match self {
+            InterpolatorState::CheckEntry {
+                time_since_start, ..
+            }
+            | InterpolatorState::InterpolateSpline {
+                time_since_start, ..
+            }
+            | InterpolatorState::CheckExit {
+                time_since_start, ..
+            }
+            | InterpolatorState::InterpolateBranch {
+                time_since_start, ..
+            } => *time_since_start,
+            InterpolatorState::Finished | InterpolatorState::Aborted { .. } => Duration::ZERO,
+        }
+    }
+
     pub fn is_aborted(&self) -> bool {
         matches!(self, Self::Aborted { .. })
     }
@@ -82,9 +121,9 @@ impl<T> InterpolatorState<T> {
                 current_frame_index,
                 time_since_start,
             } => *current_frame_index >= 1 || *time_since_start > Duration::ZERO,
-            InterpolatorState::InterpolateSpline { .. } | InterpolatorState::CheckExit { .. } => {
-                true
-            }
+            InterpolatorState::InterpolateSpline { .. }
+            | InterpolatorState::CheckExit { .. }
+            | InterpolatorState::InterpolateBranch { .. } => true,
             InterpolatorState::Finished | InterpolatorState::Aborted { .. } => false,
         }
     }
@@ -123,7 +162,7 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
         {
             return match continuous_conditions
                 .iter()
-                .map(|condition| condition.evaluate(condition_input))
+                .map(|condition| condition.evaluate(condition_input, state.time_since_start()))
                 .reduce(|accumulated, current| match (&accumulated, &current) {
                     (Response::Abort, _) => Response::Abort,
                     (_, Response::Abort) => Response::Abort,
@@ -159,7 +198,7 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                 let current_frame = &self.frames[current_frame_index];
                 match current_frame.entry_condition.as_ref().map(|condition| {
                     condition
-                        .evaluate(condition_input)
+                        .evaluate(condition_input, time_since_start)
                         .with_timeout(condition.timeout(time_since_start))
                 }) {
                     Some(Response::Abort) => InterpolatorState::Aborted {
@@ -197,13 +236,24 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                 time_since_start,
             } => {
                 let current_frame = &self.frames[current_frame_index];
+                let is_timed_out = current_frame
+                    .exit_condition
+                    .as_ref()
+                    .is_some_and(|condition| condition.timeout(time_since_start));
                 match current_frame.exit_condition.as_ref().map(|condition| {
                     condition
-                        .evaluate(condition_input)
+                        .evaluate(condition_input, time_since_start)
                         .with_timeout(condition.timeout(time_since_start))
                 }) {
-                    Some(Response::Abort) => InterpolatorState::Aborted {
-                        at_position: self.value(*state),
+                    Some(Response::Abort) => match &current_frame.exit_timeout_branch {
+                        Some(branch) if is_timed_out => InterpolatorState::InterpolateBranch {
+                            source_frame_index: current_frame_index,
+                            current_frame_index: branch.target_frame_index,
+                            time_since_start: Duration::ZERO,
+                        },
+                        _ => InterpolatorState::Aborted {
+                            at_position: self.value(*state),
+                        },
                     },
                     Some(Response::Wait) => InterpolatorState::CheckExit {
                         current_frame_index,
@@ -218,6 +268,28 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                     _ => InterpolatorState::Finished,
                 }
             }
+            InterpolatorState::InterpolateBranch {
+                source_frame_index,
+                current_frame_index,
+                time_since_start,
+            } => {
+                let is_branch_finished = self.frames[source_frame_index]
+                    .exit_timeout_branch
+                    .as_ref()
+                    .is_none_or(|branch| time_since_start >= branch.spline.total_duration());
+                if is_branch_finished {
+                    InterpolatorState::CheckExit {
+                        current_frame_index,
+                        time_since_start: Duration::ZERO,
+                    }
+                } else {
+                    InterpolatorState::InterpolateBranch {
+                        source_frame_index,
+                        current_frame_index,
+                        time_since_start: time_since_start + time_step,
+                    }
+                }
+            }
             other_state => other_state,
         };
     }
@@ -251,6 +323,14 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                 current_frame_index,
                 ..
             } => self.frames[current_frame_index].spline.end_position(),
+            InterpolatorState::InterpolateBranch {
+                source_frame_index,
+                current_frame_index,
+                time_since_start,
+            } => match &self.frames[source_frame_index].exit_timeout_branch {
+                Some(branch) => branch.spline.value_at(time_since_start),
+                None => self.frames[current_frame_index].spline.end_position(),
+            },
             InterpolatorState::Finished => self.frames.last().unwrap().spline.end_position(),
             InterpolatorState::Aborted { at_position } => at_position,
         }
@@ -282,6 +362,19 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                         time_since_start,
                     ),
                     InterpolatorState::CheckExit { .. } => Duration::ZERO,
+                    InterpolatorState::InterpolateBranch {
+                        source_frame_index,
+                        time_since_start,
+                        ..
+                    } => self.frames[source_frame_index]
+                        .exit_timeout_branch
+                        .as_ref()
+                        .map_or(Duration::ZERO, |branch| {
+                            Duration::saturating_sub(
+                                branch.spline.total_duration(),
+                                time_since_start,
+                            )
+                        }),
                     InterpolatorState::Finished => Duration::ZERO,
                     InterpolatorState::Aborted { .. } => return None,
                 };
@@ -302,7 +395,41 @@ impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<
     type Error = Report;
 
     fn try_from(motion_file: MotionFile<T>) -> Result<Self> {
+        if let Some((index, frame)) = motion_file.motion.iter().enumerate().find(|(_, frame)| {
+            frame
+                .interrupt_conditions
+                .iter()
+                .any(ContinuousConditionType::can_wait)
+        }) {
+            bail!(
+                "frame {} ({:?}) has an interrupt condition that may wait, interrupts may only continue or abort",
+                index,
+                frame.name
+            );
+        }
+
         let interpolation_mode = motion_file.interpolation_mode;
+        let branch_targets = motion_file
+            .motion
+            .iter()
+            .map(|frame| {
+                frame
+                    .on_exit_timeout
+                    .as_ref()
+                    .map(|target_name| {
+                        let target_frame_index = motion_file
+                            .motion
+                            .iter()
+                            .position(|frame| frame.name.as_ref() == Some(target_name))
+                            .ok_or_else(|| eyre!("no frame named {target_name:?} to branch to"))?;
+                        Ok((
+                            target_frame_index,
+                            motion_file.motion[target_frame_index].keyframes.clone(),
+                        ))
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let first_frame = motion_file.motion.first().unwrap();
 
@@ -315,6 +442,7 @@ impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<
                 interpolation_mode,
             )?,
             exit_condition: first_frame.exit_condition.clone(),
+            exit_timeout_branch: None,
         }];
 
         motion_frames.extend(
@@ -332,13 +460,172 @@ impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<
                             interpolation_mode,
                         )?,
                         exit_condition: second_frame.exit_condition,
+                        exit_timeout_branch: None,
                     })
                 })
                 .collect::<Result<Vec<_>, InterpolatorError>>()?,
         );
 
+        for (frame, branch_target) in motion_frames.iter_mut().zip(branch_targets) {
+            if let Some((target_frame_index, keyframes)) = branch_target {
+                frame.exit_timeout_branch = Some(Branch {
+                    target_frame_index,
+                    spline: TimedSpline::try_new_with_start(
+                        frame.spline.end_position(),
+                        keyframes,
+                        interpolation_mode,
+                    )?,
+                });
+            }
+        }
+
         Ok(Self {
             frames: motion_frames,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{from_value, json, Value};
+    use types::condition_input::ConditionInput;
+
+    use crate::{InterpolatorState, MotionFile, MotionInterpolator};
+
+    fn interpolator(exit_condition: Value) -> MotionInterpolator<f32> {
+        let motion_file: MotionFile<f32> = from_value(json!({
+            "initial_positions": 0.0,
+            "motion": [{
+                "keyframes": [{ "duration": 0.1, "positions": 1.0 }],
+                "exit_condition": exit_condition
+            }]
+        }))
+        .unwrap();
+        motion_file.try_into().unwrap()
+    }
+
+    #[test]
+    fn unmet_exit_conditions_abort_after_their_timeout() {
+        let conditions = [
+            json!({ "JointPositionCondition": {
+                "targets": [{ "joint": { "LeftLeg": "KneePitch" }, "position": 1.0 }],
+                "tolerance": 0.05,
+                "timeout_duration": 0.5
+            } }),
+            json!({ "TorsoAngleCondition": {
+                "roll": { "start": -0.1, "end": 0.1 },
+                "pitch": { "start": 0.5, "end": 1.0 },
+                "timeout_duration": 0.5
+            } }),
+            json!({ "FootContactCondition": {
+                "side": "Left",
+                "pressure_threshold": 0.5,
+                "timeout_duration": 0.5
+            } }),
+        ];
+        let time_step = Duration::from_millis(10);
+
+        for condition in conditions {
+            let interpolator = interpolator(condition.clone());
+            let mut state = InterpolatorState::INITIAL;
+            for _ in 0..50 {
+                interpolator.advance_state(&mut state, time_step, &ConditionInput::default());
+            }
+            assert!(!state.is_aborted(), "{condition}");
+            for _ in 0..50 {
+                interpolator.advance_state(&mut state, time_step, &ConditionInput::default());
+            }
+            assert!(state.is_aborted(), "{condition}");
+        }
+    }
+
+    #[test]
+    fn timed_out_exit_condition_branches_to_named_frame() {
+        let unmet_condition = json!({ "FootContactCondition": {
+            "side": "Left",
+            "pressure_threshold": 0.5,
+            "timeout_duration": 0.2
+        } });
+        let motion_file: MotionFile<f32> = from_value(json!({
+            "initial_positions": 0.0,
+            "motion": [
+                {
+                    "keyframes": [{ "duration": 0.1, "positions": 1.0 }],
+                    "exit_condition": unmet_condition,
+                    "on_exit_timeout": "recover"
+                },
+                { "keyframes": [{ "duration": 0.1, "positions": 2.0 }] },
+                { "name": "recover", "keyframes": [{ "duration": 0.5, "positions": -1.0 }] }
+            ]
+        }))
+        .unwrap();
+        let interpolator = MotionInterpolator::try_from(motion_file).unwrap();
+        let time_step = Duration::from_millis(10);
+
+        let mut state = InterpolatorState::INITIAL;
+        let mut values = Vec::new();
+        for _ in 0..200 {
+            interpolator.advance_state(&mut state, time_step, &ConditionInput::default());
+            values.push(interpolator.value(state));
+        }
+
+        assert!(matches!(state, InterpolatorState::Finished), "{state:?}");
+        assert_eq!(interpolator.value(state), -1.0);
+        assert!(values.iter().all(|&value| value < 1.0 + f32::EPSILON));
+        assert!(values
+            .windows(2)
+            .all(|window| (window[1] - window[0]).abs() < 0.15));
+    }
+
+    #[test]
+    fn branch_to_unknown_frame_is_rejected() {
+        let motion_file: MotionFile<f32> = from_value(json!({
+            "initial_positions": 0.0,
+            "motion": [{
+                "keyframes": [{ "duration": 0.1, "positions": 1.0 }],
+                "on_exit_timeout": "missing"
+            }]
+        }))
+        .unwrap();
+
+        assert!(MotionInterpolator::try_from(motion_file).is_err());
+    }
+
+    #[test]
+    fn waiting_interrupt_conditions_are_rejected() {
+        let motion_file = |interrupt_conditions: Value| -> MotionFile<f32> {
+            from_value(json!({
+                "initial_positions": 0.0,
+                "motion": [{
+                    "keyframes": [{ "duration": 0.1, "positions": 1.0 }],
+                    "interrupt_conditions": interrupt_conditions
+                }]
+            }))
+            .unwrap()
+        };
+        let joint_position = json!({ "JointPositionCondition": {
+            "targets": [{ "joint": { "LeftLeg": "KneePitch" }, "position": 1.0 }],
+            "tolerance": 0.05,
+            "timeout_duration": 0.5
+        } });
+
+        assert!(MotionInterpolator::try_from(motion_file(json!([
+            { "FallenAbort": {} },
+            { "AnyCondition": { "conditions": [{ "NoGroundContactAbort": {} }] } }
+        ])))
+        .is_ok());
+        assert!(MotionInterpolator::try_from(motion_file(json!([joint_position]))).is_err());
+        assert!(MotionInterpolator::try_from(motion_file(json!([
+            { "AllCondition": { "conditions": [{ "FallenAbort": {} }, joint_position] } }
+        ])))
+        .is_err());
+        assert!(
+            MotionInterpolator::try_from(motion_file(json!([{ "NotCondition": {
+                "condition": { "FallenAbort": {} }
+            } }])))
+            .is_err()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use crate::{condition::Response, Condition};
 
@@ -9,7 +9,7 @@ use types::condition_input::ConditionInput;
 pub struct NoGroundContactAbort {}
 
 impl Condition for NoGroundContactAbort {
-    fn evaluate(&self, condition_input: &ConditionInput) -> Response {
+    fn evaluate(&self, condition_input: &ConditionInput, _time_since_start: Duration) -> Response {
         if condition_input.ground_contact {
             Response::Continue
         } else {
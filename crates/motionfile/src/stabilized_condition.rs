@@ -30,7 +30,7 @@ where
 }
 
 impl Condition for StabilizedCondition {
-    fn evaluate(&self, condition_input: &ConditionInput) -> Response {
+    fn evaluate(&self, condition_input: &ConditionInput, _time_since_start: Duration) -> Response {
         if condition_input.filtered_angular_velocity.norm() < self.tolerance {
             return Response::Continue;
         }
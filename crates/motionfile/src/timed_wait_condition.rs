@@ -0,0 +1,36 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::{
+    condition::{Response, TimeOut},
+    motion_file::{deserialize_float_seconds, serialize_float_seconds},
+    Condition,
+};
+
+use serde::{Deserialize, Serialize};
+use types::condition_input::ConditionInput;
+
+/// Met once the condition has been checked for the given duration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedWaitCondition {
+    #[serde(
+        serialize_with = "serialize_float_seconds",
+        deserialize_with = "deserialize_float_seconds"
+    )]
+    duration: Duration,
+}
+
+impl Condition for TimedWaitCondition {
+    fn evaluate(&self, _condition_input: &ConditionInput, time_since_start: Duration) -> Response {
+        if time_since_start >= self.duration {
+            Response::Continue
+        } else {
+            Response::Wait
+        }
+    }
+}
+
+impl TimeOut for TimedWaitCondition {
+    fn timeout(&self, _time_since_start: Duration) -> bool {
+        false
+    }
+}
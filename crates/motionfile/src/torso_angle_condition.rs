@@ -0,0 +1,40 @@
+use std::{fmt::Debug, ops::Range, time::Duration};
+
+use crate::{
+    condition::{Response, TimeOut},
+    motion_file::{deserialize_float_seconds, serialize_float_seconds},
+    Condition,
+};
+
+use serde::{Deserialize, Serialize};
+use types::condition_input::ConditionInput;
+
+/// Met while roll and pitch of the torso are within their ranges, times out after
+/// `timeout_duration` when checked as entry or exit condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorsoAngleCondition {
+    roll: Range<f32>,
+    pitch: Range<f32>,
+    #[serde(
+        serialize_with = "serialize_float_seconds",
+        deserialize_with = "deserialize_float_seconds"
+    )]
+    timeout_duration: Duration,
+}
+
+impl Condition for TorsoAngleCondition {
+    fn evaluate(&self, condition_input: &ConditionInput, _time_since_start: Duration) -> Response {
+        let roll_pitch = condition_input.torso_roll_pitch;
+        if self.roll.contains(&roll_pitch.x) && self.pitch.contains(&roll_pitch.y) {
+            Response::Continue
+        } else {
+            Response::Wait
+        }
+    }
+}
+
+impl TimeOut for TorsoAngleCondition {
+    fn timeout(&self, time_since_start: Duration) -> bool {
+        time_since_start > self.timeout_duration
+    }
+}
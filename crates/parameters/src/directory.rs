@@ -10,7 +10,7 @@ use serde_json::{error, from_str, to_string_pretty, to_value, Value};
 
 use hula_types::hardware::Ids;
 
-use super::json::{clone_nested_value, merge_json, prune_equal_branches};
+use super::json::{clone_nested_value, get_nested_value, merge_json, prune_equal_branches};
 
 #[derive(Debug, thiserror::Error)]
 pub enum DirectoryError {
@@ -157,6 +157,67 @@ where
         .map_err(DirectoryError::HeadParametersOfLocationNotSet)
 }
 
+/// Scopes in the order their files are merged by [`deserialize`], later scopes override earlier ones
+pub const MERGE_ORDER: [Scope; 6] = [
+    Scope {
+        location: Location::All,
+        id: Id::All,
+    },
+    Scope {
+        location: Location::Current,
+        id: Id::All,
+    },
+    Scope {
+        location: Location::All,
+        id: Id::Body,
+    },
+    Scope {
+        location: Location::All,
+        id: Id::Head,
+    },
+    Scope {
+        location: Location::Current,
+        id: Id::Body,
+    },
+    Scope {
+        location: Location::Current,
+        id: Id::Head,
+    },
+];
+
+/// The value of a parameter as stored in the file of a single scope
+#[derive(Clone, Debug)]
+pub struct Layer {
+    pub scope: Scope,
+    pub file_path: PathBuf,
+    /// `None` if the file does not exist or does not contain the parameter
+    pub value: Option<Value>,
+}
+
+/// Collects the value of the parameter at `path` from the files of all scopes in merge order
+pub fn layers(
+    parameters_root: impl AsRef<Path>,
+    hardware_ids: &Ids,
+    path: &str,
+) -> Result<Vec<Layer>, SerializationError> {
+    MERGE_ORDER
+        .into_iter()
+        .map(|scope| {
+            let file_path = file_path_from_scope(scope, parameters_root.as_ref(), hardware_ids);
+            let value = if file_path.exists() {
+                get_nested_value(&read_from_file(&file_path)?, path).cloned()
+            } else {
+                None
+            };
+            Ok(Layer {
+                scope,
+                file_path,
+                value,
+            })
+        })
+        .collect()
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Scope {
     pub location: Location,
@@ -58,6 +58,13 @@ pub fn clone_nested_value(value: &Value, path: &str) -> Option<Value> {
     }
 }
 
+pub fn get_nested_value<'value>(value: &'value Value, path: &str) -> Option<&'value Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
 pub fn nest_value_at_path(path: &str, value: Value) -> Value {
     // ("a.b.c", value) -> { a: { b: { c: value } } }
     path.split('.')
@@ -147,6 +154,17 @@ mod tests {
         assert_eq!(copied, Some(value));
     }
 
+    #[test]
+    fn nested_values_are_found_at_paths() {
+        let value = json!({"a":{"b":{"c":42},"d":{"e":1337}}});
+
+        assert_eq!(get_nested_value(&value, "a.b.c"), Some(&json!(42)));
+        assert_eq!(get_nested_value(&value, "a.d"), Some(&json!({"e":1337})));
+        assert_eq!(get_nested_value(&value, ""), Some(&value));
+        assert_eq!(get_nested_value(&value, "a.b.c.too.long"), None);
+        assert_eq!(get_nested_value(&value, "not.matching"), None);
+    }
+
     #[test]
     fn values_are_nested_at_paths() {
         let dataset = [
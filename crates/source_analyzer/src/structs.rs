@@ -62,6 +62,21 @@ impl Structs {
                         source,
                     })?;
             }
+            cycler_structs
+                .cycle_times
+                .insert([
+                    InsertionRule::InsertField {
+                        name: "execution_time_warning_threshold".to_string(),
+                    },
+                    InsertionRule::AppendDataType {
+                        data_type: Type::Verbatim(quote! { Option<std::time::Duration> }),
+                    },
+                ])
+                .map_err(|source| Error::Hierarchy {
+                    node: "execution_time_warning_threshold".to_string(),
+                    cycler: cycler.name.clone(),
+                    source,
+                })?;
 
             for node in cycler.iter_nodes() {
                 for field in node.contexts.main_outputs.iter() {
@@ -2,7 +2,9 @@ use color_eyre::{eyre::Ok, Result};
 use context_attribute::context;
 use framework::MainOutput;
 use serde::{Deserialize, Serialize};
-use spl_network_messages::{HulkMessage, PlayerNumber, StrikerMessage, VisualRefereeMessage};
+use spl_network_messages::{
+    HulkMessage, PassMessage, PlayerNumber, StrikerMessage, VisualRefereeMessage,
+};
 use types::messages::IncomingMessage;
 
 #[derive(Deserialize, Serialize)]
@@ -36,7 +38,8 @@ impl MessageFilter {
                 message @ (HulkMessage::Striker(StrikerMessage { player_number, .. })
                 | HulkMessage::VisualReferee(VisualRefereeMessage {
                     player_number, ..
-                })),
+                })
+                | HulkMessage::Pass(PassMessage { player_number, .. })),
             ) if player_number != context.player_number => Some(IncomingMessage::Spl(*message)),
             _ => None,
         };
@@ -23,6 +23,7 @@ pub enum HulkMessage {
     Striker(StrikerMessage),
     Loser(LoserMessage),
     VisualReferee(VisualRefereeMessage),
+    Pass(PassMessage),
 }
 
 impl Default for HulkMessage {
@@ -45,6 +46,14 @@ pub struct LoserMessage {
     pub pose: Pose2<Field>,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct PassMessage {
+    pub player_number: PlayerNumber,
+    pub pose: Pose2<Field>,
+    pub receiver: PlayerNumber,
+    pub target: Point2<Field>,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 pub struct VisualRefereeMessage {
     pub player_number: PlayerNumber,
@@ -150,4 +159,15 @@ mod tests {
         });
         assert!(bincode::serialize(&test_message).unwrap().len() <= 128)
     }
+
+    #[test]
+    fn hulk_pass_message_size() {
+        let test_message = HulkMessage::Pass(PassMessage {
+            player_number: PlayerNumber::Seven,
+            pose: Pose2::default(),
+            receiver: PlayerNumber::Four,
+            target: Point::origin(),
+        });
+        assert!(bincode::serialize(&test_message).unwrap().len() <= 128)
+    }
 }
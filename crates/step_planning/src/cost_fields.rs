@@ -1,3 +1,4 @@
+pub mod obstacle_clearance;
 pub mod path_distance;
 pub mod path_progress;
 pub mod target_orientation;
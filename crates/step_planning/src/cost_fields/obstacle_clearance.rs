@@ -0,0 +1,220 @@
+use coordinate_systems::Ground;
+use geometry::polygon::is_inside_polygon;
+use linear_algebra::{Point2, Vector2};
+use types::path_obstacles::{rectangle_edges, PathObstacleShape};
+
+pub struct ObstacleClearanceField<'a> {
+    pub obstacles: &'a [PathObstacleShape],
+    pub margin: f32,
+}
+
+impl ObstacleClearanceField<'_> {
+    pub fn cost(&self, point: Point2<Ground>) -> f32 {
+        self.obstacles
+            .iter()
+            .map(|obstacle| {
+                let (clearance, _) = clearance_and_direction(obstacle, point);
+                let violation = (self.margin - clearance).max(0.0);
+
+                violation.powi(2)
+            })
+            .sum()
+    }
+
+    pub fn grad(&self, point: Point2<Ground>) -> Vector2<Ground> {
+        self.obstacles
+            .iter()
+            .map(|obstacle| {
+                let (clearance, direction) = clearance_and_direction(obstacle, point);
+                let violation = (self.margin - clearance).max(0.0);
+
+                direction * (-2.0 * violation)
+            })
+            .sum()
+    }
+}
+
+/// Returns the distance from the obstacle's boundary, which is negative inside circles and
+/// rectangles, and the direction in which it increases
+fn clearance_and_direction(
+    obstacle: &PathObstacleShape,
+    point: Point2<Ground>,
+) -> (f32, Vector2<Ground>) {
+    match obstacle {
+        PathObstacleShape::Circle(circle) => {
+            let center_to_point = point - circle.center;
+            (
+                center_to_point.norm() - circle.radius,
+                normalize_or_zero(center_to_point),
+            )
+        }
+        PathObstacleShape::LineSegment(line_segment) => {
+            let closest_point_to_point = point - line_segment.closest_point(point);
+            (
+                closest_point_to_point.norm(),
+                normalize_or_zero(closest_point_to_point),
+            )
+        }
+        PathObstacleShape::Rectangle(corners) => {
+            let closest_point_to_point = rectangle_edges(corners)
+                .map(|edge| point - edge.closest_point(point))
+                .min_by(|left, right| left.norm().total_cmp(&right.norm()))
+                .expect("rectangle has edges");
+            if is_inside_polygon(corners, &point) {
+                (
+                    -closest_point_to_point.norm(),
+                    -normalize_or_zero(closest_point_to_point),
+                )
+            } else {
+                (
+                    closest_point_to_point.norm(),
+                    normalize_or_zero(closest_point_to_point),
+                )
+            }
+        }
+    }
+}
+
+fn normalize_or_zero(vector: Vector2<Ground>) -> Vector2<Ground> {
+    vector
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector2::zeros)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use proptest::{prop_assume, proptest};
+
+    use coordinate_systems::Ground;
+    use geometry::{circle::Circle, line_segment::LineSegment, polygon::is_inside_polygon};
+    use linear_algebra::{point, vector, Point2, Vector2};
+    use types::path_obstacles::PathObstacleShape;
+
+    use crate::{
+        cost_fields::obstacle_clearance::{clearance_and_direction, ObstacleClearanceField},
+        test_utils::proptest_config,
+    };
+
+    fn test_rectangle() -> [Point2<Ground>; 4] {
+        [
+            point![1.5, 1.5],
+            point![2.5, 1.5],
+            point![2.5, 2.5],
+            point![1.5, 2.5],
+        ]
+    }
+
+    fn test_obstacles() -> [PathObstacleShape; 3] {
+        [
+            PathObstacleShape::Circle(Circle {
+                center: point![1.0, 0.0],
+                radius: 0.5,
+            }),
+            PathObstacleShape::LineSegment(LineSegment(point![-1.0, 1.0], point![-1.0, 2.0])),
+            PathObstacleShape::Rectangle(test_rectangle()),
+        ]
+    }
+
+    #[test]
+    fn test_obstacle_clearance() {
+        let obstacles = test_obstacles();
+        let cost_field = ObstacleClearanceField {
+            obstacles: &obstacles,
+            margin: 0.5,
+        };
+
+        // Far away from all obstacles
+        let sample_point_1 = point![0.0, -2.0];
+        assert_abs_diff_eq!(cost_field.cost(sample_point_1), 0.0);
+        assert_abs_diff_eq!(cost_field.grad(sample_point_1), Vector2::zeros());
+
+        // On the circle boundary
+        let sample_point_2 = point![1.0, 0.5];
+        assert_abs_diff_eq!(cost_field.cost(sample_point_2), 0.25);
+        assert_abs_diff_eq!(cost_field.grad(sample_point_2), vector![0.0, -1.0]);
+
+        // Inside the circle
+        let sample_point_3 = point![0.75, 0.0];
+        assert_abs_diff_eq!(cost_field.cost(sample_point_3), 0.5625);
+        assert_abs_diff_eq!(cost_field.grad(sample_point_3), vector![1.5, 0.0]);
+
+        // Next to the line segment
+        let sample_point_4 = point![-0.75, 1.5];
+        assert_abs_diff_eq!(cost_field.cost(sample_point_4), 0.0625);
+        assert_abs_diff_eq!(cost_field.grad(sample_point_4), vector![-0.5, 0.0]);
+
+        // Beyond the end of the line segment
+        let sample_point_5 = point![-1.0, 2.25];
+        assert_abs_diff_eq!(cost_field.cost(sample_point_5), 0.0625);
+        assert_abs_diff_eq!(cost_field.grad(sample_point_5), vector![0.0, -0.5]);
+
+        // Inside the rectangle, close to its left edge
+        let sample_point_6 = point![1.6, 2.0];
+        assert_abs_diff_eq!(cost_field.cost(sample_point_6), 0.36, epsilon = 1e-6);
+        assert_abs_diff_eq!(
+            cost_field.grad(sample_point_6),
+            vector![1.2, 0.0],
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn step_inside_rectangle_is_pushed_out() {
+        let obstacles = [PathObstacleShape::Rectangle(test_rectangle())];
+        let cost_field = ObstacleClearanceField {
+            obstacles: &obstacles,
+            margin: 0.1,
+        };
+
+        let mut step = point![2.1, 1.95];
+        for _ in 0..100 {
+            step -= cost_field.grad(step) * 0.1;
+        }
+
+        assert!(!is_inside_polygon(&test_rectangle(), &step), "{step:?}");
+        assert!(step.x() > 2.5, "{step:?}");
+    }
+
+    proptest! {
+        #![proptest_config(proptest_config())]
+        #[test]
+        fn verify_gradient(x in -2.0f32..3.0, y in -2.0f32..3.0) {
+            prop_assume!(!is_near_kink(point![x, y]));
+            verify_gradient_impl(x, y)
+        }
+    }
+
+    fn is_near_kink(point: Point2<Ground>) -> bool {
+        let is_near_rectangle_diagonal =
+            ((point.x() - 2.0).abs() - (point.y() - 2.0).abs()).abs() < 1e-2;
+        test_obstacles().iter().any(|obstacle| {
+            let (clearance, _) = clearance_and_direction(obstacle, point);
+            match obstacle {
+                PathObstacleShape::Circle(circle) => (point - circle.center).norm() < 1e-2,
+                PathObstacleShape::LineSegment(_) => clearance < 1e-2,
+                PathObstacleShape::Rectangle(corners) => {
+                    clearance.abs() < 1e-2
+                        || is_inside_polygon(corners, &point) && is_near_rectangle_diagonal
+                }
+            }
+        })
+    }
+
+    fn verify_gradient_impl(x: f32, y: f32) {
+        let obstacles = test_obstacles();
+        let cost_field = ObstacleClearanceField {
+            obstacles: &obstacles,
+            margin: 0.5,
+        };
+
+        let point = point![x, y];
+
+        crate::test_utils::verify_gradient::verify_gradient(
+            &|p| cost_field.cost(p),
+            &|p| cost_field.grad(p),
+            0.05,
+            point,
+        )
+    }
+}
@@ -8,6 +8,7 @@ use linear_algebra::{point, Orientation2};
 use types::{
     motion_command::OrientationMode,
     parameters::StepPlanningOptimizationParameters,
+    path_obstacles::PathObstacleShape,
     planned_path::{Path, PathSegment},
     support_foot::Side,
     walk_volume_extents::WalkVolumeExtents,
@@ -15,8 +16,9 @@ use types::{
 
 use crate::{
     cost_fields::{
-        path_distance::PathDistanceField, path_progress::PathProgressField,
-        target_orientation::TargetOrientationField, walk_orientation::WalkOrientationField,
+        obstacle_clearance::ObstacleClearanceField, path_distance::PathDistanceField,
+        path_progress::PathProgressField, target_orientation::TargetOrientationField,
+        walk_orientation::WalkOrientationField,
     },
     geometry::{
         orientation::Orientation,
@@ -36,8 +38,6 @@ pub mod utils;
 pub mod test_utils;
 
 pub const VARIABLES_PER_STEP: usize = 3;
-pub const NUM_STEPS: usize = 5;
-pub const NUM_VARIABLES: usize = NUM_STEPS * VARIABLES_PER_STEP;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TargetOrientationPathSide {
@@ -49,6 +49,7 @@ pub enum TargetOrientationPathSide {
 #[derive(Clone, Debug)]
 pub struct StepPlanning<'a> {
     pub path: &'a Path,
+    pub obstacles: &'a [PathObstacleShape],
     pub target_orientation: Orientation<f32>,
     pub target_orientation_path_side: TargetOrientationPathSide,
     pub distance_to_be_aligned: f32,
@@ -92,6 +93,8 @@ impl StepPlanning<'_> {
             self.path_progress().cost(progress, path_length) * cost_factors.path_progress;
         let path_distance_cost =
             self.path_distance().cost(pose.position) * cost_factors.path_distance;
+        let obstacle_clearance_cost =
+            self.obstacle_clearance().cost(pose.position) * cost_factors.obstacle_clearance;
         let walk_orientation_cost = self.walk_orientation().cost(pose.clone(), forward)
             * cost_factors.walk_orientation
             * walk_alignment_importance;
@@ -99,7 +102,11 @@ impl StepPlanning<'_> {
             * cost_factors.target_orientation
             * target_alignment_importance;
 
-        path_progress_cost + path_distance_cost + walk_orientation_cost + target_orientation_cost
+        path_progress_cost
+            + path_distance_cost
+            + obstacle_clearance_cost
+            + walk_orientation_cost
+            + target_orientation_cost
     }
 
     pub fn grad(&self, pose: Pose<f32>) -> PoseGradient<f32> {
@@ -116,6 +123,8 @@ impl StepPlanning<'_> {
             self.path_progress().grad(progress, forward, path_length) * cost_factors.path_progress;
         let path_distance_gradient =
             self.path_distance().grad(pose.position) * cost_factors.path_distance;
+        let obstacle_clearance_gradient =
+            self.obstacle_clearance().grad(pose.position) * cost_factors.obstacle_clearance;
         let walk_orientation_gradient = self.walk_orientation().grad(pose.clone(), forward)
             * cost_factors.walk_orientation
             * walk_alignment_importance;
@@ -124,7 +133,7 @@ impl StepPlanning<'_> {
             * target_alignment_importance;
 
         PoseGradient {
-            position: path_progress_gradient + path_distance_gradient,
+            position: path_progress_gradient + path_distance_gradient + obstacle_clearance_gradient,
             ..PoseGradient::zeros()
         } + walk_orientation_gradient
             + target_orientation_gradient
@@ -150,6 +159,13 @@ impl StepPlanning<'_> {
         PathDistanceField { path: self.path }
     }
 
+    fn obstacle_clearance(&self) -> ObstacleClearanceField<'_> {
+        ObstacleClearanceField {
+            obstacles: self.obstacles,
+            margin: self.parameters.obstacle_clearance_margin,
+        }
+    }
+
     fn path_progress(&self) -> PathProgressField {
         PathProgressField {
             smoothness: self.parameters.path_progress_smoothness,
@@ -6,8 +6,9 @@ use geometry::line_segment::LineSegment;
 use linear_algebra::{point, Orientation2, Point2};
 use step_planning::{
     geometry::{orientation::Orientation, pose::Pose},
-    test_path, NUM_VARIABLES,
+    test_path, VARIABLES_PER_STEP,
 };
+use step_planning_solver::StepPlanningSolver;
 use types::{
     motion_command::OrientationMode,
     parameters::{StepPlanningCostFactors, StepPlanningOptimizationParameters},
@@ -19,15 +20,18 @@ use types::{
 fn plan_steps(path: &Path) {
     const STEP_PLANNING_OPTIMIZATION_PARAMETERS: StepPlanningOptimizationParameters =
         StepPlanningOptimizationParameters {
+            num_steps: 5,
             optimizer_steps: 20,
             cost_factors: StepPlanningCostFactors {
                 path_progress: 0.5,
                 path_distance: 10.0,
+                obstacle_clearance: 50.0,
                 target_orientation: 1.0,
                 walk_orientation: 0.1,
             },
             path_alignment_tolerance: FRAC_PI_2,
             path_progress_smoothness: 0.05,
+            obstacle_clearance_margin: 0.1,
             target_orientation_ahead_tolerance: 0.5,
             target_orientation_side_alignment_tolerance: 1.4,
             hybrid_align_distance: 0.1,
@@ -44,23 +48,28 @@ fn plan_steps(path: &Path) {
 
     let distance_to_be_aligned = 0.1;
 
-    let mut variables = [0.0; NUM_VARIABLES];
+    let mut variables =
+        vec![0.0; STEP_PLANNING_OPTIMIZATION_PARAMETERS.num_steps * VARIABLES_PER_STEP];
 
-    let (_, _) = step_planning_solver::plan_steps(
-        path,
-        OrientationMode::Unspecified,
-        Orientation2::identity(),
-        distance_to_be_aligned,
-        Pose {
-            position: Point2::origin(),
-            orientation: Orientation(0.0),
-        },
-        Side::Left,
-        &mut variables,
-        &black_box(WALK_VOLUME_EXTENTS),
-        &black_box(STEP_PLANNING_OPTIMIZATION_PARAMETERS),
-    )
-    .unwrap();
+    let mut solver = StepPlanningSolver::default();
+
+    let (_, _) = solver
+        .plan_steps(
+            path,
+            &[],
+            OrientationMode::Unspecified,
+            Orientation2::identity(),
+            distance_to_be_aligned,
+            Pose {
+                position: Point2::origin(),
+                orientation: Orientation(0.0),
+            },
+            Side::Left,
+            &mut variables,
+            &black_box(WALK_VOLUME_EXTENTS),
+            &black_box(STEP_PLANNING_OPTIMIZATION_PARAMETERS),
+        )
+        .unwrap();
 }
 
 fn straight_line(c: &mut Criterion) {
@@ -1,8 +1,11 @@
 use std::{array, f32::consts::PI};
 
-use color_eyre::Result;
+use color_eyre::{
+    eyre::{bail, ensure},
+    Result,
+};
 use geometry::direction::Direction;
-use nalgebra::{Const, SVector, U1};
+use nalgebra::{Const, DVector, SVector, U1};
 use num_dual::{Derivative, DualNum, DualNumFloat, DualVec};
 use optimization_engine::{
     constraints::Constraint,
@@ -16,11 +19,13 @@ use step_planning::{
     geometry::{angle::Angle, orientation::Orientation, pose::Pose},
     step_plan::StepPlan,
     traits::{ForwardAtEndPoint, ScaledGradient, WrapDual},
-    StepPlanning, TargetOrientationPathSide, NUM_VARIABLES, VARIABLES_PER_STEP,
+    StepPlanning, TargetOrientationPathSide, VARIABLES_PER_STEP,
 };
 use types::{
     motion_command::OrientationMode, parameters::StepPlanningOptimizationParameters,
-    planned_path::Path, support_foot::Side, walk_volume_extents::WalkVolumeExtents,
+    path_obstacles::PathObstacleShape, planned_path::Path,
+    step_planning::StepPlanningSolverStatistics, support_foot::Side,
+    walk_volume_extents::WalkVolumeExtents,
 };
 
 struct WalkVolumeConstraint;
@@ -44,11 +49,30 @@ impl Constraint for WalkVolumeConstraint {
     }
 }
 
-fn duals<F: DualNumFloat + DualNum<F>>(
-    reals: &[F],
-) -> [DualVec<F, F, Const<NUM_VARIABLES>>; NUM_VARIABLES] {
-    debug_assert_eq!(reals.len(), NUM_VARIABLES);
+/// Largest step planning horizon the solver is compiled for
+pub const MAX_NUM_STEPS: usize = 8;
+
+/// Solves with the number of variables of the horizon as const generic, keeping the dual numbers
+/// and gradients on the stack
+macro_rules! solve_for_horizon {
+    ($num_steps:expr, [$($supported_num_steps:literal),+], $($argument:expr),+ $(,)?) => {
+        match $num_steps {
+            $(
+                $supported_num_steps => {
+                    let (gradient, statistics) = solve::<
+                        { $supported_num_steps * VARIABLES_PER_STEP },
+                    >($($argument),+);
+                    (DVector::from_column_slice(gradient.as_slice()), statistics)
+                }
+            )+
+            num_steps => bail!("no solver compiled for a horizon of {num_steps} steps"),
+        }
+    };
+}
 
+fn duals<F: DualNumFloat + DualNum<F>, const NUM_VARIABLES: usize>(
+    reals: &[F; NUM_VARIABLES],
+) -> [DualVec<F, F, Const<NUM_VARIABLES>>; NUM_VARIABLES] {
     array::from_fn(|row| {
         let real = reals[row];
         DualVec::new(
@@ -80,7 +104,7 @@ fn cost(variables: &[f32], step_planning: &StepPlanning) -> f32 {
     cost
 }
 
-fn open_cost(
+fn open_cost<const NUM_VARIABLES: usize>(
     step_planning: &StepPlanning,
     variables: &[f64],
     out_cost: &mut f64,
@@ -95,7 +119,7 @@ fn open_cost(
     Ok(())
 }
 
-fn gradient(
+fn gradient<const NUM_VARIABLES: usize>(
     variables: &[f32; NUM_VARIABLES],
     step_planning: &StepPlanning,
 ) -> SVector<f32, NUM_VARIABLES> {
@@ -124,7 +148,7 @@ fn gradient(
     normalize_gradient(gradient, 2.0)
 }
 
-fn open_gradient(
+fn open_gradient<const NUM_VARIABLES: usize>(
     step_planning: &StepPlanning,
     variables: &[f64],
     out_gradient: &mut [f64],
@@ -143,73 +167,138 @@ fn open_gradient(
     Ok(())
 }
 
-#[expect(clippy::too_many_arguments)]
-pub fn plan_steps(
-    path: &Path,
-    orientation_mode: OrientationMode,
-    target_orientation: Orientation2<Ground>,
-    distance_to_be_aligned: f32,
-    initial_pose: Pose<f32>,
-    initial_support_foot: Side,
-    variables: &mut [f64; NUM_VARIABLES],
-    walk_volume_extents: &WalkVolumeExtents,
-    parameters: &StepPlanningOptimizationParameters,
-) -> Result<(SVector<f32, NUM_VARIABLES>, f32)> {
-    let target_orientation = Orientation(target_orientation.angle());
-    let target_orientation_path_side = calculate_target_orientation_path_side(
-        path,
-        target_orientation,
-        parameters.target_orientation_ahead_tolerance,
-    );
+/// Plans steps with PANOC, keeping the solver's memory allocated across cycles
+#[derive(Default)]
+pub struct StepPlanningSolver {
+    panoc_cache: Option<(usize, PANOCCache)>,
+}
 
-    let step_planning = StepPlanning {
-        path,
-        initial_pose: initial_pose.clone(),
-        initial_support_foot,
-        parameters,
-        orientation_mode,
-        target_orientation,
-        target_orientation_path_side,
-        distance_to_be_aligned,
-        walk_volume_extents,
-    };
+impl StepPlanningSolver {
+    /// Optimizes `variables` in place, starting from their current values
+    ///
+    /// The number of variables must match the horizon of `parameters.num_steps` steps.
+    #[expect(clippy::too_many_arguments)]
+    pub fn plan_steps(
+        &mut self,
+        path: &Path,
+        obstacles: &[PathObstacleShape],
+        orientation_mode: OrientationMode,
+        target_orientation: Orientation2<Ground>,
+        distance_to_be_aligned: f32,
+        initial_pose: Pose<f32>,
+        initial_support_foot: Side,
+        variables: &mut [f64],
+        walk_volume_extents: &WalkVolumeExtents,
+        parameters: &StepPlanningOptimizationParameters,
+    ) -> Result<(DVector<f32>, Option<StepPlanningSolverStatistics>)> {
+        let num_variables = parameters.num_steps * VARIABLES_PER_STEP;
+        ensure!(
+            (1..=MAX_NUM_STEPS).contains(&parameters.num_steps),
+            "step planning horizon must contain between 1 and {MAX_NUM_STEPS} steps"
+        );
+        ensure!(
+            variables.len() == num_variables,
+            "expected {num_variables} variables for {} steps, got {}",
+            parameters.num_steps,
+            variables.len()
+        );
 
+        let target_orientation = Orientation(target_orientation.angle());
+        let target_orientation_path_side = calculate_target_orientation_path_side(
+            path,
+            target_orientation,
+            parameters.target_orientation_ahead_tolerance,
+        );
+
+        let step_planning = StepPlanning {
+            path,
+            obstacles,
+            initial_pose: initial_pose.clone(),
+            initial_support_foot,
+            parameters,
+            orientation_mode,
+            target_orientation,
+            target_orientation_path_side,
+            distance_to_be_aligned,
+            walk_volume_extents,
+        };
+
+        let panoc_cache = match &mut self.panoc_cache {
+            Some((cache_size, panoc_cache)) if *cache_size == num_variables => panoc_cache,
+            panoc_cache => {
+                &mut panoc_cache
+                    .insert((num_variables, new_panoc_cache(num_variables)))
+                    .1
+            }
+        };
+
+        let (gradient, statistics) = solve_for_horizon!(
+            parameters.num_steps,
+            [1, 2, 3, 4, 5, 6, 7, 8],
+            &step_planning,
+            variables,
+            panoc_cache,
+            parameters.optimizer_steps,
+        );
+
+        Ok((gradient, statistics))
+    }
+}
+
+fn solve<const NUM_VARIABLES: usize>(
+    step_planning: &StepPlanning,
+    variables: &mut [f64],
+    panoc_cache: &mut PANOCCache,
+    optimizer_steps: usize,
+) -> (
+    SVector<f32, NUM_VARIABLES>,
+    Option<StepPlanningSolverStatistics>,
+) {
     let problem = Problem::new(
         &WalkVolumeConstraint,
-        |variables, out_gradient| open_gradient(&step_planning, variables, out_gradient),
-        |variables, out_cost| open_cost(&step_planning, variables, out_cost),
+        |variables, out_gradient| {
+            open_gradient::<NUM_VARIABLES>(step_planning, variables, out_gradient)
+        },
+        |variables, out_cost| open_cost::<NUM_VARIABLES>(step_planning, variables, out_cost),
     );
 
-    let lbfgs_memory = 10;
-    let tolerance = 1e-6;
-    let mut panoc_cache = PANOCCache::new(NUM_VARIABLES, tolerance, lbfgs_memory)
-        .with_cbfgs_parameters(
-            // These parameters are needed to fix occasional instability.
-            // This would probably not be necessary if we wouldn't be casting between f32 and f64
-            // in the solver interface.
-            // TODO(rmburg): Either use f32 in the solver or f64 in step planning
-            1.0,  // default
-            1e-8, // default
-            1e-6, // reduced from 1e-10
-        );
-
-    let mut panoc =
-        PANOCOptimizer::new(problem, &mut panoc_cache).with_max_iter(parameters.optimizer_steps);
+    let mut panoc = PANOCOptimizer::new(problem, panoc_cache).with_max_iter(optimizer_steps);
 
-    let cost = match panoc.solve(variables) {
-        Ok(status) => status.cost_value() as f32,
+    let statistics = match panoc.solve(variables) {
+        Ok(status) => Some(StepPlanningSolverStatistics {
+            iterations: status.iterations(),
+            cost: status.cost_value() as f32,
+            has_converged: status.has_converged(),
+            fixed_point_residual_norm: status.norm_fpr() as f32,
+            solve_time: status.solve_time(),
+        }),
         Err(e) => {
             eprint!("PANOC error: {e:?}");
-            -1.0
+            None
         }
     };
 
-    let variables = array::from_fn(|i| variables[i] as f32);
+    let variables: [f32; NUM_VARIABLES] = array::from_fn(|i| variables[i] as f32);
 
     // TODO(rmburg) remove/refactor
-    let gradient = gradient(&variables, &step_planning);
+    let gradient = gradient(&variables, step_planning);
+
+    (gradient, statistics)
+}
+
+fn new_panoc_cache(num_variables: usize) -> PANOCCache {
+    let lbfgs_memory = 10;
+    let tolerance = 1e-6;
 
-    Ok((gradient, cost))
+    PANOCCache::new(num_variables, tolerance, lbfgs_memory).with_cbfgs_parameters(
+        // These parameters are needed to fix occasional instability.
+        // This would probably not be necessary if we wouldn't be casting between f32 and f64
+        // in the solver interface.
+        // TODO(rmburg): Either use f32 in the solver or f64 in step planning
+        1.0,  // default
+        1e-8, // default
+        1e-6, // reduced from 1e-10
+    )
 }
 
 fn calculate_target_orientation_path_side(
@@ -230,7 +319,7 @@ fn calculate_target_orientation_path_side(
     }
 }
 
-fn normalize_gradient(
+fn normalize_gradient<const NUM_VARIABLES: usize>(
     mut gradient: SVector<f32, NUM_VARIABLES>,
     max_squared_magnitude: f32,
 ) -> SVector<f32, NUM_VARIABLES> {
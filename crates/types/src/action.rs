@@ -32,8 +32,10 @@ pub enum Action {
     LookAround,
     LookAtReferee,
     NoGroundContact,
+    Pass,
     Penalize,
     PrepareJump,
+    ReceivePass,
     Search,
     SearchForLostBall,
     SitDown,
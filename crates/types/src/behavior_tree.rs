@@ -0,0 +1,71 @@
+use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
+use serde::{Deserialize, Serialize};
+
+use spl_network_messages::{PlayerNumber, SubState, Team};
+
+use crate::{action::Action, primary_state::PrimaryState, roles::Role};
+
+/// Declarative description of the behavior decision, ticked from the root every cycle.
+///
+/// Sequences tick their children in order until one fails, fallbacks until one succeeds. An
+/// action leaf is running if the action produced a motion command and failed otherwise. The first
+/// running action ends the tick and is executed.
+#[derive(
+    Clone, Debug, Deserialize, PartialEq, PathDeserialize, PathIntrospect, PathSerialize, Serialize,
+)]
+pub enum BehaviorTreeNode {
+    Sequence(Vec<BehaviorTreeNode>),
+    Fallback(Vec<BehaviorTreeNode>),
+    Condition(BehaviorCondition),
+    Action(Action),
+}
+
+#[derive(
+    Clone, Debug, Deserialize, PartialEq, PathDeserialize, PathIntrospect, PathSerialize, Serialize,
+)]
+pub enum BehaviorCondition {
+    Not(Box<BehaviorCondition>),
+    All(Vec<BehaviorCondition>),
+    Any(Vec<BehaviorCondition>),
+    Role(Role),
+    PlayerNumber(PlayerNumber),
+    PrimaryState(PrimaryState),
+    HasGameControllerState,
+    GameStateReady,
+    GameStatePlaying,
+    BallIsFree,
+    PenaltyShootout,
+    SubState(SubState),
+    KickingTeam(Team),
+    HasBall,
+    ShouldLookAround,
+    ShouldDoKickInPoseDetection,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Eq,
+    PartialEq,
+    PathDeserialize,
+    PathIntrospect,
+    PathSerialize,
+    Serialize,
+)]
+pub enum BehaviorTreeStatus {
+    Success,
+    Failure,
+    Running,
+}
+
+/// One visited node of a behavior tree tick, in depth-first order.
+#[derive(
+    Clone, Debug, Deserialize, PartialEq, PathDeserialize, PathIntrospect, PathSerialize, Serialize,
+)]
+pub struct BehaviorTreeTraceEntry {
+    pub depth: usize,
+    pub label: String,
+    pub status: BehaviorTreeStatus,
+}
@@ -1,8 +1,8 @@
-use nalgebra::Vector3;
+use nalgebra::{Vector2, Vector3};
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::fall_state::FallState;
+use crate::{fall_state::FallState, joints::Joints, sole_pressure::SolePressure};
 
 #[derive(
     Default, Debug, Clone, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
@@ -11,4 +11,7 @@ pub struct ConditionInput {
     pub filtered_angular_velocity: Vector3<f32>,
     pub fall_state: FallState,
     pub ground_contact: bool,
+    pub joint_positions: Joints<f32>,
+    pub torso_roll_pitch: Vector2<f32>,
+    pub sole_pressure: SolePressure,
 }
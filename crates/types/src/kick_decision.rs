@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use coordinate_systems::Ground;
 use linear_algebra::{Point2, Pose2};
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
@@ -35,6 +37,42 @@ pub struct KickDecision {
     pub strength: f32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect)]
+pub struct KickDecisionEvaluation {
+    pub decision: KickDecision,
+    pub goal_probability: f32,
+    pub interception_probability: f32,
+    pub score: f32,
+    pub sampled_ball_end_positions: Vec<Point2<Ground>>,
+}
+
+#[derive(
+    Debug, Default, Clone, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct KickEvaluationParameters {
+    /// Every candidate is evaluated with the same noise samples, so decisions only change if the
+    /// situation changes
+    pub random_seed: u64,
+    pub number_of_samples: usize,
+    /// Rollouts per cycle shared by all candidates, each candidate gets at most
+    /// `number_of_samples` of them
+    pub maximum_number_of_rollouts: usize,
+    /// Opponents closest to the ball that are considered in the rollouts
+    pub maximum_number_of_opponents: usize,
+    /// Evaluations are reused in between, the candidates only move with the ball
+    pub evaluation_interval: Duration,
+    /// Evaluations are only reused for candidates whose target moved less than this distance
+    pub maximum_reused_target_deviation: f32,
+    pub maximum_reused_strength_deviation: f32,
+    pub simulation_time_step: Duration,
+    pub ball_deceleration: f32,
+    pub opponent_reaction_time: Duration,
+    pub opponent_walking_speed: f32,
+    pub opponent_interception_radius: f32,
+    pub goal_probability_weight: f32,
+    pub interception_probability_weight: f32,
+}
+
 #[derive(
     Debug, Default, Clone, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
 )]
@@ -56,6 +94,14 @@ pub struct DecisionParameters {
     pub penalty_shot_kick_strength: f32,
 
     pub angle_distance_weight: f32,
+    /// Distance to a kick pose which weighs as much as a difference of one in the evaluation score
+    pub kick_pose_distance_normalization: f32,
     pub closer_to_goal_threshold: f32,
     pub goal_accuracy_margin: f32,
+
+    pub opponent_prediction_horizon: Duration,
+    pub predicted_opponent_radius: f32,
+    pub predicted_opponent_matching_distance: f32,
+
+    pub evaluation: KickEvaluationParameters,
 }
@@ -3,6 +3,7 @@ pub mod action;
 pub mod audio;
 pub mod ball_detection;
 pub mod ball_position;
+pub mod behavior_tree;
 pub mod bounding_box;
 pub mod buttons;
 pub mod calibration;
@@ -47,6 +48,7 @@ pub mod obstacle_avoiding_arms;
 pub mod obstacle_filter;
 pub mod obstacles;
 pub mod parameters;
+pub mod pass;
 pub mod path_obstacles;
 pub mod penalty_shot_direction;
 pub mod perspective_grid_candidates;
@@ -68,7 +70,10 @@ pub mod sonar_obstacle;
 pub mod sonar_values;
 pub mod stand_up;
 pub mod step;
+pub mod step_planning;
 pub mod support_foot;
+pub mod teammate_pose;
+pub mod tracked_robots;
 pub mod walk_command;
 pub mod walk_volume_extents;
 pub mod whistle;
@@ -11,9 +11,11 @@ use linear_algebra::{Point2, Vector2};
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 
 use crate::{
+    behavior_tree::BehaviorTreeNode,
     joints::head::HeadJoints,
     motion_command::{KickVariant, MotionCommand},
     roles::Role,
+    step::Step,
 };
 
 #[derive(
@@ -31,6 +33,7 @@ pub struct WhistleDetectionParameters {
 )]
 pub struct BehaviorParameters {
     pub injected_motion_command: Option<MotionCommand>,
+    pub behavior_tree: Option<BehaviorTreeNode>,
     pub lost_ball: LostBallParameters,
     pub optional_roles: Vec<Role>,
     pub path_planning: PathPlanningParameters,
@@ -80,6 +83,8 @@ pub struct RolePositionsParameters {
     pub defender_y_offset: f32,
     pub defender_passive_distance: f32,
     pub defender_passive_hysteresis: f32,
+    pub defender_opponent_ball_possession_distance: f32,
+    pub defender_opponent_anticipation_horizon: Duration,
     pub left_midfielder_distance_to_ball: f32,
     pub left_midfielder_maximum_x_in_ready_and_when_ball_is_not_free: f32,
     pub left_midfielder_minimum_x: f32,
@@ -146,9 +151,18 @@ pub struct InWalkKickInfoParameters {
     pub reached_y: Range<f32>,
     pub reached_turn: Range<f32>,
     pub shot_distance: f32,
+    pub noise: KickNoiseParameters,
     pub enabled: bool,
 }
 
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct KickNoiseParameters {
+    pub direction_standard_deviation: f32,
+    pub strength_standard_deviation: f32,
+}
+
 #[derive(
     Copy,
     Clone,
@@ -418,6 +432,58 @@ pub struct ObstacleFilterParameters {
     pub goal_post_obstacle_radius: f32,
 }
 
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct RobotTrackerParameters {
+    pub hypothesis_timeout: Duration,
+    pub teammate_pose_timeout: Duration,
+    pub measurement_matching_distance: f32,
+    pub teammate_matching_distance: f32,
+    pub velocity_decay_factor: f32,
+    pub process_noise: nalgebra::Vector4<f32>,
+    pub measurement_noise: nalgebra::Vector2<f32>,
+    pub initial_covariance: nalgebra::Vector4<f32>,
+    pub measurement_count_threshold: usize,
+}
+
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct PassSelectorParameters {
+    pub enable: bool,
+    pub teammate_pose_timeout: Duration,
+    pub incoming_pass_timeout: Duration,
+    pub announcement_interval: Duration,
+    pub minimum_pass_distance: f32,
+    pub maximum_pass_distance: f32,
+    pub minimum_forward_progress: f32,
+    pub opponent_clearance: f32,
+    pub opponent_distance_weight: f32,
+    pub minimum_kick_strength: f32,
+    /// Distance to the receiver within which a stopped ball counts as received
+    pub reception_radius: f32,
+    /// Value of a received pass in units of the kick evaluation score, added to the weighted
+    /// receiver score
+    pub reception_probability_weight: f32,
+    pub receiver_score_weight: f32,
+    /// Evaluation score by which a pass has to beat the best kick to be chosen
+    pub minimum_score_advantage: f32,
+}
+
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct CostBasedRoleAssignmentParameters {
+    pub enable: bool,
+    pub teammate_pose_timeout: Duration,
+    pub walking_speed: f32,
+    pub turning_speed: f32,
+    pub defender_distance_to_own_goal: f32,
+    pub midfielder_y_offset: f32,
+    pub keeper_distance_cost_factor: f32,
+}
+
 #[derive(
     Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
 )]
@@ -466,6 +532,7 @@ pub struct PenaltyShotDirectionParameters {
 pub struct StepPlanningCostFactors {
     pub path_progress: f32,
     pub path_distance: f32,
+    pub obstacle_clearance: f32,
     pub target_orientation: f32,
     pub walk_orientation: f32,
 }
@@ -474,10 +541,12 @@ pub struct StepPlanningCostFactors {
     Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
 )]
 pub struct StepPlanningOptimizationParameters {
+    pub num_steps: usize,
     pub optimizer_steps: usize,
     pub cost_factors: StepPlanningCostFactors,
     pub path_alignment_tolerance: f32,
     pub path_progress_smoothness: f32,
+    pub obstacle_clearance_margin: f32,
     pub target_orientation_ahead_tolerance: f32,
     pub target_orientation_side_alignment_tolerance: f32,
     pub hybrid_align_distance: f32,
@@ -492,3 +561,26 @@ pub enum StepPlannerMode {
     Mpc,
     Greedy,
 }
+
+/// Observation and action scaling of a learned locomotion policy, see
+/// `control::motion::locomotion_policy` for the layout of the observation
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct LocomotionPolicyParameters {
+    /// ONNX file in the neural network directory
+    pub model: PathBuf,
+    /// Serial motors observed and commanded by the policy, in the order of the actions
+    pub motor_indices: Vec<usize>,
+    pub default_joint_positions: Vec<f32>,
+    pub proportional_gains: Vec<f32>,
+    pub derivative_gains: Vec<f32>,
+    pub angular_velocity_scale: f32,
+    pub joint_position_scale: f32,
+    pub joint_velocity_scale: f32,
+    /// Converts the requested step into the commanded walk velocity
+    pub command_scale: Step,
+    pub gait_period: Duration,
+    pub action_scale: f32,
+    pub action_clip: f32,
+}
@@ -0,0 +1,39 @@
+use std::time::SystemTime;
+
+use coordinate_systems::Field;
+use linear_algebra::Point2;
+use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::PlayerNumber;
+
+use crate::kick_decision::KickDecision;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect)]
+pub struct PassDecision {
+    pub receiver: PlayerNumber,
+    pub target: Point2<Field>,
+    /// Trade-off between field progress and free space used to select the receiver
+    pub score: f32,
+    /// Evaluation of the better kicking side, its score is comparable to the evaluation of kicks
+    pub evaluation: PassEvaluation,
+    pub kick_decisions: Vec<KickDecision>,
+}
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct PassEvaluation {
+    pub decision: KickDecision,
+    pub reception_probability: f32,
+    pub interception_probability: f32,
+    pub score: f32,
+}
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct IncomingPass {
+    pub passer: PlayerNumber,
+    pub target: Point2<Field>,
+    pub announced_at: SystemTime,
+}
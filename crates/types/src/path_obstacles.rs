@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
-use geometry::{arc::Arc, circle::Circle, line_segment::LineSegment};
+use geometry::{arc::Arc, circle::Circle, line_segment::LineSegment, polygon::is_inside_polygon};
+use linear_algebra::Point2;
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +13,8 @@ use coordinate_systems::Ground;
 pub enum PathObstacleShape {
     Circle(Circle<Ground>),
     LineSegment(LineSegment<Ground>),
+    /// Corners in order around the rectangle
+    Rectangle([Point2<Ground>; 4]),
 }
 
 impl PathObstacleShape {
@@ -21,6 +24,11 @@ impl PathObstacleShape {
             PathObstacleShape::LineSegment(obstacle_line_segment) => {
                 obstacle_line_segment.intersects_line_segment(line_segment)
             }
+            PathObstacleShape::Rectangle(corners) => {
+                is_inside_polygon(corners, &line_segment.0)
+                    || rectangle_edges(corners)
+                        .any(|edge| edge.intersects_line_segment(line_segment))
+            }
         }
     }
 
@@ -28,6 +36,9 @@ impl PathObstacleShape {
         match self {
             PathObstacleShape::Circle(circle) => circle.overlaps_arc(arc),
             PathObstacleShape::LineSegment(line_segment) => line_segment.overlaps_arc(arc),
+            PathObstacleShape::Rectangle(corners) => {
+                rectangle_edges(corners).any(|edge| edge.overlaps_arc(arc))
+            }
         }
     }
 
@@ -46,6 +57,16 @@ impl PathObstacleShape {
     }
 }
 
+/// Edges of the rectangle given by its corners in order around it
+pub fn rectangle_edges(
+    corners: &[Point2<Ground>; 4],
+) -> impl Iterator<Item = LineSegment<Ground>> + '_ {
+    corners
+        .iter()
+        .zip(corners.iter().cycle().skip(1))
+        .map(|(start, end)| LineSegment::new(*start, *end))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect)]
 pub struct PathObstacle {
     pub shape: PathObstacleShape,
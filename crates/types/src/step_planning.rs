@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Serialize,
+    PathSerialize,
+    PathDeserialize,
+    PathIntrospect,
+    PartialEq,
+)]
+pub struct StepPlanningSolverStatistics {
+    pub iterations: usize,
+    pub cost: f32,
+    pub has_converged: bool,
+    pub fixed_point_residual_norm: f32,
+    pub solve_time: Duration,
+}
@@ -0,0 +1,99 @@
+use std::time::{Duration, SystemTime};
+
+use coordinate_systems::Field;
+use linear_algebra::Pose2;
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{HulkMessage, PlayerNumber};
+
+use crate::players::Players;
+
+/// Latest pose a teammate broadcast about itself
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct TeammatePose {
+    pub pose: Pose2<Field>,
+    pub received: SystemTime,
+    /// Only striker messages carry the time to reach the kick position, pass messages keep the last
+    /// known one
+    pub time_to_reach_kick_position: Option<Duration>,
+}
+
+impl TeammatePose {
+    /// Sender and pose of the message, if the message carries the pose of its sender
+    pub fn from_message(
+        message: &HulkMessage,
+        received: SystemTime,
+        previous_poses: &Players<Option<Self>>,
+    ) -> Option<(PlayerNumber, Self)> {
+        let (player_number, pose, time_to_reach_kick_position) = match message {
+            HulkMessage::Striker(message) => (
+                message.player_number,
+                message.pose,
+                Some(message.time_to_reach_kick_position),
+            ),
+            HulkMessage::Loser(message) => (message.player_number, message.pose, None),
+            HulkMessage::Pass(message) => (
+                message.player_number,
+                message.pose,
+                previous_poses[message.player_number]
+                    .and_then(|previous_pose| previous_pose.time_to_reach_kick_position),
+            ),
+            HulkMessage::VisualReferee(_) => return None,
+        };
+        Some((
+            player_number,
+            Self {
+                pose,
+                received,
+                time_to_reach_kick_position,
+            },
+        ))
+    }
+
+    pub fn is_outdated(&self, now: SystemTime, timeout: Duration) -> bool {
+        now.duration_since(self.received).unwrap_or_default() > timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_network_messages::{LoserMessage, PassMessage, StrikerMessage};
+
+    use super::*;
+
+    #[test]
+    fn pass_messages_keep_the_time_to_reach_the_kick_position() {
+        let mut teammate_poses = Players::new(None);
+        let time_to_reach_kick_position = Duration::from_secs(3);
+        let messages = [
+            HulkMessage::Striker(StrikerMessage {
+                player_number: PlayerNumber::Four,
+                time_to_reach_kick_position,
+                ..Default::default()
+            }),
+            HulkMessage::Pass(PassMessage {
+                player_number: PlayerNumber::Four,
+                ..Default::default()
+            }),
+        ];
+        for message in messages {
+            let (player_number, teammate_pose) =
+                TeammatePose::from_message(&message, SystemTime::UNIX_EPOCH, &teammate_poses)
+                    .unwrap();
+            teammate_poses[player_number] = Some(teammate_pose);
+        }
+        assert_eq!(
+            teammate_poses[PlayerNumber::Four]
+                .unwrap()
+                .time_to_reach_kick_position,
+            Some(time_to_reach_kick_position)
+        );
+
+        let loser = HulkMessage::Loser(LoserMessage {
+            player_number: PlayerNumber::Four,
+            ..Default::default()
+        });
+        let (_, teammate_pose) =
+            TeammatePose::from_message(&loser, SystemTime::UNIX_EPOCH, &teammate_poses).unwrap();
+        assert_eq!(teammate_pose.time_to_reach_kick_position, None);
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use coordinate_systems::Ground;
+use linear_algebra::{vector, IntoFramed, Point2, Vector2};
+use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
+use spl_network_messages::PlayerNumber;
+
+use crate::multivariate_normal_distribution::MultivariateNormalDistribution;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    PartialEq,
+    PathDeserialize,
+    PathIntrospect,
+    PathSerialize,
+    Serialize,
+)]
+pub enum RobotAffiliation {
+    Teammate {
+        player_number: PlayerNumber,
+    },
+    #[default]
+    Opponent,
+}
+
+#[derive(
+    Clone, Debug, Deserialize, PartialEq, PathDeserialize, PathIntrospect, PathSerialize, Serialize,
+)]
+pub struct TrackedRobot {
+    pub id: usize,
+    /// Position and velocity of the robot: `[x, y, velocity_x, velocity_y]`
+    pub state: MultivariateNormalDistribution<4>,
+    pub affiliation: RobotAffiliation,
+    pub measurement_count: usize,
+    pub last_update: SystemTime,
+}
+
+impl TrackedRobot {
+    pub fn position(&self) -> Point2<Ground> {
+        self.state.mean.xy().framed().as_point()
+    }
+
+    pub fn velocity(&self) -> Vector2<Ground> {
+        vector![self.state.mean.z, self.state.mean.w]
+    }
+
+    pub fn predicted_position(&self, duration: Duration) -> Point2<Ground> {
+        self.position() + self.velocity() * duration.as_secs_f32()
+    }
+
+    pub fn is_opponent(&self) -> bool {
+        self.affiliation == RobotAffiliation::Opponent
+    }
+}
@@ -8,11 +8,19 @@ use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use spl_network_messages::PlayerNumber;
 
 use crate::{
-    ball_position::HypotheticalBallPosition, calibration::CalibrationCommand,
-    fall_state::FallState, field_dimensions::Side,
-    filtered_game_controller_state::FilteredGameControllerState, kick_decision::KickDecision,
-    obstacles::Obstacle, penalty_shot_direction::PenaltyShotDirection, primary_state::PrimaryState,
-    roles::Role, rule_obstacles::RuleObstacle,
+    ball_position::HypotheticalBallPosition,
+    calibration::CalibrationCommand,
+    fall_state::FallState,
+    field_dimensions::Side,
+    filtered_game_controller_state::FilteredGameControllerState,
+    kick_decision::KickDecision,
+    obstacles::Obstacle,
+    pass::{IncomingPass, PassDecision},
+    penalty_shot_direction::PenaltyShotDirection,
+    primary_state::PrimaryState,
+    roles::Role,
+    rule_obstacles::RuleObstacle,
+    tracked_robots::TrackedRobot,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize, PathSerialize, PathIntrospect)]
@@ -23,10 +31,13 @@ pub struct WorldState {
     pub filtered_game_controller_state: Option<FilteredGameControllerState>,
     pub obstacles: Vec<Obstacle>,
     pub rule_obstacles: Vec<RuleObstacle>,
+    pub tracked_robots: Vec<TrackedRobot>,
     pub position_of_interest: Point2<Ground>,
     pub suggested_search_position: Option<Point2<Field>>,
     pub kick_decisions: Option<Vec<KickDecision>>,
     pub instant_kick_decisions: Option<Vec<KickDecision>>,
+    pub pass_decision: Option<PassDecision>,
+    pub incoming_pass: Option<IncomingPass>,
     pub robot: RobotState,
     pub calibration_command: Option<CalibrationCommand>,
     pub now: SystemTime,
@@ -41,10 +52,13 @@ impl Default for WorldState {
             filtered_game_controller_state: Default::default(),
             obstacles: Default::default(),
             rule_obstacles: Default::default(),
+            tracked_robots: Default::default(),
             position_of_interest: Point2::origin(),
             suggested_search_position: Default::default(),
             kick_decisions: Default::default(),
             instant_kick_decisions: Default::default(),
+            pass_decision: Default::default(),
+            incoming_pass: Default::default(),
             robot: Default::default(),
             calibration_command: Default::default(),
             now: UNIX_EPOCH,
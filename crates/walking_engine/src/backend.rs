@@ -0,0 +1,62 @@
+use coordinate_systems::{Robot, Walk};
+use linear_algebra::{Point2, Pose3};
+use types::{
+    joints::{body::LowerBodyJoints, leg::LegJoints},
+    support_foot::Side,
+};
+
+use crate::{
+    foot_leveling::FootLeveling, gyro_balancing::GyroBalancing,
+    parameters::StiffnessLossCompensation,
+};
+
+pub mod booster;
+pub mod nao;
+
+/// Robot specific parts of the walking engine.
+///
+/// The modes only plan and interpolate sole poses in the walk frame, everything depending on
+/// the kinematic structure and the sensors of a robot goes through this trait. Legs are
+/// represented as [`LegJoints`] with `hip_yaw_pitch` being the hip yaw of robots without the
+/// coupled NAO hip joint.
+pub trait Backend {
+    /// Forward kinematics of the sole of the given leg
+    fn sole_to_robot(&self, side: Side, leg: &LegJoints) -> Pose3<Robot>;
+
+    /// Inverse kinematics placing both soles at the given poses
+    fn leg_joints(&self, left_sole: Pose3<Robot>, right_sole: Pose3<Robot>) -> LowerBodyJoints;
+
+    /// Outline of the sole of the given side, projected to the ground
+    fn sole_outline(&self, side: Side, sole: Pose3<Walk>) -> Vec<Point2<Walk>>;
+
+    /// Load on the sole of the given side, compared against the sole pressure thresholds
+    fn sole_pressure(&self, side: Side) -> f32;
+
+    fn clamp_to_joint_limits(&self, legs: LowerBodyJoints) -> LowerBodyJoints;
+
+    /// Applies the gyro balancing to the joints of the support leg
+    fn balance_using_gyro(
+        &self,
+        legs: LowerBodyJoints,
+        balancing: &GyroBalancing,
+        support_side: Side,
+    ) -> LowerBodyJoints;
+
+    /// Applies the foot leveling to the ankle of the swing leg
+    fn level_swing_foot(
+        &self,
+        legs: LowerBodyJoints,
+        leveling: &FootLeveling,
+        support_side: Side,
+    ) -> LowerBodyJoints;
+
+    /// Compensates the deflection of the support leg under load
+    fn compensate_stiffness_loss(
+        &self,
+        legs: LowerBodyJoints,
+        parameters: &StiffnessLossCompensation,
+        last_actuated_joints: &LowerBodyJoints,
+        measured_joints: &LowerBodyJoints,
+        support_side: Side,
+    ) -> LowerBodyJoints;
+}
@@ -0,0 +1,505 @@
+use std::{f32::consts::PI, ops::Range};
+
+use coordinate_systems::{Ground, Robot, Walk};
+use geometry::polygon::is_inside_convex_hull;
+use linear_algebra::{point, Point2, Point3, Pose3, Vector3};
+use nalgebra::{Isometry3, Translation3, UnitQuaternion};
+use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
+use serde::{Deserialize, Serialize};
+use types::{
+    joints::{body::LowerBodyJoints, leg::LegJoints},
+    support_foot::Side,
+};
+
+use crate::{
+    compensate_stiffness_loss::CompensateStiffnessLossExt,
+    foot_leveling::{FootLeveling, FootLevelingExt},
+    gyro_balancing::{GyroBalancing, GyroBalancingExt},
+    parameters::StiffnessLossCompensation,
+};
+
+use super::Backend;
+
+/// Serial motor order of the Booster: head, left arm, right arm, left leg, right leg
+pub const NUMBER_OF_MOTORS: usize = 22;
+pub const LEFT_LEG_MOTORS: Range<usize> = 10..16;
+pub const RIGHT_LEG_MOTORS: Range<usize> = 16..22;
+
+/// Reads the legs from motors in serial motor order, e.g. their positions or torques
+pub fn serial_motor_legs<Motor>(
+    motors: &[Motor],
+    value: impl Fn(&Motor) -> f32,
+) -> LowerBodyJoints {
+    let leg = |motors: &[Motor]| LegJoints {
+        hip_pitch: value(&motors[0]),
+        hip_roll: value(&motors[1]),
+        hip_yaw_pitch: value(&motors[2]),
+        knee_pitch: value(&motors[3]),
+        ankle_pitch: value(&motors[4]),
+        ankle_roll: value(&motors[5]),
+    };
+
+    LowerBodyJoints {
+        left_leg: leg(&motors[LEFT_LEG_MOTORS]),
+        right_leg: leg(&motors[RIGHT_LEG_MOTORS]),
+    }
+}
+
+/// Model of the Booster robots: legs with three intersecting hip axes (pitch, roll, yaw from the
+/// torso outwards), a knee and a two axis ankle (pitch, roll)
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct BoosterParameters {
+    /// Intersection of the left hip axes, the right hip is mirrored
+    pub robot_to_left_hip: Vector3<Robot>,
+    pub thigh_length: f32,
+    pub tibia_length: f32,
+    pub ankle_to_sole: f32,
+    pub sole_length: f32,
+    pub sole_width: f32,
+    pub minimum_leg_joints: LowerBodyJoints,
+    pub maximum_leg_joints: LowerBodyJoints,
+    /// The Booster has no sole pressure sensors, the load is estimated from the knee torque
+    pub knee_torque_to_sole_pressure: f32,
+    /// Positions of the head and arm motors in serial motor order, held while walking
+    pub upper_body_positions: Vec<f32>,
+    /// Gain of the motors at full stiffness
+    pub maximum_proportional_gain: f32,
+    pub derivative_gain: f32,
+    /// Mass of the torso including the head and the arms, which are held while walking
+    pub torso_mass: f32,
+    pub torso_center_of_mass: Point3<Robot>,
+    /// Masses of the leg links, each located at the middle of its link
+    pub thigh_mass: f32,
+    pub tibia_mass: f32,
+    pub foot_mass: f32,
+}
+
+pub struct Booster<'a> {
+    pub parameters: &'a BoosterParameters,
+    /// Estimated torques of the leg joints
+    pub leg_torques: LowerBodyJoints,
+}
+
+impl BoosterParameters {
+    fn hip(&self, side: Side) -> nalgebra::Vector3<f32> {
+        let left_hip = self.robot_to_left_hip.inner;
+        match side {
+            Side::Left => left_hip,
+            Side::Right => nalgebra::vector![left_hip.x, -left_hip.y, left_hip.z],
+        }
+    }
+
+    /// Hip, knee, ankle and sole of the leg, e.g. to draw the robot
+    pub fn leg_positions(&self, side: Side, leg: &LegJoints) -> [Point3<Robot>; 4] {
+        let chain = leg_chain(self, self.hip(side), leg);
+        [
+            chain.hip,
+            chain.knee,
+            chain.ankle,
+            chain.sole.translation.vector,
+        ]
+        .map(|position| Point3::wrap(nalgebra::Point3::from(position)))
+    }
+}
+
+impl Booster<'_> {
+    fn hip(&self, side: Side) -> nalgebra::Vector3<f32> {
+        self.parameters.hip(side)
+    }
+
+    /// Center of mass of the torso and both legs
+    pub fn center_of_mass(&self, legs: &LowerBodyJoints) -> Point3<Robot> {
+        let parameters = self.parameters;
+        let mut weighted_sum = parameters.torso_center_of_mass.inner.coords * parameters.torso_mass;
+        for (side, leg) in [(Side::Left, &legs.left_leg), (Side::Right, &legs.right_leg)] {
+            let chain = leg_chain(parameters, self.hip(side), leg);
+            weighted_sum += (chain.hip + chain.knee) / 2.0 * parameters.thigh_mass
+                + (chain.knee + chain.ankle) / 2.0 * parameters.tibia_mass
+                + (chain.ankle + chain.sole.translation.vector) / 2.0 * parameters.foot_mass;
+        }
+        let total_mass = parameters.torso_mass
+            + 2.0 * (parameters.thigh_mass + parameters.tibia_mass + parameters.foot_mass);
+
+        Point3::wrap(nalgebra::Point3::from(
+            weighted_sum / total_mass.max(f32::EPSILON),
+        ))
+    }
+
+    /// Zero moment point of the center of mass under the measured linear acceleration, the same
+    /// estimate as the `zero_moment_point_provider` of the NAO
+    pub fn zero_moment_point(
+        &self,
+        center_of_mass: Point3<Robot>,
+        linear_acceleration: Vector3<Robot>,
+        robot_to_ground: &linear_algebra::Isometry3<Robot, Ground>,
+        gravity_acceleration: f32,
+    ) -> Point2<Ground> {
+        let center_of_mass = *robot_to_ground * center_of_mass;
+        let linear_acceleration = *robot_to_ground * linear_acceleration;
+        center_of_mass.xy()
+            + linear_acceleration.xy() * center_of_mass.z() / gravity_acceleration.max(f32::EPSILON)
+    }
+
+    /// Whether the point lies within the convex hull of both soles
+    pub fn is_inside_support_polygon(
+        &self,
+        legs: &LowerBodyJoints,
+        robot_to_ground: &linear_algebra::Isometry3<Robot, Ground>,
+        point: Point2<Ground>,
+    ) -> bool {
+        let soles: Vec<_> = [(Side::Left, &legs.left_leg), (Side::Right, &legs.right_leg)]
+            .into_iter()
+            .flat_map(|(side, leg)| {
+                let sole_to_ground = robot_to_ground.inner * self.sole_to_robot(side, leg).inner;
+                sole_corners(self.parameters).map(|corner| {
+                    let corner = sole_to_ground * corner;
+                    point![corner.x, corner.y]
+                })
+            })
+            .collect();
+        is_inside_convex_hull(&soles, &point)
+    }
+}
+
+/// Corners of the rectangular sole in the sole frame
+fn sole_corners(parameters: &BoosterParameters) -> [nalgebra::Point3<f32>; 4] {
+    let half_length = parameters.sole_length / 2.0;
+    let half_width = parameters.sole_width / 2.0;
+    [
+        (half_length, half_width),
+        (-half_length, half_width),
+        (-half_length, -half_width),
+        (half_length, -half_width),
+    ]
+    .map(|(x, y)| nalgebra::point![x, y, 0.0])
+}
+
+impl Backend for Booster<'_> {
+    fn sole_to_robot(&self, side: Side, leg: &LegJoints) -> Pose3<Robot> {
+        Pose3::wrap(leg_forward_kinematics(self.parameters, self.hip(side), leg))
+    }
+
+    fn leg_joints(&self, left_sole: Pose3<Robot>, right_sole: Pose3<Robot>) -> LowerBodyJoints {
+        LowerBodyJoints {
+            left_leg: leg_inverse_kinematics(
+                self.parameters,
+                self.hip(Side::Left),
+                left_sole.inner,
+            ),
+            right_leg: leg_inverse_kinematics(
+                self.parameters,
+                self.hip(Side::Right),
+                right_sole.inner,
+            ),
+        }
+    }
+
+    fn sole_outline(&self, _side: Side, sole: Pose3<Walk>) -> Vec<Point2<Walk>> {
+        sole_corners(self.parameters)
+            .into_iter()
+            .map(|corner| {
+                let corner = sole.inner * corner;
+                point![corner.x, corner.y]
+            })
+            .collect()
+    }
+
+    fn sole_pressure(&self, side: Side) -> f32 {
+        let leg = match side {
+            Side::Left => self.leg_torques.left_leg,
+            Side::Right => self.leg_torques.right_leg,
+        };
+        leg.knee_pitch.abs() * self.parameters.knee_torque_to_sole_pressure
+    }
+
+    fn clamp_to_joint_limits(&self, legs: LowerBodyJoints) -> LowerBodyJoints {
+        let minimum = self.parameters.minimum_leg_joints;
+        let maximum = self.parameters.maximum_leg_joints;
+        LowerBodyJoints {
+            left_leg: legs.left_leg.clamp(minimum.left_leg, maximum.left_leg),
+            right_leg: legs.right_leg.clamp(minimum.right_leg, maximum.right_leg),
+        }
+    }
+
+    /// The hip pitch and roll and the ankle axes of the Booster are aligned with the ones of the
+    /// NAO, the same joint corrections apply
+    fn balance_using_gyro(
+        &self,
+        legs: LowerBodyJoints,
+        balancing: &GyroBalancing,
+        support_side: Side,
+    ) -> LowerBodyJoints {
+        legs.balance_using_gyro(balancing, support_side)
+    }
+
+    fn level_swing_foot(
+        &self,
+        legs: LowerBodyJoints,
+        leveling: &FootLeveling,
+        support_side: Side,
+    ) -> LowerBodyJoints {
+        legs.level_swing_foot(leveling, support_side)
+    }
+
+    fn compensate_stiffness_loss(
+        &self,
+        legs: LowerBodyJoints,
+        parameters: &StiffnessLossCompensation,
+        last_actuated_joints: &LowerBodyJoints,
+        measured_joints: &LowerBodyJoints,
+        support_side: Side,
+    ) -> LowerBodyJoints {
+        legs.compensate_stiffness_loss(
+            parameters,
+            last_actuated_joints,
+            measured_joints,
+            support_side,
+        )
+    }
+}
+
+/// Joint positions along a leg
+struct LegChain {
+    hip: nalgebra::Vector3<f32>,
+    knee: nalgebra::Vector3<f32>,
+    ankle: nalgebra::Vector3<f32>,
+    sole: Isometry3<f32>,
+}
+
+fn leg_forward_kinematics(
+    parameters: &BoosterParameters,
+    hip: nalgebra::Vector3<f32>,
+    leg: &LegJoints,
+) -> Isometry3<f32> {
+    leg_chain(parameters, hip, leg).sole
+}
+
+fn leg_chain(
+    parameters: &BoosterParameters,
+    hip: nalgebra::Vector3<f32>,
+    leg: &LegJoints,
+) -> LegChain {
+    let hip_rotation = UnitQuaternion::from_axis_angle(&nalgebra::Vector3::y_axis(), leg.hip_pitch)
+        * UnitQuaternion::from_axis_angle(&nalgebra::Vector3::x_axis(), leg.hip_roll)
+        * UnitQuaternion::from_axis_angle(&nalgebra::Vector3::z_axis(), leg.hip_yaw_pitch);
+    let knee = hip - hip_rotation * nalgebra::Vector3::z() * parameters.thigh_length;
+
+    let tibia_rotation = hip_rotation
+        * UnitQuaternion::from_axis_angle(&nalgebra::Vector3::y_axis(), leg.knee_pitch);
+    let ankle = knee - tibia_rotation * nalgebra::Vector3::z() * parameters.tibia_length;
+
+    let foot_rotation = tibia_rotation
+        * UnitQuaternion::from_axis_angle(&nalgebra::Vector3::y_axis(), leg.ankle_pitch)
+        * UnitQuaternion::from_axis_angle(&nalgebra::Vector3::x_axis(), leg.ankle_roll);
+    let sole = ankle - foot_rotation * nalgebra::Vector3::z() * parameters.ankle_to_sole;
+
+    LegChain {
+        hip,
+        knee,
+        ankle,
+        sole: Isometry3::from_parts(Translation3::from(sole), foot_rotation),
+    }
+}
+
+/// Closed form inverse kinematics, the knee is always bent forward
+fn leg_inverse_kinematics(
+    parameters: &BoosterParameters,
+    hip: nalgebra::Vector3<f32>,
+    sole: Isometry3<f32>,
+) -> LegJoints {
+    let thigh_length = parameters.thigh_length;
+    let tibia_length = parameters.tibia_length;
+    let foot_rotation = sole.rotation;
+
+    let ankle =
+        sole.translation.vector + foot_rotation * nalgebra::Vector3::z() * parameters.ankle_to_sole;
+    let ankle_to_hip_in_foot = foot_rotation.inverse() * (hip - ankle);
+    let distance = ankle_to_hip_in_foot.norm();
+
+    let cosine_of_inner_knee_angle = ((thigh_length.powi(2) + tibia_length.powi(2)
+        - distance.powi(2))
+        / (2.0 * thigh_length * tibia_length))
+        .clamp(-1.0, 1.0);
+    let knee_pitch = PI - cosine_of_inner_knee_angle.acos();
+
+    let ankle_roll = ankle_to_hip_in_foot.y.atan2(ankle_to_hip_in_foot.z);
+    let ankle_pitch = (-thigh_length * knee_pitch.sin())
+        .atan2(tibia_length + thigh_length * knee_pitch.cos())
+        - ankle_to_hip_in_foot
+            .x
+            .atan2(ankle_to_hip_in_foot.yz().norm());
+
+    let hip_rotation = foot_rotation
+        * UnitQuaternion::from_axis_angle(&nalgebra::Vector3::x_axis(), -ankle_roll)
+        * UnitQuaternion::from_axis_angle(
+            &nalgebra::Vector3::y_axis(),
+            -(knee_pitch + ankle_pitch),
+        );
+    // hip rotation = R_y(pitch) * R_x(roll) * R_z(yaw)
+    let hip_matrix = hip_rotation.to_rotation_matrix().into_inner();
+    let hip_roll = (-hip_matrix[(1, 2)]).clamp(-1.0, 1.0).asin();
+    let hip_pitch = hip_matrix[(0, 2)].atan2(hip_matrix[(2, 2)]);
+    let hip_yaw = hip_matrix[(1, 0)].atan2(hip_matrix[(1, 1)]);
+
+    LegJoints {
+        hip_yaw_pitch: hip_yaw,
+        hip_roll,
+        hip_pitch,
+        knee_pitch,
+        ankle_pitch,
+        ankle_roll,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_8;
+
+    use linear_algebra::{point, vector};
+    use nalgebra::{Isometry3, Translation3, UnitQuaternion};
+    use types::{
+        joints::{body::LowerBodyJoints, leg::LegJoints},
+        support_foot::Side,
+    };
+
+    use super::{leg_forward_kinematics, leg_inverse_kinematics, Booster, BoosterParameters};
+
+    fn parameters() -> BoosterParameters {
+        BoosterParameters {
+            robot_to_left_hip: vector![0.0, 0.1, -0.1],
+            thigh_length: 0.3,
+            tibia_length: 0.3,
+            ankle_to_sole: 0.05,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn inverse_kinematics_reproduces_joint_angles() {
+        let parameters = parameters();
+        let booster = Booster {
+            parameters: &parameters,
+            leg_torques: Default::default(),
+        };
+        let legs = [
+            LegJoints::default(),
+            LegJoints {
+                hip_yaw_pitch: 0.2,
+                hip_roll: 0.1,
+                hip_pitch: -0.5,
+                knee_pitch: 1.0,
+                ankle_pitch: -0.5,
+                ankle_roll: -0.1,
+            },
+            LegJoints {
+                hip_yaw_pitch: -FRAC_PI_8,
+                hip_roll: -0.2,
+                hip_pitch: -0.8,
+                knee_pitch: 0.6,
+                ankle_pitch: 0.3,
+                ankle_roll: 0.15,
+            },
+        ];
+
+        for leg in legs {
+            for side in [Side::Left, Side::Right] {
+                let sole = leg_forward_kinematics(&parameters, booster.hip(side), &leg);
+                let reproduced = leg_inverse_kinematics(&parameters, booster.hip(side), sole);
+
+                for (expected, actual) in leg.into_iter().zip(reproduced) {
+                    assert!(
+                        (expected - actual).abs() < 1e-3,
+                        "expected {leg:?}, got {reproduced:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn forward_kinematics_reaches_requested_sole_pose() {
+        let parameters = parameters();
+        let hip = nalgebra::vector![0.0, 0.1, -0.1];
+        let sole = Isometry3::from_parts(
+            Translation3::new(0.05, 0.12, -0.65),
+            UnitQuaternion::from_euler_angles(0.05, -0.1, 0.2),
+        );
+
+        let leg = leg_inverse_kinematics(&parameters, hip, sole);
+        let reached = leg_forward_kinematics(&parameters, hip, &leg);
+
+        assert!((reached.translation.vector - sole.translation.vector).norm() < 1e-4);
+        assert!(reached.rotation.angle_to(&sole.rotation) < 1e-3);
+    }
+
+    #[test]
+    fn center_of_mass_weights_torso_and_legs() {
+        let parameters = BoosterParameters {
+            torso_mass: 6.0,
+            torso_center_of_mass: point![0.02, 0.0, 0.1],
+            thigh_mass: 1.0,
+            tibia_mass: 1.0,
+            foot_mass: 1.0,
+            ..parameters()
+        };
+        let booster = Booster {
+            parameters: &parameters,
+            leg_torques: Default::default(),
+        };
+
+        let center_of_mass = booster.center_of_mass(&LowerBodyJoints::default());
+
+        // straight legs hang below the hips at x = 0, thigh at -0.25, tibia at -0.55, foot at -0.725
+        let expected_z = (6.0 * 0.1 + 2.0 * (-0.25 - 0.55 - 0.725)) / 12.0;
+        assert!((center_of_mass.x() - 0.01).abs() < 1e-5);
+        assert!(center_of_mass.y().abs() < 1e-5);
+        assert!((center_of_mass.z() - expected_z).abs() < 1e-5);
+    }
+
+    #[test]
+    fn straight_right_leg_hangs_below_mirrored_hip() {
+        let positions = parameters().leg_positions(Side::Right, &LegJoints::default());
+
+        let expected = [-0.1, -0.4, -0.7, -0.75];
+        for (position, expected_z) in positions.into_iter().zip(expected) {
+            assert!(position.x().abs() < 1e-5, "{position:?}");
+            assert!((position.y() + 0.1).abs() < 1e-5, "{position:?}");
+            assert!((position.z() - expected_z).abs() < 1e-5, "{position:?}");
+        }
+    }
+
+    #[test]
+    fn zero_moment_point_leaves_support_polygon_when_accelerating() {
+        let parameters = BoosterParameters {
+            sole_length: 0.2,
+            sole_width: 0.1,
+            ..parameters()
+        };
+        let booster = Booster {
+            parameters: &parameters,
+            leg_torques: Default::default(),
+        };
+        let legs = LowerBodyJoints::default();
+        let robot_to_ground = linear_algebra::Isometry3::from_translation(0.0, 0.0, 0.75);
+        let center_of_mass = point![0.0, 0.0, 0.0];
+
+        let standing = booster.zero_moment_point(
+            center_of_mass,
+            vector![0.0, 0.0, 0.0],
+            &robot_to_ground,
+            9.81,
+        );
+        assert!(booster.is_inside_support_polygon(&legs, &robot_to_ground, standing));
+
+        let accelerating = booster.zero_moment_point(
+            center_of_mass,
+            vector![2.0, 0.0, 0.0],
+            &robot_to_ground,
+            9.81,
+        );
+        assert!(accelerating.x() > 0.1, "{accelerating:?}");
+        assert!(!booster.is_inside_support_polygon(&legs, &robot_to_ground, accelerating));
+    }
+}
@@ -0,0 +1,109 @@
+use coordinate_systems::{LeftSole, RightSole, Robot, Walk};
+use kinematics::{
+    forward::{left_sole_to_robot, right_sole_to_robot},
+    inverse::leg_angles,
+};
+use linear_algebra::{Isometry3, Point2, Pose3};
+use types::{
+    joints::{body::LowerBodyJoints, leg::LegJoints},
+    robot_dimensions::{
+        transform_left_sole_outline, transform_right_sole_outline, RobotDimensions,
+    },
+    sensor_data::ForceSensitiveResistors,
+    support_foot::Side,
+};
+
+use crate::{
+    compensate_stiffness_loss::CompensateStiffnessLossExt,
+    foot_leveling::{FootLeveling, FootLevelingExt},
+    gyro_balancing::{GyroBalancing, GyroBalancingExt},
+    parameters::StiffnessLossCompensation,
+};
+
+use super::Backend;
+
+#[derive(Clone, Debug, Default)]
+pub struct Nao {
+    pub force_sensitive_resistors: ForceSensitiveResistors,
+}
+
+impl Backend for Nao {
+    fn sole_to_robot(&self, side: Side, leg: &LegJoints) -> Pose3<Robot> {
+        match side {
+            Side::Left => left_sole_to_robot(leg).as_pose(),
+            Side::Right => right_sole_to_robot(leg).as_pose(),
+        }
+    }
+
+    fn leg_joints(&self, left_sole: Pose3<Robot>, right_sole: Pose3<Robot>) -> LowerBodyJoints {
+        let left_foot: Pose3<LeftSole> = Isometry3::from(RobotDimensions::LEFT_ANKLE_TO_LEFT_SOLE)
+            .inverse()
+            .as_pose();
+        let right_foot: Pose3<RightSole> =
+            Isometry3::from(RobotDimensions::RIGHT_ANKLE_TO_RIGHT_SOLE)
+                .inverse()
+                .as_pose();
+
+        leg_angles(
+            left_sole.as_transform() * left_foot,
+            right_sole.as_transform() * right_foot,
+        )
+    }
+
+    fn sole_outline(&self, side: Side, sole: Pose3<Walk>) -> Vec<Point2<Walk>> {
+        match side {
+            Side::Left => transform_left_sole_outline(sole.as_transform())
+                .map(|point| point.xy())
+                .collect(),
+            Side::Right => transform_right_sole_outline(sole.as_transform())
+                .map(|point| point.xy())
+                .collect(),
+        }
+    }
+
+    fn sole_pressure(&self, side: Side) -> f32 {
+        match side {
+            Side::Left => self.force_sensitive_resistors.left.sum(),
+            Side::Right => self.force_sensitive_resistors.right.sum(),
+        }
+    }
+
+    /// The NAO clamps commanded positions to its joint limits itself
+    fn clamp_to_joint_limits(&self, legs: LowerBodyJoints) -> LowerBodyJoints {
+        legs
+    }
+
+    fn balance_using_gyro(
+        &self,
+        legs: LowerBodyJoints,
+        balancing: &GyroBalancing,
+        support_side: Side,
+    ) -> LowerBodyJoints {
+        legs.balance_using_gyro(balancing, support_side)
+    }
+
+    fn level_swing_foot(
+        &self,
+        legs: LowerBodyJoints,
+        leveling: &FootLeveling,
+        support_side: Side,
+    ) -> LowerBodyJoints {
+        legs.level_swing_foot(leveling, support_side)
+    }
+
+    fn compensate_stiffness_loss(
+        &self,
+        legs: LowerBodyJoints,
+        parameters: &StiffnessLossCompensation,
+        last_actuated_joints: &LowerBodyJoints,
+        measured_joints: &LowerBodyJoints,
+        support_side: Side,
+    ) -> LowerBodyJoints {
+        legs.compensate_stiffness_loss(
+            parameters,
+            last_actuated_joints,
+            measured_joints,
+            support_side,
+        )
+    }
+}
@@ -0,0 +1,328 @@
+use std::{ops::Range, time::Duration};
+
+use coordinate_systems::Walk;
+use geometry::rectangle::Rectangle;
+use linear_algebra::{point, vector, Point2, Vector2, Vector3};
+use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
+use serde::{Deserialize, Serialize};
+use types::{step::Step, support_foot::Side};
+
+use crate::{feet::Feet, parameters::Parameters, Context};
+
+const MINIMUM_PENDULUM_HEIGHT: f32 = 0.05;
+
+/// State of the linear inverted pendulum standing on the support sole
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct CapturePoint {
+    pub center_of_mass: Point2<Walk>,
+    pub center_of_mass_velocity: Vector2<Walk>,
+    /// sqrt(gravity / height) of the pendulum
+    pub natural_frequency: f32,
+    /// Point to put the center of pressure on for the pendulum to come to rest, also known as
+    /// the divergent component of motion
+    pub position: Point2<Walk>,
+}
+
+impl CapturePoint {
+    pub fn new(
+        center_of_mass: Point2<Walk>,
+        center_of_mass_velocity: Vector2<Walk>,
+        natural_frequency: f32,
+    ) -> Self {
+        Self {
+            center_of_mass,
+            center_of_mass_velocity,
+            natural_frequency,
+            position: center_of_mass + center_of_mass_velocity / natural_frequency,
+        }
+    }
+
+    /// Estimates the pendulum from the kinematic center of mass, tilted around the support sole
+    /// by the measured torso orientation, and the angular velocity measured by the gyroscope.
+    pub fn estimate(context: &Context, support_side: Side) -> Self {
+        let current_feet = Feet::from_joints(
+            context.backend,
+            context.robot_to_walk,
+            &context.last_actuated_joints,
+            support_side,
+        );
+
+        let default_torso_rotation = context.robot_to_walk.rotation().inner;
+        let tilt = context.robot_orientation.inner * default_torso_rotation.inverse();
+
+        let support_position = current_feet.support_sole.position().inner.coords;
+        let center_of_mass = (context.robot_to_walk * *context.center_of_mass)
+            .inner
+            .coords;
+        let support_to_center_of_mass = tilt * (center_of_mass - support_position);
+
+        let angular_velocity = default_torso_rotation * context.gyro;
+        let velocity = angular_velocity.cross(&support_to_center_of_mass);
+
+        let height = support_to_center_of_mass.z.max(MINIMUM_PENDULUM_HEIGHT);
+        let natural_frequency = (context.gravity_acceleration / height).sqrt();
+
+        Self::new(
+            point![
+                support_position.x + support_to_center_of_mass.x,
+                support_position.y + support_to_center_of_mass.y
+            ],
+            vector![velocity.x, velocity.y],
+            natural_frequency,
+        )
+    }
+
+    /// Capture point after `duration` with the center of pressure staying at `center_of_pressure`
+    pub fn predict(&self, center_of_pressure: Point2<Walk>, duration: Duration) -> Point2<Walk> {
+        let divergence = (self.natural_frequency * duration.as_secs_f32()).exp();
+
+        center_of_pressure + (self.position - center_of_pressure) * divergence
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct RecoveryStep {
+    pub capture_point: Point2<Walk>,
+    /// Capture point at the time the swing sole touches down
+    pub predicted_capture_point: Point2<Walk>,
+    /// Swing sole positions reachable within the anatomic constraints
+    pub region: Rectangle<Walk>,
+    pub target: Point2<Walk>,
+    pub swing_duration: Duration,
+    pub step: Step,
+    /// Whether the predicted capture point lies inside the region, i.e. this step stops the push
+    pub is_capturable: bool,
+}
+
+/// Chooses the shortest swing duration after which the capture point can be stepped onto.
+///
+/// If no sampled duration allows that, the duration bringing the capture point closest to the
+/// reachable region is chosen and the step ends at the region's border.
+pub fn plan_recovery_step(
+    capture_point: &CapturePoint,
+    support_position: Point2<Walk>,
+    support_side: Side,
+    parameters: &Parameters,
+) -> RecoveryStep {
+    let push_recovery = &parameters.push_recovery;
+    let region = reachable_region(support_position, support_side, parameters);
+
+    let number_of_samples = push_recovery.swing_duration_samples.max(2);
+    let duration_range = push_recovery
+        .max_swing_duration
+        .saturating_sub(push_recovery.min_swing_duration);
+    let predictions: Vec<_> = (0..number_of_samples)
+        .map(|index| {
+            let swing_duration = push_recovery.min_swing_duration
+                + duration_range.mul_f32(index as f32 / (number_of_samples - 1) as f32);
+            (
+                swing_duration,
+                capture_point.predict(support_position, swing_duration),
+            )
+        })
+        .collect();
+
+    let distance_to_region =
+        |point: Point2<Walk>| (point - region.project_point_into_rect(point)).norm();
+    let (swing_duration, predicted_capture_point) = predictions
+        .iter()
+        .find(|(_, prediction)| region.contains(*prediction))
+        .or_else(|| {
+            predictions.iter().min_by(|(_, left), (_, right)| {
+                distance_to_region(*left).total_cmp(&distance_to_region(*right))
+            })
+        })
+        .copied()
+        .expect("at least two swing durations are sampled");
+
+    let target = region.project_point_into_rect(predicted_capture_point);
+    let (support_offset, swing_offset) = base_offsets(parameters, support_side);
+    let step = target - support_position - (swing_offset - support_offset).xy();
+
+    RecoveryStep {
+        capture_point: capture_point.position,
+        predicted_capture_point,
+        region,
+        target,
+        swing_duration,
+        step: Step {
+            forward: step.x(),
+            left: step.y(),
+            turn: 0.0,
+        },
+        is_capturable: region.contains(predicted_capture_point),
+    }
+}
+
+/// Swing sole positions for which both end feet satisfy the anatomic constraints
+fn reachable_region(
+    support_position: Point2<Walk>,
+    support_side: Side,
+    parameters: &Parameters,
+) -> Rectangle<Walk> {
+    let constraints = &parameters.anatomic_constraints;
+    let (support_offset, swing_offset) = base_offsets(parameters, support_side);
+    let left_valid_y = parameters.base.foot_offset_left.y()..constraints.valid_y.end;
+    let right_valid_y = constraints.valid_y.start..parameters.base.foot_offset_right.y();
+    let (support_valid_y, swing_valid_y) = match support_side {
+        Side::Left => (left_valid_y, right_valid_y),
+        Side::Right => (right_valid_y, left_valid_y),
+    };
+
+    let forward = request_range(
+        constraints.valid_x.clone(),
+        constraints.valid_x.clone(),
+        support_offset.x(),
+        swing_offset.x(),
+    );
+    let left = request_range(
+        support_valid_y,
+        swing_valid_y,
+        support_offset.y(),
+        swing_offset.y(),
+    );
+
+    let support_to_swing_offset = (swing_offset - support_offset).xy();
+    Rectangle {
+        min: support_position + support_to_swing_offset + vector![forward.start, left.start],
+        max: support_position + support_to_swing_offset + vector![forward.end, left.end],
+    }
+}
+
+/// Range of a step request component for which the support sole ends within `support_valid`
+/// and the swing sole within `swing_valid`, see [`Feet::end_from_request`]
+fn request_range(
+    support_valid: Range<f32>,
+    swing_valid: Range<f32>,
+    support_offset: f32,
+    swing_offset: f32,
+) -> Range<f32> {
+    let start = f32::max(
+        2.0 * (swing_valid.start - swing_offset),
+        2.0 * (support_offset - support_valid.end),
+    );
+    let end = f32::min(
+        2.0 * (swing_valid.end - swing_offset),
+        2.0 * (support_offset - support_valid.start),
+    );
+
+    start..end.max(start)
+}
+
+fn base_offsets(parameters: &Parameters, support_side: Side) -> (Vector3<Walk>, Vector3<Walk>) {
+    match support_side {
+        Side::Left => (
+            parameters.base.foot_offset_left,
+            parameters.base.foot_offset_right,
+        ),
+        Side::Right => (
+            parameters.base.foot_offset_right,
+            parameters.base.foot_offset_left,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use coordinate_systems::Walk;
+    use linear_algebra::{point, vector, Vector2};
+    use types::support_foot::Side;
+
+    use crate::parameters::{
+        AnatomicConstraintsParameters, Base, Parameters, PushRecoveryParameters,
+    };
+
+    use super::{plan_recovery_step, CapturePoint};
+
+    const NATURAL_FREQUENCY: f32 = 6.5;
+    const SOLE_HALF_EXTENTS: [f32; 2] = [0.05, 0.025];
+
+    fn parameters() -> Parameters {
+        Parameters {
+            anatomic_constraints: AnatomicConstraintsParameters {
+                valid_x: -0.1..0.1,
+                valid_y: -0.2..0.2,
+            },
+            base: Base {
+                foot_offset_left: vector![0.0, 0.052, 0.0],
+                foot_offset_right: vector![0.0, -0.052, 0.0],
+                ..Default::default()
+            },
+            push_recovery: PushRecoveryParameters {
+                enabled: true,
+                min_swing_duration: Duration::from_millis(150),
+                max_swing_duration: Duration::from_millis(350),
+                swing_duration_samples: 9,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Pushes the pendulum standing between the feet while supported by the left sole and
+    /// checks whether the capture point at touchdown lies within the recovery sole
+    fn survives_push(push: Vector2<Walk>) -> bool {
+        let parameters = parameters();
+        let support_position = point![0.0, 0.052];
+        let capture_point = CapturePoint::new(point![0.0, 0.0], push, NATURAL_FREQUENCY);
+
+        let recovery_step =
+            plan_recovery_step(&capture_point, support_position, Side::Left, &parameters);
+
+        let remaining = recovery_step.predicted_capture_point - recovery_step.target;
+        remaining.x().abs() <= SOLE_HALF_EXTENTS[0] && remaining.y().abs() <= SOLE_HALF_EXTENTS[1]
+    }
+
+    #[test]
+    fn survives_moderate_pushes() {
+        assert!(survives_push(vector![0.0, 0.0]));
+        assert!(survives_push(vector![0.3, 0.0]));
+        assert!(survives_push(vector![-0.3, 0.0]));
+        assert!(survives_push(vector![0.0, -0.3]));
+        assert!(survives_push(vector![0.2, -0.2]));
+    }
+
+    #[test]
+    fn fails_strong_pushes() {
+        assert!(!survives_push(vector![3.0, 0.0]));
+        assert!(!survives_push(vector![0.0, -3.0]));
+    }
+
+    #[test]
+    fn survivable_forward_push_magnitude() {
+        let maximum_survivable_push = (0..100)
+            .map(|index| index as f32 * 0.05)
+            .take_while(|&magnitude| survives_push(vector![magnitude, 0.0]))
+            .last()
+            .expect("standing still should be survivable");
+
+        assert!(
+            (0.3..1.0).contains(&maximum_survivable_push),
+            "maximum survivable push: {maximum_survivable_push} m/s"
+        );
+    }
+
+    #[test]
+    fn recovery_step_is_within_reachable_region() {
+        let parameters = parameters();
+        let support_position = point![0.0, 0.052];
+        let capture_point =
+            CapturePoint::new(point![0.0, 0.0], vector![0.3, -0.1], NATURAL_FREQUENCY);
+
+        let recovery_step =
+            plan_recovery_step(&capture_point, support_position, Side::Left, &parameters);
+
+        assert!(recovery_step.is_capturable);
+        assert!(recovery_step.region.contains(recovery_step.target));
+        assert_eq!(
+            recovery_step.swing_duration,
+            parameters.push_recovery.min_swing_duration
+        );
+        assert!(recovery_step.step.left <= 0.0);
+    }
+}
@@ -1,11 +1,10 @@
 use coordinate_systems::{Robot, Walk};
-use kinematics::forward::{left_sole_to_robot, right_sole_to_robot};
 use linear_algebra::{point, Isometry3, Orientation3, Pose2, Pose3, Vector2, Vector3};
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
 use types::{joints::body::BodyJoints, step::Step, support_foot::Side};
 
-use crate::parameters::Parameters;
+use crate::{backend::Backend, parameters::Parameters};
 
 #[derive(
     Clone, Copy, Debug, Serialize, Deserialize, PathSerialize, PathDeserialize, PathIntrospect,
@@ -43,12 +42,13 @@ impl<T> Feet<T> {
 
 impl Feet {
     pub fn from_joints(
+        backend: &dyn Backend,
         robot_to_walk: Isometry3<Robot, Walk>,
         joints: &BodyJoints,
         support_side: Side,
     ) -> Self {
-        let left_sole = robot_to_walk * left_sole_to_robot(&joints.left_leg).as_pose();
-        let right_sole = robot_to_walk * right_sole_to_robot(&joints.right_leg).as_pose();
+        let left_sole = robot_to_walk * backend.sole_to_robot(Side::Left, &joints.left_leg);
+        let right_sole = robot_to_walk * backend.sole_to_robot(Side::Right, &joints.right_leg);
 
         match support_side {
             Side::Left => Feet {
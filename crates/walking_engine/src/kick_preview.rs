@@ -0,0 +1,239 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use linear_algebra::{vector, Isometry3, Orientation3, Point2, Point3, Vector3};
+use types::{
+    cycle_time::CycleTime, joints::body::BodyJoints, motion_command::KickVariant,
+    obstacle_avoiding_arms::ArmCommands, support_foot::Side,
+    walk_volume_extents::WalkVolumeExtents,
+};
+
+use crate::{
+    backend::nao::Nao,
+    feet::Feet,
+    kick_state::KickState,
+    kick_steps::KickSteps,
+    mode::{kicking::Kicking, standing::Standing},
+    parameters::Parameters,
+    Context,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct KickPreviewSample {
+    /// Time since the start of the kick
+    pub time: Duration,
+    pub step_index: usize,
+    pub support_side: Side,
+    pub feet: Feet,
+    pub joints: BodyJoints,
+}
+
+/// Executes a kick through the walking engine without a robot.
+///
+/// The robot is assumed to stand upright without any disturbance, starting from the standing
+/// pose, and every kick step ends exactly after its step duration.
+pub fn preview_kick(
+    parameters: &Parameters,
+    kick_steps: &KickSteps,
+    variant: KickVariant,
+    kicking_side: Side,
+    strength: f32,
+    cycle_duration: Duration,
+) -> Vec<KickPreviewSample> {
+    if cycle_duration.is_zero() {
+        return Vec::new();
+    }
+
+    let backend = Nao::default();
+    let cycle_time = CycleTime {
+        start_time: UNIX_EPOCH,
+        last_cycle_duration: cycle_duration,
+    };
+    let robot_to_walk = Isometry3::from_parts(
+        vector![
+            parameters.base.torso_offset,
+            0.0,
+            parameters.base.walk_height
+        ],
+        Orientation3::new(Vector3::y_axis() * parameters.base.torso_tilt_base),
+    );
+    let robot_orientation = Orientation3::new(Vector3::y_axis() * parameters.base.torso_tilt_base);
+    let walk_volume_extents = WalkVolumeExtents::default();
+    let obstacle_avoiding_arms = ArmCommands::default();
+    let center_of_mass = Point3::origin();
+    let zero_moment_point = Point2::origin();
+
+    let context = |joints: BodyJoints| Context {
+        parameters,
+        walk_volume_extents: &walk_volume_extents,
+        kick_steps,
+        cycle_time: &cycle_time,
+        center_of_mass: &center_of_mass,
+        gravity_acceleration: 9.81,
+        zero_moment_point: &zero_moment_point,
+        consecutive_cycles_zero_moment_point_outside_support_polygon: &0,
+        backend: &backend,
+        robot_orientation: &robot_orientation,
+        robot_to_ground: None,
+        gyro: nalgebra::Vector3::zeros(),
+        last_actuated_joints: joints,
+        measured_joints: joints,
+        robot_to_walk,
+        obstacle_avoiding_arms: &obstacle_avoiding_arms,
+    };
+
+    let mut joints = Standing {}
+        .compute_commands(&context(BodyJoints::default()))
+        .positions;
+    let mut kick = KickState::new(variant, kicking_side, strength);
+    let mut support_side = kicking_side;
+    let mut time = Duration::ZERO;
+    let mut samples = Vec::new();
+
+    while !kick.is_finished(kick_steps) {
+        let mut kicking = Kicking::new(&context(joints), kick, support_side);
+        while kicking.step.time_since_start < kicking.step.plan.step_duration {
+            let context = context(joints);
+            kicking.tick(&context);
+            joints = kicking.compute_commands(&context).positions;
+            time += cycle_duration;
+            samples.push(KickPreviewSample {
+                time,
+                step_index: kick.index,
+                support_side,
+                feet: kicking.step.last_engine_feet,
+                joints,
+            });
+        }
+        kick = kick.advance_to_next_step();
+        support_side = support_side.opposite();
+    }
+
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use linear_algebra::vector;
+    use types::{motion_command::KickVariant, step::Step, support_foot::Side};
+
+    use crate::{
+        kick_steps::{JointOverride, JointOverrides, KickStep, KickSteps},
+        parameters::{Base, Parameters},
+    };
+
+    use super::preview_kick;
+
+    fn kick_step(step_duration: Duration, swing_overrides: JointOverrides) -> KickStep {
+        KickStep {
+            base_step: Step {
+                forward: 0.04,
+                left: 0.0,
+                turn: 0.0,
+            },
+            step_duration,
+            foot_lift_apex: 0.015,
+            midpoint: 0.5,
+            support_overrides: JointOverrides {
+                hip_pitch: None,
+                knee_pitch: None,
+                ankle_pitch: None,
+            },
+            swing_overrides,
+        }
+    }
+
+    fn parameters() -> Parameters {
+        Parameters {
+            base: Base {
+                foot_offset_left: vector![0.0, 0.052, 0.0],
+                foot_offset_right: vector![0.0, -0.052, 0.0],
+                walk_height: 0.23,
+                ..Default::default()
+            },
+            max_foot_speed: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn preview_covers_all_kick_steps() {
+        let no_overrides = JointOverrides {
+            hip_pitch: None,
+            knee_pitch: None,
+            ankle_pitch: None,
+        };
+        let kick_steps = KickSteps {
+            forward: vec![
+                kick_step(Duration::from_millis(250), no_overrides.clone()),
+                kick_step(Duration::from_millis(300), no_overrides),
+            ],
+            ..Default::default()
+        };
+
+        let samples = preview_kick(
+            &parameters(),
+            &kick_steps,
+            KickVariant::Forward,
+            Side::Left,
+            1.0,
+            Duration::from_millis(10),
+        );
+
+        assert_eq!(samples.len(), 25 + 30);
+        assert_eq!(samples[0].support_side, Side::Left);
+        assert_eq!(samples[24].step_index, 0);
+        assert_eq!(samples[25].step_index, 1);
+        assert_eq!(samples[25].support_side, Side::Right);
+        assert_eq!(samples.last().unwrap().time, Duration::from_millis(550));
+    }
+
+    #[test]
+    fn swing_overrides_are_scaled_by_strength() {
+        let hip_pitch_override = vec![
+            JointOverride {
+                value: 0.0,
+                timepoint: Duration::ZERO,
+            },
+            JointOverride {
+                value: -0.4,
+                timepoint: Duration::from_millis(100),
+            },
+            JointOverride {
+                value: 0.0,
+                timepoint: Duration::from_millis(200),
+            },
+        ];
+        let kick_steps = |hip_pitch| KickSteps {
+            forward: vec![kick_step(
+                Duration::from_millis(200),
+                JointOverrides {
+                    hip_pitch,
+                    knee_pitch: None,
+                    ankle_pitch: None,
+                },
+            )],
+            ..Default::default()
+        };
+        let preview = |kick_steps: &KickSteps, strength| {
+            preview_kick(
+                &parameters(),
+                kick_steps,
+                KickVariant::Forward,
+                Side::Left,
+                strength,
+                Duration::from_millis(10),
+            )
+        };
+
+        let without_override = preview(&kick_steps(None), 1.0);
+        let with_override = preview(&kick_steps(Some(hip_pitch_override)), 0.5);
+
+        let at_apex = 9;
+        assert_eq!(with_override[at_apex].time, Duration::from_millis(100));
+        let difference = with_override[at_apex].joints.right_leg.hip_pitch
+            - without_override[at_apex].joints.right_leg.hip_pitch;
+        assert!((difference + 0.2).abs() < 1e-4, "difference: {difference}");
+    }
+}
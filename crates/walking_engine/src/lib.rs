@@ -1,4 +1,5 @@
 use arm::ArmOverrides as _;
+use backend::Backend;
 use coordinate_systems::{Field, Ground, Robot, Walk};
 use kick_steps::KickSteps;
 use linear_algebra::{Isometry3, Orientation3, Point2, Point3};
@@ -8,21 +9,25 @@ use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
 use types::{
     cycle_time::CycleTime, joints::body::BodyJoints, motion_command::KickVariant,
-    motor_commands::MotorCommands, obstacle_avoiding_arms::ArmCommands,
-    sensor_data::ForceSensitiveResistors, step::Step, support_foot::Side,
-    walk_volume_extents::WalkVolumeExtents,
+    motor_commands::MotorCommands, obstacle_avoiding_arms::ArmCommands, step::Step,
+    support_foot::Side, walk_volume_extents::WalkVolumeExtents,
 };
 
 mod anatomic_constraints;
 mod arm;
-mod compensate_stiffness_loss;
+pub mod backend;
+pub mod capture_point;
+pub mod compensate_stiffness_loss;
 pub mod feet;
-mod foot_leveling;
-mod gyro_balancing;
+pub mod foot_leveling;
+pub mod gyro_balancing;
+pub mod kick_preview;
 pub mod kick_state;
 pub mod kick_steps;
 pub mod mode;
 pub mod parameters;
+#[cfg(test)]
+mod push_simulation;
 mod step_plan;
 pub mod step_state;
 mod stiffness;
@@ -38,9 +43,10 @@ pub struct Context<'a> {
     pub kick_steps: &'a KickSteps,
     pub cycle_time: &'a CycleTime,
     pub center_of_mass: &'a Point3<Robot>,
+    pub gravity_acceleration: f32,
     pub zero_moment_point: &'a Point2<Ground>,
     pub consecutive_cycles_zero_moment_point_outside_support_polygon: &'a i32,
-    pub force_sensitive_resistors: &'a ForceSensitiveResistors,
+    pub backend: &'a dyn Backend,
     pub robot_orientation: &'a Orientation3<Field>,
     pub robot_to_ground: Option<&'a Isometry3<Robot, Ground>>,
     pub gyro: nalgebra::Vector3<f32>,
@@ -6,17 +6,18 @@ use nalgebra::SimdPartialOrd;
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
 use types::{
-    joints::body::BodyJoints,
-    motion_command::KickVariant,
-    motor_commands::MotorCommands,
-    robot_dimensions::{transform_left_sole_outline, transform_right_sole_outline},
-    step::Step,
-    support_foot::Side,
+    joints::body::BodyJoints, motion_command::KickVariant, motor_commands::MotorCommands,
+    step::Step, support_foot::Side,
 };
 
 use crate::{
-    anatomic_constraints::clamp_feet_to_anatomic_constraints, feet::Feet, step_plan::StepPlan,
-    step_state::StepState, stiffness::Stiffness as _, Context,
+    anatomic_constraints::clamp_feet_to_anatomic_constraints,
+    capture_point::{plan_recovery_step, CapturePoint, RecoveryStep},
+    feet::Feet,
+    step_plan::StepPlan,
+    step_state::StepState,
+    stiffness::Stiffness as _,
+    Context,
 };
 
 #[derive(
@@ -24,13 +25,20 @@ use crate::{
 )]
 pub struct Catching {
     pub step: StepState,
+    /// Only planned if push recovery is enabled
+    pub recovery_step: Option<RecoveryStep>,
 }
 
 impl Catching {
     pub fn new(context: &Context, last_step_state: StepState, support_side: Side) -> Self {
+        if context.parameters.push_recovery.enabled {
+            return Self::new_with_recovery_step(context, last_step_state, support_side);
+        }
+
         let Some(robot_to_ground) = context.robot_to_ground else {
             return Self {
                 step: last_step_state,
+                recovery_step: None,
             };
         };
 
@@ -41,17 +49,15 @@ impl Catching {
         let mut target =
             (robot_to_walk * ground_to_robot * context.zero_moment_point.extend(0.0)).xy();
 
-        let current_feet =
-            Feet::from_joints(robot_to_walk, &context.last_actuated_joints, support_side);
-        let support_outline: Vec<_> = if support_side == Side::Left {
-            transform_left_sole_outline(current_feet.support_sole.as_transform())
-                .map(|point| point.xy())
-                .collect()
-        } else {
-            transform_right_sole_outline(current_feet.support_sole.as_transform())
-                .map(|point| point.xy())
-                .collect()
-        };
+        let current_feet = Feet::from_joints(
+            context.backend,
+            robot_to_walk,
+            &context.last_actuated_joints,
+            support_side,
+        );
+        let support_outline = context
+            .backend
+            .sole_outline(support_side, current_feet.support_sole);
         if target.x().abs() < context.parameters.catching_steps.balance_region_x
             && ((support_side == Side::Left
                 && support_outline.iter().all(|point| point.y() < target.y()))
@@ -118,6 +124,46 @@ impl Catching {
                 plan,
                 ..last_step_state
             },
+            recovery_step: None,
+        }
+    }
+
+    /// Steps onto the capture point predicted at touchdown, the swing duration is chosen as
+    /// short as possible
+    fn new_with_recovery_step(
+        context: &Context,
+        last_step_state: StepState,
+        support_side: Side,
+    ) -> Self {
+        let capture_point = CapturePoint::estimate(context, support_side);
+        let current_feet = Feet::from_joints(
+            context.backend,
+            context.robot_to_walk,
+            &context.last_actuated_joints,
+            support_side,
+        );
+        let recovery_step = plan_recovery_step(
+            &capture_point,
+            current_feet.support_sole.position().xy(),
+            support_side,
+            context.parameters,
+        );
+
+        let end_feet = Feet::end_from_request(context.parameters, recovery_step.step, support_side);
+        let mut plan = StepPlan::new_with_start_and_end_feet(
+            context,
+            support_side,
+            last_step_state.plan.start_feet,
+            end_feet,
+        );
+        plan.step_duration = last_step_state.time_since_start + recovery_step.swing_duration;
+
+        Self {
+            step: StepState {
+                plan,
+                ..last_step_state
+            },
+            recovery_step: Some(recovery_step),
         }
     }
 
@@ -281,8 +327,23 @@ pub fn should_catch(context: &Context, end_feet: Feet, support_side: Side) -> bo
     let ground_to_robot = robot_to_ground.inverse();
     let robot_to_walk = context.robot_to_walk;
 
-    let current_feet =
-        Feet::from_joints(robot_to_walk, &context.last_actuated_joints, support_side);
+    let current_feet = Feet::from_joints(
+        context.backend,
+        robot_to_walk,
+        &context.last_actuated_joints,
+        support_side,
+    );
+
+    if context.parameters.push_recovery.enabled {
+        let capture_point = CapturePoint::estimate(context, support_side);
+        return is_outside_support_polygon(
+            context,
+            end_feet,
+            support_side,
+            capture_point.position,
+            current_feet,
+        );
+    }
 
     let zmp = context.zero_moment_point;
     let target_scaling_x = if zmp.coords().x() < 0.0 {
@@ -298,10 +359,11 @@ pub fn should_catch(context: &Context, end_feet: Feet, support_side: Side) -> bo
         .component_mul(&target_scaling)
         .as_point();
 
-    is_outside_support_polygon(end_feet, support_side, target, current_feet)
+    is_outside_support_polygon(context, end_feet, support_side, target, current_feet)
 }
 
 fn is_outside_support_polygon(
+    context: &Context,
     end_feet: Feet,
     support_side: Side,
     target: Point2<Walk>,
@@ -309,28 +371,16 @@ fn is_outside_support_polygon(
 ) -> bool {
     // the red swing foot
     let target_swing_sole = end_feet.swing_sole;
-
-    let feet_outlines: Vec<_> = if support_side == Side::Left {
-        transform_left_sole_outline(current_feet.support_sole.as_transform())
-            .chain(transform_right_sole_outline(
-                current_feet.swing_sole.as_transform(),
-            ))
-            .chain(transform_right_sole_outline(
-                target_swing_sole.as_transform(),
-            ))
-            .map(|point| point.xy())
-            .collect()
-    } else {
-        transform_right_sole_outline(current_feet.support_sole.as_transform())
-            .chain(transform_left_sole_outline(
-                current_feet.swing_sole.as_transform(),
-            ))
-            .chain(transform_left_sole_outline(
-                target_swing_sole.as_transform(),
-            ))
-            .map(|point| point.xy())
-            .collect()
-    };
+    let swing_side = support_side.opposite();
+
+    let feet_outlines: Vec<_> = [
+        (support_side, current_feet.support_sole),
+        (swing_side, current_feet.swing_sole),
+        (swing_side, target_swing_sole),
+    ]
+    .into_iter()
+    .flat_map(|(side, sole)| context.backend.sole_outline(side, sole))
+    .collect();
 
     !is_inside_convex_hull(&feet_outlines, &target)
 }
@@ -31,6 +31,7 @@ pub struct Kicking {
 impl Kicking {
     pub fn new(context: &Context, kick: KickState, support_side: Side) -> Self {
         let start_feet = Feet::from_joints(
+            context.backend,
             context.robot_to_walk,
             &context.last_actuated_joints,
             support_side,
@@ -32,6 +32,7 @@ pub struct Parameters {
     pub max_support_foot_lift_speed: f32,
     pub max_turn_acceleration: f32,
     pub min_step_duration: Duration,
+    pub push_recovery: PushRecoveryParameters,
     pub sole_pressure_threshold: f32,
     pub min_sole_pressure: f32,
     pub step_midpoint: Step,
@@ -134,6 +135,17 @@ pub struct CatchingStepsParameters {
     pub balance_region_x: f32,
 }
 
+/// Plans catching steps onto the capture point instead of the scaled zero moment point
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
+)]
+pub struct PushRecoveryParameters {
+    pub enabled: bool,
+    pub min_swing_duration: Duration,
+    pub max_swing_duration: Duration,
+    pub swing_duration_samples: usize,
+}
+
 #[derive(
     Clone, Debug, Default, Deserialize, Serialize, PathSerialize, PathDeserialize, PathIntrospect,
 )]
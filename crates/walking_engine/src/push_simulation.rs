@@ -0,0 +1,447 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use coordinate_systems::{Ground, Walk};
+use linear_algebra::{vector, Isometry3, Orientation3, Point2, Point3, Vector2, Vector3};
+use types::{
+    cycle_time::CycleTime,
+    joints::body::BodyJoints,
+    obstacle_avoiding_arms::ArmCommands,
+    sensor_data::{Foot, ForceSensitiveResistors},
+    step::Step,
+    support_foot::Side,
+    walk_volume_extents::WalkVolumeExtents,
+};
+
+use crate::{
+    backend::{nao::Nao, Backend},
+    feet::Feet,
+    kick_steps::KickSteps,
+    parameters::Parameters,
+    Context, Engine,
+};
+
+const GRAVITY_ACCELERATION: f32 = 9.81;
+/// Tilt of the robot around its support sole after which it is considered fallen
+const FALLEN_TILT: f32 = 0.5;
+/// Height of the swing sole above the support sole below which it touches the ground
+const TOUCHDOWN_HEIGHT: f32 = 0.001;
+/// Summed load on the force sensitive resistors of the sole carrying the robot
+const SUPPORT_SOLE_PRESSURE: f32 = 1.0;
+/// Gains of the ankles holding the robot upright, in 1/s² and 1/s
+const ANKLE_STIFFNESS: f32 = 400.0;
+const ANKLE_DAMPING: f32 = 40.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PushOutcome {
+    pub is_fallen: bool,
+    /// Number of support changes after the push
+    pub number_of_steps: usize,
+    /// Largest tilt of the robot around its support sole after the push
+    pub maximum_tilt: f32,
+}
+
+/// Walks on the spot with the walking engine on a NAO and pushes its center of mass with the
+/// given velocity after `time_until_push`.
+///
+/// The robot is a rigid body on the soles the walking engine puts on the ground. Its ankles keep
+/// it upright as long as the center of pressure they need stays within the soles on the ground,
+/// otherwise it tips over as a linear inverted pendulum. The IMU and the force sensitive resistors
+/// are derived from this tilt, so the walking engine reacts to the push as on a robot.
+pub fn simulate_push(
+    parameters: &Parameters,
+    push: Vector2<Walk>,
+    time_until_push: Duration,
+    time_after_push: Duration,
+    cycle_duration: Duration,
+) -> PushOutcome {
+    let kick_steps = KickSteps::default();
+    let walk_volume_extents = WalkVolumeExtents::default();
+    let obstacle_avoiding_arms = ArmCommands::default();
+    let cycle_time = CycleTime {
+        start_time: UNIX_EPOCH,
+        last_cycle_duration: cycle_duration,
+    };
+    let default_torso_rotation =
+        Orientation3::<Walk>::new(Vector3::y_axis() * parameters.base.torso_tilt_base);
+    let robot_to_walk = Isometry3::from_parts(
+        vector![
+            parameters.base.torso_offset,
+            0.0,
+            parameters.base.walk_height
+        ],
+        default_torso_rotation,
+    );
+    let center_of_mass = Point3::origin();
+    let center_of_mass_in_walk = robot_to_walk * center_of_mass;
+
+    let mut engine = Engine::default();
+    let mut backend = Nao::default();
+    let mut joints = BodyJoints::default();
+    let mut plant: Option<Plant> = None;
+    let mut outcome = PushOutcome {
+        is_fallen: false,
+        number_of_steps: 0,
+        maximum_tilt: 0.0,
+    };
+
+    let mut time = Duration::ZERO;
+    while time < time_until_push + time_after_push {
+        let is_pushed = time >= time_until_push;
+
+        // The first cycle only brings the joints into the standing pose
+        let Some(plant) = plant.as_mut() else {
+            let context = Context {
+                parameters,
+                walk_volume_extents: &walk_volume_extents,
+                kick_steps: &kick_steps,
+                cycle_time: &cycle_time,
+                center_of_mass: &center_of_mass,
+                gravity_acceleration: GRAVITY_ACCELERATION,
+                zero_moment_point: &Point2::origin(),
+                consecutive_cycles_zero_moment_point_outside_support_polygon: &0,
+                backend: &backend,
+                robot_orientation: &Orientation3::wrap(default_torso_rotation.inner),
+                robot_to_ground: None,
+                gyro: nalgebra::Vector3::zeros(),
+                last_actuated_joints: joints,
+                measured_joints: joints,
+                robot_to_walk,
+                obstacle_avoiding_arms: &obstacle_avoiding_arms,
+            };
+            engine.stand(&context);
+            joints = engine.compute_commands(&context).positions;
+            let feet = Feet::from_joints(&backend, robot_to_walk, &joints, Side::Left);
+            plant = Some(Plant::standing_on(&feet));
+            continue;
+        };
+
+        let mut feet = Feet::from_joints(&backend, robot_to_walk, &joints, plant.support_side);
+        let is_swing_sole_on_ground =
+            feet.swing_sole.position().z() - feet.support_sole.position().z() <= TOUCHDOWN_HEIGHT;
+        match (plant.swing_sole.is_some(), is_swing_sole_on_ground) {
+            (true, false) => plant.swing_sole = None,
+            (false, true) => {
+                plant.touch_down(&feet);
+                feet = feet.switch();
+                if is_pushed {
+                    outcome.number_of_steps += 1;
+                }
+            }
+            _ => {}
+        }
+
+        let support_to_center_of_mass = center_of_mass_in_walk - feet.support_sole.position();
+        let tilted_support_to_center_of_mass = nalgebra::vector![
+            support_to_center_of_mass.x() + plant.displacement.x(),
+            support_to_center_of_mass.y() + plant.displacement.y(),
+            (support_to_center_of_mass.norm_squared()
+                - (support_to_center_of_mass.xy() + plant.displacement).norm_squared())
+            .max(0.0)
+            .sqrt()
+        ];
+        let tilt = nalgebra::UnitQuaternion::rotation_between(
+            &support_to_center_of_mass.inner,
+            &tilted_support_to_center_of_mass,
+        )
+        .unwrap_or_default();
+        if is_pushed {
+            outcome.maximum_tilt = outcome.maximum_tilt.max(tilt.angle());
+        }
+        if tilt.angle() > FALLEN_TILT {
+            outcome.is_fallen = true;
+            break;
+        }
+
+        let angular_velocity = tilted_support_to_center_of_mass.cross(&nalgebra::vector![
+            plant.velocity.x(),
+            plant.velocity.y(),
+            0.0
+        ]) / tilted_support_to_center_of_mass.norm_squared();
+        let gyro = default_torso_rotation.inner.inverse() * angular_velocity;
+
+        let support_pressure = Foot::fill(SUPPORT_SOLE_PRESSURE / 4.0);
+        backend.force_sensitive_resistors = match plant.support_side {
+            Side::Left => ForceSensitiveResistors {
+                left: support_pressure,
+                right: Foot::default(),
+            },
+            Side::Right => ForceSensitiveResistors {
+                left: Foot::default(),
+                right: support_pressure,
+            },
+        };
+
+        let height = tilted_support_to_center_of_mass.z;
+        let center_of_mass_on_ground =
+            plant.support_sole + support_to_center_of_mass.xy() + plant.displacement;
+        let center_of_pressure =
+            plant.center_of_pressure(&backend, &feet, center_of_mass_on_ground, height);
+        let walk_to_ground = Isometry3::<Walk, Ground>::from(Vector3::<Ground>::wrap(
+            -feet.support_sole.position().coords().inner,
+        ));
+        let robot_to_ground = walk_to_ground * robot_to_walk;
+        let zero_moment_point =
+            Point2::<Ground>::wrap((center_of_pressure - plant.support_sole).inner.into());
+
+        let context = Context {
+            parameters,
+            walk_volume_extents: &walk_volume_extents,
+            kick_steps: &kick_steps,
+            cycle_time: &cycle_time,
+            center_of_mass: &center_of_mass,
+            gravity_acceleration: GRAVITY_ACCELERATION,
+            zero_moment_point: &zero_moment_point,
+            consecutive_cycles_zero_moment_point_outside_support_polygon: &0,
+            backend: &backend,
+            robot_orientation: &Orientation3::wrap(tilt * default_torso_rotation.inner),
+            robot_to_ground: Some(&robot_to_ground),
+            gyro,
+            last_actuated_joints: joints,
+            measured_joints: joints,
+            robot_to_walk,
+            obstacle_avoiding_arms: &obstacle_avoiding_arms,
+        };
+        engine.walk(&context, Step::ZERO);
+        engine.tick(&context);
+        joints = engine.compute_commands(&context).positions;
+
+        if is_pushed && time < time_until_push + cycle_duration {
+            plant.velocity += push;
+        }
+        let acceleration =
+            (center_of_mass_on_ground - center_of_pressure) * GRAVITY_ACCELERATION / height;
+        plant.velocity += acceleration * cycle_duration.as_secs_f32();
+        plant.displacement += plant.velocity * cycle_duration.as_secs_f32();
+
+        time += cycle_duration;
+    }
+
+    outcome
+}
+
+/// Largest push in the given direction, sampled in steps of `resolution`, which the robot
+/// survives while walking on the spot
+pub fn maximum_survivable_push(
+    parameters: &Parameters,
+    direction: Vector2<Walk>,
+    resolution: f32,
+    maximum_magnitude: f32,
+) -> f32 {
+    let direction = direction.normalize();
+    let number_of_samples = (maximum_magnitude / resolution).floor() as usize;
+    (1..=number_of_samples)
+        .map(|index| index as f32 * resolution)
+        .take_while(|&magnitude| {
+            !simulate_push(
+                parameters,
+                direction * magnitude,
+                Duration::from_secs(2),
+                Duration::from_secs(3),
+                Duration::from_millis(12),
+            )
+            .is_fallen
+        })
+        .last()
+        .unwrap_or(0.0)
+}
+
+/// Soles on the ground and tilt of the robot, in a walk frame which stays fixed to the ground
+struct Plant {
+    support_side: Side,
+    support_sole: Point2<Walk>,
+    /// Only known while the swing sole touches the ground
+    swing_sole: Option<Point2<Walk>>,
+    /// Horizontal displacement of the center of mass by the tilt of the robot
+    displacement: Vector2<Walk>,
+    velocity: Vector2<Walk>,
+}
+
+impl Plant {
+    fn standing_on(feet: &Feet) -> Self {
+        Self {
+            support_side: Side::Left,
+            support_sole: feet.support_sole.position().xy(),
+            swing_sole: Some(feet.swing_sole.position().xy()),
+            displacement: Vector2::zeros(),
+            velocity: Vector2::zeros(),
+        }
+    }
+
+    /// The swing sole touches the ground where the legs put it and takes over the support
+    fn touch_down(&mut self, feet: &Feet) {
+        let swing_sole = self.support_sole
+            + (feet.swing_sole.position().xy() - feet.support_sole.position().xy());
+        self.swing_sole = Some(self.support_sole);
+        self.support_sole = swing_sole;
+        self.support_side = self.support_side.opposite();
+    }
+
+    /// The ankles counteract the tilt with the center of pressure as far as the soles on the
+    /// ground allow
+    fn center_of_pressure(
+        &self,
+        backend: &dyn Backend,
+        feet: &Feet,
+        center_of_mass: Point2<Walk>,
+        height: f32,
+    ) -> Point2<Walk> {
+        let support_outline = backend
+            .sole_outline(self.support_side, feet.support_sole)
+            .into_iter()
+            .map(|point| point + (self.support_sole - feet.support_sole.position().xy()));
+        let swing_outline = self.swing_sole.into_iter().flat_map(|swing_sole| {
+            backend
+                .sole_outline(self.support_side.opposite(), feet.swing_sole)
+                .into_iter()
+                .map(move |point| point + (swing_sole - feet.swing_sole.position().xy()))
+        });
+        let (minimum, maximum) = support_outline.chain(swing_outline).fold(
+            (
+                nalgebra::Vector2::repeat(f32::INFINITY),
+                nalgebra::Vector2::repeat(f32::NEG_INFINITY),
+            ),
+            |(minimum, maximum), point| {
+                let point = point.inner.coords;
+                (minimum.inf(&point), maximum.sup(&point))
+            },
+        );
+
+        let requested = center_of_mass
+            + (self.displacement * ANKLE_STIFFNESS + self.velocity * ANKLE_DAMPING) * height
+                / GRAVITY_ACCELERATION;
+        Point2::wrap(
+            requested
+                .inner
+                .coords
+                .zip_zip_map(&minimum, &maximum, |value, minimum, maximum| {
+                    value.clamp(minimum, maximum)
+                })
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use coordinate_systems::Walk;
+    use geometry::rectangle::Rectangle;
+    use linear_algebra::{point, vector, Vector2};
+    use types::step::Step;
+
+    use crate::parameters::{
+        AnatomicConstraintsParameters, Base, CatchingStepsParameters, Parameters,
+        PushRecoveryParameters,
+    };
+
+    use super::{maximum_survivable_push, simulate_push};
+
+    fn parameters(is_push_recovery_enabled: bool) -> Parameters {
+        Parameters {
+            anatomic_constraints: AnatomicConstraintsParameters {
+                valid_x: -0.1..0.1,
+                valid_y: -0.2..0.2,
+            },
+            base: Base {
+                foot_lift_apex: 0.015,
+                foot_offset_left: vector![0.0, 0.052, 0.0],
+                foot_offset_right: vector![0.0, -0.052, 0.0],
+                step_duration: Duration::from_millis(250),
+                step_midpoint: 0.5,
+                torso_tilt_base: 0.055,
+                walk_height: 0.23,
+                ..Default::default()
+            },
+            catching_steps: CatchingStepsParameters {
+                enabled: true,
+                target_x_scale_backward: 1.0,
+                target_x_scale_forward: 1.0,
+                target_y_scale: 1.0,
+                max_target_distance: nalgebra::vector![0.1, 0.1],
+                over_estimation_factor: vector![1.0, 1.0],
+                balance_region_x: 0.0,
+            },
+            foot_support: Rectangle {
+                min: point![-0.05, -0.1],
+                max: point![0.05, 0.1],
+            },
+            max_foot_speed: 1.5,
+            max_rotation_speed: 1.0,
+            max_step_duration: Duration::from_millis(1000),
+            max_support_foot_lift_speed: 0.05,
+            min_step_duration: Duration::from_millis(100),
+            push_recovery: PushRecoveryParameters {
+                enabled: is_push_recovery_enabled,
+                min_swing_duration: Duration::from_millis(150),
+                max_swing_duration: Duration::from_millis(350),
+                swing_duration_samples: 9,
+            },
+            sole_pressure_threshold: 0.5,
+            min_sole_pressure: 0.1,
+            step_midpoint: Step {
+                forward: 0.5,
+                left: 0.5,
+                turn: 0.0,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn push(push: Vector2<Walk>, is_push_recovery_enabled: bool) -> super::PushOutcome {
+        simulate_push(
+            &parameters(is_push_recovery_enabled),
+            push,
+            Duration::from_secs(2),
+            Duration::from_secs(3),
+            Duration::from_millis(12),
+        )
+    }
+
+    #[test]
+    fn walking_on_the_spot_without_push_does_not_fall() {
+        for is_push_recovery_enabled in [false, true] {
+            let outcome = push(Vector2::zeros(), is_push_recovery_enabled);
+
+            assert!(!outcome.is_fallen);
+            assert!(outcome.number_of_steps >= 10, "{outcome:?}");
+            assert!(outcome.maximum_tilt < 0.1, "{outcome:?}");
+        }
+    }
+
+    #[test]
+    fn push_recovery_steps_out_of_forward_push_zero_moment_point_catching_falls_from() {
+        let with_push_recovery = push(vector![0.5, 0.0], true);
+        let with_zero_moment_point = push(vector![0.5, 0.0], false);
+
+        assert!(!with_push_recovery.is_fallen, "{with_push_recovery:?}");
+        assert!(
+            with_zero_moment_point.is_fallen,
+            "{with_zero_moment_point:?}"
+        );
+    }
+
+    #[test]
+    fn push_recovery_survives_pushes_at_least_as_strong_as_zero_moment_point_catching() {
+        for direction in [
+            vector![1.0, 0.0],
+            vector![-1.0, 0.0],
+            vector![0.0, 1.0],
+            vector![0.0, -1.0],
+        ] {
+            let with_push_recovery =
+                maximum_survivable_push(&parameters(true), direction, 0.05, 1.0);
+            let with_zero_moment_point =
+                maximum_survivable_push(&parameters(false), direction, 0.05, 1.0);
+
+            assert!(
+                with_push_recovery >= with_zero_moment_point,
+                "{direction:?}: {with_push_recovery} < {with_zero_moment_point}"
+            );
+            assert!(
+                with_push_recovery >= 0.1,
+                "{direction:?}: {with_push_recovery}"
+            );
+        }
+    }
+}
@@ -25,6 +25,7 @@ pub struct StepPlan {
 impl StepPlan {
     pub fn new_from_request(context: &Context, requested_step: Step, support_side: Side) -> Self {
         let start_feet = Feet::from_joints(
+            context.backend,
             context.robot_to_walk,
             &context.last_actuated_joints,
             support_side,
@@ -1,28 +1,22 @@
 use std::{f32::consts::FRAC_PI_2, time::Duration};
 
-use coordinate_systems::{LeftSole, RightSole, Walk};
-use kinematics::inverse::leg_angles;
-use linear_algebra::{point, Isometry3, Orientation3, Point3, Pose3, Rotation3};
+use coordinate_systems::Walk;
+use linear_algebra::{point, Orientation3, Point3, Pose3, Rotation3};
 use path_serde::{PathDeserialize, PathIntrospect, PathSerialize};
 use serde::{Deserialize, Serialize};
 use splines::Interpolate;
 use types::{
     joints::{arm::ArmJoints, body::BodyJoints, leg::LegJoints, mirror::Mirror},
-    robot_dimensions::RobotDimensions,
     support_foot::Side,
 };
 
 use crate::{
-    compensate_stiffness_loss::CompensateStiffnessLossExt,
     parameters::{Parameters, SwingingArmsParameters},
     Context,
 };
 
 use super::{
-    feet::Feet,
-    foot_leveling::{FootLeveling, FootLevelingExt},
-    gyro_balancing::{GyroBalancing, GyroBalancingExt},
-    step_plan::StepPlan,
+    feet::Feet, foot_leveling::FootLeveling, gyro_balancing::GyroBalancing, step_plan::StepPlan,
 };
 
 #[derive(
@@ -73,14 +67,10 @@ impl StepState {
         self.foot_leveling
             .tick(context, self.normalized_time_since_start());
 
-        let small_weight_on_sensors = match self.plan.support_side {
-            Side::Left => {
-                context.force_sensitive_resistors.right.sum() > parameters.xy_offset_stop_weight
-            }
-            Side::Right => {
-                context.force_sensitive_resistors.left.sum() > parameters.xy_offset_stop_weight
-            }
-        };
+        let small_weight_on_sensors = context
+            .backend
+            .sole_pressure(self.plan.support_side.opposite())
+            > parameters.xy_offset_stop_weight;
 
         let xy_slip_stop = if small_weight_on_sensors
             && !self.is_support_switched(context)
@@ -95,8 +85,8 @@ impl StepState {
     }
 
     pub fn is_support_switched(&self, context: &Context) -> bool {
-        let sum_left = context.force_sensitive_resistors.left.sum();
-        let sum_right = context.force_sensitive_resistors.right.sum();
+        let sum_left = context.backend.sole_pressure(Side::Left);
+        let sum_right = context.backend.sole_pressure(Side::Right);
 
         let pressure_left = sum_left > context.parameters.sole_pressure_threshold;
         let pressure_right = sum_right > context.parameters.sole_pressure_threshold;
@@ -126,29 +116,21 @@ impl StepState {
             Side::Right => (feet.swing_sole, feet.support_sole),
         };
         let walk_to_robot = context.robot_to_walk.inverse();
-
-        let left_foot: Pose3<LeftSole> = Isometry3::from(RobotDimensions::LEFT_ANKLE_TO_LEFT_SOLE)
-            .inverse()
-            .as_pose();
-        let left_sole_to_robot = (walk_to_robot * left_sole).as_transform();
-        let right_foot: Pose3<RightSole> =
-            Isometry3::from(RobotDimensions::RIGHT_ANKLE_TO_RIGHT_SOLE)
-                .inverse()
-                .as_pose();
-        let right_sole_to_robot = (walk_to_robot * right_sole).as_transform();
-
-        let leg_joints = leg_angles(
-            left_sole_to_robot * left_foot,
-            right_sole_to_robot * right_foot,
-        )
-        .balance_using_gyro(&self.gyro_balancing, self.plan.support_side)
-        .level_swing_foot(&self.foot_leveling, self.plan.support_side)
-        .compensate_stiffness_loss(
+        let backend = context.backend;
+
+        let leg_joints = backend.leg_joints(walk_to_robot * left_sole, walk_to_robot * right_sole);
+        let leg_joints =
+            backend.balance_using_gyro(leg_joints, &self.gyro_balancing, self.plan.support_side);
+        let leg_joints =
+            backend.level_swing_foot(leg_joints, &self.foot_leveling, self.plan.support_side);
+        let leg_joints = backend.compensate_stiffness_loss(
+            leg_joints,
             &context.parameters.stiffness_loss_compensation,
             &context.last_actuated_joints.into(),
             &context.measured_joints.into(),
             self.plan.support_side,
         );
+        let leg_joints = backend.clamp_to_joint_limits(leg_joints);
 
         let left_arm = swinging_arm(
             &context.parameters.swinging_arms,
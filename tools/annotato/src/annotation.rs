@@ -2,23 +2,53 @@ use eframe::epaint::Pos2;
 use egui_plot::PlotPoint;
 use serde::{Deserialize, Serialize};
 
-use crate::{boundingbox::BoundingBox, classes::Class};
+use crate::{
+    boundingbox::{BoundingBox, Keypoint},
+    classes::Class,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AnnotationFormat {
     points: [[f32; 2]; 2],
     class: Class,
+    /// `[x, y, confidence]` of each pose keypoint in image coordinates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keypoints: Vec<[f32; 3]>,
+}
+
+impl AnnotationFormat {
+    /// Minimum and maximum corner in image coordinates
+    pub fn points(&self) -> [[f32; 2]; 2] {
+        self.points
+    }
+
+    pub fn class(&self) -> Class {
+        self.class
+    }
+
+    pub fn keypoints(&self) -> &[[f32; 3]] {
+        &self.keypoints
+    }
 }
 
 impl From<AnnotationFormat> for BoundingBox {
     fn from(value: AnnotationFormat) -> Self {
         let [[min_x, min_y], [max_x, max_y]] = value.points;
         let class = value.class;
+        let keypoints = value
+            .keypoints
+            .into_iter()
+            .map(|[x, y, confidence]| Keypoint {
+                position: PlotPoint::new(x as f64, 480. - y as f64),
+                confidence,
+            })
+            .collect();
 
         Self {
             corner: PlotPoint::new(min_x as f64, 480. - max_y as f64),
             opposing_corner: PlotPoint::new(max_x as f64, 480. - min_y as f64),
             class,
+            keypoints,
         }
     }
 }
@@ -29,9 +59,22 @@ impl From<BoundingBox> for AnnotationFormat {
         let Pos2 { x: x1, y: y1 } = rect.left_top();
         let Pos2 { x: x2, y: y2 } = rect.right_bottom();
 
+        let keypoints = value
+            .keypoints
+            .iter()
+            .map(|keypoint| {
+                [
+                    keypoint.position.x as f32,
+                    480. - keypoint.position.y as f32,
+                    keypoint.confidence,
+                ]
+            })
+            .collect();
+
         Self {
             points: [[x1, 480. - y2], [x2, 480. - y1]],
             class: value.class,
+            keypoints,
         }
     }
 }
@@ -37,6 +37,7 @@ impl AnnotatorApp {
         _: &CreationContext,
         image_folder: impl AsRef<Path>,
         annotation_json_path: impl AsRef<Path>,
+        pose_model_path: PathBuf,
         skip_introduction: bool,
     ) -> Result<Self> {
         let image_paths = glob(
@@ -69,7 +70,7 @@ impl AnnotatorApp {
         Ok(AnnotatorApp {
             phase,
             paths,
-            label_widget: LabelWidget::default(),
+            label_widget: LabelWidget::new(pose_model_path),
             model_annotations,
         })
     }
@@ -185,6 +186,8 @@ impl AnnotatorApp {
                     • start drawing a box with 'b' key
                     • end drawing a box with 'b' key
                     • delete a box by hovering and rightclicking
+                    • propose robot boxes with the pose detection with 'd' key
+                    • accept a proposed box by hovering and pressing 'a' key
                     • move in the image with left click dragging
                     • zoom in the image ctrl + mousewheel
                     • proceed to the next image with 'n' key
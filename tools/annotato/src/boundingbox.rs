@@ -3,11 +3,19 @@ use egui_plot::{PlotPoint, PlotPoints, Polygon};
 
 use crate::classes::Class;
 
+#[derive(Debug, Clone, Copy)]
+pub struct Keypoint {
+    pub position: PlotPoint,
+    pub confidence: f32,
+}
+
 #[derive(Debug, Clone)]
 pub struct BoundingBox {
     pub corner: PlotPoint,
     pub opposing_corner: PlotPoint,
     pub class: Class,
+    /// Pose keypoints proposed by the pose detection, empty for manually drawn boxes
+    pub keypoints: Vec<Keypoint>,
 }
 
 impl From<&BoundingBox> for Polygon<'_> {
@@ -27,9 +35,15 @@ impl BoundingBox {
             corner,
             opposing_corner,
             class,
+            keypoints: Vec::new(),
         }
     }
 
+    pub fn with_keypoints(mut self, keypoints: Vec<Keypoint>) -> Self {
+        self.keypoints = keypoints;
+        self
+    }
+
     pub fn set_opposing_corner(&mut self, plot_bottom_right: PlotPoint) {
         self.opposing_corner = plot_bottom_right;
     }
@@ -0,0 +1,635 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use glob::glob;
+use serde_json::{json, Value};
+
+use crate::{annotation::AnnotationFormat, classes::Class, widgets::class_selector::EnumIter};
+
+const IMAGE_WIDTH: u32 = 640;
+const IMAGE_HEIGHT: u32 = 480;
+const MINIMUM_BOX_AREA: f32 = 4.0;
+const MINIMUM_KEYPOINT_CONFIDENCE: f32 = 0.5;
+
+/// Keypoints of a robot in the order of the pose detection
+const ROBOT_KEYPOINTS: [&str; 17] = [
+    "left_eye",
+    "right_eye",
+    "nose",
+    "left_ear",
+    "right_ear",
+    "left_shoulder",
+    "right_shoulder",
+    "left_elbow",
+    "right_elbow",
+    "left_hand",
+    "right_hand",
+    "left_hip",
+    "right_hip",
+    "left_knee",
+    "right_knee",
+    "left_foot",
+    "right_foot",
+];
+
+/// Connected keypoints of a robot, as in the COCO person skeleton
+const ROBOT_SKELETON: [(&str, &str); 19] = [
+    ("left_foot", "left_knee"),
+    ("left_knee", "left_hip"),
+    ("right_foot", "right_knee"),
+    ("right_knee", "right_hip"),
+    ("left_hip", "right_hip"),
+    ("left_shoulder", "left_hip"),
+    ("right_shoulder", "right_hip"),
+    ("left_shoulder", "right_shoulder"),
+    ("left_shoulder", "left_elbow"),
+    ("right_shoulder", "right_elbow"),
+    ("left_elbow", "left_hand"),
+    ("right_elbow", "right_hand"),
+    ("left_eye", "right_eye"),
+    ("nose", "left_eye"),
+    ("nose", "right_eye"),
+    ("left_eye", "left_ear"),
+    ("right_eye", "right_ear"),
+    ("left_ear", "left_shoulder"),
+    ("right_ear", "right_shoulder"),
+];
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportFormat {
+    Yolo,
+    Coco,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Split {
+    Train,
+    Validation,
+    Test,
+}
+
+impl Split {
+    const ALL: [Split; 3] = [Split::Train, Split::Validation, Split::Test];
+
+    fn directory_name(self) -> &'static str {
+        match self {
+            Split::Train => "train",
+            Split::Validation => "val",
+            Split::Test => "test",
+        }
+    }
+}
+
+/// Fractions of the images which are put into the validation and test split, the rest is used for
+/// training
+#[derive(Clone, Copy, Debug)]
+pub struct SplitFractions {
+    pub validation: f64,
+    pub test: f64,
+}
+
+impl SplitFractions {
+    /// Assigns an image to a split by hashing its name, so the assignment of an image does not
+    /// change when further images are added to the dataset
+    pub fn split_of(&self, image_name: &str, seed: u64) -> Split {
+        let hash = seed
+            .to_le_bytes()
+            .iter()
+            .chain(image_name.as_bytes())
+            .fold(0xcbf29ce484222325_u64, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+            });
+        // names often only differ in their last characters, mix them into the high bits
+        let hash = (hash ^ (hash >> 33)).wrapping_mul(0xff51afd7ed558ccd);
+        let hash = (hash ^ (hash >> 33)).wrapping_mul(0xc4ceb9fe1a85ec53);
+        let hash = hash ^ (hash >> 33);
+        let position = hash as f64 / u64::MAX as f64;
+        if position < self.test {
+            Split::Test
+        } else if position < self.test + self.validation {
+            Split::Validation
+        } else {
+            Split::Train
+        }
+    }
+}
+
+pub struct AnnotatedImage {
+    pub image_path: PathBuf,
+    pub annotations: Vec<AnnotationFormat>,
+}
+
+impl AnnotatedImage {
+    fn file_name(&self) -> String {
+        self.image_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// All images of a local dataset, split into valid labelled images and the ones which cannot be
+/// used for training
+pub struct Dataset {
+    pub images: Vec<AnnotatedImage>,
+    pub unlabelled_images: Vec<PathBuf>,
+    pub issues: Vec<String>,
+}
+
+impl Dataset {
+    pub fn load(dataset_path: &Path) -> Result<Self> {
+        let image_folder = dataset_path.join("images");
+        if !image_folder.exists() {
+            bail!("dataset {} not present", dataset_path.display());
+        }
+
+        let mut images = Vec::new();
+        let mut unlabelled_images = Vec::new();
+        let mut issues = Vec::new();
+
+        let mut image_paths = glob(&image_folder.join("*.png").display().to_string())?
+            .collect::<Result<Vec<_>, _>>()?;
+        image_paths.sort();
+        for image_path in image_paths {
+            let label_path = image_path.with_extension("json");
+            if !label_path.exists() {
+                unlabelled_images.push(image_path);
+                continue;
+            }
+            match load_annotated_image(&image_path, &label_path) {
+                Ok(image) => images.push(image),
+                Err(error) => issues.push(format!("{}: {error:#}", image_path.display())),
+            }
+        }
+
+        for label_path in glob(&image_folder.join("*.json").display().to_string())? {
+            let label_path = label_path?;
+            if !label_path.with_extension("png").exists() {
+                issues.push(format!("{}: image is missing", label_path.display()));
+            }
+        }
+
+        Ok(Self {
+            images,
+            unlabelled_images,
+            issues,
+        })
+    }
+
+    pub fn print_statistics(&self) {
+        println!(
+            "{} labelled images, {} unlabelled images, {} issues",
+            self.images.len(),
+            self.unlabelled_images.len(),
+            self.issues.len()
+        );
+        for issue in &self.issues {
+            println!("  {issue}");
+        }
+
+        println!();
+        println!(
+            "{:<12} {:>7} {:>7}   {:>22}   {:>22}",
+            "class", "boxes", "images", "width (min/med/max)", "height (min/med/max)"
+        );
+        for class in Class::list() {
+            let boxes: Vec<_> = self
+                .images
+                .iter()
+                .flat_map(|image| &image.annotations)
+                .filter(|annotation| annotation.class() == class)
+                .map(|annotation| {
+                    let [[min_x, min_y], [max_x, max_y]] = annotation.points();
+                    (max_x - min_x, max_y - min_y)
+                })
+                .collect();
+            let number_of_images = self
+                .images
+                .iter()
+                .filter(|image| {
+                    image
+                        .annotations
+                        .iter()
+                        .any(|annotation| annotation.class() == class)
+                })
+                .count();
+            println!(
+                "{:<12} {:>7} {:>7}   {:>22}   {:>22}",
+                format!("{class:?}"),
+                boxes.len(),
+                number_of_images,
+                distribution(boxes.iter().map(|(width, _)| *width)),
+                distribution(boxes.iter().map(|(_, height)| *height)),
+            );
+        }
+    }
+
+    pub fn export(
+        &self,
+        output: &Path,
+        format: ExportFormat,
+        fractions: SplitFractions,
+        seed: u64,
+    ) -> Result<()> {
+        if !(0.0..=1.0).contains(&fractions.validation)
+            || !(0.0..=1.0).contains(&fractions.test)
+            || fractions.validation + fractions.test > 1.0
+        {
+            bail!("split fractions have to be within 0 and 1 and must not exceed 1 in total");
+        }
+        if output.exists() && fs::read_dir(output)?.next().is_some() {
+            bail!("output directory {} is not empty", output.display());
+        }
+
+        let mut splits: BTreeMap<Split, Vec<&AnnotatedImage>> = BTreeMap::new();
+        for image in &self.images {
+            splits
+                .entry(fractions.split_of(&image.file_name(), seed))
+                .or_default()
+                .push(image);
+        }
+
+        for split in Split::ALL {
+            let images = splits.get(&split).map(Vec::as_slice).unwrap_or_default();
+            let image_folder = output.join("images").join(split.directory_name());
+            fs::create_dir_all(&image_folder)
+                .wrap_err_with(|| format!("failed to create {}", image_folder.display()))?;
+            for image in images {
+                fs::copy(&image.image_path, image_folder.join(image.file_name()))
+                    .wrap_err_with(|| format!("failed to copy {}", image.image_path.display()))?;
+            }
+            match format {
+                ExportFormat::Yolo => write_yolo_labels(output, split, images)?,
+                ExportFormat::Coco => write_coco_annotations(output, split, images)?,
+            }
+            println!("{}: {} images", split.directory_name(), images.len());
+        }
+        if let ExportFormat::Yolo = format {
+            write_yolo_configuration(output)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn load_annotated_image(image_path: &Path, label_path: &Path) -> Result<AnnotatedImage> {
+    let (width, height) = image::image_dimensions(image_path)?;
+    if (width, height) != (IMAGE_WIDTH, IMAGE_HEIGHT) {
+        bail!("expected a {IMAGE_WIDTH}x{IMAGE_HEIGHT} image, got {width}x{height}");
+    }
+    let annotations: Vec<AnnotationFormat> = serde_json::from_str(
+        &fs::read_to_string(label_path)
+            .wrap_err_with(|| format!("failed to read {}", label_path.display()))?,
+    )
+    .wrap_err_with(|| format!("failed to parse {}", label_path.display()))?;
+
+    for (index, annotation) in annotations.iter().enumerate() {
+        let [[min_x, min_y], [max_x, max_y]] = annotation.points();
+        let is_inside_image = [min_x, max_x]
+            .iter()
+            .all(|x| (0.0..=IMAGE_WIDTH as f32).contains(x))
+            && [min_y, max_y]
+                .iter()
+                .all(|y| (0.0..=IMAGE_HEIGHT as f32).contains(y));
+        if !is_inside_image {
+            bail!("box {index} ({:?}) exceeds the image", annotation.class());
+        }
+        if (max_x - min_x) * (max_y - min_y) < MINIMUM_BOX_AREA || max_x < min_x || max_y < min_y {
+            bail!("box {index} ({:?}) is degenerate", annotation.class());
+        }
+        let expected_keypoints = keypoint_names(annotation.class()).len();
+        if !annotation.keypoints().is_empty() && annotation.keypoints().len() != expected_keypoints
+        {
+            bail!(
+                "box {index} ({:?}) has {} keypoints, expected {expected_keypoints}",
+                annotation.class(),
+                annotation.keypoints().len()
+            );
+        }
+    }
+
+    Ok(AnnotatedImage {
+        image_path: image_path.to_path_buf(),
+        annotations,
+    })
+}
+
+fn distribution(values: impl Iterator<Item = f32>) -> String {
+    let mut values: Vec<_> = values.collect();
+    if values.is_empty() {
+        return "-".to_string();
+    }
+    values.sort_by(f32::total_cmp);
+    format!(
+        "{:.0}/{:.0}/{:.0}",
+        values[0],
+        values[values.len() / 2],
+        values[values.len() - 1]
+    )
+}
+
+/// Zero-based as YOLO expects, COCO category ids are one higher
+fn class_id(class: Class) -> usize {
+    Class::list()
+        .iter()
+        .position(|other| *other == class)
+        .expect("every class is listed")
+}
+
+fn keypoint_names(class: Class) -> &'static [&'static str] {
+    match class {
+        Class::Robot => &ROBOT_KEYPOINTS,
+        _ => &[],
+    }
+}
+
+fn write_yolo_labels(output: &Path, split: Split, images: &[&AnnotatedImage]) -> Result<()> {
+    let label_folder = output.join("labels").join(split.directory_name());
+    fs::create_dir_all(&label_folder)
+        .wrap_err_with(|| format!("failed to create {}", label_folder.display()))?;
+    for image in images {
+        let label_path = label_folder.join(image.file_name()).with_extension("txt");
+        fs::write(&label_path, yolo_labels(&image.annotations))
+            .wrap_err_with(|| format!("failed to write {}", label_path.display()))?;
+    }
+    Ok(())
+}
+
+/// One line per box with the class id and the normalized center and size
+fn yolo_labels(annotations: &[AnnotationFormat]) -> String {
+    annotations
+        .iter()
+        .map(|annotation| {
+            let [[min_x, min_y], [max_x, max_y]] = annotation.points();
+            let width = IMAGE_WIDTH as f32;
+            let height = IMAGE_HEIGHT as f32;
+            format!(
+                "{} {:.6} {:.6} {:.6} {:.6}\n",
+                class_id(annotation.class()),
+                (min_x + max_x) / 2.0 / width,
+                (min_y + max_y) / 2.0 / height,
+                (max_x - min_x) / width,
+                (max_y - min_y) / height,
+            )
+        })
+        .collect()
+}
+
+fn write_yolo_configuration(output: &Path) -> Result<()> {
+    let mut configuration = String::from("path: .\n");
+    for split in Split::ALL {
+        let name = split.directory_name();
+        configuration.push_str(&format!("{name}: images/{name}\n"));
+    }
+    configuration.push_str("names:\n");
+    for (id, class) in Class::list().into_iter().enumerate() {
+        configuration.push_str(&format!("  {id}: {class:?}\n"));
+    }
+    let configuration_path = output.join("data.yaml");
+    fs::write(&configuration_path, configuration)
+        .wrap_err_with(|| format!("failed to write {}", configuration_path.display()))
+}
+
+fn write_coco_annotations(output: &Path, split: Split, images: &[&AnnotatedImage]) -> Result<()> {
+    let annotation_folder = output.join("annotations");
+    fs::create_dir_all(&annotation_folder)
+        .wrap_err_with(|| format!("failed to create {}", annotation_folder.display()))?;
+
+    let annotation_path = annotation_folder
+        .join(format!("instances_{}", split.directory_name()))
+        .with_extension("json");
+    fs::write(
+        &annotation_path,
+        serde_json::to_string_pretty(&coco_dataset(images))?,
+    )
+    .wrap_err_with(|| format!("failed to write {}", annotation_path.display()))?;
+    Ok(())
+}
+
+/// COCO detection and keypoint dataset, all ids start at 1 as the COCO evaluation treats 0 as
+/// missing
+fn coco_dataset(images: &[&AnnotatedImage]) -> Value {
+    let mut coco_images = Vec::new();
+    let mut coco_annotations = Vec::new();
+    for (image_index, image) in images.iter().enumerate() {
+        let image_id = image_index + 1;
+        coco_images.push(json!({
+            "id": image_id,
+            "file_name": image.file_name(),
+            "width": IMAGE_WIDTH,
+            "height": IMAGE_HEIGHT,
+        }));
+        for annotation in &image.annotations {
+            let [[min_x, min_y], [max_x, max_y]] = annotation.points();
+            let mut coco_annotation = json!({
+                "id": coco_annotations.len() + 1,
+                "image_id": image_id,
+                "category_id": class_id(annotation.class()) + 1,
+                "bbox": [min_x, min_y, max_x - min_x, max_y - min_y],
+                "area": (max_x - min_x) * (max_y - min_y),
+                "iscrowd": 0,
+            });
+            if !annotation.keypoints().is_empty() {
+                let visible = |confidence: f32| confidence >= MINIMUM_KEYPOINT_CONFIDENCE;
+                let keypoints: Vec<Value> = annotation
+                    .keypoints()
+                    .iter()
+                    .flat_map(|&[x, y, confidence]| {
+                        if visible(confidence) {
+                            [json!(x), json!(y), json!(2)]
+                        } else {
+                            [json!(0), json!(0), json!(0)]
+                        }
+                    })
+                    .collect();
+                coco_annotation["keypoints"] = Value::Array(keypoints);
+                coco_annotation["num_keypoints"] = json!(annotation
+                    .keypoints()
+                    .iter()
+                    .filter(|[_, _, confidence]| visible(*confidence))
+                    .count());
+            }
+            coco_annotations.push(coco_annotation);
+        }
+    }
+    let categories: Vec<Value> = Class::list()
+        .into_iter()
+        .map(|class| {
+            let mut category = json!({
+                "id": class_id(class) + 1,
+                "name": format!("{class:?}"),
+            });
+            let keypoints = keypoint_names(class);
+            if !keypoints.is_empty() {
+                let index = |name: &str| {
+                    keypoints
+                        .iter()
+                        .position(|keypoint| *keypoint == name)
+                        .expect("skeleton only connects listed keypoints")
+                        + 1
+                };
+                category["keypoints"] = json!(keypoints);
+                category["skeleton"] = json!(ROBOT_SKELETON
+                    .iter()
+                    .map(|(from, to)| [index(from), index(to)])
+                    .collect::<Vec<_>>());
+            }
+            category
+        })
+        .collect();
+
+    json!({
+        "images": coco_images,
+        "annotations": coco_annotations,
+        "categories": categories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde_json::{from_value, json};
+
+    use crate::annotation::AnnotationFormat;
+
+    use super::{coco_dataset, yolo_labels, AnnotatedImage, Split, SplitFractions};
+
+    const FRACTIONS: SplitFractions = SplitFractions {
+        validation: 0.2,
+        test: 0.1,
+    };
+
+    fn annotation(
+        class: &str,
+        points: [[f32; 2]; 2],
+        keypoints: Vec<[f32; 3]>,
+    ) -> AnnotationFormat {
+        from_value(json!({
+            "points": points,
+            "class": class,
+            "keypoints": keypoints,
+        }))
+        .unwrap()
+    }
+
+    fn image_names() -> Vec<String> {
+        (0..1000)
+            .map(|index| format!("image_{index:04}.png"))
+            .collect()
+    }
+
+    #[test]
+    fn split_is_deterministic_for_each_seed() {
+        let splits = |seed| {
+            image_names()
+                .iter()
+                .map(|name| FRACTIONS.split_of(name, seed))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(splits(42), splits(42));
+        assert_ne!(splits(42), splits(43));
+    }
+
+    #[test]
+    fn split_follows_fractions() {
+        let splits: Vec<_> = image_names()
+            .iter()
+            .map(|name| FRACTIONS.split_of(name, 42))
+            .collect();
+        let fraction = |split| {
+            splits.iter().filter(|other| **other == split).count() as f64 / splits.len() as f64
+        };
+
+        assert!((fraction(Split::Validation) - FRACTIONS.validation).abs() < 0.05);
+        assert!((fraction(Split::Test) - FRACTIONS.test).abs() < 0.05);
+    }
+
+    #[test]
+    fn yolo_labels_are_normalized_centers_and_sizes() {
+        let labels = yolo_labels(&[
+            annotation("Ball", [[64.0, 48.0], [192.0, 144.0]], Vec::new()),
+            annotation("Robot", [[0.0, 0.0], [640.0, 480.0]], Vec::new()),
+        ]);
+
+        assert_eq!(
+            labels,
+            "0 0.200000 0.200000 0.200000 0.200000\n1 0.500000 0.500000 1.000000 1.000000\n"
+        );
+    }
+
+    #[test]
+    fn coco_ids_start_at_one() {
+        let image = AnnotatedImage {
+            image_path: PathBuf::from("images/image_0000.png"),
+            annotations: vec![
+                annotation("Ball", [[10.0, 20.0], [30.0, 60.0]], Vec::new()),
+                annotation("XSpot", [[100.0, 100.0], [110.0, 105.0]], Vec::new()),
+            ],
+        };
+
+        let coco = coco_dataset(&[&image]);
+
+        assert_eq!(coco["images"][0]["id"], 1);
+        assert_eq!(coco["images"][0]["file_name"], "image_0000.png");
+        let annotations = coco["annotations"].as_array().unwrap();
+        assert_eq!(annotations[0]["id"], 1);
+        assert_eq!(annotations[0]["image_id"], 1);
+        assert_eq!(annotations[0]["category_id"], 1);
+        assert_eq!(annotations[0]["bbox"], json!([10.0, 20.0, 20.0, 40.0]));
+        assert_eq!(annotations[1]["category_id"], 7);
+        let category_ids: Vec<_> = coco["categories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|category| category["id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(category_ids, [1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn coco_robot_keypoints_match_category() {
+        let keypoints = (0..17)
+            .map(|index| {
+                [
+                    index as f32,
+                    2.0 * index as f32,
+                    if index < 5 { 0.1 } else { 0.9 },
+                ]
+            })
+            .collect();
+        let image = AnnotatedImage {
+            image_path: PathBuf::from("image.png"),
+            annotations: vec![annotation("Robot", [[0.0, 0.0], [100.0, 200.0]], keypoints)],
+        };
+
+        let coco = coco_dataset(&[&image]);
+
+        let robot = &coco["categories"][1];
+        assert_eq!(robot["name"], "Robot");
+        assert_eq!(robot["keypoints"].as_array().unwrap().len(), 17);
+        let skeleton = robot["skeleton"].as_array().unwrap();
+        assert!(skeleton
+            .iter()
+            .flat_map(|link| link.as_array().unwrap())
+            .all(|index| (1..=17).contains(&index.as_u64().unwrap())));
+        assert!(coco["categories"][0].get("keypoints").is_none());
+
+        let annotation = &coco["annotations"][0];
+        assert_eq!(annotation["category_id"], 2);
+        assert_eq!(annotation["num_keypoints"], 12);
+        let keypoints = annotation["keypoints"].as_array().unwrap();
+        assert_eq!(keypoints.len(), 3 * 17);
+        assert_eq!(keypoints[0..3], [json!(0), json!(0), json!(0)]);
+        assert_eq!(keypoints[15..18], [json!(5.0), json!(10.0), json!(2)]);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     fs::{self, File},
     io::Write,
+    path::PathBuf,
 };
 
 use crate::{
@@ -9,6 +10,8 @@ use crate::{
     classes::Class,
     leaderboard,
     paths::Paths,
+    pose_model::{PoseModel, PoseProposal},
+    user_toml::CONFIG,
     utils,
     widgets::{bounding_box_annotator::BoundingBoxAnnotator, class_selector::ClassSelector},
 };
@@ -26,10 +29,14 @@ pub struct LabelWidget {
     editing_bounding_box: Option<BoundingBox>,
     disable_saving: bool,
     use_model_annotations: bool,
+    pose_model_path: PathBuf,
+    pose_model: Option<PoseModel>,
+    pose_proposals: Vec<PoseProposal>,
+    pose_detection_error: Option<String>,
 }
 
-impl Default for LabelWidget {
-    fn default() -> Self {
+impl LabelWidget {
+    pub fn new(pose_model_path: PathBuf) -> Self {
         Self {
             current_paths: None,
             texture_id: None,
@@ -38,11 +45,13 @@ impl Default for LabelWidget {
             editing_bounding_box: None,
             disable_saving: false,
             use_model_annotations: true,
+            pose_model_path,
+            pose_model: None,
+            pose_proposals: Vec::new(),
+            pose_detection_error: None,
         }
     }
-}
 
-impl LabelWidget {
     pub fn has_paths(&self, paths: &Paths) -> bool {
         self.current_paths
             .as_ref()
@@ -77,6 +86,31 @@ impl LabelWidget {
                 ));
                 ui.checkbox(&mut self.disable_saving, "Disable Annotation Saving");
                 ui.checkbox(&mut self.use_model_annotations, "AI-ssist");
+
+                let config = CONFIG.get().unwrap();
+                if ui
+                    .button("Detect Robots")
+                    .on_hover_text("Propose robot boxes with the pose detection (d)")
+                    .clicked()
+                    || ui.input(|i| config.keybindings.detect.is_pressed(i))
+                {
+                    self.detect_robots();
+                }
+                if !self.pose_proposals.is_empty()
+                    && ui
+                        .button(format!("Accept {} Proposals", self.pose_proposals.len()))
+                        .clicked()
+                {
+                    self.bounding_boxes
+                        .extend(self.pose_proposals.drain(..).map(|proposal| {
+                            let mut bounding_box = proposal.bounding_box;
+                            bounding_box.clip_to_image();
+                            bounding_box
+                        }));
+                }
+                if let Some(error) = &self.pose_detection_error {
+                    ui.colored_label(Color32::RED, error);
+                }
             });
             if let Some(texture_id) = self.texture_id.clone() {
                 ui.add(BoundingBoxAnnotator::new(
@@ -85,6 +119,7 @@ impl LabelWidget {
                     &mut self.bounding_boxes,
                     &mut self.editing_bounding_box,
                     &mut self.selected_class,
+                    &mut self.pose_proposals,
                 ));
             }
         });
@@ -96,6 +131,8 @@ impl LabelWidget {
         model_annotations: Vec<BoundingBox>,
     ) -> Result<()> {
         self.bounding_boxes.clear();
+        self.pose_proposals.clear();
+        self.pose_detection_error = None;
 
         if paths.label_path.exists() {
             let existing_annotations = fs::read_to_string(&paths.label_path)?;
@@ -115,6 +152,34 @@ impl LabelWidget {
         Ok(())
     }
 
+    fn detect_robots(&mut self) {
+        let Some(paths) = &self.current_paths else {
+            return;
+        };
+        if self.pose_model.is_none() {
+            match PoseModel::try_new(&self.pose_model_path) {
+                Ok(pose_model) => self.pose_model = Some(pose_model),
+                Err(error) => {
+                    self.pose_detection_error =
+                        Some(format!("failed to load pose detection: {error:#}"));
+                    return;
+                }
+            }
+        }
+        let Some(pose_model) = self.pose_model.as_mut() else {
+            return;
+        };
+        match pose_model.detect_in_file(&paths.image_path) {
+            Ok(proposals) => {
+                self.pose_proposals = proposals;
+                self.pose_detection_error = None;
+            }
+            Err(error) => {
+                self.pose_detection_error = Some(format!("pose detection failed: {error:#}"));
+            }
+        }
+    }
+
     pub fn save_annotation(&mut self) -> Result<()> {
         let paths = self
             .current_paths
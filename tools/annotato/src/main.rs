@@ -3,9 +3,11 @@ pub mod annotation;
 pub mod annotator_app;
 pub mod boundingbox;
 pub mod classes;
+pub mod dataset;
 pub mod label_widget;
 pub mod leaderboard;
 pub mod paths;
+pub mod pose_model;
 pub mod remotedata;
 pub mod rsync;
 pub mod theme;
@@ -19,6 +21,7 @@ use annotator_app::AnnotatorApp;
 use clap::{Parser, Subcommand};
 use color_eyre::eyre::{bail, Report, Result};
 use eframe::{egui::ViewportBuilder, run_native, NativeOptions};
+use pose_model::DEFAULT_POSE_MODEL_PATH;
 use remotedata::DataCommand;
 use theme::{apply_theme, MOCHA};
 
@@ -33,7 +36,7 @@ pub struct Args {
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
-    /// Download and upload datasets
+    /// Download, upload, analyze and export datasets
     Data {
         #[command(subcommand)]
         subcommand: DataCommand,
@@ -49,12 +52,16 @@ pub enum Command {
         /// Skips downloading and uploading datasets, command will fail if dataset is not present
         #[arg(short, long, default_value = "false")]
         offline: bool,
+        /// OpenVINO model of the pose detection used to propose robot boxes
+        #[arg(long, default_value = DEFAULT_POSE_MODEL_PATH)]
+        pose_model: PathBuf,
     },
 }
 
 fn start_labelling_ui(
     image_folder: PathBuf,
     annotation_json: PathBuf,
+    pose_model: PathBuf,
     skip_introduction: bool,
 ) -> eframe::Result<()> {
     let native_options = NativeOptions {
@@ -75,7 +82,13 @@ fn start_labelling_ui(
             let context = &cc.egui_ctx;
             apply_theme(context, MOCHA);
 
-            let app = AnnotatorApp::try_new(cc, image_folder, annotation_json, skip_introduction)?;
+            let app = AnnotatorApp::try_new(
+                cc,
+                image_folder,
+                annotation_json,
+                pose_model,
+                skip_introduction,
+            )?;
 
             Ok(Box::new(app))
         }),
@@ -95,6 +108,7 @@ fn main() -> Result<()> {
             dataset_name,
             skip_introduction,
             offline,
+            pose_model,
         } => {
             let image_folder = PathBuf::from_iter(["current", &dataset_name, "images"].iter());
             let annotation_json = PathBuf::from_iter(["current", &dataset_name, "data.json"]);
@@ -105,7 +119,7 @@ fn main() -> Result<()> {
                 println!("dataset {dataset_name} not present, downloading...");
                 rsync::rsync_to_local("current", &dataset_name)?;
             }
-            start_labelling_ui(image_folder, annotation_json, skip_introduction)
+            start_labelling_ui(image_folder, annotation_json, pose_model, skip_introduction)
                 .map_err(|err| Report::msg(err.to_string()))?;
 
             if !offline {
@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{bail, eyre, ContextCompat},
+    Result,
+};
+use egui_plot::PlotPoint;
+use image::{ImageReader, RgbImage};
+use ndarray::{s, ArrayView};
+use openvino::{
+    CompiledModel, Core, DeviceType, ElementType, InferenceError::GeneralError, Tensor,
+};
+
+use crate::{
+    boundingbox::{BoundingBox, Keypoint},
+    classes::Class,
+};
+
+const IMAGE_WIDTH: u32 = 640;
+const IMAGE_HEIGHT: u32 = 480;
+const STRIDE: usize = (IMAGE_WIDTH * IMAGE_HEIGHT) as usize;
+
+const EXPECTED_OUTPUT_NAME: &str = "detections";
+const MAX_DETECTIONS: usize = 6300;
+
+const MINIMUM_BOUNDING_BOX_CONFIDENCE: f32 = 0.4;
+const MAXIMUM_INTERSECTION_OVER_UNION: f32 = 0.6;
+
+pub const DEFAULT_POSE_MODEL_PATH: &str = "etc/neural_networks/yolo11n-pose-ov-full.xml";
+
+/// A robot box with keypoints proposed by the pose detection, not yet accepted by the user
+#[derive(Debug, Clone)]
+pub struct PoseProposal {
+    pub bounding_box: BoundingBox,
+    pub confidence: f32,
+}
+
+/// The full image pose detection network of the robot, running on the CPU
+pub struct PoseModel {
+    network: CompiledModel,
+}
+
+impl PoseModel {
+    pub fn try_new(model_path: impl AsRef<Path>) -> Result<Self> {
+        let model_path = model_path.as_ref();
+        let weights_path = model_path.with_extension("bin");
+
+        let mut core = Core::new()?;
+        let model = core
+            .read_model_from_file(
+                model_path
+                    .to_str()
+                    .wrap_err("failed to get detection model path")?,
+                weights_path
+                    .to_str()
+                    .wrap_err("failed to get detection weights path")?,
+            )
+            .map_err(|error| match error {
+                GeneralError => eyre!("{error}: possible incomplete OpenVino installation"),
+                _ => eyre!("{error}: failed to read {}", model_path.display()),
+            })?;
+
+        let number_of_inputs = model.get_inputs_len()?;
+        let output_name = model.get_output_by_index(0)?.get_name()?;
+        if number_of_inputs != 1 || output_name != EXPECTED_OUTPUT_NAME {
+            bail!("expected exactly one input and output name to be '{EXPECTED_OUTPUT_NAME}'");
+        }
+
+        Ok(Self {
+            network: core.compile_model(&model, DeviceType::CPU)?,
+        })
+    }
+
+    pub fn detect_in_file(&mut self, image_path: &Path) -> Result<Vec<PoseProposal>> {
+        let image = ImageReader::open(image_path)?.decode()?.into_rgb8();
+        self.detect(&image)
+    }
+
+    pub fn detect(&mut self, image: &RgbImage) -> Result<Vec<PoseProposal>> {
+        if image.dimensions() != (IMAGE_WIDTH, IMAGE_HEIGHT) {
+            bail!(
+                "expected a {IMAGE_WIDTH}x{IMAGE_HEIGHT} image, got {}x{}",
+                image.width(),
+                image.height()
+            );
+        }
+
+        let mut tensor = Tensor::new(ElementType::F32, &self.network.get_input()?.get_shape()?)?;
+        load_into_scratchpad(tensor.get_data_mut()?, image);
+
+        let mut infer_request = self.network.create_infer_request()?;
+        infer_request.set_input_tensor(&tensor)?;
+        infer_request.infer()?;
+
+        let prediction = infer_request.get_output_tensor_by_index(0)?;
+        let prediction =
+            ArrayView::from_shape((56, MAX_DETECTIONS), prediction.get_data::<f32>()?)?;
+        let proposals = prediction
+            .columns()
+            .into_iter()
+            .filter_map(|column| {
+                let confidence = column[4];
+                if confidence < MINIMUM_BOUNDING_BOX_CONFIDENCE {
+                    return None;
+                }
+                let center_x = column[0] as f64;
+                let center_y = column[1] as f64;
+                let half_width = column[2] as f64 / 2.0;
+                let half_height = column[3] as f64 / 2.0;
+
+                let keypoints = column
+                    .slice(s![5..])
+                    .as_standard_layout()
+                    .as_slice()?
+                    .chunks_exact(3)
+                    .map(|keypoint| Keypoint {
+                        position: to_plot_point(keypoint[0] as f64, keypoint[1] as f64),
+                        confidence: keypoint[2],
+                    })
+                    .collect();
+
+                let bounding_box = BoundingBox::new(
+                    to_plot_point(center_x - half_width, center_y - half_height),
+                    to_plot_point(center_x + half_width, center_y + half_height),
+                    Class::Robot,
+                )
+                .with_keypoints(keypoints);
+                Some(PoseProposal {
+                    bounding_box,
+                    confidence,
+                })
+            })
+            .collect();
+
+        Ok(non_maximum_suppression(proposals))
+    }
+}
+
+fn to_plot_point(x: f64, y: f64) -> PlotPoint {
+    PlotPoint::new(x, IMAGE_HEIGHT as f64 - y)
+}
+
+fn load_into_scratchpad(scratchpad: &mut [f32], image: &RgbImage) {
+    for (index, pixel) in image.pixels().enumerate() {
+        let [red, green, blue] = pixel.0;
+        scratchpad[index] = red as f32 / 255.;
+        scratchpad[index + STRIDE] = green as f32 / 255.;
+        scratchpad[index + 2 * STRIDE] = blue as f32 / 255.;
+    }
+}
+
+fn non_maximum_suppression(mut candidates: Vec<PoseProposal>) -> Vec<PoseProposal> {
+    let mut proposals = Vec::new();
+    candidates.sort_unstable_by(|proposal1, proposal2| {
+        proposal1.confidence.total_cmp(&proposal2.confidence)
+    });
+
+    while let Some(proposal) = candidates.pop() {
+        candidates.retain(|candidate| {
+            proposal.bounding_box.iou(&candidate.bounding_box) < MAXIMUM_INTERSECTION_OVER_UNION
+        });
+        proposals.push(proposal);
+    }
+
+    proposals
+}
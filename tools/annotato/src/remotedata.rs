@@ -1,9 +1,12 @@
-use std::fs;
+use std::{fs, path::PathBuf};
 
 use clap::Subcommand;
-use color_eyre::Result;
+use color_eyre::{eyre::bail, Result};
 
-use crate::rsync;
+use crate::{
+    dataset::{Dataset, ExportFormat, SplitFractions},
+    rsync,
+};
 
 #[derive(Subcommand, Debug)]
 pub enum DataCommand {
@@ -23,6 +26,35 @@ pub enum DataCommand {
         /// The dataset name to be uploaded
         dataset_name: String,
     },
+    /// Validates the annotations of a local dataset and prints statistics per class
+    Statistics {
+        /// The dataset name to be analyzed
+        #[arg(required = true)]
+        dataset_name: String,
+    },
+    /// Exports the labelled images of a local dataset as train/val/test splits
+    Export {
+        /// The dataset name to be exported
+        #[arg(required = true)]
+        dataset_name: String,
+        /// Directory to export to, has to be empty
+        #[arg(short, long)]
+        output: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Yolo)]
+        format: ExportFormat,
+        /// Fraction of the images used for validation
+        #[arg(long, default_value_t = 0.1)]
+        validation_fraction: f64,
+        /// Fraction of the images used for testing
+        #[arg(long, default_value_t = 0.1)]
+        test_fraction: f64,
+        /// Seed of the split assignment, the same seed always yields the same splits
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Exports the valid images even if some annotations are invalid
+        #[arg(long, default_value = "false")]
+        skip_invalid: bool,
+    },
 }
 
 pub fn handle(command: &DataCommand) -> Result<()> {
@@ -57,6 +89,35 @@ pub fn handle(command: &DataCommand) -> Result<()> {
         DataCommand::ToRemote { dataset_name } => {
             rsync::rsync_to_host("current", dataset_name)?;
         }
+        DataCommand::Statistics { dataset_name } => {
+            let dataset = Dataset::load(&PathBuf::from_iter(["current", dataset_name]))?;
+            dataset.print_statistics();
+        }
+        DataCommand::Export {
+            dataset_name,
+            output,
+            format,
+            validation_fraction,
+            test_fraction,
+            seed,
+            skip_invalid,
+        } => {
+            let dataset = Dataset::load(&PathBuf::from_iter(["current", dataset_name]))?;
+            if !dataset.issues.is_empty() && !skip_invalid {
+                for issue in &dataset.issues {
+                    println!("{issue}");
+                }
+                bail!(
+                    "{} annotations are invalid, fix them or pass --skip-invalid",
+                    dataset.issues.len()
+                );
+            }
+            let fractions = SplitFractions {
+                validation: *validation_fraction,
+                test: *test_fraction,
+            };
+            dataset.export(output, *format, fractions, *seed)?;
+        }
     }
 
     Ok(())
@@ -22,6 +22,8 @@ pub struct KeyBindings {
     pub edit: KeyBind,
     pub draw: KeyBind,
     pub abort: KeyBind,
+    pub detect: KeyBind,
+    pub accept: KeyBind,
 
     pub select_ball: Key,
     pub select_robot: Key,
@@ -3,10 +3,17 @@ use eframe::{
     emath::{Align2, Vec2b},
     epaint::{Color32, Stroke, TextureHandle, Vec2},
 };
-use egui_plot::{Plot, PlotBounds, PlotImage, PlotPoint, PlotResponse, PlotUi, Polygon, Text};
+use egui_plot::{
+    LineStyle, Plot, PlotBounds, PlotImage, PlotPoint, PlotPoints, PlotResponse, PlotUi, Points,
+    Polygon, Text,
+};
 use std::hash::Hash;
 
-use crate::{boundingbox::BoundingBox, classes::Class, user_toml::CONFIG};
+use crate::{
+    boundingbox::BoundingBox, classes::Class, pose_model::PoseProposal, user_toml::CONFIG,
+};
+
+const MINIMUM_KEYPOINT_CONFIDENCE: f32 = 0.5;
 
 pub struct BoundingBoxAnnotator<'a> {
     id: Id,
@@ -14,6 +21,7 @@ pub struct BoundingBoxAnnotator<'a> {
     selected_class: &'a mut Class,
     bounding_boxes: &'a mut Vec<BoundingBox>,
     box_in_editing: &'a mut Option<BoundingBox>,
+    pose_proposals: &'a mut Vec<PoseProposal>,
 }
 
 impl<'a> BoundingBoxAnnotator<'a> {
@@ -23,6 +31,7 @@ impl<'a> BoundingBoxAnnotator<'a> {
         bounding_boxes: &'a mut Vec<BoundingBox>,
         box_in_editing: &'a mut Option<BoundingBox>,
         selected_class: &'a mut Class,
+        pose_proposals: &'a mut Vec<PoseProposal>,
     ) -> Self {
         Self {
             id: Id::new(id_source),
@@ -30,9 +39,25 @@ impl<'a> BoundingBoxAnnotator<'a> {
             bounding_boxes,
             box_in_editing,
             selected_class,
+            pose_proposals,
         }
     }
 
+    fn proposal_at(mouse_position: PlotPoint, proposals: &[PoseProposal]) -> Option<usize> {
+        proposals
+            .iter()
+            .enumerate()
+            .filter(|(_, proposal)| proposal.bounding_box.contains(mouse_position))
+            .min_by(|(_, proposal1), (_, proposal2)| {
+                proposal1
+                    .bounding_box
+                    .rect()
+                    .area()
+                    .total_cmp(&proposal2.bounding_box.rect().area())
+            })
+            .map(|(index, _)| index)
+    }
+
     fn delete_box_from(mouse_position: PlotPoint, bbox_list: &mut Vec<BoundingBox>) -> bool {
         if let Some(clicked_bbox_index) = bbox_list
             .iter()
@@ -55,6 +80,18 @@ impl<'a> BoundingBoxAnnotator<'a> {
 
         let config = CONFIG.get().unwrap();
 
+        if self.box_in_editing.is_none() && ui.input(|i| config.keybindings.accept.is_pressed(i)) {
+            // accept a proposed bounding box
+            if let Some(index) =
+                mouse_position.and_then(|position| Self::proposal_at(position, self.pose_proposals))
+            {
+                let mut bounding_box = self.pose_proposals.remove(index).bounding_box;
+                bounding_box.clip_to_image();
+                self.bounding_boxes.push(bounding_box);
+            }
+            return;
+        }
+
         let editing_bounding_box = match (
             self.box_in_editing.take(),
             ui.input(|i| config.keybindings.draw.is_pressed(i))
@@ -68,8 +105,14 @@ impl<'a> BoundingBoxAnnotator<'a> {
                 None
             }
             (None, _, _, true) => {
-                // delete a bounding box
-                mouse_position.map(|position| Self::delete_box_from(position, self.bounding_boxes));
+                // delete a bounding box, or reject a proposed one if there is none
+                if let Some(position) = mouse_position {
+                    if !Self::delete_box_from(position, self.bounding_boxes) {
+                        if let Some(index) = Self::proposal_at(position, self.pose_proposals) {
+                            self.pose_proposals.remove(index);
+                        }
+                    }
+                }
                 None
             }
             (Some(mut bounding_box), b_pressed, q_pressed, false) if b_pressed || q_pressed => {
@@ -153,6 +196,7 @@ impl Widget for BoundingBoxAnnotator<'_> {
                                 .fill_color(bbox.class.color().gamma_multiply(0.1))
                                 .stroke(Stroke::new(1.0, bbox.class.color().to_opaque())),
                         );
+                        show_keypoints(plot_ui, bbox, bbox.class.color().to_opaque());
                         plot_ui.text(
                             Text::new(
                                 bbox.top_left(),
@@ -165,6 +209,24 @@ impl Widget for BoundingBoxAnnotator<'_> {
                             .anchor(Align2::LEFT_BOTTOM),
                         )
                     });
+                for proposal in self.pose_proposals.iter() {
+                    let polygon: Polygon = (&proposal.bounding_box).into();
+                    plot_ui.polygon(
+                        polygon
+                            .fill_color(Color32::TRANSPARENT)
+                            .stroke(Stroke::new(1.0, Color32::GRAY))
+                            .style(LineStyle::dashed_dense()),
+                    );
+                    show_keypoints(plot_ui, &proposal.bounding_box, Color32::GRAY);
+                    plot_ui.text(
+                        Text::new(
+                            proposal.bounding_box.top_left(),
+                            RichText::new(format!("{:.2}", proposal.confidence))
+                                .color(Color32::GRAY),
+                        )
+                        .anchor(Align2::LEFT_BOTTOM),
+                    );
+                }
             });
         self.handle_bounding_box_input(&response, ui);
 
@@ -191,6 +253,23 @@ impl Widget for BoundingBoxAnnotator<'_> {
     }
 }
 
+fn show_keypoints(plot_ui: &mut PlotUi, bounding_box: &BoundingBox, color: Color32) {
+    let keypoints: Vec<_> = bounding_box
+        .keypoints
+        .iter()
+        .filter(|keypoint| keypoint.confidence >= MINIMUM_KEYPOINT_CONFIDENCE)
+        .map(|keypoint| [keypoint.position.x, keypoint.position.y])
+        .collect();
+    if keypoints.is_empty() {
+        return;
+    }
+    plot_ui.points(
+        Points::new(PlotPoints::new(keypoints))
+            .radius(2.0)
+            .color(color),
+    );
+}
+
 fn zoom_on_scroll_wheel(plot_ui: &mut PlotUi) {
     if !plot_ui.response().hovered() {
         return;
@@ -18,6 +18,7 @@ use gammaray::gammaray;
 use hulk::hulk;
 use location::location;
 use logs::logs;
+use motion::motion;
 use ping::ping;
 use player_number::player_number;
 use post_game::post_game;
@@ -43,6 +44,7 @@ mod git;
 mod hulk;
 mod location;
 mod logs;
+mod motion;
 mod ping;
 mod player_number;
 mod post_game;
@@ -100,6 +102,9 @@ enum Command {
     /// Interact with logs on NAOs
     #[command(subcommand)]
     Logs(logs::Arguments),
+    /// Convert and validate keyframe motions
+    #[command(subcommand)]
+    Motion(motion::Arguments),
     /// Run cargo nextest
     Nextest(cargo::Arguments<nextest::Arguments>),
     /// Change player numbers of NAOs in local parameters
@@ -206,6 +211,9 @@ async fn main() -> Result<()> {
         Command::Logs(arguments) => logs(arguments)
             .await
             .wrap_err("failed to execute logs command")?,
+        Command::Motion(arguments) => motion(arguments, repository)
+            .await
+            .wrap_err("failed to execute motion command")?,
         Command::Nextest(arguments) => cargo(arguments, &repository?, &[] as &[&str])
             .await
             .wrap_err("failed to execute nextest command")?,
@@ -0,0 +1,141 @@
+use std::{
+    fs::{read_to_string, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use clap::Subcommand;
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use motion_validation::{load_motion_file, validate_kick_steps, validate_motion_file, Violation};
+use motionfile::{
+    motion2::{Motion2, Motion2Conditions},
+    ContinuousConditionType, DiscreteConditionType, MotionFile,
+};
+use repository::Repository;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{from_str, to_writer_pretty};
+use walking_engine::{kick_steps::KickSteps, parameters::Parameters};
+
+/// Cycle time of the control cycler
+const SAMPLE_PERIOD: Duration = Duration::from_millis(12);
+
+#[derive(Subcommand)]
+pub enum Arguments {
+    /// Convert a .motion2 file into a motion file
+    Convert {
+        /// Path to the .motion2 file
+        input: PathBuf,
+        /// Path of the motion file to write, defaults to the input with a .json extension
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Entry condition as JSON, e.g. '{"StabilizedCondition": {...}}'
+        #[arg(long, value_parser = parse_json::<DiscreteConditionType>)]
+        entry_condition: Option<DiscreteConditionType>,
+        /// Interrupt condition as JSON, e.g. '{"FallenAbort": {}}', may be given multiple times
+        #[arg(long, value_parser = parse_json::<ContinuousConditionType>)]
+        interrupt_condition: Vec<ContinuousConditionType>,
+        /// Exit condition as JSON, e.g. '{"StabilizedCondition": {...}}'
+        #[arg(long, value_parser = parse_json::<DiscreteConditionType>)]
+        exit_condition: Option<DiscreteConditionType>,
+    },
+    /// Check motion files against the joint limits and for self-collisions
+    Validate {
+        /// Paths to the motion files
+        #[arg(required = true)]
+        motion_files: Vec<PathBuf>,
+    },
+    /// Check all kicks against the joint limits and for collisions of the legs
+    ValidateKickSteps {
+        /// Parameter file containing `walking_engine` and `kick_steps`, defaults to
+        /// etc/parameters/default.json of the repository
+        #[arg(long)]
+        parameters: Option<PathBuf>,
+    },
+}
+
+#[derive(Deserialize)]
+struct KickParameters {
+    walking_engine: Parameters,
+    kick_steps: KickSteps,
+}
+
+pub async fn motion(arguments: Arguments, repository: Result<Repository>) -> Result<()> {
+    match arguments {
+        Arguments::Convert {
+            input,
+            output,
+            entry_condition,
+            interrupt_condition,
+            exit_condition,
+        } => {
+            let motion_file = Motion2::from_path(&input)?.to_motion_file(Motion2Conditions {
+                entry_condition,
+                interrupt_conditions: interrupt_condition,
+                exit_condition,
+            })?;
+
+            let output = output.unwrap_or_else(|| input.with_extension("json"));
+            let file = File::create(&output)
+                .wrap_err_with(|| format!("failed to create {}", output.display()))?;
+            let mut writer = BufWriter::new(file);
+            to_writer_pretty(&mut writer, &motion_file)
+                .wrap_err_with(|| format!("failed to write {}", output.display()))?;
+            writeln!(writer)?;
+            println!("Wrote {}", output.display());
+        }
+        Arguments::Validate { motion_files } => {
+            let mut number_of_violations = 0;
+            for path in motion_files {
+                let motion_file = load_motion_file(&path)?;
+                let violations = validate_motion_file(&motion_file, SAMPLE_PERIOD)
+                    .wrap_err_with(|| format!("failed to validate {}", path.display()))?;
+                number_of_violations += report(&path.display().to_string(), &violations);
+            }
+            if number_of_violations > 0 {
+                bail!("found {number_of_violations} violations");
+            }
+        }
+        Arguments::ValidateKickSteps { parameters } => {
+            let path = match parameters {
+                Some(path) => path,
+                None => repository?.root.join("etc/parameters/default.json"),
+            };
+            let parameters: KickParameters = from_str(
+                &read_to_string(&path)
+                    .wrap_err_with(|| format!("failed to read {}", path.display()))?,
+            )
+            .wrap_err_with(|| format!("failed to parse {}", path.display()))?;
+            let violations = validate_kick_steps(
+                &parameters.walking_engine,
+                &parameters.kick_steps,
+                SAMPLE_PERIOD,
+            );
+            let number_of_violations = report("kick steps", &violations);
+            if number_of_violations > 0 {
+                bail!("found {number_of_violations} violations");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn report(name: &str, violations: &[Violation]) -> usize {
+    if violations.is_empty() {
+        println!("{name}: ok");
+    } else {
+        println!("{name}:");
+        for violation in violations {
+            println!("  {violation}");
+        }
+    }
+    violations.len()
+}
+
+fn parse_json<T: DeserializeOwned>(value: &str) -> Result<T> {
+    from_str(value).wrap_err("failed to parse condition")
+}
@@ -1,5 +1,6 @@
 use std::{
     fmt::{Debug, Display},
+    sync::Arc,
     time::SystemTime,
 };
 
@@ -11,6 +12,8 @@ use communication::client::{
 };
 use tokio::{select, sync::watch};
 
+use crate::value_buffer::Datum;
+
 #[derive(Clone, Debug)]
 pub struct Change<T> {
     pub timestamp: SystemTime,
@@ -118,6 +121,61 @@ impl<T: PartialEq, E> ChangeBuffer<T, E> {
             };
         }
     }
+    /// Fills the buffer with all recorded changes up to the playback time until the buffer is
+    /// dropped
+    ///
+    /// Only the changes since the last playback time are added while the time moves forward, the
+    /// buffer is rebuilt once it moves backwards
+    pub async fn replay<U>(
+        self,
+        series: Arc<Vec<Datum<U>>>,
+        mut time: watch::Receiver<SystemTime>,
+        op: impl Fn(Change<&U>) -> Result<Change<T>, E> + Send + Sync + 'static,
+    ) {
+        let mut last_replayed_time = None;
+        let mut number_of_replayed_samples = 0;
+        loop {
+            let now = *time.borrow_and_update();
+            self.sender.send_if_modified(|value| {
+                let is_rewound = last_replayed_time.is_some_and(|last_time| now < last_time);
+                if is_rewound {
+                    *value = Ok(ChangeSeries::new());
+                    number_of_replayed_samples = 0;
+                }
+                last_replayed_time = Some(now);
+
+                let new_samples = &series[number_of_replayed_samples..];
+                let number_of_new_samples = new_samples
+                    .iter()
+                    .take_while(|sample| sample.timestamp <= now)
+                    .count();
+                number_of_replayed_samples += number_of_new_samples;
+                for sample in &new_samples[..number_of_new_samples] {
+                    if value.is_err() {
+                        break;
+                    }
+                    match op(Change {
+                        timestamp: sample.timestamp,
+                        value: &sample.value,
+                    }) {
+                        Ok(change) => handle_update(value, change),
+                        Err(error) => *value = Err(error),
+                    }
+                }
+                is_rewound || number_of_new_samples > 0
+            });
+            select! {
+                changed = time.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                },
+                _ = self.sender.closed() => {
+                    break
+                }
+            };
+        }
+    }
 }
 
 fn handle_update<T: PartialEq, E>(value: &mut Result<ChangeSeries<T>, E>, datum: Change<T>) {
@@ -1,6 +1,13 @@
 use std::{
-    convert::Into, env::current_dir, iter::once, net::Ipv4Addr, path::PathBuf, str::FromStr,
-    sync::Arc, time::SystemTime,
+    convert::Into,
+    env::current_dir,
+    fs::create_dir_all,
+    iter::once,
+    net::Ipv4Addr,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
 use argument_parsers::NaoAddress;
@@ -11,8 +18,8 @@ use color_eyre::{
 };
 use eframe::{
     egui::{
-        CentralPanel, Context, CornerRadius, Id, Label, Layout, Sense, StrokeKind, TopBottomPanel,
-        Ui, Widget, WidgetText,
+        CentralPanel, Context, CornerRadius, DragValue, Id, Key, Label, Layout, Sense, Slider,
+        SliderClamping, StrokeKind, TopBottomPanel, Ui, Widget, WidgetText,
     },
     emath::Align,
     epaint::Color32,
@@ -30,17 +37,19 @@ use configuration::{
     Configuration,
 };
 use hulk_widgets::CompletionEdit;
-use log::{error, warn};
+use log::{error, info, warn};
 use nao::Nao;
 use panel::Panel;
 use panels::{
-    BallCandidatePanel, BehaviorSimulatorPanel, CameraCalibrationExportPanel, EnumPlotPanel,
-    ImageColorSelectPanel, ImagePanel, ImageSegmentsPanel, LookAtPanel, ManualCalibrationPanel,
-    MapPanel, ParameterPanel, PlotPanel, RemotePanel, SemiAutomaticCameraCalibrationPanel,
-    TextPanel, VisionTunerPanel,
+    BallCandidatePanel, BehaviorSimulatorPanel, BehaviorTreePanel, CameraCalibrationExportPanel,
+    CycleTimingsPanel, DataflowPanel, EnumPlotPanel, ImageColorSelectPanel, ImagePanel,
+    ImageSegmentsPanel, KickStepsPanel, LookAtPanel, ManualCalibrationPanel, MapPanel,
+    MotionEditorPanel, ParameterPanel, PlotPanel, RemotePanel, RobotViewPanel,
+    SemiAutomaticCameraCalibrationPanel, TextPanel, VisionTunerPanel,
 };
 use reachable_naos::ReachableNaos;
 use repository::{inspect_version::check_for_update, Repository};
+use session::Session;
 use visuals::Visuals;
 
 use crate::panels::WalkPanel;
@@ -53,7 +62,9 @@ mod panel;
 mod panels;
 mod players_buffer_handle;
 mod reachable_naos;
+mod recording;
 mod selectable_panel_macro;
+mod session;
 mod twix_painter;
 mod value_buffer;
 mod visuals;
@@ -69,6 +80,9 @@ struct Arguments {
     /// Delete the current panel setup
     #[arg(long)]
     pub clear: bool,
+    /// Replay a recorded session instead of connecting to a robot
+    #[arg(long)]
+    pub playback: Option<PathBuf>,
 }
 
 fn setup_logger() -> Result<(), InitError> {
@@ -136,17 +150,23 @@ fn main() -> Result<(), eframe::Error> {
 impl_selectable_panel!(
     BallCandidatePanel,
     BehaviorSimulatorPanel,
+    BehaviorTreePanel,
     CameraCalibrationExportPanel,
+    CycleTimingsPanel,
+    DataflowPanel,
     EnumPlotPanel,
     ImageColorSelectPanel,
     ImagePanel,
     ImageSegmentsPanel,
+    KickStepsPanel,
     LookAtPanel,
     ManualCalibrationPanel,
     MapPanel,
+    MotionEditorPanel,
     ParameterPanel,
     PlotPanel,
     RemotePanel,
+    RobotViewPanel,
     SemiAutomaticCameraCalibrationPanel,
     TextPanel,
     VisionTunerPanel,
@@ -154,9 +174,13 @@ impl_selectable_panel!(
 );
 
 struct TwixApp {
+    session: Arc<Session>,
     nao: Arc<Nao>,
     possible_addresses: Vec<Ipv4Addr>,
     address: String,
+    team_address: String,
+    playing: bool,
+    playback_speed: f32,
     reachable_naos: ReachableNaos,
     connection_intent: bool,
     panel_selection: String,
@@ -189,17 +213,26 @@ impl TwixApp {
             .or_else(|| creation_context.storage?.get_string("address"))
             .unwrap_or(Ipv4Addr::LOCALHOST.to_string());
 
-        let nao = Arc::new(Nao::new(
-            match address.split_once(":") {
-                None | Some((_, "")) => {
-                    format!("ws://{address}:1337")
-                }
-                Some((ip, port)) => {
-                    format!("ws://{ip}:{port}")
-                }
-            },
-            repository,
-        ));
+        let repository_root = repository.map(|repository| repository.root);
+        let playback_session = arguments.playback.and_then(|file_path| {
+            Session::from_recording(&file_path, repository_root.clone())
+                .inspect_err(|error| error!("failed to load recording: {error:#}"))
+                .ok()
+        });
+        let is_playback = playback_session.is_some();
+        let session = playback_session.unwrap_or_else(|| {
+            let session = Session::new(address.clone(), repository_root);
+            let team: Vec<String> = creation_context
+                .storage
+                .and_then(|storage| storage.get_string("team"))
+                .and_then(|string| from_str(&string).ok())
+                .unwrap_or_default();
+            for address in team {
+                session.add(address);
+            }
+            session
+        });
+        let nao = session.primary().nao;
 
         let connection_intent = creation_context
             .storage
@@ -207,8 +240,10 @@ impl TwixApp {
             .map(|stored| stored == "true")
             .unwrap_or(false);
 
-        if connection_intent {
-            nao.connect();
+        if connection_intent && !is_playback {
+            for robot in session.robots() {
+                robot.nao.connect();
+            }
         }
 
         let dock_state: Option<DockState<Value>> = if arguments.clear {
@@ -221,7 +256,7 @@ impl TwixApp {
         };
 
         let dock_state = match dock_state {
-            Some(dock_state) => dock_state.map_tabs(|value| Tab::new(nao.clone(), value)),
+            Some(dock_state) => dock_state.map_tabs(|value| Tab::new(&session, value)),
             None => DockState::new(vec![SelectablePanel::TextPanel(TextPanel::new(
                 nao.clone(),
                 None,
@@ -235,7 +270,10 @@ impl TwixApp {
         context.set_keybinds(Arc::new(configuration.keys));
 
         let reachable_naos = ReachableNaos::new(context.clone());
-        nao.on_change(move || context.request_repaint());
+        for robot in session.robots() {
+            let context = context.clone();
+            robot.nao.on_change(move || context.request_repaint());
+        }
 
         let visual = creation_context
             .storage
@@ -247,6 +285,7 @@ impl TwixApp {
         let panel_selection = "".to_string();
 
         Self {
+            session,
             nao,
             reachable_naos,
             connection_intent,
@@ -256,7 +295,188 @@ impl TwixApp {
             visual,
             possible_addresses,
             address,
+            team_address: String::new(),
+            playing: false,
+            playback_speed: 1.0,
+        }
+    }
+
+    fn connection_controls(&mut self, ui: &mut Ui, context: &Context) {
+        let address_input = CompletionEdit::new(
+            ui.id().with("nao-selector"),
+            &self.possible_addresses,
+            &mut self.address,
+        )
+        .ui(ui, |ui, selected, ip| {
+            let show_green = self.reachable_naos.is_reachable(*ip);
+            let color = if show_green {
+                Color32::GREEN
+            } else {
+                Color32::WHITE
+            };
+            ui.selectable_label(selected, WidgetText::from(ip.to_string()).color(color))
+        });
+
+        if address_input.gained_focus() {
+            self.reachable_naos.query_reachability();
+        }
+        if context.keybind_pressed(KeybindAction::FocusAddress) {
+            address_input.request_focus();
+        }
+        if address_input.changed() || address_input.lost_focus() {
+            self.session.set_primary_address(self.address.clone());
+            self.connection_intent = true;
+            self.nao.connect();
+        }
+        let (connect_text, color) = match self.nao.connection_status() {
+            Status::Disconnected => ("Disconnected", Color32::RED),
+            Status::Connecting => ("Connecting", Color32::YELLOW),
+            Status::Connected => ("Connected", Color32::GREEN),
+        };
+        let connect_text = WidgetText::from(connect_text).color(color);
+        if ui
+            .checkbox(&mut self.connection_intent, connect_text)
+            .changed()
+        {
+            for robot in self.session.robots() {
+                if self.connection_intent {
+                    robot.nao.connect();
+                } else {
+                    robot.nao.disconnect();
+                }
+            }
+        }
+        ui.menu_button(format!("Team ({})", self.session.team().len()), |ui| {
+            self.team_menu(ui, context);
+        });
+        if context.keybind_pressed(KeybindAction::Reconnect) {
+            self.nao.disconnect();
+            self.connection_intent = true;
+            self.nao.connect();
+        }
+    }
+
+    fn playback_controls(&mut self, ui: &mut Ui, context: &Context) {
+        let nao = self.nao.clone();
+        let Some(playback) = nao.playback() else {
+            return;
+        };
+        ui.label(format!("Playback of {}", playback.recording().address));
+        if ui.button(if self.playing { "⏸" } else { "▶" }).clicked() {
+            self.playing = !self.playing;
+        }
+        let duration = playback.duration().as_secs_f32();
+        let mut position = playback.position().as_secs_f32();
+        if self.playing {
+            position += context.input(|input| input.stable_dt) * self.playback_speed;
+            if position >= duration {
+                self.playing = false;
+            }
+            context.request_repaint();
+        }
+        let slider = ui.add(
+            Slider::new(&mut position, 0.0..=duration)
+                .suffix(" s")
+                .clamping(SliderClamping::Always),
+        );
+        if self.playing || slider.changed() {
+            playback.seek(Duration::from_secs_f32(position.clamp(0.0, duration)));
+        }
+        ui.add(
+            DragValue::new(&mut self.playback_speed)
+                .range(0.1..=10.0)
+                .speed(0.1)
+                .suffix("x"),
+        );
+    }
+
+    fn toggle_recording(&mut self) {
+        if self.session.is_recording() {
+            for file_path in self.session.stop_recording() {
+                info!("recording written to {}", file_path.display());
+            }
+            return;
+        }
+        let Some(directory) = dirs::data_local_dir() else {
+            error!("failed to find a directory for recordings");
+            return;
+        };
+        let directory = directory.join("hulks").join("recordings");
+        if let Err(error) = create_dir_all(&directory) {
+            error!("failed to create {}: {error}", directory.display());
+            return;
+        }
+        match self.session.start_recording(&directory) {
+            Ok(file_paths) => {
+                for file_path in file_paths {
+                    info!("recording to {}", file_path.display());
+                }
+            }
+            Err(error) => {
+                self.session.stop_recording();
+                error!("failed to start recording: {error:#}");
+            }
+        }
+    }
+
+    fn add_team_robot(&mut self, context: &Context) {
+        let address = self.team_address.trim().to_string();
+        if address.is_empty() {
+            return;
+        }
+        let nao = self.session.add(address);
+        let context = context.clone();
+        nao.on_change(move || context.request_repaint());
+        if self.connection_intent {
+            nao.connect();
         }
+        self.team_address.clear();
+    }
+
+    /// Tabs of a removed robot fall back to the primary connection
+    fn remove_team_robot(&mut self, address: &str) {
+        self.session.remove(address);
+        for (_, tab) in self.dock_state.iter_all_tabs_mut() {
+            if tab.robot.as_deref() == Some(address) {
+                tab.select_robot(&self.session, None);
+            }
+        }
+    }
+
+    fn team_menu(&mut self, ui: &mut Ui, context: &Context) {
+        let mut removed_robot = None;
+        for robot in self.session.team() {
+            ui.horizontal(|ui| {
+                let status_color = match robot.nao.connection_status() {
+                    Status::Disconnected => Color32::RED,
+                    Status::Connecting => Color32::YELLOW,
+                    Status::Connected => Color32::GREEN,
+                };
+                ui.label(WidgetText::from("⏺").color(robot.color));
+                ui.label(WidgetText::from(robot.address.as_str()).color(status_color));
+                if ui.button("Remove").clicked() {
+                    removed_robot = Some(robot.address.clone());
+                }
+            });
+        }
+        if let Some(address) = removed_robot {
+            self.remove_team_robot(&address);
+        }
+        ui.horizontal(|ui| {
+            let address_input = CompletionEdit::new(
+                ui.id().with("team-selector"),
+                &self.possible_addresses,
+                &mut self.team_address,
+            )
+            .ui(ui, |ui, selected, ip| {
+                ui.selectable_label(selected, ip.to_string())
+            });
+            if ui.button("Add").clicked()
+                || (address_input.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)))
+            {
+                self.add_team_robot(context);
+            }
+        });
     }
 
     fn focus_left(&mut self, node_id: NodeIndex, surface_index: SurfaceIndex) -> Option<()> {
@@ -375,61 +595,19 @@ impl App for TwixApp {
         TopBottomPanel::top("top_bar").show(context, |ui| {
             ui.horizontal(|ui| {
                 ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-                    let address_input = CompletionEdit::new(
-                        ui.id().with("nao-selector"),
-                        &self.possible_addresses,
-                        &mut self.address,
-                    )
-                    .ui(ui, |ui, selected, ip| {
-                        let show_green = self.reachable_naos.is_reachable(*ip);
-                        let color = if show_green {
-                            Color32::GREEN
+                    if self.nao.playback().is_some() {
+                        self.playback_controls(ui, context);
+                    } else {
+                        self.connection_controls(ui, context);
+                        let record_text = if self.session.is_recording() {
+                            "⏹ Stop Recording"
                         } else {
-                            Color32::WHITE
+                            "⏺ Record"
                         };
-                        ui.selectable_label(selected, WidgetText::from(ip.to_string()).color(color))
-                    });
-
-                    if address_input.gained_focus() {
-                        self.reachable_naos.query_reachability();
-                    }
-                    if context.keybind_pressed(KeybindAction::FocusAddress) {
-                        address_input.request_focus();
-                    }
-                    if address_input.changed() || address_input.lost_focus() {
-                        match &self.address.split_once(":") {
-                            None | Some((_, "")) => {
-                                let address = &self.address;
-                                self.nao.set_address(format!("ws://{address}:1337"));
-                            }
-                            Some((ip, port)) => {
-                                self.nao.set_address(format!("ws://{ip}:{port}"));
-                            }
-                        }
-                        self.connection_intent = true;
-                        self.nao.connect();
-                    }
-                    let (connect_text, color) = match self.nao.connection_status() {
-                        Status::Disconnected => ("Disconnected", Color32::RED),
-                        Status::Connecting => ("Connecting", Color32::YELLOW),
-                        Status::Connected => ("Connected", Color32::GREEN),
-                    };
-                    let connect_text = WidgetText::from(connect_text).color(color);
-                    if ui
-                        .checkbox(&mut self.connection_intent, connect_text)
-                        .changed()
-                    {
-                        if self.connection_intent {
-                            self.nao.connect();
-                        } else {
-                            self.nao.disconnect();
+                        if ui.button(record_text).clicked() {
+                            self.toggle_recording();
                         }
                     }
-                    if context.keybind_pressed(KeybindAction::Reconnect) {
-                        self.nao.disconnect();
-                        self.connection_intent = true;
-                        self.nao.connect();
-                    }
 
                     if self.active_tab_index() != Some(self.last_focused_tab) {
                         self.last_focused_tab =
@@ -453,11 +631,11 @@ impl App for TwixApp {
                         panel_input.request_focus();
                     }
                     if panel_input.changed() {
-                        match SelectablePanel::try_from_name(
-                            &self.panel_selection,
-                            self.nao.clone(),
-                            None,
-                        ) {
+                        let nao = self
+                            .active_tab()
+                            .map(|tab| tab.nao(&self.session))
+                            .unwrap_or_else(|| self.nao.clone());
+                        match SelectablePanel::try_from_name(&self.panel_selection, nao, None) {
                             Ok(panel) => {
                                 if let Some(active_tab) = self.active_tab() {
                                     active_tab.panel = Ok(panel);
@@ -534,10 +712,8 @@ impl App for TwixApp {
 
             if context.keybind_pressed(KeybindAction::DuplicateTab) {
                 if let Some((_, tab)) = self.dock_state.find_active_focused() {
-                    let new_tab = tab.save();
-                    self.dock_state.push_to_focused_leaf(Tab::from(
-                        SelectablePanel::new(self.nao.clone(), Some(&new_tab)).unwrap(),
-                    ));
+                    let new_tab = Tab::new(&self.session, &tab.save());
+                    self.dock_state.push_to_focused_leaf(new_tab);
                 }
             }
 
@@ -569,9 +745,14 @@ impl App for TwixApp {
                     .set_focused_node_and_surface((0.into(), 0.into()));
             }
 
+            for value in self.session.take_tab_requests() {
+                let tab = Tab::new(&self.session, &value);
+                self.dock_state.push_to_focused_leaf(tab);
+            }
+
             let mut style = egui_dock::Style::from_egui(ui.style().as_ref());
             style.buttons.add_tab_align = TabAddAlign::Left;
-            let mut tab_viewer = TabViewer::default();
+            let mut tab_viewer = TabViewer::new(self.session.clone());
             DockArea::new(&mut self.dock_state)
                 .style(style)
                 .show_add_buttons(true)
@@ -603,6 +784,15 @@ impl App for TwixApp {
 
         storage.set_string("dock_state", to_string(&dock_state).unwrap());
         storage.set_string("address", self.address.to_string());
+        if self.nao.playback().is_none() {
+            let team: Vec<_> = self
+                .session
+                .team()
+                .into_iter()
+                .map(|robot| robot.address)
+                .collect();
+            storage.set_string("team", to_string(&team).unwrap());
+        }
         storage.set_string(
             "connection_intent",
             if self.connection_intent {
@@ -634,6 +824,8 @@ impl TwixApp {
 
 struct Tab {
     id: Id,
+    /// Address of the team robot this tab is connected to, `None` selects the primary connection
+    robot: Option<String>,
     panel: Result<SelectablePanel, (Report, Value)>,
 }
 
@@ -641,32 +833,78 @@ impl From<SelectablePanel> for Tab {
     fn from(panel: SelectablePanel) -> Self {
         Self {
             id: Id::new(SystemTime::now()),
+            robot: None,
             panel: Ok(panel),
         }
     }
 }
 
 impl Tab {
-    fn new(nao: Arc<Nao>, value: &Value) -> Self {
+    fn new(session: &Arc<Session>, value: &Value) -> Self {
+        let robot = value
+            .get("_robot")
+            .and_then(|robot| robot.as_str())
+            .and_then(|address| session.find(address));
+        let nao = robot
+            .as_ref()
+            .map(|robot| robot.nao.clone())
+            .unwrap_or_else(|| session.primary().nao);
         Self {
             id: Id::new(SystemTime::now()),
+            robot: robot.map(|robot| robot.address),
             panel: SelectablePanel::new(nao, Some(value)).map_err(|error| (error, value.clone())),
         }
     }
 
+    fn nao(&self, session: &Session) -> Arc<Nao> {
+        self.robot
+            .as_ref()
+            .and_then(|address| session.find(address))
+            .map(|robot| robot.nao)
+            .unwrap_or_else(|| session.primary().nao)
+    }
+
+    fn select_robot(&mut self, session: &Arc<Session>, robot: Option<String>) {
+        let mut value = self.save();
+        match robot {
+            Some(address) => value["_robot"] = Value::String(address),
+            None => {
+                if let Some(object) = value.as_object_mut() {
+                    object.remove("_robot");
+                }
+            }
+        }
+        let id = self.id;
+        *self = Self::new(session, &value);
+        self.id = id;
+    }
+
     fn save(&self) -> Value {
-        match &self.panel {
+        let mut value = match &self.panel {
             Ok(panel) => panel.save(),
             Err((_report, value)) => value.clone(),
+        };
+        if let Some(robot) = &self.robot {
+            value["_robot"] = Value::String(robot.clone());
         }
+        value
     }
 }
 
-#[derive(Default)]
 struct TabViewer {
+    session: Arc<Session>,
     nodes_to_add_tabs_to: Vec<(SurfaceIndex, NodeIndex)>,
 }
 
+impl TabViewer {
+    fn new(session: Arc<Session>) -> Self {
+        Self {
+            session,
+            nodes_to_add_tabs_to: Vec::new(),
+        }
+    }
+}
+
 impl egui_dock::TabViewer for TabViewer {
     type Tab = Tab;
 
@@ -695,9 +933,43 @@ impl egui_dock::TabViewer for TabViewer {
     }
 
     fn title(&mut self, tab: &mut Self::Tab) -> eframe::egui::WidgetText {
-        match &mut tab.panel {
-            Ok(panel) => format!("{panel}").into(),
-            Err((error, _value)) => WidgetText::from(format!("{error}")).color(Color32::LIGHT_RED),
+        match (&mut tab.panel, &tab.robot) {
+            (Ok(panel), None) => format!("{panel}").into(),
+            (Ok(panel), Some(robot)) => format!("{panel} ({robot})").into(),
+            (Err((error, _value)), _) => {
+                WidgetText::from(format!("{error}")).color(Color32::LIGHT_RED)
+            }
+        }
+    }
+
+    fn context_menu(
+        &mut self,
+        ui: &mut Ui,
+        tab: &mut Self::Tab,
+        _surface: SurfaceIndex,
+        _node: NodeIndex,
+    ) {
+        ui.label("Robot");
+        let primary = self.session.primary();
+        if ui
+            .selectable_label(tab.robot.is_none(), primary.address)
+            .clicked()
+        {
+            tab.select_robot(&self.session, None);
+            ui.close_menu();
+        }
+        for robot in self.session.team() {
+            let is_selected = tab.robot.as_ref() == Some(&robot.address);
+            if ui
+                .selectable_label(
+                    is_selected,
+                    WidgetText::from(robot.address.as_str()).color(robot.color),
+                )
+                .clicked()
+            {
+                tab.select_robot(&self.session, Some(robot.address));
+                ui.close_menu();
+            }
         }
     }
 
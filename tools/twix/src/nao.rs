@@ -1,4 +1,9 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, SystemTime},
+};
 
 use bincode::deserialize;
 use color_eyre::{
@@ -14,25 +19,63 @@ use tokio::{
 
 use communication::{
     client::{Client, ClientHandle, PathsEvent, Status},
-    messages::{Path, TextOrBinary},
+    messages::{Format, Path, TextOrBinary},
 };
 use hula_types::hardware::Ids;
-use parameters::{directory::Scope, json::nest_value_at_path};
+use parameters::{
+    directory::{layers, Layer, Scope},
+    json::nest_value_at_path,
+};
 use repository::Repository;
 
 use crate::{
     change_buffer::{Change, ChangeBuffer, ChangeBufferHandle},
+    recording::{Playback, Recorder},
+    session::Session,
     value_buffer::{Buffer, BufferHandle, Datum},
 };
 
+/// A parameter write of this session, the previous value allows undoing it
+#[derive(Clone, Debug)]
+pub struct ParameterWrite {
+    pub timestamp: SystemTime,
+    pub path: Path,
+    pub previous: Option<Value>,
+    pub value: Value,
+}
+
 pub struct Nao {
     runtime: Runtime,
     client: ClientHandle,
     repository: Option<Repository>,
+    session: Weak<Session>,
+    subscribed_paths: Mutex<HashSet<(Path, Format)>>,
+    recorder: Mutex<Option<Recorder>>,
+    playback: Option<Playback>,
+    parameter_history: Arc<Mutex<Vec<ParameterWrite>>>,
 }
 
 impl Nao {
-    pub fn new(address: String, repository: Option<Repository>) -> Self {
+    pub fn new(address: String, repository: Option<Repository>, session: Weak<Session>) -> Self {
+        Self::with_playback(address, repository, session, None)
+    }
+
+    /// Creates a connection which is backed by a recording instead of a robot
+    pub fn from_playback(
+        playback: Playback,
+        repository: Option<Repository>,
+        session: Weak<Session>,
+    ) -> Self {
+        let address = playback.recording().address.clone();
+        Self::with_playback(address, repository, session, Some(playback))
+    }
+
+    fn with_playback(
+        address: String,
+        repository: Option<Repository>,
+        session: Weak<Session>,
+        playback: Option<Playback>,
+    ) -> Self {
         let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
 
         let (client, handle) = Client::new(address);
@@ -42,10 +85,72 @@ impl Nao {
             runtime,
             client: handle,
             repository,
+            session,
+            subscribed_paths: Mutex::new(HashSet::new()),
+            recorder: Mutex::new(None),
+            playback,
+            parameter_history: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    pub fn playback(&self) -> Option<&Playback> {
+        self.playback.as_ref()
+    }
+
+    /// Records all paths subscribed so far and all paths subscribed while recording
+    pub fn start_recording(&self, file_path: PathBuf, address: String) -> Result<()> {
+        if self.playback.is_some() {
+            return Err(eyre!("cannot record during playback"));
+        }
+        let paths = match self.latest_paths().as_ref() {
+            Some(Ok(paths)) => paths.clone(),
+            _ => Default::default(),
+        };
+        let recorder = Recorder::start(file_path, address, paths)?;
+        let mut active_recorder = self.recorder.lock().unwrap();
+        let _guard = self.runtime.enter();
+        for (path, format) in self.subscribed_paths.lock().unwrap().iter() {
+            recorder.record(self.client.clone(), path.clone(), *format);
+        }
+        *active_recorder = Some(recorder);
+        Ok(())
+    }
+
+    /// Stops the recording and returns the path of the written file
+    pub fn stop_recording(&self) -> Option<PathBuf> {
+        let recorder = self.recorder.lock().unwrap().take()?;
+        Some(recorder.file_path().to_path_buf())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().unwrap().is_some()
+    }
+
+    fn register_subscription(&self, path: &Path, format: Format) {
+        let is_new = self
+            .subscribed_paths
+            .lock()
+            .unwrap()
+            .insert((path.clone(), format));
+        if let (true, Some(recorder)) = (is_new, self.recorder.lock().unwrap().as_ref()) {
+            let _guard = self.runtime.enter();
+            recorder.record(self.client.clone(), path.clone(), format);
+        }
+    }
+
+    pub fn repository(&self) -> Option<&Repository> {
+        self.repository.as_ref()
+    }
+
+    /// The session this connection belongs to, used by panels showing data of the whole team
+    pub fn session(&self) -> Option<Arc<Session>> {
+        self.session.upgrade()
+    }
+
     pub fn connect(&self) {
+        if self.playback.is_some() {
+            return;
+        }
         let client = self.client.clone();
         self.runtime.spawn(async move { client.connect().await });
     }
@@ -67,6 +172,9 @@ impl Nao {
     }
 
     pub fn latest_paths(&self) -> PathsEvent {
+        if let Some(playback) = &self.playback {
+            return Arc::new(Some(Ok(playback.recording().paths.clone())));
+        }
         self.client.paths.borrow().clone()
     }
 
@@ -77,9 +185,14 @@ impl Nao {
     where
         for<'de> T: serde::Deserialize<'de> + Send + Sync + 'static,
     {
-        let (timestamp, bytes) = self
-            .runtime
-            .block_on(self.client.read_binary(path.into()))?;
+        let path = path.into();
+        if let Some(playback) = &self.playback {
+            let datum = playback
+                .read_binary(&path)
+                .ok_or_else(|| eyre!("no value of {path} recorded before the playback time"))?;
+            return Ok((datum.timestamp, deserialize(&datum.value)?));
+        }
+        let (timestamp, bytes) = self.runtime.block_on(self.client.read_binary(path))?;
         let value = deserialize(&bytes)?;
         Ok((timestamp, value))
     }
@@ -96,6 +209,21 @@ impl Nao {
         let path = path.into();
         let _guard = self.runtime.enter();
         let (task, buffer) = Buffer::new(history);
+        if let Some(playback) = &self.playback {
+            let series = playback.text_series(&path);
+            let time = playback.subscribe_time();
+            spawn(async move {
+                task.replay(series, time, |datum| -> Result<_, Report> {
+                    Ok(Datum {
+                        timestamp: datum.timestamp,
+                        value: datum.value.clone(),
+                    })
+                })
+                .await;
+            });
+            return buffer;
+        }
+        self.register_subscription(&path, Format::Text);
         let client = self.client.clone();
         spawn(async move {
             let subscription = client.subscribe_text(path).await;
@@ -115,6 +243,21 @@ impl Nao {
         let path = path.into();
         let _guard = self.runtime.enter();
         let (task, buffer) = ChangeBuffer::new();
+        if let Some(playback) = &self.playback {
+            let series = playback.text_series(&path);
+            let time = playback.subscribe_time();
+            spawn(async move {
+                task.replay(series, time, |change| -> Result<_, Report> {
+                    Ok(Change {
+                        timestamp: change.timestamp,
+                        value: change.value.clone(),
+                    })
+                })
+                .await;
+            });
+            return buffer;
+        }
+        self.register_subscription(&path, Format::Text);
         let client = self.client.clone();
         spawn(async move {
             let subscription = client.subscribe_text(path).await;
@@ -148,6 +291,22 @@ impl Nao {
         let path = path.into();
         let _guard = self.runtime.enter();
         let (task, buffer) = Buffer::new(history);
+        if let Some(playback) = &self.playback {
+            let series = playback.binary_series(&path);
+            let time = playback.subscribe_time();
+            spawn(async move {
+                task.replay(series, time, |datum| -> Result<_, Report> {
+                    Ok(Datum {
+                        timestamp: datum.timestamp,
+                        value: deserialize(datum.value)
+                            .wrap_err("bincode deserialization failed")?,
+                    })
+                })
+                .await;
+            });
+            return buffer;
+        }
+        self.register_subscription(&path, Format::Binary);
         let client = self.client.clone();
         spawn(async move {
             let subscription = client.subscribe_binary(path).await;
@@ -164,10 +323,64 @@ impl Nao {
     }
 
     pub fn write(&self, path: impl Into<Path>, value: TextOrBinary) {
-        let client = self.client.clone();
         let path = path.into();
+        if self.playback.is_some() {
+            error!("cannot write {path} during playback");
+            return;
+        }
+        let client = self.client.clone();
+        let parameter_history = self.parameter_history.clone();
         self.runtime.spawn(async move {
-            if let Err(error) = client.write(path, value).await {
+            let parameter_write = match &value {
+                TextOrBinary::Text(value) if path.starts_with("parameters.") => {
+                    let previous = client
+                        .read_text(path.clone())
+                        .await
+                        .ok()
+                        .map(|(_, previous)| previous);
+                    Some(ParameterWrite {
+                        timestamp: SystemTime::now(),
+                        path: path.clone(),
+                        previous,
+                        value: value.clone(),
+                    })
+                }
+                _ => None,
+            };
+            match client.write(path, value).await {
+                Ok(()) => {
+                    if let Some(parameter_write) = parameter_write {
+                        parameter_history.lock().unwrap().push(parameter_write);
+                    }
+                }
+                Err(error) => error!("{error:#}"),
+            }
+        });
+    }
+
+    /// All parameter writes of this session, oldest first
+    pub fn parameter_history(&self) -> Vec<ParameterWrite> {
+        self.parameter_history.lock().unwrap().clone()
+    }
+
+    /// Restores the value which the parameter had before the latest write
+    pub fn undo_parameter_write(&self) {
+        let Some(parameter_write) = self.parameter_history.lock().unwrap().pop() else {
+            return;
+        };
+        let Some(previous) = parameter_write.previous else {
+            error!(
+                "cannot undo write of {}, previous value is unknown",
+                parameter_write.path
+            );
+            return;
+        };
+        let client = self.client.clone();
+        self.runtime.spawn(async move {
+            if let Err(error) = client
+                .write(parameter_write.path, TextOrBinary::Text(previous))
+                .await
+            {
                 error!("{error:#}")
             }
         });
@@ -194,6 +407,21 @@ impl Nao {
         });
         Ok(())
     }
+
+    /// The value of the parameter in the files of all scopes of this robot, in merge order
+    pub fn parameter_layers(&self, path: &str) -> Result<Vec<Layer>> {
+        let parameters_root = self
+            .repository
+            .as_ref()
+            .ok_or_eyre("repository not available, cannot read parameters")?
+            .root
+            .join("etc/parameters/");
+        let (_, bytes) = self
+            .runtime
+            .block_on(self.client.read_binary("hardware_ids"))?;
+        let ids: Ids = bincode::deserialize(&bytes).wrap_err("bincode deserialization failed")?;
+        layers(parameters_root, &ids, path).wrap_err("failed to read parameter files")
+    }
 }
 
 async fn store_parameters(
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use eframe::{
+    egui::{Label, Response, RichText, ScrollArea, Ui, Widget},
+    epaint::Color32,
+};
+use serde_json::Value;
+
+use types::behavior_tree::{BehaviorTreeStatus, BehaviorTreeTraceEntry};
+
+use crate::{nao::Nao, panel::Panel, value_buffer::BufferHandle};
+
+const INDENTATION: f32 = 16.0;
+
+pub struct BehaviorTreePanel {
+    trace: BufferHandle<Vec<BehaviorTreeTraceEntry>>,
+    show_inactive_nodes: bool,
+}
+
+impl Panel for BehaviorTreePanel {
+    const NAME: &'static str = "Behavior Tree";
+
+    fn new(nao: Arc<Nao>, _: Option<&Value>) -> Self {
+        let trace = nao.subscribe_value("Control.additional_outputs.behavior_tree_trace");
+        Self {
+            trace,
+            show_inactive_nodes: true,
+        }
+    }
+}
+
+impl Widget for &mut BehaviorTreePanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.checkbox(
+            &mut self.show_inactive_nodes,
+            "Show failed and succeeded nodes",
+        );
+        let trace = match self.trace.get_last_value() {
+            Ok(Some(trace)) => trace,
+            Ok(None) => return ui.label("no data available"),
+            Err(error) => return ui.label(error.to_string()),
+        };
+        if trace.is_empty() {
+            return ui.label("behavior tree inactive, fixed actions were executed");
+        }
+        ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for entry in &trace {
+                    if !self.show_inactive_nodes && entry.status != BehaviorTreeStatus::Running {
+                        continue;
+                    }
+                    let color = match entry.status {
+                        BehaviorTreeStatus::Running => Color32::GREEN,
+                        BehaviorTreeStatus::Success => Color32::LIGHT_BLUE,
+                        BehaviorTreeStatus::Failure => Color32::GRAY,
+                    };
+                    ui.horizontal(|ui| {
+                        ui.add_space(entry.depth as f32 * INDENTATION);
+                        ui.add(Label::new(
+                            RichText::new(format!("{} ({:?})", entry.label, entry.status))
+                                .color(color),
+                        ));
+                    });
+                }
+            });
+        ui.label(format!("{} nodes visited", trace.len()))
+    }
+}
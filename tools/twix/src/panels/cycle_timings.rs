@@ -0,0 +1,319 @@
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use eframe::{
+    egui::{
+        pos2, vec2, Align2, ComboBox, CornerRadius, DragValue, FontId, Grid, Rect, Response,
+        ScrollArea, Sense, Ui, Widget,
+    },
+    epaint::{Color32, Stroke, StrokeKind},
+};
+use egui_plot::{Bar, BarChart, Plot, VLine};
+use serde_json::{json, Value};
+
+use crate::{nao::Nao, panel::Panel, value_buffer::BufferHandle};
+
+const DEFAULT_CYCLER: &str = "Control";
+const THRESHOLD_KEY: &str = "execution_time_warning_threshold";
+const FLAME_HEIGHT: f32 = 24.0;
+const HISTOGRAM_BIN_WIDTH_MILLISECONDS: f64 = 0.5;
+
+struct CycleTiming {
+    total: Duration,
+    threshold: Option<Duration>,
+    nodes: Vec<(String, Duration)>,
+}
+
+impl CycleTiming {
+    fn from_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        let total = serde_json::from_value(object.get("total")?.clone()).ok()?;
+        let threshold = object
+            .get(THRESHOLD_KEY)
+            .and_then(|threshold| serde_json::from_value(threshold.clone()).ok())
+            .flatten();
+        let mut nodes: Vec<_> = object
+            .iter()
+            .filter(|(name, _)| *name != "total" && *name != THRESHOLD_KEY)
+            .filter_map(|(name, duration)| {
+                Some((name.clone(), serde_json::from_value(duration.clone()).ok()?))
+            })
+            .collect();
+        nodes.sort_by(|(_, left), (_, right)| right.cmp(left));
+        Some(Self {
+            total,
+            threshold,
+            nodes,
+        })
+    }
+
+    fn is_deadline_missed(&self) -> bool {
+        self.threshold
+            .is_some_and(|threshold| self.total > threshold)
+    }
+}
+
+pub struct CycleTimingsPanel {
+    nao: Arc<Nao>,
+    cycler: String,
+    history: Duration,
+    buffer: BufferHandle<Value>,
+}
+
+impl Panel for CycleTimingsPanel {
+    const NAME: &'static str = "Cycle Timings";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let cycler = value
+            .and_then(|value| value.get("cycler"))
+            .and_then(|value| value.as_str())
+            .unwrap_or(DEFAULT_CYCLER)
+            .to_string();
+        let history = value
+            .and_then(|value| value.get("history"))
+            .and_then(|value| value.as_f64())
+            .map(Duration::from_secs_f64)
+            .unwrap_or(Duration::from_secs(10));
+        let buffer = subscribe(&nao, &cycler, history);
+        Self {
+            nao,
+            cycler,
+            history,
+            buffer,
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "cycler": self.cycler,
+            "history": self.history.as_secs_f64(),
+        })
+    }
+}
+
+fn subscribe(nao: &Nao, cycler: &str, history: Duration) -> BufferHandle<Value> {
+    nao.subscribe_buffered_json(format!("{cycler}.cycle_timings"), history)
+}
+
+/// Cycler instances whose cycle timings appear in the output hierarchy of the robot, the
+/// hierarchy only lists the fields, e.g. `Control.cycle_timings.total`
+fn cyclers(nao: &Nao) -> BTreeSet<String> {
+    match nao.latest_paths().as_ref() {
+        Some(Ok(paths)) => paths
+            .keys()
+            .filter_map(|path| path.split_once(".cycle_timings."))
+            .map(|(cycler, _)| cycler.to_string())
+            .collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+impl Widget for &mut CycleTimingsPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            let mut cycler_changed = false;
+            ComboBox::from_id_salt("cycler_selector")
+                .selected_text(self.cycler.as_str())
+                .show_ui(ui, |ui| {
+                    let cyclers = cyclers(&self.nao);
+                    if cyclers.is_empty() {
+                        ui.label("no cyclers available");
+                    }
+                    for cycler in cyclers {
+                        cycler_changed |= ui
+                            .selectable_value(&mut self.cycler, cycler.clone(), cycler)
+                            .changed();
+                    }
+                });
+            if cycler_changed {
+                self.buffer = subscribe(&self.nao, &self.cycler, self.history);
+            }
+            let mut history = self.history.as_secs_f64();
+            if ui
+                .add(
+                    DragValue::new(&mut history)
+                        .range(1.0..=120.0)
+                        .prefix("History: ")
+                        .suffix(" s"),
+                )
+                .changed()
+            {
+                self.history = Duration::from_secs_f64(history);
+                self.buffer.set_history(self.history);
+            }
+        });
+
+        let timings: Vec<_> = match self.buffer.get() {
+            Ok(series) => series
+                .iter()
+                .filter_map(|datum| CycleTiming::from_value(&datum.value))
+                .collect(),
+            Err(error) => return ui.label(format!("{error:#}")),
+        };
+        let Some(latest) = timings.last() else {
+            return ui.label("no data available");
+        };
+
+        show_flame(ui, latest);
+        show_summary(ui, &timings);
+        show_histogram(ui, &timings, latest.threshold);
+        ScrollArea::vertical()
+            .show(ui, |ui| show_node_table(ui, &timings))
+            .inner
+    }
+}
+
+/// Draws the nodes of a single cycle as a bar whose segments are proportional to their durations
+fn show_flame(ui: &mut Ui, timing: &CycleTiming) {
+    let (rect, response) =
+        ui.allocate_exact_size([ui.available_width(), FLAME_HEIGHT].into(), Sense::hover());
+    let scale = timing
+        .threshold
+        .map_or(timing.total, |threshold| threshold.max(timing.total))
+        .as_secs_f32()
+        .max(f32::EPSILON);
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, CornerRadius::ZERO, ui.visuals().extreme_bg_color);
+
+    let mut left = rect.left();
+    let mut hovered_node = None;
+    for (index, (name, duration)) in timing.nodes.iter().enumerate() {
+        let width = duration.as_secs_f32() / scale * rect.width();
+        let segment = Rect::from_min_max(pos2(left, rect.top()), pos2(left + width, rect.bottom()));
+        let color = Color32::from_rgb(255, 120 + (index * 37 % 120) as u8, 40);
+        painter.rect(
+            segment,
+            CornerRadius::ZERO,
+            color,
+            Stroke::new(0.5, Color32::BLACK),
+            StrokeKind::Inside,
+        );
+        if width > 60.0 {
+            painter.text(
+                segment.left_center() + vec2(4.0, 0.0),
+                Align2::LEFT_CENTER,
+                name,
+                FontId::default(),
+                Color32::BLACK,
+            );
+        }
+        if response
+            .hover_pos()
+            .is_some_and(|position| segment.contains(position))
+        {
+            hovered_node = Some((name, duration));
+        }
+        left += width;
+    }
+    if let Some(threshold) = timing.threshold {
+        let x = rect.left() + threshold.as_secs_f32() / scale * rect.width();
+        painter.vline(x, rect.y_range(), Stroke::new(2.0, Color32::RED));
+    }
+    if let Some((name, duration)) = hovered_node {
+        response
+            .on_hover_text_at_pointer(format!("{name}: {:.3} ms", duration.as_secs_f64() * 1000.0));
+    }
+}
+
+fn show_summary(ui: &mut Ui, timings: &[CycleTiming]) {
+    let deadline_misses = timings
+        .iter()
+        .filter(|timing| timing.is_deadline_missed())
+        .count();
+    let mean = timings.iter().map(|timing| timing.total).sum::<Duration>() / timings.len() as u32;
+    let maximum = timings
+        .iter()
+        .map(|timing| timing.total)
+        .max()
+        .unwrap_or_default();
+    ui.label(format!(
+        "{} cycles, mean {:.3} ms, max {:.3} ms, {deadline_misses} deadline misses ({:.1}%)",
+        timings.len(),
+        mean.as_secs_f64() * 1000.0,
+        maximum.as_secs_f64() * 1000.0,
+        deadline_misses as f64 / timings.len() as f64 * 100.0,
+    ));
+}
+
+fn show_histogram(ui: &mut Ui, timings: &[CycleTiming], threshold: Option<Duration>) {
+    let mut counts = Vec::<usize>::new();
+    for timing in timings {
+        let bin = (timing.total.as_secs_f64() * 1000.0 / HISTOGRAM_BIN_WIDTH_MILLISECONDS) as usize;
+        if bin >= counts.len() {
+            counts.resize(bin + 1, 0);
+        }
+        counts[bin] += 1;
+    }
+    let bars = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(bin, count)| {
+            let center = (bin as f64 + 0.5) * HISTOGRAM_BIN_WIDTH_MILLISECONDS;
+            let is_deadline_missed =
+                threshold.is_some_and(|threshold| center > threshold.as_secs_f64() * 1000.0);
+            Bar::new(center, *count as f64)
+                .width(HISTOGRAM_BIN_WIDTH_MILLISECONDS)
+                .fill(if is_deadline_missed {
+                    Color32::RED
+                } else {
+                    Color32::LIGHT_BLUE
+                })
+        })
+        .collect();
+    Plot::new("cycle_duration_histogram")
+        .height(150.0)
+        .x_axis_label("cycle duration [ms]")
+        .y_axis_label("cycles")
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars));
+            if let Some(threshold) = threshold {
+                plot_ui.vline(VLine::new(threshold.as_secs_f64() * 1000.0).color(Color32::RED));
+            }
+        });
+}
+
+fn show_node_table(ui: &mut Ui, timings: &[CycleTiming]) -> Response {
+    let mut statistics: Vec<(&str, Duration, Duration)> = Vec::new();
+    for timing in timings {
+        for (name, duration) in &timing.nodes {
+            match statistics
+                .iter_mut()
+                .find(|(statistics_name, _, _)| statistics_name == name)
+            {
+                Some((_, sum, maximum)) => {
+                    *sum += *duration;
+                    *maximum = (*maximum).max(*duration);
+                }
+                None => statistics.push((name, *duration, *duration)),
+            }
+        }
+    }
+    statistics.sort_by(|(_, left, _), (_, right, _)| right.cmp(left));
+    let mean_total = timings
+        .iter()
+        .map(|timing| timing.total)
+        .sum::<Duration>()
+        .as_secs_f64()
+        .max(f64::EPSILON);
+
+    Grid::new("node_timings")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("Node");
+            ui.strong("Mean [ms]");
+            ui.strong("Max [ms]");
+            ui.strong("Share");
+            ui.end_row();
+            for (name, sum, maximum) in statistics {
+                ui.label(name);
+                ui.label(format!(
+                    "{:.3}",
+                    sum.as_secs_f64() * 1000.0 / timings.len() as f64
+                ));
+                ui.label(format!("{:.3}", maximum.as_secs_f64() * 1000.0));
+                ui.label(format!("{:.1}%", sum.as_secs_f64() / mean_total * 100.0));
+                ui.end_row();
+            }
+        })
+        .response
+}
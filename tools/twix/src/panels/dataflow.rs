@@ -0,0 +1,540 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{
+        mpsc::{channel, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use color_eyre::{
+    eyre::{eyre, ContextCompat},
+    Result,
+};
+use eframe::{
+    egui::{
+        vec2, Align2, ComboBox, CornerRadius, FontId, Pos2, Rect, Response, ScrollArea, Sense,
+        Shape, Ui, Vec2, Widget,
+    },
+    epaint::{Color32, CubicBezierShape, Stroke, StrokeKind},
+};
+use serde_json::{json, Value};
+
+use hulk_manifest::collect_hulk_cyclers;
+use source_analyzer::{
+    contexts::Field,
+    cyclers::{generate_dependency_graph, Cycler, Cyclers},
+    error::Error,
+};
+
+use crate::{
+    nao::Nao,
+    panel::Panel,
+    panels::{PlotPanel, TextPanel},
+    value_buffer::BufferHandle,
+};
+
+const NODE_SIZE: [f32; 2] = [190.0, 24.0];
+const COLUMN_SPACING: f32 = 90.0;
+const ROW_SPACING: f32 = 16.0;
+const EDGE_SAMPLES: usize = 16;
+const EDGE_HOVER_DISTANCE: f32 = 5.0;
+
+struct GraphNode {
+    name: String,
+    /// Name of the cycler instance for nodes standing in for inputs of other cyclers
+    external_instance: Option<String>,
+    layer: usize,
+    row: usize,
+}
+
+struct Edge {
+    source: usize,
+    target: usize,
+    /// Instance providing the value, `None` if it is provided by the own cycler
+    cycler_instance: Option<String>,
+    segments: String,
+}
+
+impl Edge {
+    fn path(&self, own_instance: &str) -> String {
+        let instance = self.cycler_instance.as_deref().unwrap_or(own_instance);
+        format!("{instance}.main_outputs.{}", self.segments)
+    }
+}
+
+/// Nodes and main output connections of a single cycler, layered by their dependencies
+struct CyclerGraph {
+    name: String,
+    instances: Vec<String>,
+    nodes: Vec<GraphNode>,
+    edges: Vec<Edge>,
+}
+
+impl CyclerGraph {
+    fn new(cycler: &Cycler) -> Result<Self> {
+        let cycler_nodes: Vec<_> = cycler.iter_nodes().cloned().collect();
+        let output_to_node: BTreeMap<_, _> = cycler_nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, node)| {
+                node.contexts
+                    .main_outputs
+                    .iter()
+                    .filter_map(move |field| match field {
+                        Field::MainOutput { name, .. } => Some((name.to_string(), index)),
+                        _ => None,
+                    })
+            })
+            .collect();
+        let order = generate_dependency_graph(&cycler_nodes, &output_to_node, &BTreeSet::new())?
+            .toposort_or_scc()
+            .map_err(|cycles| {
+                Error::CircularDependency(
+                    cycles
+                        .into_iter()
+                        .map(|cycle| {
+                            cycle
+                                .into_iter()
+                                .map(|index| cycler_nodes[index].name.clone())
+                                .collect()
+                        })
+                        .collect(),
+                )
+            })?;
+
+        let mut nodes: Vec<_> = cycler_nodes
+            .iter()
+            .map(|node| GraphNode {
+                name: node.name.clone(),
+                external_instance: None,
+                layer: 0,
+                row: 0,
+            })
+            .collect();
+        let mut edges = Vec::new();
+        let mut external_nodes = BTreeMap::new();
+        for (target, node) in cycler_nodes.iter().enumerate() {
+            for field in &node.contexts.cycle_context {
+                let (cycler_instance, path) = match field {
+                    Field::HistoricInput { path, .. } => (None, path),
+                    Field::Input {
+                        cycler_instance,
+                        path,
+                        ..
+                    }
+                    | Field::RequiredInput {
+                        cycler_instance,
+                        path,
+                        ..
+                    } => (cycler_instance.clone(), path),
+                    Field::PerceptionInput {
+                        cycler_instance,
+                        path,
+                        ..
+                    } => (Some(cycler_instance.clone()), path),
+                    _ => continue,
+                };
+                let segments = path.to_segments().join(".");
+                let source = match &cycler_instance {
+                    Some(instance) => {
+                        *external_nodes.entry(instance.clone()).or_insert_with(|| {
+                            nodes.push(GraphNode {
+                                name: instance.clone(),
+                                external_instance: Some(instance.clone()),
+                                layer: 0,
+                                row: 0,
+                            });
+                            nodes.len() - 1
+                        })
+                    }
+                    None => {
+                        let Some(output) = path.segments.first() else {
+                            continue;
+                        };
+                        // the dependency graph already ensured that every own input is produced
+                        output_to_node[&output.name]
+                    }
+                };
+                edges.push(Edge {
+                    source,
+                    target,
+                    cycler_instance,
+                    segments,
+                });
+            }
+        }
+
+        let mut graph = Self {
+            name: cycler.name.clone(),
+            instances: cycler.instances.clone(),
+            nodes,
+            edges,
+        };
+        graph.assign_layers(&order);
+        Ok(graph)
+    }
+
+    /// Places every node one layer right of its latest producer, visiting the nodes in the
+    /// topological order of the dependency graph
+    fn assign_layers(&mut self, order: &[usize]) {
+        let has_external_nodes = self
+            .nodes
+            .iter()
+            .any(|node| node.external_instance.is_some());
+        let first_layer = usize::from(has_external_nodes);
+        for &target in order {
+            self.nodes[target].layer = self
+                .edges
+                .iter()
+                .filter(|edge| edge.target == target)
+                .map(|edge| self.nodes[edge.source].layer + 1)
+                .max()
+                .unwrap_or(first_layer)
+                .max(first_layer);
+        }
+        let mut rows = HashMap::<usize, usize>::new();
+        for node in &mut self.nodes {
+            let row = rows.entry(node.layer).or_default();
+            node.row = *row;
+            *row += 1;
+        }
+    }
+
+    fn node_rect(&self, index: usize, origin: Pos2) -> Rect {
+        let node = &self.nodes[index];
+        Rect::from_min_size(
+            origin
+                + vec2(
+                    node.layer as f32 * (NODE_SIZE[0] + COLUMN_SPACING),
+                    node.row as f32 * (NODE_SIZE[1] + ROW_SPACING),
+                ),
+            NODE_SIZE.into(),
+        )
+    }
+
+    fn size(&self) -> Vec2 {
+        let columns = self.nodes.iter().map(|node| node.layer + 1).max();
+        let rows = self.nodes.iter().map(|node| node.row + 1).max();
+        vec2(
+            columns.unwrap_or_default() as f32 * (NODE_SIZE[0] + COLUMN_SPACING),
+            rows.unwrap_or_default() as f32 * (NODE_SIZE[1] + ROW_SPACING),
+        )
+    }
+}
+
+fn load_graphs(nao: &Nao) -> Receiver<Result<Vec<CyclerGraph>>> {
+    let (sender, receiver) = channel();
+    let crates_root = nao
+        .repository()
+        .map(|repository| repository.root.join("crates"));
+    thread::spawn(move || {
+        let graphs = crates_root
+            .wrap_err("repository not available, cannot analyze sources")
+            .and_then(|crates_root| {
+                // parse errors only know their location on the thread which created them
+                collect_hulk_cyclers(crates_root).map_err(|error| eyre!("{error}"))
+            })
+            .and_then(|cyclers: Cyclers| {
+                cyclers
+                    .cyclers
+                    .iter()
+                    .map(|cycler| {
+                        CyclerGraph::new(cycler).map_err(|error| {
+                            eyre!("failed to analyze cycler {}: {error}", cycler.name)
+                        })
+                    })
+                    .collect()
+            });
+        let _ = sender.send(graphs);
+    });
+    receiver
+}
+
+enum Graphs {
+    Loading(Receiver<Result<Vec<CyclerGraph>>>),
+    Loaded(Result<Vec<CyclerGraph>>),
+}
+
+pub struct DataflowPanel {
+    nao: Arc<Nao>,
+    graphs: Graphs,
+    cycler: String,
+    instance: Option<String>,
+    selected_node: Option<String>,
+    values: HashMap<String, BufferHandle<Value>>,
+}
+
+impl Panel for DataflowPanel {
+    const NAME: &'static str = "Dataflow";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let cycler = value
+            .and_then(|value| value.get("cycler"))
+            .and_then(|value| value.as_str())
+            .unwrap_or("Control")
+            .to_string();
+        let instance = value
+            .and_then(|value| value.get("instance"))
+            .and_then(|value| value.as_str())
+            .map(ToString::to_string);
+        let selected_node = value
+            .and_then(|value| value.get("selected_node"))
+            .and_then(|value| value.as_str())
+            .map(ToString::to_string);
+        Self {
+            graphs: Graphs::Loading(load_graphs(&nao)),
+            nao,
+            cycler,
+            instance,
+            selected_node,
+            values: HashMap::new(),
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "cycler": self.cycler,
+            "instance": self.instance,
+            "selected_node": self.selected_node,
+        })
+    }
+}
+
+impl Widget for &mut DataflowPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if let Graphs::Loading(receiver) = &self.graphs {
+            match receiver.try_recv() {
+                Ok(graphs) => self.graphs = Graphs::Loaded(graphs),
+                Err(TryRecvError::Empty) => {
+                    return ui
+                        .horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("analyzing sources...");
+                        })
+                        .response
+                }
+                Err(TryRecvError::Disconnected) => {
+                    return ui.label("source analysis terminated unexpectedly")
+                }
+            }
+        }
+        let graphs = match &self.graphs {
+            Graphs::Loaded(Ok(graphs)) => graphs,
+            Graphs::Loaded(Err(error)) => return ui.label(format!("{error:#}")),
+            Graphs::Loading(_) => unreachable!("graphs have been received above"),
+        };
+        let Some(graph) = graphs
+            .iter()
+            .find(|graph| graph.name == self.cycler)
+            .or(graphs.first())
+        else {
+            return ui.label("no cyclers found");
+        };
+
+        let mut selection_changed = false;
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt("cycler_selector")
+                .selected_text(graph.name.as_str())
+                .show_ui(ui, |ui| {
+                    for graph in graphs {
+                        selection_changed |= ui
+                            .selectable_value(&mut self.cycler, graph.name.clone(), &graph.name)
+                            .changed();
+                    }
+                });
+            if graph.instances.len() > 1 {
+                let mut instance = self
+                    .instance
+                    .clone()
+                    .unwrap_or_else(|| graph.instances[0].clone());
+                ComboBox::from_id_salt("instance_selector")
+                    .selected_text(instance.as_str())
+                    .show_ui(ui, |ui| {
+                        for candidate in &graph.instances {
+                            selection_changed |= ui
+                                .selectable_value(&mut instance, candidate.clone(), candidate)
+                                .changed();
+                        }
+                    });
+                self.instance = Some(instance);
+            }
+            ui.label(
+                "Select a node to show live values, click an edge to open it, right-click to plot",
+            );
+        });
+        if selection_changed {
+            self.selected_node = None;
+            self.values.clear();
+        }
+        let instance = self
+            .instance
+            .clone()
+            .filter(|instance| graph.instances.contains(instance))
+            .or_else(|| graph.instances.first().cloned())
+            .unwrap_or_else(|| graph.name.clone());
+
+        let selected_node = self
+            .selected_node
+            .as_ref()
+            .and_then(|name| graph.nodes.iter().position(|node| node.name == *name));
+        if let Some(selected_node) = selected_node {
+            for edge in &graph.edges {
+                if edge.source == selected_node || edge.target == selected_node {
+                    let path = edge.path(&instance);
+                    self.values
+                        .entry(path.clone())
+                        .or_insert_with(|| self.nao.subscribe_json(path));
+                }
+            }
+        }
+
+        ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let (rect, response) =
+                    ui.allocate_exact_size(graph.size() + vec2(16.0, 16.0), Sense::click());
+                let origin = rect.min + vec2(8.0, 8.0);
+                let painter = ui.painter_at(rect);
+                let pointer = response.hover_pos();
+
+                let mut hovered_edge = None;
+                for (index, edge) in graph.edges.iter().enumerate() {
+                    let start = graph.node_rect(edge.source, origin).right_center();
+                    let end = graph.node_rect(edge.target, origin).left_center();
+                    let bend = ((end.x - start.x).abs() / 2.0).max(COLUMN_SPACING / 2.0);
+                    let curve = CubicBezierShape::from_points_stroke(
+                        [start, start + vec2(bend, 0.0), end - vec2(bend, 0.0), end],
+                        false,
+                        Color32::TRANSPARENT,
+                        Stroke::NONE,
+                    );
+                    let points: Vec<_> = (0..=EDGE_SAMPLES)
+                        .map(|sample| curve.sample(sample as f32 / EDGE_SAMPLES as f32))
+                        .collect();
+                    if pointer.is_some_and(|pointer| {
+                        distance_to_polyline(pointer, &points) < EDGE_HOVER_DISTANCE
+                    }) {
+                        hovered_edge = Some((index, points.clone()));
+                    }
+
+                    let is_selected = selected_node
+                        .is_some_and(|node| edge.source == node || edge.target == node);
+                    let is_live = is_selected
+                        && self
+                            .values
+                            .get(&edge.path(&instance))
+                            .is_some_and(|buffer| buffer.get_last().ok().flatten().is_some());
+                    let color = match (is_selected, is_live) {
+                        (true, true) => Color32::GREEN,
+                        (true, false) => ui.visuals().strong_text_color(),
+                        (false, _) if selected_node.is_some() => {
+                            ui.visuals().weak_text_color().gamma_multiply(0.3)
+                        }
+                        (false, _) => ui.visuals().weak_text_color(),
+                    };
+                    let width = if is_selected { 2.0 } else { 1.0 };
+                    painter.add(Shape::line(points, Stroke::new(width, color)));
+                }
+                if let Some((_, points)) = &hovered_edge {
+                    painter.add(Shape::line(
+                        points.clone(),
+                        Stroke::new(3.0, Color32::YELLOW),
+                    ));
+                }
+
+                let mut hovered_node = None;
+                for (index, node) in graph.nodes.iter().enumerate() {
+                    let node_rect = graph.node_rect(index, origin);
+                    if pointer.is_some_and(|pointer| node_rect.contains(pointer)) {
+                        hovered_node = Some(index);
+                    }
+                    let fill = if selected_node == Some(index) {
+                        ui.visuals().selection.bg_fill
+                    } else if node.external_instance.is_some() {
+                        ui.visuals().faint_bg_color
+                    } else {
+                        ui.visuals().extreme_bg_color
+                    };
+                    painter.rect(
+                        node_rect,
+                        CornerRadius::same(4),
+                        fill,
+                        ui.visuals().widgets.noninteractive.bg_stroke,
+                        StrokeKind::Inside,
+                    );
+                    painter.text(
+                        node_rect.center(),
+                        Align2::CENTER_CENTER,
+                        &node.name,
+                        FontId::default(),
+                        ui.visuals().text_color(),
+                    );
+                }
+
+                if let Some(index) = hovered_node {
+                    if response.clicked() {
+                        self.selected_node = Some(graph.nodes[index].name.clone())
+                            .filter(|_| graph.nodes[index].external_instance.is_none());
+                        self.values.clear();
+                    }
+                } else if let Some((index, _)) = hovered_edge {
+                    let edge = &graph.edges[index];
+                    let path = edge.path(&instance);
+                    if response.clicked() {
+                        open_tab(
+                            &self.nao,
+                            json!({
+                                "_panel_type": TextPanel::NAME,
+                                "path": path,
+                            }),
+                        );
+                    } else if response.secondary_clicked() {
+                        open_tab(&self.nao, PlotPanel::value_for_path(&path));
+                    }
+                    let value = self
+                        .values
+                        .get(&path)
+                        .and_then(|buffer| buffer.get_last_value().ok().flatten())
+                        .map(|value| format!("\n{}", value_preview(&value)))
+                        .unwrap_or_default();
+                    let description = format!(
+                        "{path}\n{} → {}{value}",
+                        graph.nodes[edge.source].name, graph.nodes[edge.target].name
+                    );
+                    response.on_hover_text_at_pointer(description);
+                } else if response.clicked() {
+                    self.selected_node = None;
+                    self.values.clear();
+                }
+            });
+        ui.response()
+    }
+}
+
+fn open_tab(nao: &Arc<Nao>, value: Value) {
+    if let Some(session) = nao.session() {
+        session.open_tab(nao, value);
+    }
+}
+
+fn distance_to_polyline(point: Pos2, polyline: &[Pos2]) -> f32 {
+    polyline
+        .windows(2)
+        .map(|segment| {
+            let direction = segment[1] - segment[0];
+            let length_squared = direction.length_sq().max(f32::EPSILON);
+            let t = ((point - segment[0]).dot(direction) / length_squared).clamp(0.0, 1.0);
+            point.distance(segment[0] + direction * t)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn value_preview(value: &Value) -> String {
+    const MAXIMUM_LENGTH: usize = 200;
+    let text = value.to_string();
+    match text.char_indices().nth(MAXIMUM_LENGTH) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text,
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, write},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local};
+use color_eyre::{
+    eyre::{ContextCompat, WrapErr},
+    Result,
+};
+use coordinate_systems::Pixel;
+use eframe::egui::{DragValue, Ui};
+use image::RgbImage;
+use linear_algebra::{vector, Point2};
+use log::{info, warn};
+use serde::Serialize;
+use types::{ball_detection::BallPercept, pose_detection::HumanPose};
+
+use crate::{nao::Nao, value_buffer::BufferHandle};
+
+use super::cycler_selector::VisionCycler;
+
+const IMAGE_WIDTH: f32 = 640.0;
+const IMAGE_HEIGHT: f32 = 480.0;
+/// Detections are buffered long enough to still be available once their image is displayed
+const DETECTION_HISTORY: Duration = Duration::from_secs(2);
+/// The object detection receives the image in a cycle of its own, which starts within this
+/// tolerance of the vision cycle of the same image, less than half the time between two images
+const POSE_DETECTION_TOLERANCE: Duration = Duration::from_millis(15);
+
+/// The subset of annotato's classes for which twix receives detections
+#[derive(Clone, Copy, Debug, Serialize)]
+enum Class {
+    Ball,
+    Robot,
+}
+
+/// Serializes like `annotato::annotation::AnnotationFormat`, i.e. the min and max corner in pixels
+#[derive(Clone, Debug, Serialize)]
+struct Annotation {
+    points: [[f32; 2]; 2],
+    class: Class,
+}
+
+impl Annotation {
+    fn new(min: Point2<Pixel>, max: Point2<Pixel>, class: Class) -> Self {
+        Self {
+            points: [
+                [
+                    min.x().clamp(0.0, IMAGE_WIDTH),
+                    min.y().clamp(0.0, IMAGE_HEIGHT),
+                ],
+                [
+                    max.x().clamp(0.0, IMAGE_WIDTH),
+                    max.y().clamp(0.0, IMAGE_HEIGHT),
+                ],
+            ],
+            class,
+        }
+    }
+}
+
+/// Saves camera images together with their detections as an annotato dataset
+///
+/// The detections are stored as model annotations in `data.json`, annotato suggests them when
+/// labelling an image so that they only have to be corrected.
+pub struct AnnotationCapture {
+    nao: Arc<Nao>,
+    balls: BufferHandle<Option<Vec<BallPercept>>>,
+    human_poses: BufferHandle<Vec<HumanPose>>,
+    directory: Option<PathBuf>,
+    interval: Duration,
+    last_capture: Option<SystemTime>,
+    model_annotations: BTreeMap<String, Vec<Annotation>>,
+}
+
+impl AnnotationCapture {
+    pub fn new(nao: Arc<Nao>, cycler: VisionCycler) -> Self {
+        let (balls, human_poses) = subscribe(&nao, cycler);
+        Self {
+            nao,
+            balls,
+            human_poses,
+            directory: None,
+            interval: Duration::from_secs(1),
+            last_capture: None,
+            model_annotations: BTreeMap::new(),
+        }
+    }
+
+    pub fn update_cycler(&mut self, cycler: VisionCycler) {
+        (self.balls, self.human_poses) = subscribe(&self.nao, cycler);
+        self.directory = None;
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, cycler: VisionCycler) {
+        let mut capturing = self.directory.is_some();
+        let checkbox = ui
+            .checkbox(&mut capturing, "Capture")
+            .on_hover_text("Save images with their detections as annotato labels");
+        if checkbox.changed() {
+            self.directory = None;
+            if capturing {
+                match create_capture_directory(cycler) {
+                    Ok(directory) => {
+                        info!("capturing annotations to '{}'", directory.display());
+                        self.directory = Some(directory);
+                        self.last_capture = None;
+                        self.model_annotations.clear();
+                    }
+                    Err(error) => warn!("failed to start capturing annotations: {error:#}"),
+                }
+            }
+        }
+        if let Some(directory) = &self.directory {
+            let mut interval = self.interval.as_secs_f32();
+            if ui
+                .add(
+                    DragValue::new(&mut interval)
+                        .range(0.0..=60.0)
+                        .speed(0.1)
+                        .suffix("s"),
+                )
+                .on_hover_text("Minimum time between two captures")
+                .changed()
+            {
+                self.interval = Duration::from_secs_f32(interval);
+            }
+            ui.label(format!("{} captured", self.model_annotations.len()))
+                .on_hover_text(directory.display().to_string());
+        }
+    }
+
+    /// Saves the image if capturing, the interval since the last capture has passed and the
+    /// detections of this image have been received
+    pub fn capture(
+        &mut self,
+        cycler: VisionCycler,
+        timestamp: SystemTime,
+        image: impl FnOnce() -> Result<RgbImage>,
+    ) {
+        let Some(directory) = self.directory.clone() else {
+            return;
+        };
+        let is_due = self.last_capture.is_none_or(|last_capture| {
+            timestamp
+                .duration_since(last_capture)
+                .is_ok_and(|elapsed| elapsed >= self.interval)
+        });
+        if !is_due {
+            return;
+        }
+        let annotations = match self.annotations(timestamp) {
+            Ok(Some(annotations)) => Ok(annotations),
+            Ok(None) => return,
+            Err(error) => Err(error),
+        };
+        self.last_capture = Some(timestamp);
+
+        let file_name = format!(
+            "{cycler:?}_{}.png",
+            DateTime::<Local>::from(timestamp).format("%H-%M-%S-%3f")
+        );
+        let image_path = directory.join("images").join(&file_name);
+        let result = annotations.and_then(|annotations| {
+            image()?
+                .save(&image_path)
+                .wrap_err_with(|| format!("failed to save {}", image_path.display()))?;
+            self.model_annotations.insert(file_name, annotations);
+            let data_path = directory.join("data.json");
+            write(
+                &data_path,
+                serde_json::to_string_pretty(&self.model_annotations)?,
+            )
+            .wrap_err_with(|| format!("failed to write {}", data_path.display()))
+        });
+        if let Err(error) = result {
+            warn!("failed to capture annotations: {error:#}");
+        }
+    }
+
+    /// Annotations from the detections of the image taken at the timestamp, `None` while they
+    /// have not been received yet
+    fn annotations(&self, timestamp: SystemTime) -> Result<Option<Vec<Annotation>>> {
+        // outputs of the vision cycle which received the image share its timestamp
+        let Some(balls) = self
+            .balls
+            .get()?
+            .into_iter()
+            .find(|datum| datum.timestamp == timestamp)
+        else {
+            return Ok(None);
+        };
+        let balls = balls.value.unwrap_or_default();
+
+        let human_poses = self.human_poses.get()?;
+        // without any pose detections the object detection is not running and robots are left
+        // for labelling by hand
+        let human_poses = if human_poses.is_empty() {
+            Vec::new()
+        } else {
+            let Some(human_poses) = human_poses.into_iter().find(|datum| {
+                datum
+                    .timestamp
+                    .duration_since(timestamp)
+                    .unwrap_or_else(|error| error.duration())
+                    <= POSE_DETECTION_TOLERANCE
+            }) else {
+                return Ok(None);
+            };
+            human_poses.value
+        };
+
+        let ball_annotations = balls.iter().map(|ball| {
+            let circle = ball.image_location;
+            let radius = vector![circle.radius, circle.radius];
+            Annotation::new(circle.center - radius, circle.center + radius, Class::Ball)
+        });
+        let robot_annotations = human_poses.iter().map(|pose| {
+            let area = pose.bounding_box.area;
+            Annotation::new(area.min, area.max, Class::Robot)
+        });
+        Ok(Some(ball_annotations.chain(robot_annotations).collect()))
+    }
+}
+
+fn subscribe(
+    nao: &Nao,
+    cycler: VisionCycler,
+) -> (
+    BufferHandle<Option<Vec<BallPercept>>>,
+    BufferHandle<Vec<HumanPose>>,
+) {
+    let cycler_path = cycler.as_path();
+    let object_detection_cycler = match cycler {
+        VisionCycler::Top => "ObjectDetectionTop",
+        VisionCycler::Bottom => "ObjectDetectionBottom",
+    };
+    (
+        nao.subscribe_buffered_value(
+            format!("{cycler_path}.main_outputs.balls"),
+            DETECTION_HISTORY,
+        ),
+        nao.subscribe_buffered_value(
+            format!("{object_detection_cycler}.main_outputs.accepted_human_poses"),
+            DETECTION_HISTORY,
+        ),
+    )
+}
+
+fn create_capture_directory(cycler: VisionCycler) -> Result<PathBuf> {
+    let directory = dirs::data_local_dir()
+        .wrap_err("failed to find a directory for annotations")?
+        .join("hulks")
+        .join("annotations")
+        .join(format!(
+            "{}_{cycler:?}",
+            Local::now().format("%Y-%m-%d_%H-%M-%S")
+        ));
+    create_dir_all(directory.join("images"))
+        .wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+    Ok(directory)
+}
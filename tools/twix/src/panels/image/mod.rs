@@ -1,11 +1,11 @@
-use std::{env::temp_dir, fs::create_dir_all, path::PathBuf, sync::Arc};
+use std::{env::temp_dir, fs::create_dir_all, path::PathBuf, sync::Arc, time::SystemTime};
 
 use chrono::{DateTime, Utc};
 use color_eyre::{eyre::eyre, Result};
 use coordinate_systems::Pixel;
 use eframe::egui::{ColorImage, Response, SizeHint, TextureOptions, Ui, UiBuilder, Widget};
 use geometry::rectangle::Rectangle;
-use image::RgbImage;
+use image::{load_from_memory_with_format, ImageFormat, RgbImage};
 use linear_algebra::{point, vector};
 use log::{info, warn};
 use serde_json::{json, Value};
@@ -21,10 +21,12 @@ use crate::{
 };
 
 use self::{
+    annotations::AnnotationCapture,
     cycler_selector::{VisionCycler, VisionCyclerSelector},
     overlay::Overlays,
 };
 
+mod annotations;
 pub mod cycler_selector;
 pub mod overlay;
 mod overlays;
@@ -34,11 +36,38 @@ enum RawOrJpeg {
     Jpeg(BufferHandle<JpegImage>),
 }
 
+impl RawOrJpeg {
+    fn get_last_timestamp(&self) -> Result<Option<SystemTime>> {
+        match self {
+            RawOrJpeg::Raw(buffer) => buffer.get_last_timestamp(),
+            RawOrJpeg::Jpeg(buffer) => buffer.get_last_timestamp(),
+        }
+    }
+
+    fn get_last_rgb_image(&self) -> Result<RgbImage> {
+        match self {
+            RawOrJpeg::Raw(buffer) => {
+                let ycbcr = buffer
+                    .get_last_value()?
+                    .ok_or_else(|| eyre!("no image available"))?;
+                Ok(RgbImage::from(ycbcr))
+            }
+            RawOrJpeg::Jpeg(buffer) => {
+                let jpeg = buffer
+                    .get_last_value()?
+                    .ok_or_else(|| eyre!("no image available"))?;
+                Ok(load_from_memory_with_format(&jpeg.data, ImageFormat::Jpeg)?.into_rgb8())
+            }
+        }
+    }
+}
+
 pub struct ImagePanel {
     nao: Arc<Nao>,
     image_buffer: RawOrJpeg,
     cycler: VisionCycler,
     overlays: Overlays,
+    annotation_capture: AnnotationCapture,
     zoom_and_pan: ZoomAndPanTransform,
 }
 
@@ -72,11 +101,13 @@ impl Panel for ImagePanel {
             value.and_then(|value| value.get("overlays")),
             cycler,
         );
+        let annotation_capture = AnnotationCapture::new(nao.clone(), cycler);
         Self {
             nao,
             image_buffer,
             cycler,
             overlays,
+            annotation_capture,
             zoom_and_pan: ZoomAndPanTransform::default(),
         }
     }
@@ -118,16 +149,13 @@ impl Widget for &mut ImagePanel {
             if cycler_selector.ui(ui).changed() {
                 self.resubscribe(jpeg);
                 self.overlays.update_cycler(self.cycler);
+                self.annotation_capture.update_cycler(self.cycler);
             }
             self.overlays.combo_box(ui, self.cycler);
             if ui.checkbox(&mut jpeg, "JPEG").changed() {
                 self.resubscribe(jpeg);
             }
-            let maybe_timestamp = match &self.image_buffer {
-                RawOrJpeg::Raw(buffer) => buffer.get_last_timestamp(),
-                RawOrJpeg::Jpeg(buffer) => buffer.get_last_timestamp(),
-            };
-            if let Ok(Some(timestamp)) = maybe_timestamp {
+            if let Ok(Some(timestamp)) = self.image_buffer.get_last_timestamp() {
                 let date: DateTime<Utc> = timestamp.into();
                 ui.label(date.format("%T%.3f").to_string());
             }
@@ -150,7 +178,13 @@ impl Widget for &mut ImagePanel {
                     }
                 }
             }
+            self.annotation_capture.ui(ui, self.cycler);
         });
+        if let Ok(Some(timestamp)) = self.image_buffer.get_last_timestamp() {
+            let image_buffer = &self.image_buffer;
+            self.annotation_capture
+                .capture(self.cycler, timestamp, || image_buffer.get_last_rgb_image());
+        }
         let (response, mut painter) = TwixPainter::allocate(
             ui,
             vector![640.0, 480.0],
@@ -0,0 +1,464 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::Result;
+use communication::messages::TextOrBinary;
+use eframe::egui::{Color32, ComboBox, DragValue, Response, ScrollArea, Slider, Ui, Widget};
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoint, PlotUi, Points, VLine};
+use parameters::directory::Scope;
+use serde_json::{to_value, Value};
+use types::{motion_command::KickVariant, support_foot::Side};
+use walking_engine::{
+    kick_preview::{preview_kick, KickPreviewSample},
+    kick_steps::{JointOverride, JointOverrides, KickStep, KickSteps},
+    parameters::Parameters,
+};
+
+use crate::{log_error::LogError, nao::Nao, panel::Panel, value_buffer::BufferHandle};
+
+const VARIANTS: [KickVariant; 3] = [KickVariant::Forward, KickVariant::Turn, KickVariant::Side];
+const PREVIEW_CYCLE_DURATION: Duration = Duration::from_millis(12);
+/// Maximum distance of the pointer in pixels to grab a keyframe
+const GRAB_RADIUS: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug)]
+enum Overrides {
+    Support,
+    Swing,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Joint {
+    HipPitch,
+    KneePitch,
+    AnklePitch,
+}
+
+#[derive(Clone, Copy)]
+struct Keyframe {
+    overrides: Overrides,
+    joint: Joint,
+    index: usize,
+}
+
+pub struct KickStepsPanel {
+    nao: Arc<Nao>,
+    kick_steps: BufferHandle<KickSteps>,
+    walking_engine_parameters: BufferHandle<Parameters>,
+    /// Local modifications, not yet written to the robot
+    edited_kick_steps: Option<KickSteps>,
+    variant: KickVariant,
+    step_index: usize,
+    kicking_side: Side,
+    strength: f32,
+    dragged_keyframe: Option<Keyframe>,
+}
+
+impl Panel for KickStepsPanel {
+    const NAME: &'static str = "Kick Steps";
+
+    fn new(nao: Arc<Nao>, _value: Option<&Value>) -> Self {
+        let kick_steps = nao.subscribe_value("parameters.kick_steps");
+        let walking_engine_parameters = nao.subscribe_value("parameters.walking_engine");
+
+        Self {
+            nao,
+            kick_steps,
+            walking_engine_parameters,
+            edited_kick_steps: None,
+            variant: KickVariant::Forward,
+            step_index: 0,
+            kicking_side: Side::Left,
+            strength: 1.0,
+            dragged_keyframe: None,
+        }
+    }
+}
+
+impl KickStepsPanel {
+    fn write(&self, kick_steps: &KickSteps) {
+        self.nao.write(
+            "parameters.kick_steps",
+            TextOrBinary::Text(to_value(kick_steps).unwrap()),
+        );
+    }
+
+    fn save(&self, kick_steps: &KickSteps, scope: Scope) -> Result<()> {
+        self.nao
+            .store_parameters("kick_steps", to_value(kick_steps)?, scope)
+    }
+
+    fn selector(&mut self, ui: &mut Ui, number_of_steps: usize) {
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt("kick_variant_selector")
+                .selected_text(format!("{:?}", self.variant))
+                .show_ui(ui, |ui| {
+                    for variant in VARIANTS {
+                        ui.selectable_value(&mut self.variant, variant, format!("{variant:?}"));
+                    }
+                });
+            ComboBox::from_id_salt("kicking_side_selector")
+                .selected_text(format!("{:?}", self.kicking_side))
+                .show_ui(ui, |ui| {
+                    for side in [Side::Left, Side::Right] {
+                        ui.selectable_value(&mut self.kicking_side, side, format!("{side:?}"));
+                    }
+                });
+            ui.add(Slider::new(&mut self.strength, 0.0..=1.0).text("Strength"));
+            if number_of_steps > 0 {
+                ui.add(
+                    DragValue::new(&mut self.step_index)
+                        .range(0..=number_of_steps - 1)
+                        .prefix("Step: "),
+                );
+            }
+        });
+        self.step_index = self.step_index.min(number_of_steps.saturating_sub(1));
+    }
+}
+
+impl Widget for &mut KickStepsPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let (mut kick_steps, mut is_edited) = match self.edited_kick_steps.take() {
+            Some(kick_steps) => (kick_steps, true),
+            None => match self.kick_steps.get_last_value() {
+                Ok(Some(kick_steps)) => (kick_steps, false),
+                Ok(None) => return ui.label("no kick steps"),
+                Err(error) => {
+                    return ui.colored_label(Color32::RED, format!("Error (kick steps): {error}"));
+                }
+            },
+        };
+        let mut is_discarded = false;
+
+        let response = ui
+            .vertical(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Write to robot").clicked() {
+                        self.write(&kick_steps);
+                    }
+                    if ui.button("Save to current location").clicked() {
+                        self.save(&kick_steps, Scope::current_location()).log_err();
+                    }
+                    is_discarded = ui.button("Discard changes").clicked();
+                });
+                self.selector(ui, kick_steps.num_steps(self.variant));
+                ui.separator();
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    let steps = steps_mut(&mut kick_steps, self.variant);
+                    match steps.get_mut(self.step_index) {
+                        Some(step) => {
+                            is_edited |= step_parameters(ui, step);
+                            ui.label("Joint overrides (support dashed, swing solid)");
+                            is_edited |= self.joint_overrides_plot(ui, step);
+                        }
+                        None => {
+                            ui.label("no kick steps for this variant");
+                        }
+                    }
+
+                    ui.label("Sole trajectories previewed through the walking engine");
+                    match self.walking_engine_parameters.get_last_value() {
+                        Ok(Some(parameters)) => {
+                            let samples = preview_kick(
+                                &parameters,
+                                &kick_steps,
+                                self.variant,
+                                self.kicking_side,
+                                self.strength,
+                                PREVIEW_CYCLE_DURATION,
+                            );
+                            self.preview_plot(ui, &samples);
+                        }
+                        Ok(None) => {
+                            ui.label("no walking engine parameters");
+                        }
+                        Err(error) => {
+                            ui.colored_label(
+                                Color32::RED,
+                                format!("Error (walking engine parameters): {error}"),
+                            );
+                        }
+                    }
+                });
+            })
+            .response;
+
+        if is_edited && !is_discarded {
+            self.edited_kick_steps = Some(kick_steps);
+        }
+        response
+    }
+}
+
+impl KickStepsPanel {
+    fn joint_overrides_plot(&mut self, ui: &mut Ui, step: &mut KickStep) -> bool {
+        Plot::new(ui.id().with("kick_joint_overrides"))
+            .view_aspect(2.5)
+            .allow_drag(false)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                let is_dragged = self.drag_keyframe(plot_ui, step);
+
+                for (overrides, joint, keyframes) in keyframe_series(step) {
+                    let points: Vec<_> = keyframes
+                        .iter()
+                        .map(|keyframe| [keyframe.timepoint.as_secs_f64(), keyframe.value as f64])
+                        .collect();
+                    let name = format!("{overrides:?} {joint:?}");
+                    let style = match overrides {
+                        Overrides::Support => LineStyle::dashed_loose(),
+                        Overrides::Swing => LineStyle::Solid,
+                    };
+                    plot_ui.line(
+                        Line::new(points.clone())
+                            .name(&name)
+                            .style(style)
+                            .color(joint.color()),
+                    );
+                    plot_ui.points(
+                        Points::new(points)
+                            .name(&name)
+                            .radius(4.0)
+                            .color(joint.color()),
+                    );
+                }
+                plot_ui.vline(
+                    VLine::new(step.step_duration.as_secs_f64())
+                        .name("step duration")
+                        .color(Color32::GRAY),
+                );
+
+                is_dragged
+            })
+            .inner
+    }
+
+    /// Moves the keyframe closest to the pointer while dragging, keyframes cannot pass their
+    /// neighbors to keep the overrides sorted by time
+    fn drag_keyframe(&mut self, plot_ui: &mut PlotUi, step: &mut KickStep) -> bool {
+        let response = plot_ui.response().clone();
+        if response.drag_started() {
+            self.dragged_keyframe = response.interact_pointer_pos().and_then(|pointer| {
+                keyframe_series(step)
+                    .flat_map(|(overrides, joint, keyframes)| {
+                        keyframes.iter().enumerate().map(move |(index, keyframe)| {
+                            (
+                                Keyframe {
+                                    overrides,
+                                    joint,
+                                    index,
+                                },
+                                PlotPoint::new(keyframe.timepoint.as_secs_f64(), keyframe.value),
+                            )
+                        })
+                    })
+                    .map(|(keyframe, position)| {
+                        let distance = plot_ui.screen_from_plot(position).distance(pointer);
+                        (keyframe, distance)
+                    })
+                    .filter(|(_, distance)| *distance < GRAB_RADIUS)
+                    .min_by(|(_, left), (_, right)| left.total_cmp(right))
+                    .map(|(keyframe, _)| keyframe)
+            });
+        }
+        if response.drag_stopped() {
+            self.dragged_keyframe = None;
+        }
+
+        let (Some(dragged_keyframe), Some(pointer)) =
+            (self.dragged_keyframe, plot_ui.pointer_coordinate())
+        else {
+            return false;
+        };
+        let Some(keyframes) = joint_overrides_mut(step, dragged_keyframe.overrides)
+            .series_mut(dragged_keyframe.joint)
+            .as_mut()
+        else {
+            return false;
+        };
+        let index = dragged_keyframe.index;
+        if index >= keyframes.len() {
+            return false;
+        }
+        let earliest = index
+            .checked_sub(1)
+            .map_or(Duration::ZERO, |previous| keyframes[previous].timepoint);
+        let latest = keyframes
+            .get(index + 1)
+            .map_or(Duration::MAX, |next| next.timepoint);
+        let timepoint = Duration::try_from_secs_f64(pointer.x.max(0.0))
+            .unwrap_or(Duration::MAX)
+            .clamp(earliest, latest);
+        keyframes[index] = JointOverride {
+            value: pointer.y as f32,
+            timepoint,
+        };
+        true
+    }
+
+    fn preview_plot(&self, ui: &mut Ui, samples: &[KickPreviewSample]) {
+        let mut series: [(&str, Vec<[f64; 2]>); 4] = [
+            ("left sole x", Vec::new()),
+            ("left sole z", Vec::new()),
+            ("right sole x", Vec::new()),
+            ("right sole z", Vec::new()),
+        ];
+        for sample in samples {
+            let time = sample.time.as_secs_f64();
+            let (left_sole, right_sole) = match sample.support_side {
+                Side::Left => (sample.feet.support_sole, sample.feet.swing_sole),
+                Side::Right => (sample.feet.swing_sole, sample.feet.support_sole),
+            };
+            series[0].1.push([time, left_sole.position().x() as f64]);
+            series[1].1.push([time, left_sole.position().z() as f64]);
+            series[2].1.push([time, right_sole.position().x() as f64]);
+            series[3].1.push([time, right_sole.position().z() as f64]);
+        }
+        let selected_step_times: Vec<_> = samples
+            .iter()
+            .filter(|sample| sample.step_index == self.step_index)
+            .map(|sample| sample.time.as_secs_f64())
+            .collect();
+
+        Plot::new(ui.id().with("kick_preview"))
+            .view_aspect(2.5)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                for (name, points) in series {
+                    plot_ui.line(Line::new(points).name(name));
+                }
+                if let (Some(start), Some(end)) =
+                    (selected_step_times.first(), selected_step_times.last())
+                {
+                    for time in [start, end] {
+                        plot_ui.vline(VLine::new(*time).name("selected step").color(Color32::GRAY));
+                    }
+                }
+            });
+    }
+}
+
+impl Joint {
+    fn color(self) -> Color32 {
+        match self {
+            Joint::HipPitch => Color32::RED,
+            Joint::KneePitch => Color32::GREEN,
+            Joint::AnklePitch => Color32::BLUE,
+        }
+    }
+}
+
+trait JointOverridesExt {
+    fn series_mut(&mut self, joint: Joint) -> &mut Option<Vec<JointOverride>>;
+}
+
+impl JointOverridesExt for JointOverrides {
+    fn series_mut(&mut self, joint: Joint) -> &mut Option<Vec<JointOverride>> {
+        match joint {
+            Joint::HipPitch => &mut self.hip_pitch,
+            Joint::KneePitch => &mut self.knee_pitch,
+            Joint::AnklePitch => &mut self.ankle_pitch,
+        }
+    }
+}
+
+fn joint_overrides_mut(step: &mut KickStep, overrides: Overrides) -> &mut JointOverrides {
+    match overrides {
+        Overrides::Support => &mut step.support_overrides,
+        Overrides::Swing => &mut step.swing_overrides,
+    }
+}
+
+fn keyframe_series(
+    step: &KickStep,
+) -> impl Iterator<Item = (Overrides, Joint, &Vec<JointOverride>)> {
+    [
+        (Overrides::Support, &step.support_overrides),
+        (Overrides::Swing, &step.swing_overrides),
+    ]
+    .into_iter()
+    .flat_map(|(overrides, joint_overrides)| {
+        [
+            (Joint::HipPitch, &joint_overrides.hip_pitch),
+            (Joint::KneePitch, &joint_overrides.knee_pitch),
+            (Joint::AnklePitch, &joint_overrides.ankle_pitch),
+        ]
+        .into_iter()
+        .filter_map(move |(joint, keyframes)| {
+            keyframes
+                .as_ref()
+                .map(|keyframes| (overrides, joint, keyframes))
+        })
+    })
+}
+
+fn steps_mut(kick_steps: &mut KickSteps, variant: KickVariant) -> &mut Vec<KickStep> {
+    match variant {
+        KickVariant::Forward => &mut kick_steps.forward,
+        KickVariant::Turn => &mut kick_steps.turn,
+        KickVariant::Side => &mut kick_steps.side,
+    }
+}
+
+fn step_parameters(ui: &mut Ui, step: &mut KickStep) -> bool {
+    let mut is_changed = false;
+    ui.horizontal(|ui| {
+        let mut step_duration = step.step_duration.as_secs_f32() * 1000.0;
+        if ui
+            .add(
+                DragValue::new(&mut step_duration)
+                    .range(0.0..=2000.0)
+                    .prefix("Duration: ")
+                    .suffix(" ms"),
+            )
+            .changed()
+        {
+            step.step_duration = Duration::from_secs_f32(step_duration / 1000.0);
+            is_changed = true;
+        }
+        is_changed |= ui
+            .add(
+                DragValue::new(&mut step.foot_lift_apex)
+                    .speed(0.001)
+                    .prefix("Lift apex: ")
+                    .suffix(" m"),
+            )
+            .changed();
+        is_changed |= ui
+            .add(
+                DragValue::new(&mut step.midpoint)
+                    .range(0.0..=1.0)
+                    .speed(0.01)
+                    .prefix("Midpoint: "),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        is_changed |= ui
+            .add(
+                DragValue::new(&mut step.base_step.forward)
+                    .speed(0.001)
+                    .prefix("Forward: ")
+                    .suffix(" m"),
+            )
+            .changed();
+        is_changed |= ui
+            .add(
+                DragValue::new(&mut step.base_step.left)
+                    .speed(0.001)
+                    .prefix("Left: ")
+                    .suffix(" m"),
+            )
+            .changed();
+        is_changed |= ui
+            .add(
+                DragValue::new(&mut step.base_step.turn)
+                    .speed(0.01)
+                    .prefix("Turn: ")
+                    .suffix(" rad"),
+            )
+            .changed();
+    });
+    is_changed
+}
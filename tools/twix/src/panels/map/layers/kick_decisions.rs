@@ -2,8 +2,14 @@ use std::sync::Arc;
 
 use color_eyre::Result;
 use coordinate_systems::Ground;
-use eframe::epaint::{Color32, Stroke};
-use types::{field_dimensions::FieldDimensions, kick_decision::KickDecision};
+use eframe::{
+    egui::{Align2, FontId},
+    epaint::{Color32, Stroke},
+};
+use types::{
+    field_dimensions::FieldDimensions,
+    kick_decision::{KickDecision, KickDecisionEvaluation},
+};
 
 use crate::{
     nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::BufferHandle,
@@ -12,6 +18,7 @@ use crate::{
 pub struct KickDecisions {
     kick_decisions: BufferHandle<Option<Vec<KickDecision>>>,
     instant_kick_decisions: BufferHandle<Option<Vec<KickDecision>>>,
+    kick_decision_evaluations: BufferHandle<Option<Vec<KickDecisionEvaluation>>>,
 }
 
 impl Layer<Ground> for KickDecisions {
@@ -21,9 +28,12 @@ impl Layer<Ground> for KickDecisions {
         let kick_decisions = nao.subscribe_value("Control.main_outputs.kick_decisions");
         let instant_kick_decisions =
             nao.subscribe_value("Control.main_outputs.instant_kick_decisions");
+        let kick_decision_evaluations =
+            nao.subscribe_value("Control.additional_outputs.kick_decision_evaluations");
         Self {
             kick_decisions,
             instant_kick_decisions,
+            kick_decision_evaluations,
         }
     }
 
@@ -34,6 +44,7 @@ impl Layer<Ground> for KickDecisions {
     ) -> Result<()> {
         self.draw_kick_decisions(painter)?;
         self.draw_instant_kick_decisions(painter)?;
+        self.draw_kick_decision_evaluations(painter)?;
 
         Ok(())
     }
@@ -91,6 +102,28 @@ impl KickDecisions {
         );
         Ok(())
     }
+
+    fn draw_kick_decision_evaluations(&self, painter: &TwixPainter<Ground>) -> Result<()> {
+        let Some(evaluations) = self.kick_decision_evaluations.get_last_value()?.flatten() else {
+            return Ok(());
+        };
+        for evaluation in evaluations {
+            for end_position in &evaluation.sampled_ball_end_positions {
+                painter.circle_filled(*end_position, 0.01, Color32::from_white_alpha(60));
+            }
+            painter.floating_text(
+                evaluation.decision.target,
+                Align2::LEFT_BOTTOM,
+                format!(
+                    "goal {:.2}, intercepted {:.2}",
+                    evaluation.goal_probability, evaluation.interception_probability
+                ),
+                FontId::default(),
+                Color32::WHITE,
+            );
+        }
+        Ok(())
+    }
 }
 
 fn draw_kick_decisions<'a>(
@@ -18,6 +18,7 @@ mod planned_steps;
 mod pose_detection;
 mod referee_position;
 mod robot_pose;
+mod tracked_robots;
 mod walking;
 
 pub use ball_filter::BallFilter;
@@ -40,4 +41,5 @@ pub use planned_steps::PlannedSteps;
 pub use pose_detection::PoseDetection;
 pub use referee_position::RefereePosition;
 pub use robot_pose::RobotPose;
+pub use tracked_robots::TrackedRobots;
 pub use walking::Walking;
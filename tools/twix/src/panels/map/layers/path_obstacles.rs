@@ -6,7 +6,7 @@ use eframe::epaint::{Color32, Stroke};
 use coordinate_systems::Ground;
 use types::{
     field_dimensions::FieldDimensions,
-    path_obstacles::{PathObstacle, PathObstacleShape},
+    path_obstacles::{rectangle_edges, PathObstacle, PathObstacleShape},
 };
 
 use crate::{
@@ -43,6 +43,11 @@ impl Layer<Ground> for PathObstacles {
                     PathObstacleShape::LineSegment(line_segment) => {
                         painter.line_segment(line_segment.0, line_segment.1, path_obstacle_stroke)
                     }
+                    PathObstacleShape::Rectangle(corners) => {
+                        for edge in rectangle_edges(&corners) {
+                            painter.line_segment(edge.0, edge.1, path_obstacle_stroke)
+                        }
+                    }
                 }
             }
         }
@@ -5,7 +5,7 @@ use eframe::{egui::Color32, epaint::Stroke};
 
 use coordinate_systems::{Ground, UpcomingSupport};
 use linear_algebra::{point, vector, Isometry2, Orientation2, Orientation3, Pose2, Pose3};
-use step_planning::{NUM_STEPS, NUM_VARIABLES};
+use step_planning::VARIABLES_PER_STEP;
 use types::{field_dimensions::FieldDimensions, step::Step, support_foot::Side};
 
 use crate::{
@@ -16,9 +16,9 @@ use super::walking::paint_sole_polygon;
 
 pub struct PlannedSteps {
     direct_step: BufferHandle<Option<Step>>,
-    step_plan: BufferHandle<Option<[Step; NUM_STEPS]>>,
-    step_plan_greedy: BufferHandle<Option<[Step; NUM_STEPS]>>,
-    step_plan_gradient: BufferHandle<Option<[f32; NUM_VARIABLES]>>,
+    step_plan: BufferHandle<Option<Vec<Step>>>,
+    step_plan_greedy: BufferHandle<Option<Vec<Step>>>,
+    step_plan_gradient: BufferHandle<Option<Vec<f32>>>,
     ground_to_upcoming_support: BufferHandle<Option<Isometry2<Ground, UpcomingSupport>>>,
     // foot_offset_left: BufferHandle<Option<Vector3<Ground>>>,
     // foot_offset_right: BufferHandle<Option<Vector3<Ground>>>,
@@ -113,20 +113,20 @@ impl Layer<Ground> for PlannedSteps {
                 painter,
                 Color32::RED,
                 ground_to_upcoming_support,
-                step_plan,
-                step_plan_gradient,
+                &step_plan,
+                &step_plan_gradient,
                 next_support_side,
             );
         }
 
         if let Some(step_plan_greedy) = step_plan_greedy {
-            let dummy_gradient = [0.0; NUM_VARIABLES];
+            let dummy_gradient = vec![0.0; step_plan_greedy.len() * VARIABLES_PER_STEP];
             paint_step_plan(
                 painter,
                 Color32::BLUE,
                 ground_to_upcoming_support,
-                step_plan_greedy,
-                dummy_gradient,
+                &step_plan_greedy,
+                &dummy_gradient,
                 next_support_side,
             );
         }
@@ -144,8 +144,8 @@ fn paint_step_plan(
     painter: &TwixPainter<Ground>,
     color: Color32,
     ground_to_upcoming_support: Isometry2<Ground, UpcomingSupport>,
-    step_plan: [Step; NUM_STEPS],
-    step_plan_gradient: [f32; NUM_VARIABLES],
+    step_plan: &[Step],
+    step_plan_gradient: &[f32],
     next_support_side: Side,
     // foot_offset_left: Vector3<Ground>,
     // foot_offset_right: Vector3<Ground>,
@@ -182,7 +182,7 @@ fn paint_step_plan(
         },
     );
 
-    let gradients = step_plan_gradient.chunks_exact(3);
+    let gradients = step_plan_gradient.chunks_exact(VARIABLES_PER_STEP);
     for (PlannedStep { pose, support_side }, gradient) in planned_steps.zip(gradients) {
         paint_planned_step(painter, color, pose, support_side);
 
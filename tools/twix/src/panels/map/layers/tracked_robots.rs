@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use eframe::{
+    egui::{Align2, FontId},
+    epaint::{Color32, Stroke},
+};
+
+use coordinate_systems::Ground;
+use types::{field_dimensions::FieldDimensions, tracked_robots::TrackedRobot};
+
+use crate::{
+    nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::BufferHandle,
+};
+
+const ROBOT_RADIUS: f32 = 0.15;
+
+pub struct TrackedRobots {
+    tracked_robots: BufferHandle<Vec<TrackedRobot>>,
+}
+
+impl Layer<Ground> for TrackedRobots {
+    const NAME: &'static str = "Tracked Robots";
+
+    fn new(nao: Arc<Nao>) -> Self {
+        let tracked_robots = nao.subscribe_value("Control.main_outputs.tracked_robots");
+        Self { tracked_robots }
+    }
+
+    fn paint(
+        &self,
+        painter: &TwixPainter<Ground>,
+        _field_dimensions: &FieldDimensions,
+    ) -> Result<()> {
+        let Some(tracked_robots) = self.tracked_robots.get_last_value()? else {
+            return Ok(());
+        };
+        for robot in tracked_robots {
+            let color = if robot.is_opponent() {
+                Color32::RED
+            } else {
+                Color32::BLUE
+            };
+            let position = robot.position();
+            painter.circle_stroke(position, ROBOT_RADIUS, Stroke::new(0.02, color));
+            painter.line_segment(
+                position,
+                position + robot.velocity(),
+                Stroke::new(0.02, color),
+            );
+            painter.floating_text(
+                position,
+                Align2::CENTER_CENTER,
+                robot.id.to_string(),
+                FontId::default(),
+                color,
+            );
+        }
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ use types::{
     robot_kinematics::RobotKinematics, step::Step, support_foot::Side,
 };
 use walking_engine::{
+    backend::nao::Nao as NaoBackend,
     feet::Feet,
     mode::{
         catching::Catching, kicking::Kicking, starting::Starting, stopping::Stopping, walking, Mode,
@@ -120,7 +121,7 @@ impl Layer<Ground> for Walking {
                 Mode::Starting(Starting { step })
                 | Mode::Walking(walking::Walking { step, .. })
                 | Mode::Kicking(Kicking { step, .. })
-                | Mode::Catching(Catching { step })
+                | Mode::Catching(Catching { step, .. })
                 | Mode::Stopping(Stopping { step, .. }),
             ..
         } = engine
@@ -249,7 +250,12 @@ fn paint_target_feet(
     stroke: Stroke,
 ) {
     let walk_to_robot = robot_to_walk.inverse();
-    let current_feet = Feet::from_joints(robot_to_walk, &last_actuated_joints, support_side);
+    let current_feet = Feet::from_joints(
+        &NaoBackend::default(),
+        robot_to_walk,
+        &last_actuated_joints,
+        support_side,
+    );
 
     struct SupportSole;
     let upcoming_walk_to_support_sole = end_support_sole.as_transform::<SupportSole>().inverse();
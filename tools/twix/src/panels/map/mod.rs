@@ -15,15 +15,20 @@ use crate::{
     zoom_and_pan::ZoomAndPanTransform,
 };
 
-use self::layer::{EnabledLayer, Layer};
+use self::{
+    layer::{EnabledLayer, Layer},
+    team::TeamOverlay,
+};
 
 mod layer;
 mod layers;
+mod team;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 enum PlotType {
     Field,
     Ground,
+    Team,
 }
 
 trait GenericLayer {
@@ -61,7 +66,9 @@ impl<T: Layer<Ground>> GenericLayer for EnabledLayer<T, Ground> {
 }
 
 pub struct MapPanel {
+    nao: Arc<Nao>,
     current_plot_type: PlotType,
+    team: TeamOverlay,
 
     field_dimensions: BufferHandle<FieldDimensions>,
     ground_to_field: BufferHandle<Option<Isometry2<Ground, Field>>>,
@@ -85,6 +92,7 @@ pub struct MapPanel {
     feet_detection: EnabledLayer<layers::FeetDetection, Ground>,
     ball_filter: EnabledLayer<layers::BallFilter, Ground>,
     obstacle_filter: EnabledLayer<layers::ObstacleFilter, Ground>,
+    tracked_robots: EnabledLayer<layers::TrackedRobots, Ground>,
     walking: EnabledLayer<layers::Walking, Ground>,
     localization: EnabledLayer<layers::Localization, Field>,
     planned_steps: EnabledLayer<layers::PlannedSteps, Ground>,
@@ -112,6 +120,7 @@ impl Panel for MapPanel {
         let feet_detection = EnabledLayer::new(nao.clone(), value, false);
         let ball_filter = EnabledLayer::new(nao.clone(), value, false);
         let obstacle_filter = EnabledLayer::new(nao.clone(), value, false);
+        let tracked_robots = EnabledLayer::new(nao.clone(), value, false);
         let walking = EnabledLayer::new(nao.clone(), value, false);
         let localization = EnabledLayer::new(nao.clone(), value, false);
         let planned_steps = EnabledLayer::new(nao.clone(), value, false);
@@ -129,7 +138,9 @@ impl Panel for MapPanel {
             .unwrap_or_default();
 
         Self {
+            nao,
             current_plot_type,
+            team: TeamOverlay::default(),
             field_dimensions,
             ground_to_field,
             zoom_and_pan,
@@ -151,6 +162,7 @@ impl Panel for MapPanel {
             feet_detection,
             ball_filter,
             obstacle_filter,
+            tracked_robots,
             walking,
             localization,
             planned_steps,
@@ -180,6 +192,7 @@ impl Panel for MapPanel {
             "feet_detection": self.feet_detection.save(),
             "ball_filter": self.ball_filter.save(),
             "obstacle_filter": self.obstacle_filter.save(),
+            "tracked_robots": self.tracked_robots.save(),
             "walking": self.walking.save(),
             "localization": self.localization.save(),
             "planned_steps": self.planned_steps.save(),
@@ -209,6 +222,7 @@ impl Widget for &mut MapPanel {
                 self.feet_detection.checkbox(ui);
                 self.ball_filter.checkbox(ui);
                 self.obstacle_filter.checkbox(ui);
+                self.tracked_robots.checkbox(ui);
                 self.walking.checkbox(ui);
                 self.localization.checkbox(ui);
                 self.planned_steps.checkbox(ui);
@@ -218,6 +232,7 @@ impl Widget for &mut MapPanel {
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.current_plot_type, PlotType::Ground, "Ground");
                     ui.selectable_value(&mut self.current_plot_type, PlotType::Field, "Field");
+                    ui.selectable_value(&mut self.current_plot_type, PlotType::Team, "Team");
                 });
         });
 
@@ -235,7 +250,7 @@ impl Widget for &mut MapPanel {
             .flatten()
             .unwrap_or_default();
         let (response, mut painter) = match self.current_plot_type {
-            PlotType::Field => {
+            PlotType::Field | PlotType::Team => {
                 let width = field_dimensions.width;
                 let length = field_dimensions.length;
                 let border = field_dimensions.border_strip_width;
@@ -303,6 +318,8 @@ impl Widget for &mut MapPanel {
             .generic_paint(&painter, ground_to_field, &field_dimensions);
         self.obstacle_filter
             .generic_paint(&painter, ground_to_field, &field_dimensions);
+        self.tracked_robots
+            .generic_paint(&painter, ground_to_field, &field_dimensions);
         self.walking
             .generic_paint(&painter, ground_to_field, &field_dimensions);
         self.localization
@@ -310,6 +327,14 @@ impl Widget for &mut MapPanel {
         self.planned_steps
             .generic_paint(&painter, ground_to_field, &field_dimensions);
 
+        if self.current_plot_type == PlotType::Team {
+            let robots = match self.nao.session() {
+                Some(session) => session.robots(),
+                None => Vec::new(),
+            };
+            self.team.paint(&painter, &field_dimensions, &robots);
+        }
+
         response
     }
 }
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use eframe::{
+    egui::{Align2, FontId},
+    epaint::{Color32, Stroke},
+};
+
+use coordinate_systems::{Field, Ground};
+use linear_algebra::Isometry2;
+use types::{ball_position::BallPosition, field_dimensions::FieldDimensions, obstacles::Obstacle};
+
+use crate::{nao::Nao, session::Robot, twix_painter::TwixPainter, value_buffer::BufferHandle};
+
+struct TeamMember {
+    nao: Arc<Nao>,
+    ground_to_field: BufferHandle<Option<Isometry2<Ground, Field>>>,
+    ball_position: BufferHandle<Option<BallPosition<Ground>>>,
+    obstacles: BufferHandle<Vec<Obstacle>>,
+}
+
+impl TeamMember {
+    fn new(nao: Arc<Nao>) -> Self {
+        Self {
+            ground_to_field: nao.subscribe_value("Control.main_outputs.ground_to_field"),
+            ball_position: nao.subscribe_value("Control.main_outputs.ball_position"),
+            obstacles: nao.subscribe_value("Control.main_outputs.obstacles"),
+            nao,
+        }
+    }
+}
+
+/// Overlays the pose, ball and obstacles of every robot in the session in field coordinates
+#[derive(Default)]
+pub struct TeamOverlay {
+    members: Vec<TeamMember>,
+}
+
+impl TeamOverlay {
+    pub fn paint(
+        &mut self,
+        painter: &TwixPainter<Field>,
+        field_dimensions: &FieldDimensions,
+        robots: &[Robot],
+    ) {
+        self.synchronize(robots);
+
+        for (robot, member) in robots.iter().zip(&self.members) {
+            let Some(ground_to_field) = member
+                .ground_to_field
+                .get_last_value()
+                .ok()
+                .flatten()
+                .flatten()
+            else {
+                continue;
+            };
+
+            if let Some(obstacles) = member.obstacles.get_last_value().ok().flatten() {
+                let stroke = Stroke {
+                    width: 0.025,
+                    color: robot.color,
+                };
+                for obstacle in obstacles {
+                    painter.circle_stroke(
+                        ground_to_field * obstacle.position,
+                        obstacle.radius_at_hip_height,
+                        stroke,
+                    );
+                }
+            }
+
+            if let Some(ball) = member
+                .ball_position
+                .get_last_value()
+                .ok()
+                .flatten()
+                .flatten()
+            {
+                painter.circle(
+                    ground_to_field * ball.position,
+                    field_dimensions.ball_radius,
+                    Color32::WHITE,
+                    Stroke {
+                        width: 0.02,
+                        color: robot.color,
+                    },
+                );
+            }
+
+            painter.pose(
+                ground_to_field.as_pose(),
+                0.15,
+                0.25,
+                robot.color,
+                Stroke {
+                    width: 0.02,
+                    color: Color32::BLACK,
+                },
+            );
+            painter.floating_text(
+                ground_to_field.as_pose().position(),
+                Align2::CENTER_BOTTOM,
+                robot.address.clone(),
+                FontId::default(),
+                robot.color,
+            );
+        }
+    }
+
+    /// Keeps subscriptions of known robots and subscribes to newly added ones
+    fn synchronize(&mut self, robots: &[Robot]) {
+        let is_synchronized = self.members.len() == robots.len()
+            && self
+                .members
+                .iter()
+                .zip(robots)
+                .all(|(member, robot)| Arc::ptr_eq(&member.nao, &robot.nao));
+        if is_synchronized {
+            return;
+        }
+        let mut members = std::mem::take(&mut self.members);
+        self.members = robots
+            .iter()
+            .map(|robot| {
+                match members
+                    .iter()
+                    .position(|member| Arc::ptr_eq(&member.nao, &robot.nao))
+                {
+                    Some(index) => members.swap_remove(index),
+                    None => TeamMember::new(robot.nao.clone()),
+                }
+            })
+            .collect();
+    }
+}
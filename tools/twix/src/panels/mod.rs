@@ -1,17 +1,23 @@
 mod automatic_camera_calibration_export;
 mod ball_candidates;
 mod behavior_simulator;
+mod behavior_tree;
 mod camera_calibration;
+mod cycle_timings;
+mod dataflow;
 mod enum_plot;
 mod image;
 mod image_color_select;
 mod image_segments;
+mod kick_steps;
 mod look_at;
 mod manual_camera_calibration;
 mod map;
+mod motion_editor;
 mod parameter;
 mod plot;
 mod remote;
+mod robot_view;
 mod text;
 mod vision_tuner;
 mod walk;
@@ -21,17 +27,23 @@ pub use automatic_camera_calibration_export::{
 };
 pub use ball_candidates::BallCandidatePanel;
 pub use behavior_simulator::BehaviorSimulatorPanel;
+pub use behavior_tree::BehaviorTreePanel;
 pub use camera_calibration::SemiAutomaticCameraCalibrationPanel;
+pub use cycle_timings::CycleTimingsPanel;
+pub use dataflow::DataflowPanel;
 pub use enum_plot::EnumPlotPanel;
 pub use image::ImagePanel;
 pub use image_color_select::ImageColorSelectPanel;
 pub use image_segments::ImageSegmentsPanel;
+pub use kick_steps::KickStepsPanel;
 pub use look_at::LookAtPanel;
 pub use manual_camera_calibration::ManualCalibrationPanel;
 pub use map::MapPanel;
+pub use motion_editor::MotionEditorPanel;
 pub use parameter::ParameterPanel;
 pub use plot::PlotPanel;
 pub use remote::RemotePanel;
+pub use robot_view::RobotViewPanel;
 pub use text::TextPanel;
 pub use vision_tuner::VisionTunerPanel;
 pub use walk::WalkPanel;
@@ -0,0 +1,515 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{
+    eyre::{ContextCompat, WrapErr},
+    Result,
+};
+use communication::{client::Status, messages::TextOrBinary};
+use eframe::egui::{Color32, ComboBox, DragValue, Response, ScrollArea, Slider, Ui, Widget};
+use egui_plot::{Legend, Line, Plot, VLine};
+use motionfile::{KeyFrame, MotionFile, SplineInterpolator, TimedSpline};
+use serde_json::{json, to_value, to_writer_pretty, Value};
+use types::{
+    joints::{Joints, JointsName},
+    motion_command::MotionCommand,
+};
+
+use crate::{log_error::LogError, nao::Nao, panel::Panel, value_buffer::BufferHandle};
+
+const DEFAULT_MOTION_FILE: &str = "etc/motions/stand_up_front.json";
+const DEFAULT_KEYFRAME_DURATION: Duration = Duration::from_secs(1);
+const SAMPLE_DURATION: Duration = Duration::from_millis(10);
+const PREVIEW_WRITE_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JointGroup {
+    Head,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
+}
+
+impl JointGroup {
+    const ALL: [JointGroup; 5] = [
+        JointGroup::Head,
+        JointGroup::LeftArm,
+        JointGroup::RightArm,
+        JointGroup::LeftLeg,
+        JointGroup::RightLeg,
+    ];
+
+    fn contains(self, name: JointsName) -> bool {
+        matches!(
+            (self, name),
+            (JointGroup::Head, JointsName::Head(_))
+                | (JointGroup::LeftArm, JointsName::LeftArm(_))
+                | (JointGroup::RightArm, JointsName::RightArm(_))
+                | (JointGroup::LeftLeg, JointsName::LeftLeg(_))
+                | (JointGroup::RightLeg, JointsName::RightLeg(_))
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RobotMode {
+    /// The robot is not influenced by the editor
+    Released,
+    /// Stiffness is off, the robot can be moved by hand to record keyframes
+    Recording,
+    /// The robot follows the scrubbed position of the motion
+    Preview,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Selection {
+    frame: usize,
+    keyframe: usize,
+}
+
+pub struct MotionEditorPanel {
+    nao: Arc<Nao>,
+    positions: BufferHandle<Joints<f32>>,
+    path: String,
+    motion_file: Option<MotionFile<Joints<f32>>>,
+    selection: Option<Selection>,
+    joint_group: JointGroup,
+    robot_mode: RobotMode,
+    time: Duration,
+    playing: bool,
+    /// Positions last written while previewing, writes without changes are skipped
+    written_preview_positions: Option<(Instant, Value)>,
+    /// The connection was lost while the robot was not released
+    is_reset_pending: bool,
+}
+
+impl Panel for MotionEditorPanel {
+    const NAME: &'static str = "Motion Editor";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let positions = nao.subscribe_value("Control.main_outputs.sensor_data.positions");
+        let path = value
+            .and_then(|value| value.get("path"))
+            .and_then(|value| value.as_str())
+            .unwrap_or(DEFAULT_MOTION_FILE)
+            .to_string();
+
+        Self {
+            nao,
+            positions,
+            path,
+            motion_file: None,
+            selection: None,
+            joint_group: JointGroup::LeftLeg,
+            robot_mode: RobotMode::Released,
+            time: Duration::ZERO,
+            playing: false,
+            written_preview_positions: None,
+            is_reset_pending: false,
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "path": self.path,
+        })
+    }
+}
+
+impl MotionEditorPanel {
+    fn resolve_path(&self) -> Result<PathBuf> {
+        let path = Path::new(&self.path);
+        if path.is_absolute() {
+            return Ok(path.to_path_buf());
+        }
+        let repository = self
+            .nao
+            .repository()
+            .wrap_err("repository not available, cannot resolve relative path")?;
+        Ok(repository.root.join(path))
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let motion_file = MotionFile::from_path(self.resolve_path()?)?;
+        self.motion_file = Some(motion_file);
+        self.selection = None;
+        self.time = Duration::ZERO;
+        Ok(())
+    }
+
+    fn store(&self, motion_file: &MotionFile<Joints<f32>>) -> Result<()> {
+        let path = self.resolve_path()?;
+        let file =
+            File::create(&path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        to_writer_pretty(&mut writer, motion_file)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    fn set_robot_mode(&mut self, robot_mode: RobotMode) {
+        self.robot_mode = robot_mode;
+        self.is_reset_pending = false;
+        let (motion_command, positions) = match robot_mode {
+            RobotMode::Released => (Value::Null, Value::Null),
+            RobotMode::Recording => (
+                to_value(MotionCommand::Animation { stiff: false }).unwrap(),
+                Value::Null,
+            ),
+            RobotMode::Preview => (
+                to_value(MotionCommand::Animation { stiff: true }).unwrap(),
+                self.preview_positions(),
+            ),
+        };
+        self.written_preview_positions =
+            (robot_mode == RobotMode::Preview).then(|| (Instant::now(), positions.clone()));
+        // positions first, the robot must not become stiff at the previously saved pose
+        self.nao.write(
+            "parameters.animation.injected_positions",
+            TextOrBinary::Text(positions),
+        );
+        self.nao.write(
+            "parameters.behavior.injected_motion_command",
+            TextOrBinary::Text(motion_command),
+        );
+    }
+
+    /// Writes the positions at the current time if they changed, at most every
+    /// [`PREVIEW_WRITE_INTERVAL`] during playback
+    fn write_preview_positions(&mut self) {
+        let positions = self.preview_positions();
+        if let Some((written_at, written_positions)) = &self.written_preview_positions {
+            if *written_positions == positions
+                || (self.playing && written_at.elapsed() < PREVIEW_WRITE_INTERVAL)
+            {
+                return;
+            }
+        }
+        self.nao.write(
+            "parameters.animation.injected_positions",
+            TextOrBinary::Text(positions.clone()),
+        );
+        self.written_preview_positions = Some((Instant::now(), positions));
+    }
+
+    /// The robot keeps the injected parameters while the connection is lost, they are reset as
+    /// soon as it is connected again
+    fn release_on_disconnect(&mut self) {
+        match self.nao.connection_status() {
+            Status::Connected => {
+                if self.is_reset_pending {
+                    self.set_robot_mode(RobotMode::Released);
+                }
+            }
+            Status::Disconnected | Status::Connecting => {
+                if self.robot_mode != RobotMode::Released {
+                    self.robot_mode = RobotMode::Released;
+                    self.written_preview_positions = None;
+                    self.is_reset_pending = true;
+                }
+            }
+        }
+    }
+
+    fn preview_positions(&self) -> Value {
+        self.motion_file
+            .as_ref()
+            .and_then(interpolator)
+            .map(|mut interpolator| {
+                interpolator.advance_by(self.time);
+                to_value(interpolator.value()).unwrap()
+            })
+            .unwrap_or(Value::Null)
+    }
+
+    fn record_keyframe(&mut self) -> Result<()> {
+        let positions = self
+            .positions
+            .get_last_value()?
+            .wrap_err("no joint positions received")?;
+        let motion_file = self
+            .motion_file
+            .as_mut()
+            .wrap_err("no motion file loaded")?;
+        let keyframe = KeyFrame {
+            duration: DEFAULT_KEYFRAME_DURATION,
+            positions,
+        };
+        let selection = match self.selection {
+            Some(selection) => Selection {
+                frame: selection.frame,
+                keyframe: selection.keyframe + 1,
+            },
+            None => Selection {
+                frame: motion_file
+                    .motion
+                    .len()
+                    .checked_sub(1)
+                    .wrap_err("motion file has no frames")?,
+                keyframe: motion_file.motion.last().unwrap().keyframes.len(),
+            },
+        };
+        motion_file.motion[selection.frame]
+            .keyframes
+            .insert(selection.keyframe, keyframe);
+        self.selection = Some(selection);
+        Ok(())
+    }
+}
+
+impl Drop for MotionEditorPanel {
+    fn drop(&mut self) {
+        if self.robot_mode != RobotMode::Released || self.is_reset_pending {
+            self.set_robot_mode(RobotMode::Released);
+        }
+    }
+}
+
+impl Widget for &mut MotionEditorPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        self.release_on_disconnect();
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Motion file:");
+                ui.text_edit_singleline(&mut self.path);
+                if ui.button("Load").clicked() {
+                    self.load().log_err();
+                }
+                if let Some(motion_file) = &self.motion_file {
+                    if ui.button("Save").clicked() {
+                        self.store(motion_file).log_err();
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Robot:");
+                for (robot_mode, label) in [
+                    (RobotMode::Released, "Released"),
+                    (RobotMode::Recording, "Unstiff (recording)"),
+                    (RobotMode::Preview, "Preview"),
+                ] {
+                    if ui
+                        .selectable_label(self.robot_mode == robot_mode, label)
+                        .clicked()
+                        && self.robot_mode != robot_mode
+                    {
+                        self.set_robot_mode(robot_mode);
+                    }
+                }
+                if ui.button("Record keyframe").clicked() {
+                    self.record_keyframe().log_err();
+                }
+            });
+            ui.separator();
+
+            let Some(total_duration) = self
+                .motion_file
+                .as_ref()
+                .and_then(interpolator)
+                .map(|interpolator| interpolator.total_duration())
+            else {
+                ui.label("no motion loaded, or the motion has no keyframes");
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(if self.playing { "Pause" } else { "Play" })
+                    .clicked()
+                {
+                    self.playing = !self.playing;
+                    if self.playing && self.time >= total_duration {
+                        self.time = Duration::ZERO;
+                    }
+                }
+                let mut seconds = self.time.as_secs_f32();
+                ui.style_mut().spacing.slider_width = ui.available_width() - 100.0;
+                if ui
+                    .add(
+                        Slider::new(&mut seconds, 0.0..=total_duration.as_secs_f32())
+                            .suffix(" s")
+                            .text("Time"),
+                    )
+                    .changed()
+                {
+                    self.playing = false;
+                    self.time = Duration::from_secs_f32(seconds);
+                }
+            });
+            if self.playing {
+                self.time += Duration::from_secs_f32(ui.input(|input| input.stable_dt));
+                if self.time >= total_duration {
+                    self.time = total_duration;
+                    self.playing = false;
+                }
+                ui.ctx().request_repaint();
+            }
+            self.time = self.time.min(total_duration);
+            if self.robot_mode == RobotMode::Preview {
+                self.write_preview_positions();
+            }
+
+            ComboBox::from_id_salt("motion_editor_joint_group")
+                .selected_text(format!("{:?}", self.joint_group))
+                .show_ui(ui, |ui| {
+                    for joint_group in JointGroup::ALL {
+                        ui.selectable_value(
+                            &mut self.joint_group,
+                            joint_group,
+                            format!("{joint_group:?}"),
+                        );
+                    }
+                });
+            if let Some(motion_file) = &self.motion_file {
+                trajectory_plot(ui, motion_file, self.joint_group, self.time);
+            }
+            ui.separator();
+
+            ScrollArea::vertical().show(ui, |ui| {
+                self.keyframes_list(ui);
+            });
+        })
+        .response
+    }
+}
+
+impl MotionEditorPanel {
+    fn keyframes_list(&mut self, ui: &mut Ui) {
+        let Some(motion_file) = &mut self.motion_file else {
+            return;
+        };
+        let mut removed = None;
+        let mut keyframe_time = Duration::ZERO;
+        for (frame_index, frame) in motion_file.motion.iter_mut().enumerate() {
+            ui.label(format!(
+                "Frame {frame_index}: {}",
+                frame.name.as_deref().unwrap_or("unnamed")
+            ));
+            ui.indent(("motion_editor_frame", frame_index), |ui| {
+                if let Some(entry_condition) = &frame.entry_condition {
+                    ui.label(format!("Entry condition: {entry_condition:?}"));
+                }
+                for interrupt_condition in &frame.interrupt_conditions {
+                    ui.label(format!("Interrupt condition: {interrupt_condition:?}"));
+                }
+                for (keyframe_index, keyframe) in frame.keyframes.iter_mut().enumerate() {
+                    keyframe_time += keyframe.duration;
+                    let selection = Selection {
+                        frame: frame_index,
+                        keyframe: keyframe_index,
+                    };
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(
+                                self.selection == Some(selection),
+                                format!("Keyframe {keyframe_index}"),
+                            )
+                            .clicked()
+                        {
+                            self.selection = Some(selection);
+                            self.playing = false;
+                            self.time = keyframe_time;
+                        }
+                        let mut seconds = keyframe.duration.as_secs_f32();
+                        if ui
+                            .add(
+                                DragValue::new(&mut seconds)
+                                    .range(0.01..=10.0)
+                                    .speed(0.01)
+                                    .suffix(" s"),
+                            )
+                            .changed()
+                        {
+                            keyframe.duration = Duration::from_secs_f32(seconds);
+                        }
+                        if ui.button("Delete").clicked() {
+                            removed = Some(selection);
+                        }
+                    });
+                }
+                if let Some(exit_condition) = &frame.exit_condition {
+                    ui.label(format!("Exit condition: {exit_condition:?}"));
+                }
+            });
+        }
+
+        if let Some(removed) = removed {
+            motion_file.motion[removed.frame]
+                .keyframes
+                .remove(removed.keyframe);
+            self.selection = None;
+        }
+    }
+}
+
+/// Plays all frames one after another, conditions are not evaluated offline
+fn interpolator(motion_file: &MotionFile<Joints<f32>>) -> Option<SplineInterpolator<Joints<f32>>> {
+    let keyframes = motion_file
+        .motion
+        .iter()
+        .flat_map(|frame| frame.keyframes.iter().cloned())
+        .collect();
+    TimedSpline::try_new_with_start(
+        motion_file.initial_positions,
+        keyframes,
+        motion_file.interpolation_mode,
+    )
+    .ok()
+    .map(SplineInterpolator::from)
+}
+
+fn trajectory_plot(
+    ui: &mut Ui,
+    motion_file: &MotionFile<Joints<f32>>,
+    joint_group: JointGroup,
+    time: Duration,
+) {
+    let Some(mut interpolator) = interpolator(motion_file) else {
+        return;
+    };
+    let mut samples = Vec::new();
+    loop {
+        samples.push((interpolator.current_duration(), interpolator.value()));
+        if interpolator.is_finished() {
+            break;
+        }
+        interpolator.advance_by(SAMPLE_DURATION);
+    }
+
+    Plot::new(ui.id().with("motion_editor_trajectories"))
+        .view_aspect(2.5)
+        .legend(Legend::default())
+        .show(ui, |plot_ui| {
+            let names = Joints::<f32>::default()
+                .enumerate()
+                .map(|(name, _)| name)
+                .filter(|name| joint_group.contains(*name));
+            for name in names {
+                let points: Vec<_> = samples
+                    .iter()
+                    .map(|(time, positions)| [time.as_secs_f64(), positions[name] as f64])
+                    .collect();
+                plot_ui.line(Line::new(points).name(format!("{name:?}")));
+            }
+
+            let mut keyframe_time = Duration::ZERO;
+            for frame in &motion_file.motion {
+                for keyframe in &frame.keyframes {
+                    keyframe_time += keyframe.duration;
+                    plot_ui.vline(VLine::new(keyframe_time.as_secs_f64()).color(Color32::GRAY));
+                }
+            }
+            plot_ui.vline(
+                VLine::new(time.as_secs_f64())
+                    .name("time")
+                    .color(Color32::YELLOW),
+            );
+        });
+}
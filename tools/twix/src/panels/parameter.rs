@@ -1,22 +1,38 @@
 use std::sync::Arc;
 
-use crate::{log_error::LogError, nao::Nao, panel::Panel, value_buffer::BufferHandle};
+use crate::{
+    log_error::LogError, nao::Nao, panel::Panel, session::Robot, value_buffer::BufferHandle,
+};
+use chrono::{DateTime, Local};
 use color_eyre::{
     eyre::{eyre, Error},
     Result,
 };
 use communication::messages::TextOrBinary;
-use eframe::egui::{Response, ScrollArea, TextEdit, Ui, Widget};
+use eframe::egui::{
+    CollapsingHeader, Color32, Grid, Response, RichText, ScrollArea, TextEdit, Ui, Widget,
+};
 use hulk_widgets::{NaoPathCompletionEdit, PathFilter};
 use log::error;
-use parameters::directory::Scope;
+use parameters::{
+    directory::{Layer, Scope},
+    json::{merge_json, prune_equal_branches},
+};
 use serde_json::{json, Value};
 
+struct RobotParameter {
+    address: String,
+    color: Color32,
+    buffer: BufferHandle<Value>,
+}
+
 pub struct ParameterPanel {
     nao: Arc<Nao>,
     path: String,
     buffer: Option<BufferHandle<Value>>,
     parameter_value: Result<String>,
+    layers: Option<Result<Vec<Layer>>>,
+    robot_parameters: Vec<RobotParameter>,
 }
 
 impl Panel for ParameterPanel {
@@ -34,6 +50,8 @@ impl Panel for ParameterPanel {
             path: path.unwrap_or("").to_string(),
             buffer: value_buffer,
             parameter_value: Err(eyre!("no subscription")),
+            layers: None,
+            robot_parameters: Vec::new(),
         }
     }
     fn save(&self) -> Value {
@@ -55,6 +73,8 @@ impl Widget for &mut ParameterPanel {
                 ));
                 if path_edit.changed() {
                     self.buffer = Some(self.nao.subscribe_json(&self.path));
+                    self.layers = None;
+                    self.robot_parameters.clear();
                 }
                 let settable = self.buffer.is_some()
                     && self
@@ -90,6 +110,7 @@ impl Widget for &mut ParameterPanel {
                                         Scope::current_head(),
                                     )
                                     .log_err();
+                                self.layers = None;
                             }
                             Err(error) => error!(
                                 "parameter panel: failed to serialize parameter value: {error:#?}"
@@ -110,6 +131,7 @@ impl Widget for &mut ParameterPanel {
                                         Scope::current_body(),
                                     )
                                     .log_err();
+                                self.layers = None;
                             }
                             Err(error) => error!(
                                 "parameter panel: failed to serialize parameter value: {error:#?}"
@@ -150,7 +172,203 @@ impl Widget for &mut ParameterPanel {
                     }
                 }
             }
+
+            if let Some(local_parameter_path) = self.path.strip_prefix("parameters.") {
+                let local_parameter_path = local_parameter_path.to_string();
+                CollapsingHeader::new("Differences")
+                    .id_salt("differences")
+                    .show(ui, |ui| self.show_differences(ui, &local_parameter_path));
+                CollapsingHeader::new("Scopes")
+                    .id_salt("scopes")
+                    .show(ui, |ui| self.show_scopes(ui, &local_parameter_path));
+                CollapsingHeader::new("Robots")
+                    .id_salt("robots")
+                    .show(ui, |ui| self.show_robots(ui));
+            }
+            CollapsingHeader::new("History")
+                .id_salt("history")
+                .show(ui, |ui| self.show_history(ui));
         })
         .response
     }
 }
+
+impl ParameterPanel {
+    fn layers(&mut self, local_parameter_path: &str) -> &Result<Vec<Layer>> {
+        self.layers
+            .get_or_insert_with(|| self.nao.parameter_layers(local_parameter_path))
+    }
+
+    fn show_differences(&mut self, ui: &mut Ui, local_parameter_path: &str) {
+        let live_value = match self.buffer.as_ref().map(|buffer| buffer.get_last_value()) {
+            Some(Ok(Some(value))) => value,
+            Some(Ok(None)) | None => {
+                ui.label("no live value available");
+                return;
+            }
+            Some(Err(error)) => {
+                ui.label(format!("{error:#}"));
+                return;
+            }
+        };
+        let layers = match self.layers(local_parameter_path) {
+            Ok(layers) => layers,
+            Err(error) => {
+                ui.label(format!("{error:#}"));
+                return;
+            }
+        };
+        let mut file_value = Value::Null;
+        for value in layers.iter().filter_map(|layer| layer.value.as_ref()) {
+            merge_json(&mut file_value, value);
+        }
+        if live_value == file_value {
+            ui.label("live value matches the parameter files");
+            return;
+        }
+        let mut only_live = live_value.clone();
+        prune_equal_branches(&mut only_live, &file_value);
+        let mut only_files = file_value;
+        prune_equal_branches(&mut only_files, &live_value);
+        ui.label(RichText::new("Live").strong());
+        show_json(ui, &only_live);
+        ui.label(RichText::new("Files").strong());
+        show_json(ui, &only_files);
+    }
+
+    fn show_scopes(&mut self, ui: &mut Ui, local_parameter_path: &str) {
+        if ui.button("Refresh").clicked() {
+            self.layers = None;
+        }
+        let layers = match self.layers(local_parameter_path) {
+            Ok(layers) => layers,
+            Err(error) => {
+                ui.label(format!("{error:#}"));
+                return;
+            }
+        };
+        let overriding_layer = layers.iter().rposition(|layer| layer.value.is_some());
+        Grid::new("scopes").striped(true).show(ui, |ui| {
+            for (index, layer) in layers.iter().enumerate() {
+                let scope = format!("{:?} / {:?}", layer.scope.location, layer.scope.id);
+                if Some(index) == overriding_layer {
+                    ui.label(RichText::new(scope).strong());
+                } else {
+                    ui.label(scope);
+                }
+                let file_name = layer
+                    .file_path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ui.label(file_name)
+                    .on_hover_text(layer.file_path.display().to_string());
+                match &layer.value {
+                    Some(value) => show_json(ui, value),
+                    None => {
+                        ui.weak("not set");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn show_robots(&mut self, ui: &mut Ui) {
+        let Some(session) = self.nao.session() else {
+            ui.label("no session available");
+            return;
+        };
+        let robots = session.robots();
+        let is_outdated = robots.len() != self.robot_parameters.len()
+            || robots
+                .iter()
+                .zip(&self.robot_parameters)
+                .any(|(robot, parameter)| robot.address != parameter.address);
+        if is_outdated {
+            self.robot_parameters = robots
+                .into_iter()
+                .map(
+                    |Robot {
+                         address,
+                         nao,
+                         color,
+                     }| RobotParameter {
+                        address,
+                        color,
+                        buffer: nao.subscribe_json(&self.path),
+                    },
+                )
+                .collect();
+        }
+        let own_value = self
+            .buffer
+            .as_ref()
+            .and_then(|buffer| buffer.get_last_value().ok().flatten());
+        Grid::new("robots").striped(true).show(ui, |ui| {
+            for parameter in &self.robot_parameters {
+                ui.label(RichText::new(&parameter.address).color(parameter.color));
+                match parameter.buffer.get_last_value() {
+                    Ok(Some(value)) => {
+                        let text = serde_json::to_string(&value).unwrap_or_default();
+                        if own_value
+                            .as_ref()
+                            .is_some_and(|own_value| *own_value != value)
+                        {
+                            ui.label(RichText::new(text).color(Color32::ORANGE))
+                                .on_hover_text("differs from the value of this tab's robot");
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                    Ok(None) => {
+                        ui.weak("no data available");
+                    }
+                    Err(error) => {
+                        ui.label(format!("{error:#}"));
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn show_history(&mut self, ui: &mut Ui) {
+        let history = self.nao.parameter_history();
+        if history.is_empty() {
+            ui.label("no parameters written in this session");
+            return;
+        }
+        if ui
+            .button("Undo")
+            .on_hover_text("Restore the value before the latest write")
+            .clicked()
+        {
+            self.nao.undo_parameter_write();
+        }
+        Grid::new("history").striped(true).show(ui, |ui| {
+            for parameter_write in history.iter().rev() {
+                ui.label(
+                    DateTime::<Local>::from(parameter_write.timestamp)
+                        .format("%H:%M:%S")
+                        .to_string(),
+                );
+                ui.label(&parameter_write.path);
+                match &parameter_write.previous {
+                    Some(previous) => show_json(ui, previous),
+                    None => {
+                        ui.weak("unknown");
+                    }
+                }
+                ui.label("→");
+                show_json(ui, &parameter_write.value);
+                ui.end_row();
+            }
+        });
+    }
+}
+
+fn show_json(ui: &mut Ui, value: &Value) {
+    let text = serde_json::to_string(value).unwrap_or_default();
+    ui.label(RichText::new(text).monospace());
+}
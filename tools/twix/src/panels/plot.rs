@@ -1,9 +1,17 @@
 use std::{
+    fmt::Write,
+    fs::{create_dir_all, write},
+    iter::once,
+    path::PathBuf,
     sync::Arc,
     time::{Duration, SystemTime},
 };
 
-use color_eyre::eyre::{Context, OptionExt};
+use chrono::Local;
+use color_eyre::{
+    eyre::{Context, ContextCompat, OptionExt},
+    Result,
+};
 use eframe::{
     egui::{Button, CollapsingHeader, DragValue, Response, TextEdit, TextStyle, Ui, Widget},
     epaint::Color32,
@@ -11,11 +19,57 @@ use eframe::{
 use egui_plot::{Line, MarkerShape, Plot as EguiPlot, PlotPoints, Points};
 use hulk_widgets::{NaoPathCompletionEdit, PathFilter};
 use itertools::Itertools;
-use mlua::{Function, Lua, LuaSerdeExt};
+use log::{error, info};
+use mlua::{Function, Lua, LuaSerdeExt, MultiValue};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, to_string_pretty, Value};
 
-use crate::{nao::Nao, panel::Panel, value_buffer::BufferHandle};
+use crate::{
+    nao::Nao,
+    panel::Panel,
+    value_buffer::{BufferHandle, Datum},
+};
+
+/// Helpers available to all conversion functions, `time` is set to the time of the evaluated sample
+/// and the state of the time window helpers is reset before a series is evaluated
+const LUA_PRELUDE: &str = r#"
+local state = {}
+
+function reset_state()
+  state = {}
+end
+
+function norm(vector)
+  local sum = 0
+  for _, component in pairs(vector) do
+    sum = sum + component * component
+  end
+  return math.sqrt(sum)
+end
+
+function derivative(name, value)
+  local previous = state[name]
+  state[name] = { time = time, value = value }
+  if previous == nil or previous.time == time then
+    return 0 / 0
+  end
+  return (value - previous.value) / (time - previous.time)
+end
+
+function moving_average(name, value, window)
+  local samples = state[name] or {}
+  state[name] = samples
+  table.insert(samples, { time = time, value = value })
+  while samples[1].time < time - window do
+    table.remove(samples, 1)
+  end
+  local sum = 0
+  for _, sample in ipairs(samples) do
+    sum = sum + sample.value
+  end
+  return sum / #samples
+end
+"#;
 
 const DEFAULT_LINE_COLORS: &[Color32] = &[
     Color32::from_rgb(31, 119, 180),
@@ -35,6 +89,11 @@ struct LineData {
     path: String,
     #[serde(skip)]
     buffer: Option<BufferHandle<Value>>,
+    /// Paths whose values are passed to the conversion function as further arguments
+    #[serde(default)]
+    additional_paths: Vec<String>,
+    #[serde(skip)]
+    additional_buffers: Vec<Option<BufferHandle<Value>>>,
     color: Color32,
     #[serde(skip)]
     #[serde(default = "LineData::create_lua")]
@@ -52,7 +111,11 @@ struct LineData {
 
 impl LineData {
     fn create_lua() -> Lua {
-        Lua::new()
+        let lua = Lua::new();
+        lua.load(LUA_PRELUDE)
+            .exec()
+            .expect("failed to load lua prelude");
+        lua
     }
 
     fn set_lua(&mut self) {
@@ -72,6 +135,8 @@ impl LineData {
         let mut line_data = Self {
             path: String::new(),
             buffer: None,
+            additional_paths: Vec::new(),
+            additional_buffers: Vec::new(),
             color,
             lua,
             lua_text,
@@ -89,31 +154,84 @@ impl LineData {
         self.is_highlighted = is_highlighted
     }
 
-    fn plot(&self, latest_timestamp: Option<SystemTime>) -> PlotPoints<'_> {
-        let lua_function: Function = self.lua.globals().get("conversion_function").unwrap();
-        self.buffer
-            .as_ref()
+    fn subscribe(&mut self, nao: &Nao, buffer_history: Duration) {
+        self.buffer = (!self.path.is_empty())
+            .then(|| nao.subscribe_buffered_json(&self.path, buffer_history));
+        self.additional_buffers = self
+            .additional_paths
+            .iter()
+            .map(|path| {
+                (!path.is_empty()).then(|| nao.subscribe_buffered_json(path, buffer_history))
+            })
+            .collect();
+    }
+
+    fn set_history(&self, buffer_history: Duration) {
+        for buffer in self
+            .buffer
+            .iter()
+            .chain(self.additional_buffers.iter().flatten())
+        {
+            buffer.set_history(buffer_history);
+        }
+    }
+
+    fn call(
+        &self,
+        function: &Function,
+        time: f64,
+        value: &Value,
+        additional_values: impl Iterator<Item = Option<Value>>,
+    ) -> mlua::Result<f64> {
+        self.lua.globals().set("time", time)?;
+        let arguments = once(self.lua.to_value(value))
+            .chain(additional_values.map(|value| match value {
+                Some(value) => self.lua.to_value(&value),
+                None => Ok(mlua::Value::Nil),
+            }))
+            .collect::<mlua::Result<Vec<_>>>()?;
+        function.call(MultiValue::from_vec(arguments))
+    }
+
+    /// Evaluates the conversion function for every buffered value, additional paths are sampled at
+    /// the timestamp of the value
+    fn evaluate(&self, latest_timestamp: Option<SystemTime>) -> Vec<[f64; 2]> {
+        let (Some(buffer), Some(latest_timestamp)) = (&self.buffer, latest_timestamp) else {
+            return Vec::new();
+        };
+        let Ok(series) = buffer.get() else {
+            return Vec::new();
+        };
+        let additional_series: Vec<_> = self
+            .additional_buffers
+            .iter()
             .map(|buffer| {
                 buffer
-                    .get()
-                    .map(|buffered_values| {
-                        PlotPoints::from_iter(buffered_values.iter().map(|datum| {
-                            let value = lua_function
-                                .call(self.lua.to_value(&datum.value))
-                                .unwrap_or(f64::NAN);
-                            [
-                                -latest_timestamp
-                                    .unwrap()
-                                    .duration_since(datum.timestamp)
-                                    .unwrap_or(Duration::ZERO)
-                                    .as_secs_f64(),
-                                value,
-                            ]
-                        }))
-                    })
+                    .as_ref()
+                    .and_then(|buffer| buffer.get().ok())
                     .unwrap_or_default()
             })
-            .unwrap_or_default()
+            .collect();
+        let lua_function: Function = self.lua.globals().get("conversion_function").unwrap();
+        if let Ok(reset_state) = self.lua.globals().get::<Function>("reset_state") {
+            let _ = reset_state.call::<()>(());
+        }
+        series
+            .iter()
+            .map(|datum| {
+                let time = -latest_timestamp
+                    .duration_since(datum.timestamp)
+                    .unwrap_or(Duration::ZERO)
+                    .as_secs_f64();
+                let additional_values = additional_series
+                    .iter()
+                    .map(|series| value_at(series, datum.timestamp));
+                let value = self
+                    .call(&lua_function, time, &datum.value, additional_values)
+                    .unwrap_or(f64::NAN);
+                [time, value]
+            })
+            .collect()
     }
 
     fn show_settings(&mut self, ui: &mut Ui, id: usize, nao: &Nao, buffer_history: Duration) {
@@ -129,6 +247,7 @@ impl LineData {
                 let handle = nao.subscribe_buffered_json(&self.path, buffer_history);
                 self.buffer = Some(handle);
             }
+            self.show_additional_paths(ui, id, nao, buffer_history);
 
             ui.color_edit_button_srgba(&mut self.color);
 
@@ -151,42 +270,108 @@ impl LineData {
                             Err(error) => format!("{error:#}"),
                         };
                         ui.label(pretty_json);
-                        let code_edit = TextEdit::multiline(&mut self.lua_text)
-                            .font(TextStyle::Monospace)
-                            .code_editor()
-                            .lock_focus(true);
-                        if ui.add(code_edit).changed() {
-                            self.lua_error = match self.lua.load(&self.lua_text).eval::<Function>()
-                            {
-                                Ok(function) => {
-                                    self.lua
-                                        .globals()
-                                        .set("conversion_function", function)
-                                        .unwrap();
-                                    None
-                                }
-                                Err(error) => Some(format!("{error:#}")),
-                            };
-                        }
-                        if let Some(error) = &self.lua_error {
-                            ui.colored_label(Color32::RED, error);
-                        } else if let Ok(value) = &latest_value {
-                            let lua_function: Function =
-                                self.lua.globals().get("conversion_function").unwrap();
-                            let value = lua_function.call::<f64>(self.lua.to_value(&value));
-                            match value {
-                                Ok(value) => {
-                                    ui.label(value.to_string());
-                                }
-                                Err(error) => {
-                                    ui.colored_label(Color32::RED, error.to_string());
-                                }
-                            }
-                        }
+                        ui.vertical(|ui| {
+                            ui.label(
+                                "Helpers: norm(vector), derivative(name, value), \
+                                 moving_average(name, value, window), time",
+                            )
+                            .on_hover_text(
+                                "Values of additional paths are passed as further arguments. \
+                                 `name` identifies the state of a time window helper, `time` is \
+                                 the time of the sample in seconds.",
+                            );
+                            self.show_code_edit(ui, &latest_value);
+                        });
                     });
                 });
         });
     }
+
+    fn show_code_edit(&mut self, ui: &mut Ui, latest_value: &Result<Value>) {
+        let code_edit = TextEdit::multiline(&mut self.lua_text)
+            .font(TextStyle::Monospace)
+            .code_editor()
+            .lock_focus(true);
+        if ui.add(code_edit).changed() {
+            self.lua_error = match self.lua.load(&self.lua_text).eval::<Function>() {
+                Ok(function) => {
+                    self.lua
+                        .globals()
+                        .set("conversion_function", function)
+                        .unwrap();
+                    None
+                }
+                Err(error) => Some(format!("{error:#}")),
+            };
+        }
+        if let Some(error) = &self.lua_error {
+            ui.colored_label(Color32::RED, error);
+        } else if let Ok(value) = &latest_value {
+            let lua_function: Function = self.lua.globals().get("conversion_function").unwrap();
+            let additional_values = self
+                .additional_buffers
+                .iter()
+                .map(|buffer| buffer.as_ref()?.get_last_value().ok().flatten());
+            let value = self.call(&lua_function, 0.0, value, additional_values);
+            match value {
+                Ok(value) => {
+                    ui.label(value.to_string());
+                }
+                Err(error) => {
+                    ui.colored_label(Color32::RED, error.to_string());
+                }
+            }
+        }
+    }
+
+    fn show_additional_paths(
+        &mut self,
+        ui: &mut Ui,
+        id: usize,
+        nao: &Nao,
+        buffer_history: Duration,
+    ) {
+        ui.vertical(|ui| {
+            let mut removed_index = None;
+            for (index, path) in self.additional_paths.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.button("X").clicked() {
+                        removed_index = Some(index);
+                    }
+                    let path_field = ui.add(NaoPathCompletionEdit::new(
+                        ui.id().with(id).with("additional-path").with(index),
+                        nao.latest_paths(),
+                        path,
+                        PathFilter::Readable,
+                    ));
+                    if path_field.changed() {
+                        self.additional_buffers[index] =
+                            Some(nao.subscribe_buffered_json(&*path, buffer_history));
+                    }
+                });
+            }
+            if let Some(index) = removed_index {
+                self.additional_paths.remove(index);
+                self.additional_buffers.remove(index);
+            }
+            if ui
+                .button("✚ Path")
+                .on_hover_text("Add a path whose value is passed to the conversion function")
+                .clicked()
+            {
+                self.additional_paths.push(String::new());
+                self.additional_buffers.push(None);
+            }
+        });
+    }
+}
+
+/// The latest value which was received before the timestamp
+fn value_at(series: &[Datum<Value>], timestamp: SystemTime) -> Option<Value> {
+    let index = series.partition_point(|datum| datum.timestamp <= timestamp);
+    series
+        .get(index.checked_sub(1)?)
+        .map(|datum| datum.value.clone())
 }
 
 pub struct PlotPanel {
@@ -210,11 +395,7 @@ impl Panel for PlotPanel {
                         let mut line_data =
                             serde_json::from_value::<LineData>(line_data.clone()).ok()?;
                         line_data.set_lua();
-                        if !line_data.path.is_empty() {
-                            let handle = nao
-                                .subscribe_buffered_json(&line_data.path, DEFAULT_BUFFER_HISTORY);
-                            line_data.buffer = Some(handle);
-                        }
+                        line_data.subscribe(&nao, DEFAULT_BUFFER_HISTORY);
                         Some(line_data)
                     })
                     .collect_vec()
@@ -236,16 +417,29 @@ impl Panel for PlotPanel {
 }
 
 impl PlotPanel {
-    fn plot(&self, ui: &mut Ui) -> Response {
-        let latest_timestamp = self
-            .lines
+    /// Saved value of a plot with a single line showing the path
+    pub fn value_for_path(path: &str) -> Value {
+        let mut line_data = LineData::new(DEFAULT_LINE_COLORS[0]);
+        line_data.path = path.to_string();
+        json!({
+            "_panel_type": Self::NAME,
+            "lines": [line_data],
+        })
+    }
+
+    fn latest_timestamp(&self) -> Option<SystemTime> {
+        self.lines
             .iter()
             .filter_map(|line_data| {
                 let buffer = line_data.buffer.as_ref()?;
                 let last = buffer.get_last_timestamp().ok().flatten()?;
                 Some(last)
             })
-            .max();
+            .max()
+    }
+
+    fn plot(&self, ui: &mut Ui) -> Response {
+        let latest_timestamp = self.latest_timestamp();
 
         let plot_points = self
             .lines
@@ -253,7 +447,7 @@ impl PlotPanel {
             .filter(|line_data| !line_data.is_hidden)
             .map(|line_data| {
                 (
-                    line_data.plot(latest_timestamp),
+                    PlotPoints::new(line_data.evaluate(latest_timestamp)),
                     line_data.show_scatter,
                     line_data.is_highlighted,
                     line_data.color,
@@ -291,16 +485,46 @@ impl PlotPanel {
                 .prefix("History [s]:");
             if ui.add(widget).changed() {
                 self.buffer_history = Duration::from_secs_f64(history_in_seconds);
-                for buffer in self
-                    .lines
-                    .iter_mut()
-                    .filter_map(|data| data.buffer.as_ref())
-                {
-                    buffer.set_history(self.buffer_history);
+                for line_data in &self.lines {
+                    line_data.set_history(self.buffer_history);
+                }
+            }
+            if ui
+                .button("Export CSV")
+                .on_hover_text("Write the plotted values of all visible lines to a CSV file")
+                .clicked()
+            {
+                match self.export_csv() {
+                    Ok(file_path) => info!("plot exported to {}", file_path.display()),
+                    Err(error) => error!("failed to export plot: {error:#}"),
                 }
             }
         });
     }
+
+    fn export_csv(&self) -> Result<PathBuf> {
+        let directory = dirs::data_local_dir()
+            .wrap_err("failed to find a directory for plot exports")?
+            .join("hulks")
+            .join("plots");
+        create_dir_all(&directory)
+            .wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+        let file_path = directory.join(format!("{}.csv", Local::now().format("%Y-%m-%d_%H-%M-%S")));
+
+        let latest_timestamp = self.latest_timestamp();
+        let mut csv = String::from("line,path,time,value\n");
+        for (index, line_data) in self.lines.iter().enumerate() {
+            if line_data.is_hidden {
+                continue;
+            }
+            for [time, value] in line_data.evaluate(latest_timestamp) {
+                writeln!(csv, "{index},{},{time},{value}", line_data.path)?;
+            }
+        }
+        write(&file_path, csv)
+            .wrap_err_with(|| format!("failed to write {}", file_path.display()))?;
+        Ok(file_path)
+    }
 }
 
 impl Widget for &mut PlotPanel {
@@ -0,0 +1,70 @@
+use booster::MotorState;
+use linear_algebra::Point3;
+use types::support_foot::Side;
+use walking_engine::backend::{
+    booster::{serial_motor_legs, Booster, BoosterParameters, NUMBER_OF_MOTORS},
+    Backend,
+};
+
+use super::{Primitive, RobotGeometry};
+
+/// The kinematics of the Booster only know the lengths of its links, they are drawn this thick
+const LINK_RADIUS: f32 = 0.03;
+
+/// Legs of the Booster posed by the kinematics of the walking engine, the head and the arms are
+/// not part of the model
+pub fn booster_geometry(
+    parameters: &BoosterParameters,
+    motor_states: &[MotorState],
+) -> Option<RobotGeometry> {
+    if motor_states.len() < NUMBER_OF_MOTORS {
+        return None;
+    }
+    let legs = serial_motor_legs(motor_states, |motor| motor.position);
+    let backend = Booster {
+        parameters,
+        leg_torques: Default::default(),
+    };
+
+    let mut primitives = Vec::new();
+    let [left_sole, right_sole] =
+        [(Side::Left, legs.left_leg), (Side::Right, legs.right_leg)].map(|(side, leg)| {
+            let [hip, knee, ankle, sole] = parameters.leg_positions(side, &leg);
+            primitives.extend(
+                [(hip, knee), (knee, ankle), (ankle, sole)].map(|(start, end)| {
+                    Primitive::Cylinder {
+                        start,
+                        end,
+                        radius: LINK_RADIUS,
+                    }
+                }),
+            );
+
+            let sole_to_robot = backend.sole_to_robot(side, &leg).inner;
+            let half_length = parameters.sole_length / 2.0;
+            let half_width = parameters.sole_width / 2.0;
+            [
+                (half_length, half_width),
+                (-half_length, half_width),
+                (-half_length, -half_width),
+                (half_length, -half_width),
+            ]
+            .map(|(x, y)| Point3::wrap(sole_to_robot * nalgebra::point![x, y, 0.0]))
+            .to_vec()
+        });
+    let [left_hip, right_hip] = [Side::Left, Side::Right].map(|side| {
+        let [hip, ..] = parameters.leg_positions(side, &Default::default());
+        hip
+    });
+    primitives.push(Primitive::Cylinder {
+        start: left_hip,
+        end: right_hip,
+        radius: LINK_RADIUS,
+    });
+
+    Some(RobotGeometry {
+        primitives,
+        left_sole,
+        right_sole,
+    })
+}
@@ -0,0 +1,524 @@
+use std::{f32::consts::FRAC_PI_2, sync::Arc};
+
+use color_eyre::{
+    eyre::{eyre, ContextCompat},
+    Result,
+};
+use eframe::egui::{
+    vec2, Align2, Color32, ComboBox, CornerRadius, FontId, Painter, Pos2, Response, Sense, Shape,
+    Stroke, Ui, Widget,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use booster::MotorState;
+use coordinate_systems::{Ground, Robot, Screen};
+use geometry::convex_hull::reduce_to_convex_hull;
+use linear_algebra::{center, point, vector, Isometry3, Point2, Point3};
+use types::joints::Joints;
+use walking_engine::backend::booster::BoosterParameters;
+
+use crate::{nao::Nao, panel::Panel, value_buffer::BufferHandle};
+
+use self::{booster::booster_geometry, nao::NaoModel};
+
+mod booster;
+mod nao;
+
+/// Height of the robot which is visible at the default zoom level
+const VIEW_HEIGHT: f32 = 0.7;
+/// Soles lower than this above the lowest sole are considered to be in contact with the ground
+const SOLE_CONTACT_HEIGHT: f32 = 0.01;
+const GRID_SPACING: f32 = 0.1;
+const GRID_LINES: i32 = 5;
+const ROTATION_PER_PIXEL: f32 = 0.01;
+
+struct Camera {
+    yaw: f32,
+    pitch: f32,
+    pixels_per_meter: f32,
+    center: Pos2,
+    target: Point3<Ground>,
+}
+
+impl Camera {
+    /// Orthographic projection looking at the target from the direction given by yaw and pitch
+    fn project(&self, point: Point3<Ground>) -> Pos2 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let right = vector![-sin_yaw, cos_yaw, 0.0];
+        let up = vector![-sin_pitch * cos_yaw, -sin_pitch * sin_yaw, cos_pitch];
+        let offset = point - self.target;
+        self.center + vec2(offset.dot(&right), -offset.dot(&up)) * self.pixels_per_meter
+    }
+
+    /// Points closer to the camera have a larger distance, used to paint from back to front
+    fn distance_towards_camera(&self, point: Point3<Ground>) -> f32 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let backward = vector![cos_pitch * cos_yaw, cos_pitch * sin_yaw, sin_pitch];
+        (point - self.target).dot(&backward)
+    }
+
+    fn line(&self, points: impl IntoIterator<Item = Point3<Ground>>, stroke: Stroke) -> Shape {
+        Shape::line(
+            points
+                .into_iter()
+                .map(|point| self.project(point))
+                .collect(),
+            stroke,
+        )
+    }
+}
+
+/// Shape of the robot's geometry in the robot frame
+pub enum Primitive {
+    Sphere {
+        center: Point3<Robot>,
+        radius: f32,
+    },
+    Cylinder {
+        start: Point3<Robot>,
+        end: Point3<Robot>,
+        radius: f32,
+    },
+    Box {
+        corners: [Point3<Robot>; 8],
+    },
+}
+
+pub struct RobotGeometry {
+    pub primitives: Vec<Primitive>,
+    pub left_sole: Vec<Point3<Robot>>,
+    pub right_sole: Vec<Point3<Robot>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+enum RobotType {
+    #[default]
+    Nao,
+    Booster,
+}
+
+enum Model {
+    Nao {
+        model: Result<NaoModel>,
+        joints: BufferHandle<Joints<f32>>,
+    },
+    Booster {
+        parameters: BufferHandle<BoosterParameters>,
+        motor_states: BufferHandle<Vec<MotorState>>,
+    },
+}
+
+impl Model {
+    fn new(nao: &Nao, robot_type: RobotType) -> Self {
+        match robot_type {
+            RobotType::Nao => Self::Nao {
+                model: nao
+                    .repository()
+                    .wrap_err("repository not available, cannot load the URDF model")
+                    .and_then(|repository| {
+                        NaoModel::from_path(repository.root.join("tools/mio/assets/NAO.urdf"))
+                    }),
+                joints: nao.subscribe_value("Control.main_outputs.sensor_data.positions"),
+            },
+            RobotType::Booster => Self::Booster {
+                parameters: nao.subscribe_value("parameters.booster_walking_engine"),
+                motor_states: nao
+                    .subscribe_value("Control.main_outputs.low_state.motor_state_serial"),
+            },
+        }
+    }
+
+    fn geometry(&self) -> Result<Option<RobotGeometry>> {
+        match self {
+            Self::Nao { model, joints } => {
+                let model = model.as_ref().map_err(|error| eyre!("{error:#}"))?;
+                Ok(joints
+                    .get_last_value()?
+                    .map(|joints| model.geometry(&joints)))
+            }
+            Self::Booster {
+                parameters,
+                motor_states,
+            } => {
+                let Some(parameters) = parameters.get_last_value()? else {
+                    return Ok(None);
+                };
+                Ok(motor_states
+                    .get_last_value()?
+                    .and_then(|motor_states| booster_geometry(&parameters, &motor_states)))
+            }
+        }
+    }
+}
+
+pub struct RobotViewPanel {
+    nao: Arc<Nao>,
+    robot_type: RobotType,
+    model: Model,
+    robot_to_ground: BufferHandle<Option<Isometry3<Robot, Ground>>>,
+    center_of_mass: BufferHandle<Point3<Robot>>,
+    zero_moment_point: BufferHandle<Point2<Ground>>,
+    yaw: f32,
+    pitch: f32,
+    zoom: f32,
+    show_support_polygon: bool,
+    show_center_of_mass: bool,
+    show_zero_moment_point: bool,
+}
+
+impl Panel for RobotViewPanel {
+    const NAME: &'static str = "Robot View";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let get_f32 = |key: &str, default: f32| {
+            value
+                .and_then(|value| value.get(key))
+                .and_then(|value| value.as_f64())
+                .map_or(default, |value| value as f32)
+        };
+        let get_bool = |key: &str| {
+            value
+                .and_then(|value| value.get(key))
+                .and_then(|value| value.as_bool())
+                .unwrap_or(true)
+        };
+        let robot_type = value
+            .and_then(|value| value.get("robot_type"))
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default();
+        Self {
+            model: Model::new(&nao, robot_type),
+            robot_type,
+            robot_to_ground: nao.subscribe_value("Control.main_outputs.robot_to_ground"),
+            center_of_mass: nao.subscribe_value("Control.main_outputs.center_of_mass"),
+            zero_moment_point: nao.subscribe_value("Control.main_outputs.zero_moment_point"),
+            nao,
+            yaw: get_f32("yaw", 0.6),
+            pitch: get_f32("pitch", 0.3),
+            zoom: get_f32("zoom", 1.0),
+            show_support_polygon: get_bool("show_support_polygon"),
+            show_center_of_mass: get_bool("show_center_of_mass"),
+            show_zero_moment_point: get_bool("show_zero_moment_point"),
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "robot_type": self.robot_type,
+            "yaw": self.yaw,
+            "pitch": self.pitch,
+            "zoom": self.zoom,
+            "show_support_polygon": self.show_support_polygon,
+            "show_center_of_mass": self.show_center_of_mass,
+            "show_zero_moment_point": self.show_zero_moment_point,
+        })
+    }
+}
+
+impl Widget for &mut RobotViewPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            let mut robot_type_changed = false;
+            ComboBox::from_id_salt("robot_type_selector")
+                .selected_text(format!("{:?}", self.robot_type))
+                .show_ui(ui, |ui| {
+                    for robot_type in [RobotType::Nao, RobotType::Booster] {
+                        robot_type_changed |= ui
+                            .selectable_value(
+                                &mut self.robot_type,
+                                robot_type,
+                                format!("{robot_type:?}"),
+                            )
+                            .changed();
+                    }
+                });
+            if robot_type_changed {
+                self.model = Model::new(&self.nao, self.robot_type);
+            }
+            ui.checkbox(&mut self.show_support_polygon, "Support Polygon");
+            ui.checkbox(&mut self.show_center_of_mass, "Center of Mass");
+            ui.checkbox(&mut self.show_zero_moment_point, "Zero Moment Point");
+            if ui.button("Reset View").clicked() {
+                self.yaw = 0.6;
+                self.pitch = 0.3;
+                self.zoom = 1.0;
+            }
+        });
+
+        let geometry = match self.model.geometry() {
+            Ok(Some(geometry)) => geometry,
+            Ok(None) => return ui.label("no joint angles available"),
+            Err(error) => return ui.label(format!("{error:#}")),
+        };
+        let robot_to_ground = self
+            .robot_to_ground
+            .get_last_value()
+            .ok()
+            .flatten()
+            .flatten()
+            .unwrap_or_else(|| standing_on_lowest_sole(&geometry));
+
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::drag());
+        if response.dragged() {
+            let delta = response.drag_delta();
+            self.yaw -= delta.x * ROTATION_PER_PIXEL;
+            self.pitch = (self.pitch + delta.y * ROTATION_PER_PIXEL).clamp(-FRAC_PI_2, FRAC_PI_2);
+        }
+        if response.hovered() {
+            let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+            self.zoom = (self.zoom * (scroll * 0.005).exp()).clamp(0.1, 10.0);
+        }
+
+        let robot_position = robot_to_ground.translation();
+        let camera = Camera {
+            yaw: self.yaw,
+            pitch: self.pitch,
+            pixels_per_meter: rect.height() / VIEW_HEIGHT * self.zoom,
+            center: rect.center(),
+            target: point![robot_position.x(), robot_position.y(), VIEW_HEIGHT / 2.0],
+        };
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, CornerRadius::ZERO, ui.visuals().extreme_bg_color);
+
+        paint_grid(
+            &painter,
+            &camera,
+            robot_position.xy(),
+            ui.visuals().weak_text_color(),
+        );
+
+        let left_sole: Vec<_> = geometry
+            .left_sole
+            .iter()
+            .map(|point| robot_to_ground * *point)
+            .collect();
+        let right_sole: Vec<_> = geometry
+            .right_sole
+            .iter()
+            .map(|point| robot_to_ground * *point)
+            .collect();
+        if self.show_support_polygon {
+            paint_support_polygon(&painter, &camera, &left_sole, &right_sole);
+        }
+        for sole in [&left_sole, &right_sole] {
+            painter.add(Shape::closed_line(
+                sole.iter().map(|point| camera.project(*point)).collect(),
+                Stroke::new(1.5, ui.visuals().text_color()),
+            ));
+        }
+
+        paint_primitives(
+            &painter,
+            &camera,
+            &geometry.primitives,
+            robot_to_ground,
+            ui.visuals().widgets.inactive.bg_fill,
+            ui.visuals().strong_text_color(),
+        );
+
+        if self.show_center_of_mass {
+            if let Ok(Some(center_of_mass)) = self.center_of_mass.get_last_value() {
+                let center_of_mass = robot_to_ground * center_of_mass;
+                let on_ground = point![center_of_mass.x(), center_of_mass.y(), 0.0];
+                painter.extend(Shape::dashed_line(
+                    &[camera.project(center_of_mass), camera.project(on_ground)],
+                    Stroke::new(1.0, Color32::YELLOW),
+                    4.0,
+                    4.0,
+                ));
+                paint_marker(&painter, &camera, center_of_mass, Color32::YELLOW, "CoM");
+                paint_marker(&painter, &camera, on_ground, Color32::YELLOW, "");
+            }
+        }
+        if self.show_zero_moment_point {
+            if let Ok(Some(zero_moment_point)) = self.zero_moment_point.get_last_value() {
+                paint_marker(
+                    &painter,
+                    &camera,
+                    zero_moment_point.extend(0.0),
+                    Color32::from_rgb(255, 0, 255),
+                    "ZMP",
+                );
+            }
+        }
+
+        response
+    }
+}
+
+/// Without a ground estimate the robot is placed upright with its lowest sole on the ground
+fn standing_on_lowest_sole(geometry: &RobotGeometry) -> Isometry3<Robot, Ground> {
+    let lowest_sole = geometry
+        .left_sole
+        .iter()
+        .chain(&geometry.right_sole)
+        .map(|point| point.z())
+        .fold(f32::INFINITY, f32::min);
+    if lowest_sole.is_finite() {
+        Isometry3::from(vector![0.0, 0.0, -lowest_sole])
+    } else {
+        Isometry3::identity()
+    }
+}
+
+fn paint_grid(painter: &Painter, camera: &Camera, center: Point2<Ground>, color: Color32) {
+    let stroke = Stroke::new(0.5, color.gamma_multiply(0.5));
+    let extent = GRID_LINES as f32 * GRID_SPACING;
+    let center = point![
+        (center.x() / GRID_SPACING).round() * GRID_SPACING,
+        (center.y() / GRID_SPACING).round() * GRID_SPACING,
+    ];
+    for index in -GRID_LINES..=GRID_LINES {
+        let offset = index as f32 * GRID_SPACING;
+        painter.add(camera.line(
+            [
+                point![center.x() + offset, center.y() - extent, 0.0],
+                point![center.x() + offset, center.y() + extent, 0.0],
+            ],
+            stroke,
+        ));
+        painter.add(camera.line(
+            [
+                point![center.x() - extent, center.y() + offset, 0.0],
+                point![center.x() + extent, center.y() + offset, 0.0],
+            ],
+            stroke,
+        ));
+    }
+}
+
+/// Fills the convex hull of all soles which are in contact with the ground
+fn paint_support_polygon(
+    painter: &Painter,
+    camera: &Camera,
+    left_sole: &[Point3<Ground>],
+    right_sole: &[Point3<Ground>],
+) {
+    let height = |sole: &[Point3<Ground>]| {
+        sole.iter()
+            .map(|point| point.z())
+            .fold(f32::INFINITY, f32::min)
+    };
+    let lowest = height(left_sole).min(height(right_sole));
+    let points: Vec<_> = [left_sole, right_sole]
+        .into_iter()
+        .filter(|sole| height(sole) - lowest < SOLE_CONTACT_HEIGHT)
+        .flatten()
+        .map(|point| point.xy())
+        .collect();
+    let hull = reduce_to_convex_hull(&points);
+    painter.add(Shape::convex_polygon(
+        hull.into_iter()
+            .map(|point| camera.project(point.extend(0.0)))
+            .collect(),
+        Color32::from_rgba_unmultiplied(0, 200, 0, 60),
+        Stroke::new(1.0, Color32::from_rgb(0, 200, 0)),
+    ));
+}
+
+/// Paints the primitives from back to front, so that closer ones cover those behind them
+fn paint_primitives(
+    painter: &Painter,
+    camera: &Camera,
+    primitives: &[Primitive],
+    robot_to_ground: Isometry3<Robot, Ground>,
+    fill: Color32,
+    color: Color32,
+) {
+    let stroke = Stroke::new(1.0, color);
+    let mut shapes: Vec<_> = primitives
+        .iter()
+        .map(|primitive| match primitive {
+            Primitive::Sphere { center, radius } => {
+                let center = robot_to_ground * *center;
+                let position = camera.project(center);
+                let radius = radius * camera.pixels_per_meter;
+                (
+                    camera.distance_towards_camera(center),
+                    vec![
+                        Shape::circle_filled(position, radius, fill),
+                        Shape::circle_stroke(position, radius, stroke),
+                    ],
+                )
+            }
+            Primitive::Cylinder { start, end, radius } => {
+                let start = robot_to_ground * *start;
+                let end = robot_to_ground * *end;
+                (
+                    camera.distance_towards_camera(center(start, end)),
+                    capsule(
+                        camera.project(start),
+                        camera.project(end),
+                        radius * camera.pixels_per_meter,
+                        fill,
+                        stroke,
+                    ),
+                )
+            }
+            Primitive::Box { corners } => {
+                let corners = corners.map(|corner| robot_to_ground * corner);
+                let projected: Vec<Point2<Screen>> = corners
+                    .iter()
+                    .map(|corner| {
+                        let position = camera.project(*corner);
+                        point![position.x, position.y]
+                    })
+                    .collect();
+                let hull = reduce_to_convex_hull(&projected)
+                    .into_iter()
+                    .map(|point| Pos2::new(point.x(), point.y()))
+                    .collect();
+                (
+                    camera.distance_towards_camera(center(corners[0], corners[6])),
+                    vec![Shape::convex_polygon(hull, fill, stroke)],
+                )
+            }
+        })
+        .collect();
+    shapes.sort_by(|(left, _), (right, _)| left.total_cmp(right));
+    painter.extend(shapes.into_iter().flat_map(|(_, shapes)| shapes));
+}
+
+/// Projection of a cylinder with rounded ends, the ends stay circles from every direction
+fn capsule(start: Pos2, end: Pos2, radius: f32, fill: Color32, stroke: Stroke) -> Vec<Shape> {
+    let direction = (end - start).normalized();
+    let normal = vec2(-direction.y, direction.x) * radius;
+    let mut shapes = vec![
+        Shape::circle_filled(start, radius, fill),
+        Shape::circle_stroke(start, radius, stroke),
+        Shape::circle_filled(end, radius, fill),
+        Shape::circle_stroke(end, radius, stroke),
+    ];
+    // the body covers the inner halves of the outlined ends
+    if direction.x.is_finite() && direction.y.is_finite() {
+        shapes.push(Shape::convex_polygon(
+            vec![start + normal, end + normal, end - normal, start - normal],
+            fill,
+            Stroke::NONE,
+        ));
+        shapes.push(Shape::line_segment([start + normal, end + normal], stroke));
+        shapes.push(Shape::line_segment([start - normal, end - normal], stroke));
+    }
+    shapes
+}
+
+fn paint_marker(
+    painter: &Painter,
+    camera: &Camera,
+    point: Point3<Ground>,
+    color: Color32,
+    label: &str,
+) {
+    let position = camera.project(point);
+    painter.circle(position, 4.0, color, Stroke::new(1.0, Color32::BLACK));
+    painter.text(
+        position + vec2(6.0, 0.0),
+        Align2::LEFT_CENTER,
+        label,
+        FontId::default(),
+        color,
+    );
+}
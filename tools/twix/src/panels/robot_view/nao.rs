@@ -0,0 +1,186 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use kinematics::forward::robot_kinematics;
+use linear_algebra::Point3;
+use nalgebra::{Isometry3, Translation3, Unit, UnitQuaternion, Vector3};
+use types::{
+    joints::{Joints, JointsName},
+    robot_dimensions::{transform_left_sole_outline, transform_right_sole_outline},
+};
+use urdf_rs::{Geometry, JointType, Pose};
+
+use super::{Primitive, RobotGeometry};
+
+/// Link of the model whose frame is the robot frame
+const BASE_LINK: &str = "base_link";
+
+struct ModelJoint {
+    parent: String,
+    child: String,
+    origin: Isometry3<f32>,
+    /// Joint of the robot and the axis it rotates about, `None` for fixed joints
+    rotation: Option<(JointsName, Unit<Vector3<f32>>)>,
+}
+
+/// Collision shapes of the NAO's URDF model, posed by the joints of the robot
+pub struct NaoModel {
+    /// Joints ordered from the base link outwards, every parent is posed before its children
+    joints: Vec<ModelJoint>,
+    collisions: HashMap<String, Vec<(Isometry3<f32>, Geometry)>>,
+}
+
+impl NaoModel {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let robot = urdf_rs::read_file(path.as_ref())
+            .wrap_err_with(|| format!("failed to read {}", path.as_ref().display()))?;
+
+        let mut joints = Vec::new();
+        let mut posed_links = HashSet::from([BASE_LINK.to_string()]);
+        let mut remaining_joints = robot.joints;
+        loop {
+            let (reachable, unreachable): (Vec<_>, Vec<_>) = remaining_joints
+                .into_iter()
+                .partition(|joint| posed_links.contains(&joint.parent.link));
+            if reachable.is_empty() {
+                break;
+            }
+            for joint in reachable {
+                let rotation = match joint.joint_type {
+                    JointType::Revolute | JointType::Continuous => {
+                        joints_name(&joint.name).map(|name| {
+                            let axis = joint.axis.xyz;
+                            let axis = Vector3::new(axis[0] as f32, axis[1] as f32, axis[2] as f32);
+                            (name, Unit::new_normalize(axis))
+                        })
+                    }
+                    _ => None,
+                };
+                posed_links.insert(joint.child.link.clone());
+                joints.push(ModelJoint {
+                    parent: joint.parent.link,
+                    child: joint.child.link,
+                    origin: isometry(&joint.origin),
+                    rotation,
+                });
+            }
+            remaining_joints = unreachable;
+        }
+
+        let collisions = robot
+            .links
+            .into_iter()
+            .filter(|link| !link.collision.is_empty())
+            .map(|link| {
+                let shapes = link
+                    .collision
+                    .into_iter()
+                    .map(|collision| (isometry(&collision.origin), collision.geometry))
+                    .collect();
+                (link.name, shapes)
+            })
+            .collect();
+
+        Ok(Self { joints, collisions })
+    }
+
+    pub fn geometry(&self, joints: &Joints<f32>) -> RobotGeometry {
+        let mut link_to_robot = HashMap::from([(BASE_LINK, Isometry3::identity())]);
+        for joint in &self.joints {
+            let Some(parent_to_robot) = link_to_robot.get(joint.parent.as_str()).copied() else {
+                continue;
+            };
+            let rotation = joint
+                .rotation
+                .map_or(UnitQuaternion::identity(), |(name, axis)| {
+                    UnitQuaternion::from_axis_angle(&axis, joints[name])
+                });
+            link_to_robot.insert(
+                joint.child.as_str(),
+                parent_to_robot * joint.origin * rotation,
+            );
+        }
+
+        let primitives = self
+            .collisions
+            .iter()
+            .filter_map(|(link, shapes)| Some((link_to_robot.get(link.as_str())?, shapes)))
+            .flat_map(|(link_to_robot, shapes)| {
+                shapes
+                    .iter()
+                    .filter_map(|(origin, geometry)| primitive(link_to_robot * origin, geometry))
+            })
+            .collect();
+
+        let kinematics = robot_kinematics(joints);
+        RobotGeometry {
+            primitives,
+            left_sole: transform_left_sole_outline(kinematics.left_leg.sole_to_robot).collect(),
+            right_sole: transform_right_sole_outline(kinematics.right_leg.sole_to_robot).collect(),
+        }
+    }
+}
+
+/// URDF joints are named like `LShoulderPitch` or `HeadYaw`
+fn joints_name(urdf_name: &str) -> Option<JointsName> {
+    Joints::fill(())
+        .enumerate()
+        .map(|(name, _)| name)
+        .find(|name| {
+            let urdf_joint_name = match name {
+                JointsName::Head(joint) => format!("Head{joint:?}"),
+                JointsName::LeftArm(joint) => format!("L{joint:?}"),
+                JointsName::RightArm(joint) => format!("R{joint:?}"),
+                JointsName::LeftLeg(joint) => format!("L{joint:?}"),
+                JointsName::RightLeg(joint) => format!("R{joint:?}"),
+            };
+            urdf_joint_name == urdf_name
+        })
+}
+
+fn isometry(pose: &Pose) -> Isometry3<f32> {
+    Isometry3::from_parts(
+        Translation3::new(pose.xyz[0] as f32, pose.xyz[1] as f32, pose.xyz[2] as f32),
+        UnitQuaternion::from_euler_angles(
+            pose.rpy[0] as f32,
+            pose.rpy[1] as f32,
+            pose.rpy[2] as f32,
+        ),
+    )
+}
+
+fn primitive(shape_to_robot: Isometry3<f32>, geometry: &Geometry) -> Option<Primitive> {
+    let point = |x: f64, y: f64, z: f64| {
+        Point3::wrap(shape_to_robot * nalgebra::point![x as f32, y as f32, z as f32])
+    };
+    match *geometry {
+        Geometry::Sphere { radius } => Some(Primitive::Sphere {
+            center: point(0.0, 0.0, 0.0),
+            radius: radius as f32,
+        }),
+        Geometry::Cylinder { radius, length } => Some(Primitive::Cylinder {
+            start: point(0.0, 0.0, -length / 2.0),
+            end: point(0.0, 0.0, length / 2.0),
+            radius: radius as f32,
+        }),
+        Geometry::Box { ref size } => {
+            let [x, y, z] = [size[0] / 2.0, size[1] / 2.0, size[2] / 2.0];
+            Some(Primitive::Box {
+                corners: [
+                    point(-x, -y, -z),
+                    point(x, -y, -z),
+                    point(x, y, -z),
+                    point(-x, y, -z),
+                    point(-x, -y, z),
+                    point(x, -y, z),
+                    point(x, y, z),
+                    point(-x, y, z),
+                ],
+            })
+        }
+        _ => None,
+    }
+}
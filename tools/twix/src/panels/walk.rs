@@ -13,6 +13,8 @@ use types::{
     support_foot::Side,
 };
 use walking_engine::{
+    backend::nao::Nao as NaoBackend,
+    capture_point::{CapturePoint, RecoveryStep},
     feet::Feet,
     mode::{
         catching::Catching, kicking::Kicking, starting::Starting, stopping::Stopping,
@@ -28,6 +30,7 @@ pub struct WalkPanel {
     last_actuated_commands: BufferHandle<Option<MotorCommands<Joints<f32>>>>,
     robot_to_ground: BufferHandle<Option<Isometry3<Robot, Ground>>>,
     zero_moment_point: BufferHandle<Point2<Ground>>,
+    capture_point: BufferHandle<Option<CapturePoint>>,
 }
 
 impl Panel for WalkPanel {
@@ -40,6 +43,7 @@ impl Panel for WalkPanel {
             nao.subscribe_value("Control.additional_outputs.actuated_motor_commands");
         let robot_to_ground = nao.subscribe_value("Control.main_outputs.robot_to_ground");
         let zero_moment_point = nao.subscribe_value("Control.main_outputs.zero_moment_point");
+        let capture_point = nao.subscribe_value("Control.additional_outputs.walking.capture_point");
 
         Self {
             walking_engine,
@@ -47,6 +51,7 @@ impl Panel for WalkPanel {
             last_actuated_commands,
             robot_to_ground,
             zero_moment_point,
+            capture_point,
         }
     }
     fn save(&self) -> Value {
@@ -100,6 +105,8 @@ impl Widget for &mut WalkPanel {
             }
         };
 
+        let capture_point = self.capture_point.get_last_value().ok().flatten().flatten();
+
         let zero_moment_point_in_walk =
             robot_to_walk * robot_to_ground.inverse() * zero_moment_point.extend(0.0);
 
@@ -110,6 +117,7 @@ impl Widget for &mut WalkPanel {
                 robot_to_walk,
                 last_actuated_joints,
                 zero_moment_point_in_walk,
+                capture_point,
             );
         });
 
@@ -122,7 +130,7 @@ fn step_plan(engine: &Engine) -> Option<&StepState> {
         Mode::Starting(Starting { step }) => Some(step),
         Mode::Walking(Walking { step, .. }) => Some(step),
         Mode::Kicking(Kicking { step, .. }) => Some(step),
-        Mode::Catching(Catching { step }) => Some(step),
+        Mode::Catching(Catching { step, .. }) => Some(step),
         Mode::Stopping(Stopping { step, .. }) => Some(step),
         _ => None,
     }
@@ -134,6 +142,7 @@ fn draw_top_down_plot(
     robot_to_walk: Isometry3<Robot, Walk>,
     last_actuated_joints: Joints,
     zero_moment_point: Point3<Walk>,
+    capture_point: Option<CapturePoint>,
 ) -> Option<Response> {
     let step = step_plan(engine)?;
     let response = Plot::new(ui.next_auto_id().with("Walk Top Down Plot"))
@@ -149,8 +158,12 @@ fn draw_top_down_plot(
             let end_feet_color = Color32::RED;
             plot_feet(ui, support_side, end_feet, end_feet_color);
 
-            let current_feet =
-                Feet::from_joints(robot_to_walk, &last_actuated_joints.body(), support_side);
+            let current_feet = Feet::from_joints(
+                &NaoBackend::default(),
+                robot_to_walk,
+                &last_actuated_joints.body(),
+                support_side,
+            );
             let current_feet_color = Color32::BLUE;
             plot_feet(ui, support_side, current_feet, current_feet_color);
             ui.points(
@@ -161,10 +174,67 @@ fn draw_top_down_plot(
                 .radius(5.0)
                 .shape(MarkerShape::Asterisk),
             );
+            if let Some(capture_point) = capture_point {
+                plot_point(
+                    ui,
+                    capture_point.position,
+                    MarkerShape::Diamond,
+                    Color32::GREEN,
+                );
+            }
+            if let Mode::Catching(Catching {
+                recovery_step: Some(recovery_step),
+                ..
+            }) = &engine.mode
+            {
+                plot_recovery_step(ui, recovery_step);
+            }
         });
     Some(response.response)
 }
 
+fn plot_recovery_step(ui: &mut PlotUi, recovery_step: &RecoveryStep) {
+    let region = recovery_step.region;
+    let corners = [
+        (region.min.x(), region.min.y()),
+        (region.max.x(), region.min.y()),
+        (region.max.x(), region.max.y()),
+        (region.min.x(), region.max.y()),
+    ]
+    .into_iter()
+    .map(|(x, y)| PlotPoint::new(x as f64, y as f64))
+    .collect();
+    let color = if recovery_step.is_capturable {
+        Color32::GREEN
+    } else {
+        Color32::RED
+    };
+    ui.polygon(
+        Polygon::new(PlotPoints::Owned(corners))
+            .stroke(Stroke::new(1.0, color))
+            .fill_color(color.gamma_multiply(0.1)),
+    );
+    plot_point(
+        ui,
+        recovery_step.predicted_capture_point,
+        MarkerShape::Diamond,
+        color,
+    );
+    plot_point(ui, recovery_step.target, MarkerShape::Cross, color);
+}
+
+fn plot_point(ui: &mut PlotUi, point: Point2<Walk>, shape: MarkerShape, color: Color32) {
+    ui.points(
+        Points::new(PlotPoints::Owned(vec![PlotPoint::new(
+            point.x(),
+            point.y(),
+        )]))
+        .radius(5.0)
+        .shape(shape)
+        .color(color),
+    );
+}
+
 fn plot_feet(ui: &mut PlotUi, support_side: Side, feet: Feet, color: Color32) {
     match support_side {
         Side::Left => {
@@ -0,0 +1,294 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Write},
+    path::{Path as FilePath, PathBuf},
+    sync::Arc,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use bincode::{deserialize_from, serialize_into};
+use color_eyre::{
+    eyre::{Context, ContextCompat},
+    Result,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    select, spawn,
+    sync::{broadcast::error::RecvError, mpsc, watch},
+};
+
+use communication::{
+    client::{protocol::SubscriptionEvent, ClientHandle, SubscriptionHandle},
+    messages::{Format, Path, Paths},
+};
+
+use crate::value_buffer::Datum;
+
+pub const RECORDING_EXTENSION: &str = "twixrec";
+
+#[derive(Deserialize, Serialize)]
+struct RecordingHeader {
+    address: String,
+    paths: Paths,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Record {
+    path: Path,
+    timestamp: SystemTime,
+    value: RecordedValue,
+}
+
+#[derive(Deserialize, Serialize)]
+enum RecordedValue {
+    /// JSON encoded value of a text subscription
+    Text(String),
+    /// Bincode encoded value of a binary subscription
+    Binary(Vec<u8>),
+}
+
+/// Writes all values of the recorded paths to a file until it is dropped.
+///
+/// The file consists of a bincode encoded header followed by a stream of bincode encoded records.
+pub struct Recorder {
+    file_path: PathBuf,
+    records: mpsc::UnboundedSender<Record>,
+    stop: watch::Sender<()>,
+}
+
+impl Recorder {
+    pub fn start(file_path: PathBuf, address: String, paths: Paths) -> Result<Self> {
+        let file = File::create(&file_path)
+            .wrap_err_with(|| format!("failed to create {}", file_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        serialize_into(&mut writer, &RecordingHeader { address, paths })
+            .wrap_err("failed to write recording header")?;
+
+        let (records, mut receiver) = mpsc::unbounded_channel::<Record>();
+        thread::spawn(move || {
+            while let Some(record) = receiver.blocking_recv() {
+                if let Err(error) = serialize_into(&mut writer, &record) {
+                    error!("failed to write record: {error:#}");
+                    return;
+                }
+            }
+            if let Err(error) = writer.flush() {
+                error!("failed to flush recording: {error:#}");
+            }
+        });
+        let (stop, _) = watch::channel(());
+
+        Ok(Self {
+            file_path,
+            records,
+            stop,
+        })
+    }
+
+    pub fn file_path(&self) -> &FilePath {
+        &self.file_path
+    }
+
+    /// Subscribes to the path and forwards every received value to the file, needs to be called
+    /// from within a tokio runtime
+    pub fn record(&self, client: ClientHandle, path: Path, format: Format) {
+        let records = self.records.clone();
+        let stop = self.stop.subscribe();
+        spawn(async move {
+            match format {
+                Format::Text => {
+                    let subscription = client.subscribe_text(path.clone()).await;
+                    forward(subscription, path, stop, records, |value| {
+                        RecordedValue::Text(value.to_string())
+                    })
+                    .await
+                }
+                Format::Binary => {
+                    let subscription = client.subscribe_binary(path.clone()).await;
+                    forward(subscription, path, stop, records, |value| {
+                        RecordedValue::Binary(value.clone())
+                    })
+                    .await
+                }
+                _ => error!("cannot record {path}: unsupported format {format:?}"),
+            }
+        });
+    }
+}
+
+async fn forward<T: Debug>(
+    mut subscription: SubscriptionHandle<T>,
+    path: Path,
+    mut stop: watch::Receiver<()>,
+    records: mpsc::UnboundedSender<Record>,
+    convert: impl Fn(&T) -> RecordedValue,
+) {
+    loop {
+        select! {
+            maybe_event = subscription.receiver.recv() => {
+                let event = match maybe_event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let (SubscriptionEvent::Successful { timestamp, value }
+                | SubscriptionEvent::Update { timestamp, value }) = event.as_ref()
+                else {
+                    continue;
+                };
+                let record = Record {
+                    path: path.clone(),
+                    timestamp: *timestamp,
+                    value: convert(value),
+                };
+                if records.send(record).is_err() {
+                    break;
+                }
+            },
+            _ = stop.changed() => break,
+        }
+    }
+}
+
+type Series<T> = Arc<Vec<Datum<T>>>;
+
+/// A recording file loaded into memory
+pub struct Recording {
+    pub address: String,
+    pub paths: Paths,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    text: HashMap<Path, Series<Value>>,
+    binary: HashMap<Path, Series<Vec<u8>>>,
+}
+
+impl Recording {
+    pub fn load(file_path: &FilePath) -> Result<Self> {
+        let file = File::open(file_path)
+            .wrap_err_with(|| format!("failed to open {}", file_path.display()))?;
+        let mut reader = BufReader::new(file);
+        let header: RecordingHeader =
+            deserialize_from(&mut reader).wrap_err("failed to read recording header")?;
+
+        let mut text: HashMap<Path, Vec<Datum<Value>>> = HashMap::new();
+        let mut binary: HashMap<Path, Vec<Datum<Vec<u8>>>> = HashMap::new();
+        loop {
+            let record: Record = match deserialize_from(&mut reader) {
+                Ok(record) => record,
+                Err(error) => match *error {
+                    bincode::ErrorKind::Io(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                        break
+                    }
+                    error => return Err(error).wrap_err("failed to read record"),
+                },
+            };
+            match record.value {
+                RecordedValue::Text(value) => {
+                    let value = serde_json::from_str(&value)
+                        .wrap_err_with(|| format!("failed to parse value of {}", record.path))?;
+                    text.entry(record.path).or_default().push(Datum {
+                        timestamp: record.timestamp,
+                        value,
+                    });
+                }
+                RecordedValue::Binary(value) => {
+                    binary.entry(record.path).or_default().push(Datum {
+                        timestamp: record.timestamp,
+                        value,
+                    });
+                }
+            }
+        }
+
+        let timestamps = text
+            .values()
+            .flatten()
+            .map(|datum| datum.timestamp)
+            .chain(binary.values().flatten().map(|datum| datum.timestamp));
+        let start = timestamps
+            .clone()
+            .min()
+            .wrap_err("recording does not contain any data")?;
+        let end = timestamps.max().unwrap_or(start);
+
+        Ok(Self {
+            address: header.address,
+            paths: header.paths,
+            start,
+            end,
+            text: into_sorted_series(text),
+            binary: into_sorted_series(binary),
+        })
+    }
+}
+
+fn into_sorted_series<T>(series: HashMap<Path, Vec<Datum<T>>>) -> HashMap<Path, Series<T>> {
+    series
+        .into_iter()
+        .map(|(path, mut data)| {
+            data.sort_by_key(|datum| datum.timestamp);
+            (path, Arc::new(data))
+        })
+        .collect()
+}
+
+/// Replays a recording, subscriptions follow the current playback time
+pub struct Playback {
+    recording: Recording,
+    time: watch::Sender<SystemTime>,
+}
+
+impl Playback {
+    pub fn new(recording: Recording) -> Self {
+        let (time, _) = watch::channel(recording.start);
+        Self { recording, time }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.recording
+            .end
+            .duration_since(self.recording.start)
+            .unwrap_or_default()
+    }
+
+    pub fn position(&self) -> Duration {
+        self.time
+            .borrow()
+            .duration_since(self.recording.start)
+            .unwrap_or_default()
+    }
+
+    pub fn seek(&self, position: Duration) {
+        self.time
+            .send_replace(self.recording.start + position.min(self.duration()));
+    }
+
+    pub fn subscribe_time(&self) -> watch::Receiver<SystemTime> {
+        self.time.subscribe()
+    }
+
+    pub fn text_series(&self, path: &str) -> Series<Value> {
+        self.recording.text.get(path).cloned().unwrap_or_default()
+    }
+
+    pub fn binary_series(&self, path: &str) -> Series<Vec<u8>> {
+        self.recording.binary.get(path).cloned().unwrap_or_default()
+    }
+
+    /// The last binary value of the path which was received before the current playback time
+    pub fn read_binary(&self, path: &str) -> Option<Datum<Vec<u8>>> {
+        let series = self.recording.binary.get(path)?;
+        let now = *self.time.borrow();
+        let index = series.partition_point(|datum| datum.timestamp <= now);
+        series.get(index.checked_sub(1)?).cloned()
+    }
+}
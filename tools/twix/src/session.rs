@@ -0,0 +1,186 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use chrono::Local;
+use color_eyre::Result;
+use eframe::epaint::Color32;
+use repository::Repository;
+use serde_json::Value;
+
+use crate::{
+    nao::Nao,
+    recording::{Playback, Recording, RECORDING_EXTENSION},
+};
+
+const ROBOT_COLORS: [Color32; 7] = [
+    Color32::from_rgb(230, 25, 75),
+    Color32::from_rgb(60, 180, 75),
+    Color32::from_rgb(0, 130, 200),
+    Color32::from_rgb(245, 130, 48),
+    Color32::from_rgb(145, 30, 180),
+    Color32::from_rgb(70, 240, 240),
+    Color32::from_rgb(240, 50, 230),
+];
+
+#[derive(Clone)]
+pub struct Robot {
+    pub address: String,
+    pub nao: Arc<Nao>,
+    pub color: Color32,
+}
+
+/// All robot connections of a twix window.
+///
+/// The first robot is the primary connection which is controlled by the address bar, further
+/// robots are added to the team and can be selected per tab.
+pub struct Session {
+    repository_root: Option<PathBuf>,
+    robots: RwLock<Vec<Robot>>,
+    tab_requests: Mutex<Vec<Value>>,
+}
+
+impl Session {
+    pub fn new(primary_address: String, repository_root: Option<PathBuf>) -> Arc<Self> {
+        let session = Arc::new(Self {
+            repository_root,
+            robots: RwLock::new(Vec::new()),
+            tab_requests: Mutex::new(Vec::new()),
+        });
+        session.add(primary_address);
+        session
+    }
+
+    /// Creates a session whose primary robot replays a recording
+    pub fn from_recording(file_path: &Path, repository_root: Option<PathBuf>) -> Result<Arc<Self>> {
+        let recording = Recording::load(file_path)?;
+        let address = format!("{} (recording)", recording.address);
+        let session = Arc::new(Self {
+            repository_root,
+            robots: RwLock::new(Vec::new()),
+            tab_requests: Mutex::new(Vec::new()),
+        });
+        let nao = Arc::new(Nao::from_playback(
+            Playback::new(recording),
+            session.repository_root.clone().map(Repository::new),
+            Arc::downgrade(&session),
+        ));
+        session.robots.write().unwrap().push(Robot {
+            address,
+            nao,
+            color: ROBOT_COLORS[0],
+        });
+        Ok(session)
+    }
+
+    pub fn primary(&self) -> Robot {
+        self.robots.read().unwrap()[0].clone()
+    }
+
+    pub fn set_primary_address(&self, address: String) {
+        let mut robots = self.robots.write().unwrap();
+        robots[0].nao.set_address(to_websocket_url(&address));
+        robots[0].address = address;
+    }
+
+    /// Connects to a further robot, returns the existing connection if the address is already known
+    pub fn add(self: &Arc<Self>, address: String) -> Arc<Nao> {
+        if let Some(robot) = self.find(&address) {
+            return robot.nao;
+        }
+        let mut robots = self.robots.write().unwrap();
+        let nao = Arc::new(Nao::new(
+            to_websocket_url(&address),
+            self.repository_root.clone().map(Repository::new),
+            Arc::downgrade(self),
+        ));
+        let color = ROBOT_COLORS[robots.len() % ROBOT_COLORS.len()];
+        robots.push(Robot {
+            address,
+            nao: nao.clone(),
+            color,
+        });
+        nao
+    }
+
+    /// Disconnects and removes a team robot, the primary connection cannot be removed
+    pub fn remove(&self, address: &str) {
+        let mut robots = self.robots.write().unwrap();
+        if let Some(index) = robots
+            .iter()
+            .skip(1)
+            .position(|robot| robot.address == address)
+        {
+            robots.remove(index + 1).nao.disconnect();
+        }
+    }
+
+    pub fn find(&self, address: &str) -> Option<Robot> {
+        self.robots
+            .read()
+            .unwrap()
+            .iter()
+            .find(|robot| robot.address == address)
+            .cloned()
+    }
+
+    pub fn robots(&self) -> Vec<Robot> {
+        self.robots.read().unwrap().clone()
+    }
+
+    pub fn team(&self) -> Vec<Robot> {
+        self.robots.read().unwrap()[1..].to_vec()
+    }
+
+    /// Requests a new tab showing the saved panel value connected to the same robot as `nao`
+    pub fn open_tab(&self, nao: &Arc<Nao>, mut value: Value) {
+        if let Some(robot) = self
+            .team()
+            .into_iter()
+            .find(|robot| Arc::ptr_eq(&robot.nao, nao))
+        {
+            value["_robot"] = Value::String(robot.address);
+        }
+        self.tab_requests.lock().unwrap().push(value);
+    }
+
+    pub fn take_tab_requests(&self) -> Vec<Value> {
+        std::mem::take(&mut self.tab_requests.lock().unwrap())
+    }
+
+    /// Records every robot of the session into its own file in the directory
+    pub fn start_recording(&self, directory: &Path) -> Result<Vec<PathBuf>> {
+        let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
+        self.robots()
+            .into_iter()
+            .map(|robot| {
+                let address = robot.address.replace([':', '/'], "_");
+                let file_path =
+                    directory.join(format!("{timestamp}_{address}.{RECORDING_EXTENSION}"));
+                robot
+                    .nao
+                    .start_recording(file_path.clone(), robot.address.clone())?;
+                Ok(file_path)
+            })
+            .collect()
+    }
+
+    pub fn stop_recording(&self) -> Vec<PathBuf> {
+        self.robots()
+            .into_iter()
+            .filter_map(|robot| robot.nao.stop_recording())
+            .collect()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.robots().iter().any(|robot| robot.nao.is_recording())
+    }
+}
+
+pub fn to_websocket_url(address: &str) -> String {
+    match address.split_once(":") {
+        None | Some((_, "")) => format!("ws://{address}:1337"),
+        Some((ip, port)) => format!("ws://{ip}:{port}"),
+    }
+}
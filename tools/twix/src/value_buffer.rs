@@ -130,6 +130,44 @@ impl<T, E> Buffer<T, E> {
             };
         }
     }
+    /// Fills the buffer with the recorded data visible at the playback time until the buffer is
+    /// dropped
+    pub async fn replay<U>(
+        self,
+        series: Arc<Vec<Datum<U>>>,
+        mut time: watch::Receiver<SystemTime>,
+        op: impl Fn(Datum<&U>) -> Result<Datum<T>, E> + Send + Sync + 'static,
+    ) {
+        loop {
+            let now = *time.borrow_and_update();
+            let history = *self.history.lock().await;
+            let right = series.partition_point(|sample| sample.timestamp <= now);
+            let oldest = now.checked_sub(history).unwrap_or(SystemTime::UNIX_EPOCH);
+            let left = series
+                .partition_point(|sample| sample.timestamp < oldest)
+                .min(right.saturating_sub(1));
+            let update = series[left..right]
+                .iter()
+                .map(|sample| {
+                    op(Datum {
+                        timestamp: sample.timestamp,
+                        value: &sample.value,
+                    })
+                })
+                .collect();
+            let _ = self.sender.send(update);
+            select! {
+                changed = time.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                },
+                _ = self.sender.closed() => {
+                    break
+                }
+            };
+        }
+    }
 }
 
 fn handle_update<T, E>(value: &mut Result<Vec<Datum<T>>, E>, datum: Datum<T>, history: Duration) {